@@ -7,9 +7,11 @@ complete -c eza -s 1 -l oneline -d "Display one entry per line"
 complete -c eza -s l -l long -d "Display extended file metadata as a table"
 complete -c eza -s G -l grid -d "Display entries in a grid"
 complete -c eza -s x -l across -d "Sort the grid across, rather than downwards"
+complete -c eza -l grid-rows -d "Aim for this many rows in the grid" -x
 complete -c eza -s R -l recurse -d "Recurse into directories"
 complete -c eza -s T -l tree -d "Recurse into directories as a tree"
 complete -c eza -s X -l dereference -d "Dereference symbolic links when displaying information"
+complete -c eza -l dereference-command-line -d "Dereference only symbolic links given as arguments"
 complete -c eza -s F -l classify -d "Display type indicator by file names"
 complete -c eza -l color \
     -l colour -d "When to use terminal colours" -x -a "
@@ -20,14 +22,66 @@ complete -c eza -l color \
 "
 complete -c eza -l color-scale \
     -l colour-scale -d "Highlight levels of file sizes distinctly"
+complete -c eza -l color-columns \
+    -l colour-columns -d "When to colour the long view's columns" -x -a "
+    always\t'Colour the columns'
+    dim\t'Dim the columns'
+    never\t'Leave the columns uncoloured'
+"
+complete -c eza -l color-owners \
+    -l colour-owners -d "Colour each user and group by its name"
+complete -c eza -l theme -d "Which built-in colour theme to use" -x -a "
+    auto\t'Pick a theme for the terminal background'
+    light\t'The theme for light backgrounds'
+    dark\t'The theme for dark backgrounds'
+    default\t'The default colours'
+    gruvbox\t'Gruvbox'
+    solarized-dark\t'Solarized, for dark backgrounds'
+    solarized-light\t'Solarized, for light backgrounds'
+    catppuccin\t'Catppuccin Mocha'
+    high-contrast\t'Bold basic colours'
+"
 complete -c eza -l icons -d "When to display icons" -x -a "
   always\t'Always display icons'
   auto\t'Display icons if standard output is a terminal'
   automatic\t'Display icons if standard output is a terminal'
   never\t'Never display icons'
 "
+complete -c eza -l icon-color \
+    -l icon-colour -d "How to colour icons" -x -a "
+    file\t'Use the colour of the file name'
+    name\t'Use the style of the file name'
+    fixed\t'Use the same colour for every icon'
+"
+complete -c eza -l icon-set -d "Which icons to use" -x -a "
+    nerd3\t'Use Nerd Fonts v3 icons'
+    nerd2\t'Use Nerd Fonts v2 icons'
+    unicode\t'Use plain Unicode symbols'
+    emoji\t'Use emoji'
+"
 complete -c eza -l no-quotes -d "Don't quote file names with spaces"
+complete -c eza -l empty-placeholder -d "Show a placeholder line for directories with no entries"
+complete -c eza -l count-only -d "Print the number and total size of entries instead of listing them"
+complete -c eza -l summary -d "Print the number and total size of entries after listing them"
+complete -c eza -l prompt -d "Print a one-line summary of the directory for use in shell prompts"
+complete -c eza -l limit -d "List only the first N entries" -x
+complete -c eza -l limit-marker -d "Say how many entries were left out by --limit"
+complete -c eza -l save-snapshot -d "Save the sizes of everything beneath the listed paths" -r -F
+complete -c eza -l update-snapshot -d "Update a saved snapshot, only reading the directories that changed" -r -F
+complete -c eza -l forget -d "Forget the options remembered for the directory"
 complete -c eza -l hyperlink -d "Display entries as hyperlinks"
+complete -c eza -l semantic-markers -d "Mark where the output starts and ends for the terminal"
+complete -c eza -l flush -d "When to write out the output" -x -a "
+    line\t'After every line'
+    dir\t'After each directory'
+    end\t'Once everything has been listed'
+"
+complete -c eza -l group-by -d "Split the listing into sections" -x -a "
+    category\t'By the category of each file'
+    extension\t'By the extension of each file'
+    first-letter\t'By the first letter of each name'
+    none\t'Do not split the listing up'
+"
 complete -c eza -l smart-group -d "Only show group if it has a different name from owner"
 
 # Filtering and sorting options
@@ -35,13 +89,37 @@ complete -c eza -l group-directories-first -d "Sort directories before other fil
 complete -c eza -l git-ignore -d "Ignore files mentioned in '.gitignore'"
 complete -c eza -s a -l all -d "Show hidden and 'dot' files. Use this twice to also show the '.' and '..' directories"
 complete -c eza -s A -l almost-all -d "Equivalent to --all; included for compatibility with `ls -A`"
-complete -c eza -s d -l list-dirs -d "List directories like regular files"
+complete -c eza -l hidden-mode -d "Which files are hidden" -x -a "
+    dots\t'Files with names beginning with a dot'
+    native\t'Files with the platform hidden flag'
+    both\t'Files hidden in either way'
+"
+complete -c eza -s d -l list-dirs -d "List directories like regular files" -a "auto\t'List contents only of paths ending in a slash'"
 complete -c eza -s L -l level -d "Limit the depth of recursion" -x -a "1 2 3 4 5 6 7 8 9"
+complete -c eza -l min-depth -d "Skip the first levels of recursion" -x -a "1 2 3 4 5 6 7 8 9"
+complete -c eza -l follow-links -d "Recurse into the directories that links point to"
+complete -c eza -l flat -d "List everything beneath the directories in one view, by path"
+complete -c eza -l prune-glob -d "Don't recurse into directories that match these glob patterns" -r
+complete -c eza -l one-file-system -d "Don't recurse into directories on other filesystems"
+complete -c eza -l inline-errors -d "Print errors about unreadable directories where they happen"
+complete -c eza -l collapse-configs -d "List a tree's tool config files on one line per directory"
+complete -c eza -l tree-counts -d "Show how many files are beneath each directory in a tree"
+complete -c eza -l compact-tree -d "List chains of single directories in a tree on one line"
+complete -c eza -l tree-style -d "Which characters to draw trees with" -x -a "
+    unicode\t'Thin box-drawing characters'
+    ascii\t'Plain ASCII characters'
+    rounded\t'Box-drawing characters with rounded corners'
+    bold\t'Heavy box-drawing characters'
+"
+complete -c eza -l tree-limit -d "List at most this many entries in each directory of a tree" -x
+complete -c eza -l diff -d "Compare two directories in one tree"
+complete -c eza -l only-changes -d "Collapse the entries that are the same in a diff"
 complete -c eza -s w -l width -d "Limits column output of grid, 0 implies auto-width"
 complete -c eza -s r -l reverse -d "Reverse the sort order"
 complete -c eza -s s -l sort -d "Which field to sort by" -x -a "
     accessed\t'Sort by file accessed time'
     age\t'Sort by file modified time (newest first)'
+    category\t'Sort by file category'
     changed\t'Sort by changed time'
     created\t'Sort by file modified time'
     date\t'Sort by file modified time'
@@ -51,30 +129,99 @@ complete -c eza -s s -l sort -d "Which field to sort by" -x -a "
     Extension\t'Sort by file extension (uppercase first)'
     filename\t'Sort by filename'
     Filename\t'Sort by filename (uppercase first)'
+    group\t'Sort by group name'
     inode\t'Sort by file inode'
+    links\t'Sort by number of hard links'
     modified\t'Sort by file modified time'
     name\t'Sort by filename'
     Name\t'Sort by filename (uppercase first)'
+    name-length\t'Sort by filename length'
     newest\t'Sort by file modified time (newest first)'
     none\t'Do not sort files at all'
     oldest\t'Sort by file modified time'
+    owner\t'Sort by owner name'
+    random\t'Shuffle files into a random order'
     size\t'Sort by file size'
     time\t'Sort by file modified time'
     type\t'Sort by file type'
 "
+complete -c eza -l seed -d "Seed for the random sort order" -x
+complete -c eza -l tie-break -d "How to order files that sort the same" -x -a "
+    name\t'Sort them by name'
+    none\t'Leave them in the order they were read in'
+    inode\t'Sort them by inode'
+"
 
 complete -c eza -s I -l ignore-glob -d "Ignore files that match these glob patterns" -r
+complete -c eza -l regex -d "Only list files with names matching this regex" -x
+complete -c eza -l iregex -d "Only list files with names matching this regex, ignoring case" -x
+complete -c eza -l ignore-regex -d "Ignore files with names matching this regex" -x
+complete -c eza -l ignore-iregex -d "Ignore files with names matching this regex, ignoring case" -x
+complete -c eza -l size -d "Only list files in this range of sizes" -x
+complete -c eza -l newer-than -d "Only list files newer than this time" -x
+complete -c eza -l older-than -d "Only list files older than this time" -x
 complete -c eza -s D -l only-dirs -d "List only directories"
 complete -c eza -s f -l only-files -d "List only files"
+complete -c eza -l only-links -d "List only symbolic links"
+complete -c eza -l type -d "List only these types of entry" -x -a "
+    f\t'Regular files'
+    d\t'Directories'
+    l\t'Symlinks'
+    x\t'Executable files'
+    s\t'Sockets'
+    p\t'Named pipes'
+    b\t'Block devices'
+    c\t'Character devices'
+"
+complete -c eza -l only -d "List only files in these categories" -x -a "
+    image\t'Images'
+    video\t'Videos'
+    music\t'Lossy music'
+    lossless\t'Lossless music'
+    crypto\t'Cryptography-related files'
+    document\t'Documents'
+    compressed\t'Compressed files'
+    temp\t'Temporary files'
+    compiled\t'Compilation artifacts'
+    build\t'Build files'
+    source\t'Source code'
+    config\t'Tool config files'
+"
+complete -c eza -l exclude-category -d "Don't list files in these categories" -x -a "
+    image\t'Images'
+    video\t'Videos'
+    music\t'Lossy music'
+    lossless\t'Lossless music'
+    crypto\t'Cryptography-related files'
+    document\t'Documents'
+    compressed\t'Compressed files'
+    temp\t'Temporary files'
+    compiled\t'Compilation artifacts'
+    build\t'Build files'
+    source\t'Source code'
+    config\t'Tool config files'
+"
+complete -c eza -l broken-links -d "List only symlinks that point to nothing"
+complete -c eza -l no-broken-links -d "Don't list symlinks that point to nothing"
+complete -c eza -l has-xattr -d "List only files with this extended attribute" -x
 
 # Long view options
 complete -c eza -s b -l binary -d "List file sizes with binary prefixes"
 complete -c eza -s B -l bytes -d "List file sizes in bytes, without any prefixes"
+complete -c eza -l block-size -d "List file sizes as a number of blocks of this size" -x
+complete -c eza -l size-style -d "How to format file sizes" -x -a "
+    default\t'Use decimal prefixes'
+    binary\t'Use binary prefixes'
+    bytes\t'List sizes in bytes'
+    grouped\t'List sizes in bytes with grouped digits'
+"
 complete -c eza -s g -l group -d "List each file's group"
 complete -c eza -s h -l header -d "Add a header row to each column"
+complete -c eza -l header-interval -d "Repeat the header row after every N rows" -x
 complete -c eza -s H -l links -d "List each file's number of hard links"
 complete -c eza -s i -l inode -d "List each file's inode number"
 complete -c eza -s S -l blocksize -d "List each file's size of allocated file system blocks"
+complete -c eza -l disk-usage -d "Show the space files take up on disk as their size"
 complete -c eza -s t -l time -d "Which timestamp field to list" -x -a "
     modified\t'Display modified time'
     changed\t'Display changed time'
@@ -94,12 +241,28 @@ complete -c eza -l time-style -d "How to format timestamps" -x -a "
     full-iso\t'Display full ISO timestamps, up to the nanosecond'
     relative\t'Display relative timestamps'
 "
+complete -c eza -l age -d "Show how long ago each file's timestamp was" -f -a "
+    seconds\t'Count in seconds'
+    minutes\t'Count in minutes'
+    hours\t'Count in hours'
+    days\t'Count in days'
+"
 complete -c eza -l total-size -d "Show recursive directory size"
+complete -c eza -l threads -d "How many threads to look up file details with" -x -a "auto"
 complete -c eza -l no-permissions -d "Suppress the permissions field"
 complete -c eza -s o -l octal-permissions -d "List each file's permission in octal format"
 complete -c eza -l no-filesize -d "Suppress the filesize field"
 complete -c eza -l no-user -d "Suppress the user field"
 complete -c eza -l no-time -d "Suppress the time field"
+complete -c eza -l wrap-names -d "Carry names too long for the terminal onto the next lines"
+complete -c eza -l table-style -d "Which lines to draw around the columns" -x -a "
+    borders\t'A box around the table'
+    compact\t'Lines between the columns'
+    none\t'Spaces between the columns'
+"
+complete -c eza -l align -d "Which side to align columns to, such as inode=right" -x
+complete -c eza -l hide -d "Which columns not to list" -x -a "inode octal perms links size blocksize user group context modified changed created accessed age git repos repos-no-status codeowners delta time"
+complete -c eza -l columns -d "Which columns to list, in order" -x -a "inode octal perms links size blocksize user group context modified changed created accessed age git repos repos-no-status codeowners delta name"
 complete -c eza -s M -l mounts -d "Show mount details"
 
 # Optional extras
@@ -107,5 +270,8 @@ complete -c eza -l git -d "List each file's Git status, if tracked"
 complete -c eza -l no-git -d "Suppress Git status"
 complete -c eza -l git-repos -d "List each git-repos status and branch name"
 complete -c eza -l git-repos-no-status -d "List each git-repos branch name (much faster)"
+complete -c eza -l git-summary -d "Print how many files have each kind of Git change"
 complete -c eza -s '@' -l extended -d "List each file's extended attributes and sizes"
 complete -c eza -s Z -l context -d "List each file's security context"
+complete -c eza -l codeowners -d "List each file's owners from the CODEOWNERS file"
+complete -c eza -l delta -d "Show how much each entry has grown since a saved snapshot" -r -F
@@ -0,0 +1,123 @@
+//! Storage for the paths of a directory’s entries.
+//!
+//! A large recursive listing reads millions of entries, and giving each
+//! one’s path its own heap allocation costs more than the path itself. On
+//! Unix, a directory’s paths all get appended to one buffer instead, and the
+//! files read from it borrow slices of that buffer rather than copying them.
+//! Windows paths can’t be sliced out of a byte buffer, so there each one is
+//! still a separate `PathBuf`.
+
+use std::ffi::OsStr;
+use std::path::Path;
+#[cfg(windows)]
+use std::path::PathBuf;
+use std::slice;
+
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+
+/// The paths of the entries in a directory, in the order they were read.
+#[cfg(unix)]
+#[derive(Default)]
+pub struct PathArena {
+    /// Every path, one after another.
+    bytes: Vec<u8>,
+
+    /// Where each path ends in `bytes`, which is also where the next begins.
+    ends: Vec<usize>,
+}
+
+#[cfg(unix)]
+impl PathArena {
+    /// Adds the path of an entry in the directory at `dir`, which is the
+    /// same path as `dir.join(name)` would produce.
+    pub fn push(&mut self, dir: &Path, name: &OsStr) {
+        let dir = dir.as_os_str().as_bytes();
+        self.bytes.extend_from_slice(dir);
+        if !dir.is_empty() && !dir.ends_with(b"/") {
+            self.bytes.push(b'/');
+        }
+
+        self.bytes.extend_from_slice(name.as_bytes());
+        self.ends.push(self.bytes.len());
+    }
+
+    /// Iterates over the paths, in the order they were added.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            bytes: &self.bytes,
+            start: 0,
+            ends: self.ends.iter(),
+        }
+    }
+}
+
+/// Iterator over the paths in an arena.
+#[cfg(unix)]
+pub struct Iter<'a> {
+    bytes: &'a [u8],
+    start: usize,
+    ends: slice::Iter<'a, usize>,
+}
+
+#[cfg(unix)]
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a Path;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let end = *self.ends.next()?;
+        let path = Path::new(OsStr::from_bytes(&self.bytes[self.start..end]));
+        self.start = end;
+        Some(path)
+    }
+}
+
+/// The paths of the entries in a directory, in the order they were read.
+#[cfg(windows)]
+#[derive(Default)]
+pub struct PathArena {
+    paths: Vec<PathBuf>,
+}
+
+#[cfg(windows)]
+impl PathArena {
+    /// Adds the path of an entry in the directory at `dir`.
+    pub fn push(&mut self, dir: &Path, name: &OsStr) {
+        self.paths.push(dir.join(name));
+    }
+
+    /// Iterates over the paths, in the order they were added.
+    pub fn iter(&self) -> Iter<'_> {
+        self.paths.iter().map(PathBuf::as_path as _)
+    }
+}
+
+/// Iterator over the paths in an arena.
+#[cfg(windows)]
+pub type Iter<'a> = std::iter::Map<slice::Iter<'a, PathBuf>, fn(&PathBuf) -> &Path>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn same_as_join() {
+        let mut arena = PathArena::default();
+        for dir in ["", ".", "/", "/tmp", "src/", "a/b"] {
+            arena.push(Path::new(dir), OsStr::new("file.rs"));
+        }
+
+        let joined = ["", ".", "/", "/tmp", "src/", "a/b"]
+            .into_iter()
+            .map(|dir| Path::new(dir).join("file.rs"))
+            .collect::<Vec<_>>();
+
+        assert!(arena.iter().eq(joined.iter().map(PathBuf::as_path)));
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(PathArena::default().iter().count(), 0);
+    }
+}
@@ -0,0 +1,268 @@
+//! Looking up who owns each file in a repository, from the `CODEOWNERS` file
+//! that GitHub and GitLab use to pick reviewers.
+//!
+//! The file is a list of patterns, each followed by the users or teams
+//! that own the files matching it, with the *last* matching pattern
+//! winning. Patterns work like the ones in `.gitignore` files: they match
+//! anywhere in the repository unless they start with or contain a `/`, and
+//! a pattern that matches a directory also matches everything inside it.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use log::*;
+
+/// Where the file is looked for, relative to the root of the repository, in
+/// the order GitHub looks for it.
+static LOCATIONS: &[&str] = &[
+    ".github/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+];
+
+/// The owners of the file at the given path, separated by spaces, if it’s
+/// in a repository with a `CODEOWNERS` file and a pattern in it matches.
+pub fn owners(path: &Path, is_directory: bool) -> Option<String> {
+    static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+
+    let name = path.file_name()?;
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+
+    let (dir, codeowners) = CACHE
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .lookup(parent)?;
+
+    let path = dir.join(name);
+    let relative = path.strip_prefix(&codeowners.root).ok()?;
+    codeowners
+        .owners(relative, is_directory)
+        .map(ToOwned::to_owned)
+}
+
+/// The `CODEOWNERS` files that have been read so far, and which one each
+/// directory that’s been listed uses, as there are usually lots of files
+/// in each directory and lots of directories in each repository.
+#[derive(Default)]
+struct Cache {
+    dirs: HashMap<PathBuf, Option<(PathBuf, Arc<CodeOwners>)>>,
+    roots: HashMap<PathBuf, Option<Arc<CodeOwners>>>,
+}
+
+impl Cache {
+    /// The canonical path of the directory, along with the `CODEOWNERS`
+    /// file for its repository.
+    fn lookup(&mut self, dir: &Path) -> Option<(PathBuf, Arc<CodeOwners>)> {
+        if let Some(found) = self.dirs.get(dir) {
+            return found.clone();
+        }
+
+        let found = dir.canonicalize().ok().and_then(|canonical| {
+            let root = canonical
+                .ancestors()
+                .find(|d| d.join(".git").exists())?
+                .to_path_buf();
+
+            let codeowners = self
+                .roots
+                .entry(root.clone())
+                .or_insert_with(|| CodeOwners::load(root).map(Arc::new))
+                .clone()?;
+
+            Some((canonical, codeowners))
+        });
+
+        self.dirs.insert(dir.to_path_buf(), found.clone());
+        found
+    }
+}
+
+/// The rules read from a repository’s `CODEOWNERS` file.
+struct CodeOwners {
+    /// The root of the repository, which patterns are relative to.
+    root: PathBuf,
+
+    /// The rules, in the order they’re in the file.
+    rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    fn load(root: PathBuf) -> Option<Self> {
+        let contents = LOCATIONS
+            .iter()
+            .find_map(|location| fs::read_to_string(root.join(location)).ok())?;
+
+        Some(Self {
+            rules: parse(&contents),
+            root,
+        })
+    }
+
+    /// The owners of the file at the given path, relative to the root.
+    /// Files matching a pattern without any owners aren’t owned by anyone.
+    fn owners(&self, path: &Path, is_directory: bool) -> Option<&str> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(path, is_directory))
+            .map(|rule| rule.owners.as_str())
+            .filter(|owners| !owners.is_empty())
+    }
+}
+
+/// One line of a `CODEOWNERS` file.
+#[derive(Debug)]
+struct Rule {
+    pattern: glob::Pattern,
+
+    /// Whether the pattern ends with a `/`, so it only matches directories.
+    directories_only: bool,
+
+    /// Whether the pattern ends with `/*`, so it only matches the files
+    /// directly inside a directory, and not the ones further down.
+    direct_only: bool,
+
+    /// The owners, separated by spaces.
+    owners: String,
+}
+
+impl Rule {
+    fn new(pattern: &str, owners: String) -> Option<Self> {
+        let directories_only = pattern.ends_with('/');
+        let direct_only = pattern.ends_with("/*");
+        let pattern = pattern.trim_end_matches('/');
+
+        // Patterns without a `/` other than at the end match at any depth.
+        let glob = match pattern.strip_prefix('/') {
+            Some("") => return None,
+            Some(anchored) => anchored.to_owned(),
+            None if pattern.contains('/') => pattern.to_owned(),
+            None => format!("**/{pattern}"),
+        };
+
+        match glob::Pattern::new(&glob) {
+            Ok(pattern) => Some(Self {
+                pattern,
+                directories_only,
+                direct_only,
+                owners,
+            }),
+            Err(e) => {
+                warn!("Invalid CODEOWNERS pattern {:?}: {}", glob, e);
+                None
+            }
+        }
+    }
+
+    fn matches(&self, path: &Path, is_directory: bool) -> bool {
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..glob::MatchOptions::new()
+        };
+
+        if (is_directory || !self.directories_only) && self.pattern.matches_path_with(path, options)
+        {
+            return true;
+        }
+
+        !self.direct_only
+            && path
+                .ancestors()
+                .skip(1)
+                .take_while(|dir| !dir.as_os_str().is_empty())
+                .any(|dir| self.pattern.matches_path_with(dir, options))
+    }
+}
+
+/// Reads the rules out of a `CODEOWNERS` file. GitLab’s section headers,
+/// which look like `[Section]`, are skipped.
+fn parse(contents: &str) -> Vec<Rule> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|line| !line.starts_with('[') && !line.starts_with("^["))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pattern = fields.next()?;
+            let owners = fields
+                .take_while(|field| !field.starts_with('#'))
+                .collect::<Vec<_>>()
+                .join(" ");
+            Rule::new(pattern, owners)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn example() -> CodeOwners {
+        CodeOwners {
+            root: PathBuf::from("/repo"),
+            rules: parse(
+                "# Everything else\n\
+                 *       @org/everyone\n\
+                 *.rs    @rustacean  @org/rust # inline comment\n\
+                 /docs/  @writer\n\
+                 build/  @builder\n\
+                 src/*   @top-level\n\
+                 [Section]\n\
+                 /vendor\n",
+            ),
+        }
+    }
+
+    fn owners(path: &str, is_directory: bool) -> Option<String> {
+        example()
+            .owners(Path::new(path), is_directory)
+            .map(ToOwned::to_owned)
+    }
+
+    #[test]
+    fn anywhere() {
+        assert_eq!(owners("README.md", false), Some("@org/everyone".into()));
+        assert_eq!(
+            owners("a/b/c.rs", false),
+            Some("@rustacean @org/rust".into())
+        );
+    }
+
+    #[test]
+    fn anchored_directory() {
+        assert_eq!(owners("docs", true), Some("@writer".into()));
+        assert_eq!(owners("docs/guide/intro.md", false), Some("@writer".into()));
+        assert_eq!(owners("docs", false), Some("@org/everyone".into()));
+        assert_eq!(
+            owners("sub/docs/intro.md", false),
+            Some("@org/everyone".into())
+        );
+    }
+
+    #[test]
+    fn directory_anywhere() {
+        assert_eq!(
+            owners("tools/build/out.txt", false),
+            Some("@builder".into())
+        );
+    }
+
+    #[test]
+    fn direct_children() {
+        assert_eq!(owners("src/main.c", false), Some("@top-level".into()));
+        assert_eq!(owners("src/fs/dir.c", false), Some("@org/everyone".into()));
+    }
+
+    #[test]
+    fn unowned() {
+        assert_eq!(owners("vendor/lib.rs", false), None);
+    }
+}
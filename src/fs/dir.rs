@@ -4,10 +4,14 @@ use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::slice::Iter as SliceIter;
+use std::sync::OnceLock;
 
 use log::*;
 
-use crate::fs::File;
+use crate::fs::arena::{self, PathArena};
+use crate::fs::feature::xattr::Attribute;
+use crate::fs::metadata::{self, Metadata};
+use crate::fs::{readahead, File};
 
 /// A **Dir** provides a cached list of the file paths in a directory that’s
 /// being listed.
@@ -16,13 +20,44 @@ use crate::fs::File;
 /// check the existence of surrounding files, then highlight themselves
 /// accordingly. (See `File#get_source_files`)
 pub struct Dir {
-    /// A vector of the files that have been read from this directory.
-    contents: Vec<PathBuf>,
+    /// The paths of the files that have been read from this directory.
+    contents: PathArena,
+
+    /// What has been looked up about each of those files, once the
+    /// directory has been set up to resolve them ahead of being listed.
+    resolved: OnceLock<Vec<Resolved>>,
 
     /// The path that was read.
     pub path: PathBuf,
 }
 
+/// The metadata and extended attributes of one of a directory’s files, which
+/// can be looked up on another thread before the file gets listed, so the
+/// listing itself doesn’t have to wait for them.
+#[derive(Default)]
+pub struct Resolved {
+    metadata: OnceLock<io::Result<Metadata>>,
+    pub(crate) xattrs: OnceLock<Vec<Attribute>>,
+}
+
+impl Resolved {
+    /// The file’s metadata, statting it if that hasn’t been done yet.
+    pub(crate) fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let metadata = self.metadata.get_or_init(|| {
+            debug!("Statting file {:?}", path);
+            metadata::symlink_metadata(path)
+        });
+
+        match metadata {
+            Ok(metadata) => Ok(metadata.clone()),
+            Err(e) => Err(e.raw_os_error().map_or_else(
+                || io::Error::new(e.kind(), e.to_string()),
+                io::Error::from_raw_os_error,
+            )),
+        }
+    }
+}
+
 impl Dir {
     /// Create a new Dir object filled with all the files in the directory
     /// pointed to by the given path. Fails if the directory can’t be read, or
@@ -32,15 +67,40 @@ impl Dir {
     /// The `read_dir` iterator doesn’t actually yield the `.` and `..`
     /// entries, so if the user wants to see them, we’ll have to add them
     /// ourselves after the files have been read.
+    ///
+    /// When recursing, the directory might have been read ahead already.
     pub fn read_dir(path: PathBuf) -> io::Result<Self> {
+        if let Some(read) = readahead::take(&path) {
+            info!("Read directory ahead {:?}", &path);
+            return read;
+        }
+
+        Self::read_dir_now(path)
+    }
+
+    /// Reads the directory at the given path right away.
+    pub(crate) fn read_dir_now(path: PathBuf) -> io::Result<Self> {
         info!("Reading directory {:?}", &path);
 
-        let contents = fs::read_dir(&path)?
-            .map(|result| result.map(|entry| entry.path()))
-            .collect::<Result<_, _>>()?;
+        let mut contents = PathArena::default();
+        for entry in fs::read_dir(&path)? {
+            contents.push(&path, &entry?.file_name());
+        }
 
         info!("Read directory success {:?}", &path);
-        Ok(Self { contents, path })
+        Ok(Self {
+            contents,
+            resolved: OnceLock::new(),
+            path,
+        })
+    }
+
+    /// Makes the files listed from this directory keep what gets looked up
+    /// about them, so that listing them once ahead of time on another thread
+    /// saves the real listing from having to do it again.
+    pub fn resolve_ahead(&self) {
+        self.resolved
+            .get_or_init(|| self.contents.iter().map(|_| Resolved::default()).collect());
     }
 
     /// Produce an iterator of IO results of trying to read all the files in
@@ -48,6 +108,7 @@ impl Dir {
     pub fn files<'dir, 'ig>(
         &'dir self,
         dots: DotFilter,
+        hidden: HiddenMode,
         git: Option<&'ig GitCache>,
         git_ignoring: bool,
         deref_links: bool,
@@ -55,9 +116,11 @@ impl Dir {
     ) -> Files<'dir, 'ig> {
         Files {
             inner: self.contents.iter(),
+            resolved: self.resolved.get().map(|resolved| resolved.iter()),
             dir: self,
             dotfiles: dots.shows_dotfiles(),
             dots: dots.dots(),
+            hidden,
             git,
             git_ignoring,
             deref_links,
@@ -65,22 +128,60 @@ impl Dir {
         }
     }
 
+    /// The paths of the files that were read from this directory.
+    pub(crate) fn paths(&self) -> arena::Iter<'_> {
+        self.contents.iter()
+    }
+
     /// Whether this directory contains a file with the given path.
     pub fn contains(&self, path: &Path) -> bool {
-        self.contents.iter().any(|p| p.as_path() == path)
+        self.contents.iter().any(|p| p == path)
     }
 
     /// Append a path onto the path specified by this directory.
     pub fn join(&self, child: &Path) -> PathBuf {
         self.path.join(child)
     }
+
+    /// The identity of the directory at the given path, following links, or
+    /// `None` if there isn’t one there. Windows doesn’t hand out inodes, so
+    /// there it’s always `None`.
+    #[cfg(unix)]
+    pub fn identity(path: &Path) -> Option<DirIdentity> {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = fs::metadata(path).ok()?;
+        metadata
+            .is_dir()
+            .then(|| DirIdentity(metadata.dev(), metadata.ino()))
+    }
+
+    #[cfg(windows)]
+    pub fn identity(_path: &Path) -> Option<DirIdentity> {
+        None
+    }
+}
+
+/// What tells directories apart however they were got to, following links
+/// or not: the device each one is on, and its inode there.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct DirIdentity(u64, u64);
+
+impl DirIdentity {
+    /// Whether the two directories are on the same filesystem.
+    pub fn same_device(self, other: Self) -> bool {
+        self.0 == other.0
+    }
 }
 
 /// Iterator over reading the contents of a directory as `File` objects.
 #[allow(clippy::struct_excessive_bools)]
 pub struct Files<'dir, 'ig> {
     /// The internal iterator over the paths that have been read already.
-    inner: SliceIter<'dir, PathBuf>,
+    inner: arena::Iter<'dir>,
+
+    /// What has been looked up about each of those paths, if anything.
+    resolved: Option<SliceIter<'dir, Resolved>>,
 
     /// The directory that begat those paths.
     dir: &'dir Dir,
@@ -92,6 +193,9 @@ pub struct Files<'dir, 'ig> {
     /// any files have been listed.
     dots: DotsNext,
 
+    /// Which files count as hidden, when dotfiles aren’t being included.
+    hidden: HiddenMode,
+
     git: Option<&'ig GitCache>,
 
     git_ignoring: bool,
@@ -118,15 +222,14 @@ impl<'dir, 'ig> Files<'dir, 'ig> {
     fn next_visible_file(&mut self) -> Option<Result<File<'dir>, (PathBuf, io::Error)>> {
         loop {
             if let Some(path) = self.inner.next() {
+                let resolved = self.resolved.as_mut().and_then(Iterator::next);
                 let filename = File::filename(path);
-                if !self.dotfiles && filename.starts_with('.') {
-                    continue;
-                }
 
                 // Also hide _prefix files on Windows because it's used by old applications
                 // as an alternative to dot-prefix files.
-                #[cfg(windows)]
-                if !self.dotfiles && filename.starts_with('_') {
+                let hidden_name =
+                    filename.starts_with('.') || (cfg!(windows) && filename.starts_with('_'));
+                if !self.dotfiles && self.hidden.checks_names() && hidden_name {
                     continue;
                 }
 
@@ -137,19 +240,27 @@ impl<'dir, 'ig> Files<'dir, 'ig> {
                     }
                 }
 
-                let file = File::from_args(
-                    path.clone(),
-                    self.dir,
-                    filename,
-                    self.deref_links,
-                    self.total_size,
-                )
-                .map_err(|e| (path.clone(), e));
-
-                // Windows has its own concept of hidden files, when dotfiles are
-                // hidden Windows hidden files should also be filtered out
-                #[cfg(windows)]
-                if !self.dotfiles && file.as_ref().is_ok_and(|f| f.attributes().hidden) {
+                let file = match resolved {
+                    Some(resolved) => File::from_resolved(
+                        path,
+                        self.dir,
+                        filename,
+                        resolved,
+                        self.deref_links,
+                        self.total_size,
+                    ),
+                    None => {
+                        File::from_args(path, self.dir, filename, self.deref_links, self.total_size)
+                    }
+                }
+                .map_err(|e| (path.to_path_buf(), e));
+
+                // Windows and macOS have their own concept of hidden files,
+                // which can replace or be added to dotfiles
+                if !self.dotfiles
+                    && self.hidden.checks_flag()
+                    && file.as_ref().is_ok_and(File::is_natively_hidden)
+                {
                     continue;
                 }
 
@@ -161,6 +272,47 @@ impl<'dir, 'ig> Files<'dir, 'ig> {
     }
 }
 
+/// Which entries count as hidden, and are only listed with `--all`.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum HiddenMode {
+    /// Entries with names beginning with a dot, or an underscore on Windows.
+    Dots,
+
+    /// Entries with the platform’s own hidden flag set, which is the Hidden
+    /// attribute on Windows and `UF_HIDDEN` on macOS. Other platforms don’t
+    /// have one, so this is the same as `Dots` on them.
+    Native,
+
+    /// Entries that are hidden in either way.
+    Both,
+}
+
+impl Default for HiddenMode {
+    fn default() -> Self {
+        // Windows has always hidden both kinds of file.
+        if cfg!(windows) {
+            Self::Both
+        } else {
+            Self::Dots
+        }
+    }
+}
+
+impl HiddenMode {
+    /// Whether the platform has a hidden flag for `Native` to check.
+    const HAS_FLAG: bool = cfg!(any(windows, target_os = "macos"));
+
+    /// Whether names beginning with a dot are hidden.
+    fn checks_names(self) -> bool {
+        self != Self::Native || !Self::HAS_FLAG
+    }
+
+    /// Whether files with the platform’s hidden flag set are hidden.
+    fn checks_flag(self) -> bool {
+        self != Self::Dots && Self::HAS_FLAG
+    }
+}
+
 /// The dot directories that need to be listed before actual files, if any.
 /// If these aren’t being printed, then `FilesNext` is used to skip them.
 enum DotsNext {
@@ -223,7 +375,7 @@ impl Default for DotFilter {
 
 impl DotFilter {
     /// Whether this filter should show dotfiles in a listing.
-    fn shows_dotfiles(self) -> bool {
+    pub fn shows_dotfiles(self) -> bool {
         match self {
             Self::JustFiles => false,
             Self::Dotfiles => true,
@@ -1,5 +1,9 @@
 //! What to do when encountering a directory?
 
+use std::path::{is_separator, Path};
+
+use crate::fs::{Dir, DirIdentity, File};
+
 /// The action to take when trying to list a file that turns out to be a
 /// directory.
 ///
@@ -25,6 +29,11 @@ pub enum DirAction {
     /// of having its contents queried.
     AsFile,
 
+    /// This directory should be listed as a file, unless its path ends with a
+    /// slash, in which case its contents should be listed. This lets shell
+    /// globs such as `*/` pick which directories get opened.
+    AsFileUnlessSlash,
+
     /// This directory should not be listed, and should instead be opened and
     /// *its* files listed separately. This is the default behaviour.
     List,
@@ -44,17 +53,28 @@ impl DirAction {
         }
     }
 
-    /// Whether to treat directories as regular files or not.
-    pub fn treat_dirs_as_files(self) -> bool {
+    /// Whether to treat a directory given as an argument as a regular file or
+    /// not, which can depend on how its path was written.
+    pub fn treat_arg_as_file(self, path: &Path) -> bool {
         match self {
             Self::AsFile => true,
+            Self::AsFileUnlessSlash => !has_trailing_separator(path),
             Self::Recurse(o) => o.tree,
             Self::List => false,
         }
     }
 }
 
+/// Whether a path was written with a trailing slash, such as `src/`.
+fn has_trailing_separator(path: &Path) -> bool {
+    path.to_string_lossy()
+        .chars()
+        .last()
+        .map_or(false, is_separator)
+}
+
 /// The options that determine how to recurse into a directory.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub struct RecurseOptions {
     /// Whether recursion should be done as a tree or as multiple individual
@@ -64,9 +84,33 @@ pub struct RecurseOptions {
     /// The maximum number of times that recursion should descend to, if one
     /// is specified.
     pub max_depth: Option<usize>,
+
+    /// The number of levels to recurse through before listing anything, if
+    /// one is specified.
+    pub min_depth: Option<usize>,
+
+    /// Whether to descend into the directories that links point to, as well
+    /// as the ones that are really there.
+    pub follow_links: bool,
+
+    /// Whether to stay on the filesystem that the listing started on,
+    /// rather than descending into the directories other ones are mounted
+    /// on.
+    pub one_file_system: bool,
+
+    /// Whether to draw a tree flat, putting each file’s path where its
+    /// branches would go, which is how `--recurse --flat` lists everything
+    /// beneath the directories in one view.
+    pub flat: bool,
 }
 
 impl RecurseOptions {
+    /// Whether the directories that have been descended into need to be
+    /// kept track of, to spot cycles or other filesystems with.
+    pub fn tracks_ancestors(self) -> bool {
+        self.follow_links || self.one_file_system
+    }
+
     /// Returns whether a directory of the given depth would be too deep.
     pub fn is_too_deep(self, depth: usize) -> bool {
         match self.max_depth {
@@ -74,4 +118,74 @@ impl RecurseOptions {
             Some(d) => d <= depth,
         }
     }
+
+    /// Returns whether the entries at the given depth would be too shallow
+    /// to be listed.
+    pub fn is_too_shallow(self, depth: usize) -> bool {
+        match self.min_depth {
+            None => false,
+            Some(d) => depth < d,
+        }
+    }
+
+    /// Works out whether to descend into a file, given the directories
+    /// that have been descended into to get to it. When links are being
+    /// followed, a link back to one of those is a cycle, as following it
+    /// would go round and round. When staying on one filesystem, the first
+    /// of them is the one the listing started at.
+    pub fn descent(self, file: &File<'_>, ancestors: &[DirIdentity]) -> Descent {
+        let linked = self.follow_links && file.is_link() && file.points_to_directory();
+        if !(file.is_directory() || linked) {
+            return Descent::Skip;
+        }
+
+        if !self.tracks_ancestors() {
+            return Descent::Into(None);
+        }
+
+        let elsewhere = |identity: DirIdentity| {
+            self.one_file_system
+                && ancestors
+                    .first()
+                    .is_some_and(|start| !start.same_device(identity))
+        };
+
+        match Dir::identity(&file.path) {
+            Some(identity) if ancestors.contains(&identity) => Descent::Cycle,
+            Some(identity) if elsewhere(identity) => Descent::Skip,
+            Some(identity) => Descent::Into(Some(identity)),
+            // Links whose directories can’t be told apart can’t be checked
+            // for cycles, so they don’t get followed.
+            None if file.is_directory() => Descent::Into(None),
+            None => Descent::Skip,
+        }
+    }
+}
+
+/// Whether recursion goes into a file.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Descent {
+    /// It isn’t a directory, it’s a link that isn’t being followed, or it’s
+    /// on another filesystem that isn’t being gone into.
+    Skip,
+
+    /// It’s a directory to descend into, along with what identifies it, so
+    /// the directories beneath it can be checked, when links are being
+    /// followed or the listing is staying on one filesystem.
+    Into(Option<DirIdentity>),
+
+    /// It’s a link back to a directory that’s already being descended into.
+    Cycle,
+}
+
+impl Descent {
+    /// The directories that have been descended into to get to the files
+    /// inside this one.
+    pub fn ancestors(self, ancestors: &[DirIdentity]) -> Vec<DirIdentity> {
+        let mut within = ancestors.to_vec();
+        if let Self::Into(Some(identity)) = self {
+            within.push(identity);
+        }
+        within
+    }
 }
@@ -1,4 +1,7 @@
 //! Getting the Git status of files and directories.
+//!
+//! Mercurial and Jujutsu working copies are supported too: their statuses are
+//! translated into Git’s status flags, so everything after discovery is shared.
 
 use std::env;
 use std::ffi::OsStr;
@@ -6,11 +9,56 @@ use std::ffi::OsStr;
 use std::os::unix::ffi::OsStrExt;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
 
 use log::*;
 
 use crate::fs::fields as f;
 
+mod cache;
+mod jujutsu;
+mod mercurial;
+
+/// A **version-control backend** lists the statuses of the files in one
+/// working copy. Git is queried through `git2`, and the other systems are
+/// queried by running their command-line tools.
+trait Backend: Send {
+    /// Returns the statuses of every interesting path in the working copy,
+    /// expressed as Git status flags so they can be aggregated the same way.
+    fn statuses(&self, workdir: &Path) -> Git;
+
+    /// Returns a string that changes whenever the statuses could have
+    /// changed, so they can be cached on disk, or `None` if they can’t be.
+    fn cache_key(&self) -> Option<String> {
+        None
+    }
+}
+
+impl Backend for git2::Repository {
+    fn statuses(&self, workdir: &Path) -> Git {
+        repo_to_statuses(self, workdir)
+    }
+
+    /// Git’s statuses are keyed by the commit that HEAD points to and by the
+    /// index file, which gets rewritten whenever it changes.
+    fn cache_key(&self) -> Option<String> {
+        let head = self
+            .head()
+            .ok()
+            .and_then(|h| h.target())
+            .map_or_else(|| String::from("unborn"), |oid| oid.to_string());
+
+        let index = std::fs::metadata(self.path().join("index")).ok()?;
+        let mtime = index.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(format!(
+            "{head} {}.{:09} {}",
+            mtime.as_secs(),
+            mtime.subsec_nanos(),
+            index.len()
+        ))
+    }
+}
+
 /// A **Git cache** is assembled based on the user’s input arguments.
 ///
 /// This uses vectors to avoid the overhead of hashing: it’s not worth it when the
@@ -21,6 +69,9 @@ pub struct GitCache {
 
     /// Paths that we’ve confirmed do not have Git repositories underneath them.
     misses: Vec<PathBuf>,
+
+    /// Whether statuses should be cached on disk between invocations.
+    disk_cache: bool,
 }
 
 impl GitCache {
@@ -32,9 +83,24 @@ impl GitCache {
         self.repos
             .iter()
             .find(|repo| repo.has_path(index))
-            .map(|repo| repo.search(index, prefix_lookup))
+            .map(|repo| repo.search(index, prefix_lookup, self.disk_cache))
             .unwrap_or_default()
     }
+
+    /// Queries the statuses of every repository before they’re first needed,
+    /// so it can happen while the files to look them up for are being read.
+    pub fn prefetch(&self) {
+        for repo in &self.repos {
+            repo.search(&repo.workdir, true, self.disk_cache);
+        }
+    }
+
+    /// Makes repositories keep their statuses in an on-disk cache, which only
+    /// gets invalidated when HEAD or the index changes.
+    pub fn with_disk_cache(mut self, disk_cache: bool) -> Self {
+        self.disk_cache = disk_cache;
+        self
+    }
 }
 
 use std::iter::FromIterator;
@@ -47,6 +113,7 @@ impl FromIterator<PathBuf> for GitCache {
         let mut git = Self {
             repos: Vec::with_capacity(iter.size_hint().0),
             misses: Vec::new(),
+            disk_cache: false,
         };
 
         if let Ok(path) = env::var("GIT_DIR") {
@@ -96,9 +163,10 @@ impl FromIterator<PathBuf> for GitCache {
 }
 
 /// A **Git repository** is one we’ve discovered somewhere on the filesystem.
+/// Despite the name, it can also be a Mercurial or Jujutsu working copy.
 pub struct GitRepo {
-    /// The queryable contents of the repository: either a `git2` repo, or the
-    /// cached results from when we queried it last time.
+    /// The queryable contents of the repository: either a backend to query,
+    /// or the cached results from when we queried it last time.
     contents: Mutex<GitContents>,
 
     /// The working directory of this repository.
@@ -118,7 +186,7 @@ pub struct GitRepo {
 /// A repository’s queried state.
 enum GitContents {
     /// All the interesting Git stuff goes through this.
-    Before { repo: git2::Repository },
+    Before { repo: Box<dyn Backend> },
 
     /// Temporary value used in `repo_to_statuses` so we can move the
     /// repository out of the `Before` variant.
@@ -140,7 +208,7 @@ impl GitRepo {
     /// The temporary `Processing` enum variant is used after the `git2`
     /// repository is moved out, but before the results have been moved in!
     /// See <https://stackoverflow.com/q/45985827/3484614>
-    fn search(&self, index: &Path, prefix_lookup: bool) -> f::Git {
+    fn search(&self, index: &Path, prefix_lookup: bool, disk_cache: bool) -> f::Git {
         use std::mem::replace;
 
         let mut contents = self.contents.lock().unwrap();
@@ -151,12 +219,34 @@ impl GitRepo {
 
         debug!("Querying Git repo {:?} for the first time", &self.workdir);
         let repo = replace(&mut *contents, GitContents::Processing).inner_repo();
-        let statuses = repo_to_statuses(&repo, &self.workdir);
+        let statuses = self.query(&*repo, disk_cache);
         let result = statuses.status(index, prefix_lookup);
         let _processing = replace(&mut *contents, GitContents::After { statuses });
         result
     }
 
+    /// Queries the backend for its statuses, going through the on-disk cache
+    /// if it’s enabled and the backend supports it.
+    fn query(&self, repo: &dyn Backend, disk_cache: bool) -> Git {
+        let key = if disk_cache { repo.cache_key() } else { None };
+
+        if let Some(ref key) = key {
+            if let Some(statuses) = cache::load(&self.workdir, key) {
+                debug!(
+                    "Git repo {:?} has been found in the disk cache",
+                    &self.workdir
+                );
+                return statuses;
+            }
+        }
+
+        let statuses = repo.statuses(&self.workdir);
+        if let Some(ref key) = key {
+            cache::save(&self.workdir, key, &statuses);
+        }
+        statuses
+    }
+
     /// Whether this repository has the given working directory.
     fn has_workdir(&self, path: &Path) -> bool {
         self.workdir == path
@@ -171,38 +261,75 @@ impl GitRepo {
     /// Open a Git repository. Depending on the flags, the path is either
     /// the repository's "gitdir" (or a "gitlink" to the gitdir), or the
     /// path is the start of a rootwards search for the repository.
+    ///
+    /// When searching, a Mercurial or Jujutsu working copy found at or
+    /// beneath the Git working directory takes priority over it, so nested
+    /// repositories and colocated Jujutsu repositories use the right tool.
     fn discover(path: PathBuf, flags: git2::RepositoryOpenFlags) -> Result<Self, PathBuf> {
         info!("Opening Git repository for {:?} ({:?})", path, flags);
         let unused: [&OsStr; 0] = [];
-        let repo = match git2::Repository::open_ext(&path, flags, unused) {
-            Ok(r) => r,
+        let git = match git2::Repository::open_ext(&path, flags, unused) {
+            Ok(repo) => {
+                if let Some(workdir) = repo.workdir() {
+                    let workdir = workdir.to_path_buf();
+                    let repo: Box<dyn Backend> = Box::new(repo);
+                    Some((workdir, repo))
+                } else {
+                    warn!("Repository has no workdir?");
+                    None
+                }
+            }
             Err(e) => {
                 error!("Error opening Git repository for {path:?}: {e:?}");
-                return Err(path);
+                None
             }
         };
 
-        if let Some(workdir) = repo.workdir() {
-            let workdir = workdir.to_path_buf();
-            let contents = Mutex::new(GitContents::Before { repo });
-            Ok(Self {
-                contents,
-                workdir,
-                original_path: path,
-                extra_paths: Vec::new(),
-            })
+        let other = if flags.contains(git2::RepositoryOpenFlags::NO_SEARCH) {
+            None
         } else {
-            warn!("Repository has no workdir?");
-            Err(path)
+            discover_other_vcs(&path)
+        };
+
+        let (workdir, repo) = match (git, other) {
+            (Some(git), Some(other)) if !other.0.starts_with(&git.0) => git,
+            (_, Some(other)) => other,
+            (Some(git), None) => git,
+            (None, None) => return Err(path),
+        };
+
+        Ok(Self {
+            contents: Mutex::new(GitContents::Before { repo }),
+            workdir,
+            original_path: path,
+            extra_paths: Vec::new(),
+        })
+    }
+}
+
+/// Searches rootwards from the given path for the nearest Mercurial or
+/// Jujutsu working copy, recognised by its `.hg` or `.jj` directory.
+fn discover_other_vcs(path: &Path) -> Option<(PathBuf, Box<dyn Backend>)> {
+    for dir in reorient(path).ancestors() {
+        if dir.join(".jj").is_dir() {
+            debug!("Discovered Jujutsu working copy at {:?}", dir);
+            return Some((dir.to_path_buf(), Box::new(jujutsu::Jujutsu)));
+        }
+
+        if dir.join(".hg").is_dir() {
+            debug!("Discovered Mercurial working copy at {:?}", dir);
+            return Some((dir.to_path_buf(), Box::new(mercurial::Mercurial)));
         }
     }
+
+    None
 }
 
 impl GitContents {
     /// Assumes that the repository hasn’t been queried, and extracts it
     /// (consuming the value) if it has. This is needed because the entire
     /// enum variant gets replaced when a repo is queried (see above).
-    fn inner_repo(self) -> git2::Repository {
+    fn inner_repo(self) -> Box<dyn Backend> {
         if let Self::Before { repo } = self {
             repo
         } else {
@@ -217,6 +344,23 @@ impl GitContents {
 /// passed in rather than deriving it from the `Repository` again.
 fn repo_to_statuses(repo: &git2::Repository, workdir: &Path) -> Git {
     let mut statuses = Vec::new();
+    add_statuses(repo, workdir, &mut statuses);
+    Git { statuses }
+}
+
+/// Adds a repository’s statuses to the list, followed by the statuses of any
+/// repositories nested inside it. Git doesn’t look inside nested repositories
+/// (including submodules), so their files have to be checked against their
+/// own ignore rules to match what `git status` would show from inside them.
+///
+/// The user’s global excludes file and `$GIT_DIR/info/exclude` are already
+/// taken into account by `git2`.
+fn add_statuses(
+    repo: &git2::Repository,
+    workdir: &Path,
+    statuses: &mut Vec<(PathBuf, git2::Status)>,
+) {
+    let mut nested = Vec::new();
 
     info!("Getting Git statuses for repo with workdir {:?}", workdir);
     match repo.statuses(None) {
@@ -228,6 +372,16 @@ fn repo_to_statuses(repo: &git2::Repository, workdir: &Path) -> Git {
                 // https://github.com/ogham/exa/issues/698
                 #[cfg(not(target_family = "unix"))]
                 let path = workdir.join(Path::new(e.path().unwrap()));
+
+                // An untracked nested repository gets reported as a single
+                // new directory, rather than as the files inside it.
+                if e.status() == git2::Status::WT_NEW
+                    && e.path_bytes().ends_with(b"/")
+                    && path.join(".git").exists()
+                {
+                    nested.push(path.clone());
+                }
+
                 let elem = (path, e.status());
                 statuses.push(elem);
             }
@@ -240,7 +394,25 @@ fn repo_to_statuses(repo: &git2::Repository, workdir: &Path) -> Git {
         }
     }
 
-    Git { statuses }
+    match repo.submodules() {
+        Ok(submodules) => nested.extend(submodules.iter().map(|sm| workdir.join(sm.path()))),
+        Err(e) => error!("Error looking up Git submodules: {:?}", e),
+    }
+
+    for path in nested {
+        match git2::Repository::open(&path) {
+            Ok(nested_repo) => {
+                if let Some(nested_workdir) = nested_repo.workdir() {
+                    let nested_workdir = nested_workdir.to_path_buf();
+                    add_statuses(&nested_repo, &nested_workdir, statuses);
+                }
+            }
+            Err(e) => {
+                // Submodules that haven’t been checked out can’t be opened.
+                debug!("Skipping nested repository {:?}: {:?}", path, e);
+            }
+        }
+    }
 }
 
 // The `repo.statuses` call above takes a long time. exa debug output:
@@ -317,6 +489,29 @@ impl Git {
     }
 }
 
+/// Converts the raw bytes of a path printed by a version-control tool into
+/// a path.
+#[cfg(target_family = "unix")]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(target_family = "unix"))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Converts a path into raw bytes, the inverse of `path_from_bytes`.
+#[cfg(target_family = "unix")]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(target_family = "unix"))]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().into_owned().into_bytes()
+}
+
 /// Converts a path to an absolute path based on the current directory.
 /// Paths need to be absolute for them to be compared properly, otherwise
 /// you’d ask a repo about “./README.md” but it only knows about
@@ -442,3 +637,56 @@ impl f::SubdirGitRepo {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn statuses(statuses: &[(&str, git2::Status)]) -> Git {
+        let statuses = statuses
+            .iter()
+            .map(|&(path, status)| (PathBuf::from(path), status))
+            .collect();
+        Git { statuses }
+    }
+
+    #[test]
+    fn directory_rolls_up_deep_changes() {
+        let git = statuses(&[("/repo/a/b/c/d/file", git2::Status::WT_MODIFIED)]);
+        let status = git.status(Path::new("/repo/a"), true);
+        assert_eq!(status.unstaged, f::GitStatus::Modified);
+        assert_eq!(status.staged, f::GitStatus::NotModified);
+    }
+
+    #[test]
+    fn directory_rolls_up_staged_and_unstaged() {
+        let git = statuses(&[
+            ("/repo/a/b/staged", git2::Status::INDEX_NEW),
+            ("/repo/a/c/d/unstaged", git2::Status::WT_DELETED),
+        ]);
+        let status = git.status(Path::new("/repo/a"), true);
+        assert_eq!(status.staged, f::GitStatus::New);
+        assert_eq!(status.unstaged, f::GitStatus::Deleted);
+    }
+
+    #[test]
+    fn sibling_changes_do_not_roll_up() {
+        let git = statuses(&[("/repo/ab/file", git2::Status::WT_MODIFIED)]);
+        let status = git.status(Path::new("/repo/a"), true);
+        assert_eq!(status.unstaged, f::GitStatus::NotModified);
+    }
+
+    #[test]
+    fn files_do_not_roll_up() {
+        let git = statuses(&[("/repo/a/file", git2::Status::WT_MODIFIED)]);
+        let status = git.status(Path::new("/repo/a"), false);
+        assert_eq!(status.unstaged, f::GitStatus::NotModified);
+    }
+
+    #[test]
+    fn ignored_parent_applies_to_children() {
+        let git = statuses(&[("/repo/target", git2::Status::IGNORED)]);
+        let status = git.status(Path::new("/repo/target/debug/deep"), true);
+        assert_eq!(status.unstaged, f::GitStatus::Ignored);
+    }
+}
@@ -0,0 +1,159 @@
+//! Caching Git statuses on disk, so repeated invocations in the same large
+//! repository don’t have to scan the entire working tree every time.
+//!
+//! Each working directory gets its own cache file, which starts with the key
+//! it was written under. If the key no longer matches, the file is ignored
+//! and gets overwritten with fresh statuses.
+
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use log::*;
+
+use super::{path_from_bytes, path_to_bytes, Git};
+
+/// Written at the top of every cache file, so the format can be changed.
+const MAGIC: &[u8] = b"eza-git-cache 1\n";
+
+/// Loads the statuses cached for the working directory, as long as they
+/// were saved under the same key.
+pub(super) fn load(workdir: &Path, key: &str) -> Option<Git> {
+    let path = cache_file(workdir)?;
+    let contents = fs::read(&path).ok()?;
+    let statuses = decode(&contents, workdir, key);
+    if statuses.is_none() {
+        debug!("Disk cache {:?} is stale", path);
+    }
+    statuses
+}
+
+/// Saves the statuses for the working directory under the given key. Errors
+/// are only logged, as the cache is just an optimisation.
+pub(super) fn save(workdir: &Path, key: &str, statuses: &Git) {
+    let Some(path) = cache_file(workdir) else {
+        return;
+    };
+
+    // Write to a temporary file first, so a concurrent invocation never
+    // sees half a cache file.
+    let temp = path.with_extension("tmp");
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(&temp, encode(workdir, key, statuses)))
+        .and_then(|()| fs::rename(&temp, &path));
+
+    if let Err(e) = result {
+        warn!("Error saving Git statuses to {:?}: {:?}", path, e);
+    }
+}
+
+/// The cache file for a working directory, in `$XDG_CACHE_HOME/eza/git`,
+/// falling back to `~/.cache/eza/git`.
+fn cache_file(workdir: &Path) -> Option<PathBuf> {
+    let dir = env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+
+    let mut hasher = DefaultHasher::new();
+    workdir.hash(&mut hasher);
+    Some(
+        dir.join("eza")
+            .join("git")
+            .join(format!("{:016x}", hasher.finish())),
+    )
+}
+
+/// Serialises the statuses: the magic line, the key on its own line, then
+/// the working directory and each status as NUL-terminated records.
+fn encode(workdir: &Path, key: &str, statuses: &Git) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(key.as_bytes());
+    bytes.push(b'\n');
+    bytes.extend(path_to_bytes(workdir));
+    bytes.push(b'\0');
+
+    for (path, status) in &statuses.statuses {
+        bytes.extend_from_slice(status.bits().to_string().as_bytes());
+        bytes.push(b'\t');
+        bytes.extend(path_to_bytes(path));
+        bytes.push(b'\0');
+    }
+
+    bytes
+}
+
+/// Deserialises the statuses, returning `None` if they were written for a
+/// different key or working directory, or can’t be read at all. (Different
+/// working directories could end up with the same file name.)
+fn decode(bytes: &[u8], workdir: &Path, key: &str) -> Option<Git> {
+    let bytes = bytes.strip_prefix(MAGIC)?;
+    let bytes = bytes.strip_prefix(key.as_bytes())?.strip_prefix(b"\n")?;
+
+    let mut records = bytes.split(|&b| b == b'\0');
+    if records.next()? != path_to_bytes(workdir) {
+        return None;
+    }
+
+    let mut statuses = Vec::new();
+    for record in records.filter(|r| !r.is_empty()) {
+        let tab = record.iter().position(|&b| b == b'\t')?;
+        let bits = std::str::from_utf8(&record[..tab]).ok()?.parse().ok()?;
+        let status = git2::Status::from_bits(bits)?;
+        statuses.push((path_from_bytes(&record[tab + 1..]), status));
+    }
+
+    Some(Git { statuses })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn example() -> Git {
+        Git {
+            statuses: vec![
+                (
+                    PathBuf::from("/repo/src/main.rs"),
+                    git2::Status::WT_MODIFIED,
+                ),
+                (PathBuf::from("/repo/with\ttab"), git2::Status::INDEX_NEW),
+                (PathBuf::from("/repo/.git"), git2::Status::IGNORED),
+            ],
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes = encode(Path::new("/repo"), "key", &example());
+        let decoded = decode(&bytes, Path::new("/repo"), "key").unwrap();
+        assert_eq!(decoded.statuses, example().statuses);
+    }
+
+    #[test]
+    fn stale_key() {
+        let bytes = encode(Path::new("/repo"), "old", &example());
+        assert!(decode(&bytes, Path::new("/repo"), "new").is_none());
+    }
+
+    #[test]
+    fn key_prefix() {
+        let bytes = encode(Path::new("/repo"), "key", &example());
+        assert!(decode(&bytes, Path::new("/repo"), "ke").is_none());
+    }
+
+    #[test]
+    fn different_workdir() {
+        let bytes = encode(Path::new("/repo"), "key", &example());
+        assert!(decode(&bytes, Path::new("/other"), "key").is_none());
+    }
+
+    #[test]
+    fn garbage() {
+        assert!(decode(b"not a cache file", Path::new("/repo"), "key").is_none());
+    }
+}
@@ -0,0 +1,130 @@
+//! Getting the status of files in a Jujutsu working copy.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use log::*;
+
+use super::{path_from_bytes, Backend, Git};
+
+/// Jujutsu working copies are queried by running `jj diff --summary`, which
+/// lists the changes made in the working-copy commit.
+pub struct Jujutsu;
+
+impl Backend for Jujutsu {
+    fn statuses(&self, workdir: &Path) -> Git {
+        info!("Getting Jujutsu statuses for working copy {:?}", workdir);
+
+        // Paths are printed relative to the current directory, so running
+        // from the root makes them relative to it.
+        let output = Command::new("jj")
+            .args(["diff", "--summary", "--color=never", "--no-pager"])
+            .current_dir(workdir)
+            .output();
+
+        let mut statuses = match output {
+            Ok(output) if output.status.success() => parse_summary(&output.stdout)
+                .into_iter()
+                .map(|(path, status)| (workdir.join(path), status))
+                .collect(),
+            Ok(output) => {
+                error!("Error looking up Jujutsu statuses: {:?}", output.status);
+                Vec::new()
+            }
+            Err(e) => {
+                error!("Error running jj: {:?}", e);
+                Vec::new()
+            }
+        };
+
+        // A colocated repository has a `.git` directory next to the `.jj` one.
+        statuses.push((workdir.join(".jj"), git2::Status::IGNORED));
+        statuses.push((workdir.join(".git"), git2::Status::IGNORED));
+        Git { statuses }
+    }
+}
+
+/// Parses the output of `jj diff --summary`, where each line is a status
+/// character, a space, and the path. Jujutsu has no staging area, so every
+/// status is reported as a working tree status.
+fn parse_summary(output: &[u8]) -> Vec<(PathBuf, git2::Status)> {
+    output
+        .split(|&b| b == b'\n')
+        .filter_map(|line| {
+            let (&code, rest) = line.split_first()?;
+            let path = rest.strip_prefix(b" ")?;
+
+            #[rustfmt::skip]
+            let status = match code {
+                b'M'        => git2::Status::WT_MODIFIED,
+                b'A' | b'C' => git2::Status::WT_NEW,
+                b'D'        => git2::Status::WT_DELETED,
+                b'R'        => git2::Status::WT_RENAMED,
+                _           => return None,
+            };
+
+            Some((path_from_bytes(&renamed_to(path)), status))
+        })
+        .collect()
+}
+
+/// Renames and copies are printed as `dir/{old => new}` (or `{old => new}`
+/// when everything changed), so this works out the path of the new file.
+fn renamed_to(path: &[u8]) -> Vec<u8> {
+    let find = |needle: &[u8]| path.windows(needle.len()).position(|w| w == needle);
+
+    let (Some(open), Some(arrow), Some(close)) = (find(b"{"), find(b" => "), find(b"}")) else {
+        return path.to_vec();
+    };
+
+    if !(open < arrow && arrow < close) {
+        return path.to_vec();
+    }
+
+    let mut new = path[..open].to_vec();
+    new.extend_from_slice(&path[arrow + 4..close]);
+    new.extend_from_slice(&path[close + 1..]);
+    new
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn statuses() {
+        let output = b"M src/main.rs\nA new.rs\nD gone.rs\n";
+        assert_eq!(
+            parse_summary(output),
+            vec![
+                (PathBuf::from("src/main.rs"), git2::Status::WT_MODIFIED),
+                (PathBuf::from("new.rs"), git2::Status::WT_NEW),
+                (PathBuf::from("gone.rs"), git2::Status::WT_DELETED),
+            ]
+        );
+    }
+
+    #[test]
+    fn renamed_in_directory() {
+        assert_eq!(
+            parse_summary(b"R src/{old.rs => new.rs}\n"),
+            vec![(PathBuf::from("src/new.rs"), git2::Status::WT_RENAMED)]
+        );
+    }
+
+    #[test]
+    fn renamed_everything() {
+        assert_eq!(
+            parse_summary(b"R {a/old.rs => b/new.rs}\n"),
+            vec![(PathBuf::from("b/new.rs"), git2::Status::WT_RENAMED)]
+        );
+    }
+
+    #[test]
+    fn braces_in_names() {
+        assert_eq!(
+            parse_summary(b"M {weird}.txt\n"),
+            vec![(PathBuf::from("{weird}.txt"), git2::Status::WT_MODIFIED)]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Getting the status of files in a Mercurial working copy.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use log::*;
+
+use super::{path_from_bytes, Backend, Git};
+
+/// Mercurial working copies are queried by running `hg status`.
+pub struct Mercurial;
+
+impl Backend for Mercurial {
+    fn statuses(&self, workdir: &Path) -> Git {
+        info!("Getting Mercurial statuses for working copy {:?}", workdir);
+
+        // HGPLAIN makes the output immune to the user’s configuration, and
+        // running from the root makes the paths relative to it.
+        let output = Command::new("hg")
+            .args(["status", "--modified", "--added", "--removed"])
+            .args(["--deleted", "--unknown", "--ignored", "--print0"])
+            .env("HGPLAIN", "1")
+            .current_dir(workdir)
+            .output();
+
+        let mut statuses = match output {
+            Ok(output) if output.status.success() => parse_status(&output.stdout)
+                .into_iter()
+                .map(|(path, status)| (workdir.join(path), status))
+                .collect(),
+            Ok(output) => {
+                error!("Error looking up Mercurial statuses: {:?}", output.status);
+                Vec::new()
+            }
+            Err(e) => {
+                error!("Error running hg: {:?}", e);
+                Vec::new()
+            }
+        };
+
+        statuses.push((workdir.join(".hg"), git2::Status::IGNORED));
+        Git { statuses }
+    }
+}
+
+/// Parses the NUL-separated output of `hg status --print0`, where each entry
+/// is a status character, a space, and the path. Mercurial has no staging
+/// area, so every status is reported as a working tree status.
+fn parse_status(output: &[u8]) -> Vec<(PathBuf, git2::Status)> {
+    output
+        .split(|&b| b == b'\0')
+        .filter_map(|entry| {
+            let (&code, rest) = entry.split_first()?;
+            let path = rest.strip_prefix(b" ")?;
+
+            #[rustfmt::skip]
+            let status = match code {
+                b'M'        => git2::Status::WT_MODIFIED,
+                b'A' | b'?' => git2::Status::WT_NEW,
+                b'R' | b'!' => git2::Status::WT_DELETED,
+                b'I'        => git2::Status::IGNORED,
+                _           => return None,
+            };
+
+            Some((path_from_bytes(path), status))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn statuses() {
+        let output = b"M src/main.rs\0A new.rs\0! gone.rs\0? stray\0I target\0";
+        assert_eq!(
+            parse_status(output),
+            vec![
+                (PathBuf::from("src/main.rs"), git2::Status::WT_MODIFIED),
+                (PathBuf::from("new.rs"), git2::Status::WT_NEW),
+                (PathBuf::from("gone.rs"), git2::Status::WT_DELETED),
+                (PathBuf::from("stray"), git2::Status::WT_NEW),
+                (PathBuf::from("target"), git2::Status::IGNORED),
+            ]
+        );
+    }
+
+    #[test]
+    fn spaces_in_paths() {
+        let output = b"M a file with spaces\0";
+        assert_eq!(
+            parse_status(output),
+            vec![(
+                PathBuf::from("a file with spaces"),
+                git2::Status::WT_MODIFIED
+            )]
+        );
+    }
+
+    #[test]
+    fn nothing() {
+        assert_eq!(parse_status(b""), vec![]);
+    }
+}
@@ -29,6 +29,12 @@ pub mod git {
         pub fn get(&self, _index: &Path, _prefix_lookup: bool) -> f::Git {
             unreachable!();
         }
+
+        pub fn prefetch(&self) {}
+
+        pub fn with_disk_cache(self, _disk_cache: bool) -> Self {
+            self
+        }
     }
 
     impl f::SubdirGitRepo {
@@ -1,14 +1,19 @@
 //! Files, and methods and fields to access their metadata.
 
+use std::borrow::Cow;
+#[cfg(unix)]
+use std::cell::Cell;
 #[cfg(unix)]
 use std::collections::HashMap;
 use std::io;
 #[cfg(unix)]
-use std::os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt};
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
 #[cfg(windows)]
 use std::os::windows::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 #[cfg(unix)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(unix)]
 use std::sync::Mutex;
 use std::sync::OnceLock;
 
@@ -18,15 +23,23 @@ use log::*;
 #[cfg(unix)]
 use once_cell::sync::Lazy;
 
-use crate::fs::dir::Dir;
+use crate::fs::dir::{Dir, Resolved};
 use crate::fs::feature::xattr;
 use crate::fs::feature::xattr::{Attribute, FileAttributes};
 use crate::fs::fields as f;
+use crate::fs::metadata::{self, Metadata};
 use crate::fs::recursive_size::RecursiveSize;
 
 use super::mounts::all_mounts;
 use super::mounts::MountedFs;
 
+#[cfg(unix)]
+thread_local! {
+    /// Whether this thread is one of the ones walking the subdirectories of
+    /// a directory, so it shouldn’t start any more threads of its own.
+    static WALKING: Cell<bool> = Cell::new(false);
+}
+
 // Maps (device_id, inode) => (size_in_bytes, size_in_blocks)
 // Mutex::new is const but HashMap::new is not const requiring us to use lazy
 // initialization.
@@ -36,6 +49,20 @@ use super::mounts::MountedFs;
 static DIRECTORY_SIZE_CACHE: Lazy<Mutex<HashMap<(u64, u64), (u64, u64)>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+/// How many threads to walk the subdirectories of a directory with when
+/// working out its total size, which is the count from `--threads`.
+#[cfg(unix)]
+static WALK_THREADS: AtomicUsize = AtomicUsize::new(1);
+
+/// Sets how many threads to work out the total sizes of directories with.
+/// This has to be done before any files get listed.
+pub fn set_walk_threads(threads: u32) {
+    #[cfg(unix)]
+    WALK_THREADS.store(threads as usize, Ordering::Relaxed);
+    #[cfg(windows)]
+    let _ = threads;
+}
+
 /// A **File** is a wrapper around one of Rust’s `PathBuf` values, along with
 /// associated data about the file.
 ///
@@ -49,7 +76,10 @@ pub struct File<'dir> {
     /// This is used to compare against certain filenames (such as checking if
     /// it’s “Makefile” or something) and to highlight only the filename in
     /// colour when displaying the path.
-    pub name: String,
+    ///
+    /// Like the path, this borrows from the directory it was read from when
+    /// it can, rather than being copied out of it.
+    pub name: Cow<'dir, str>,
 
     /// The file’s name’s extension, if present, extracted from the name.
     ///
@@ -62,14 +92,17 @@ pub struct File<'dir> {
     /// around, as certain operations involve looking up the file’s absolute
     /// location (such as searching for compiled files) or using its original
     /// path (following a symlink).
-    pub path: PathBuf,
+    ///
+    /// Files read from a directory borrow their paths from it, so listing a
+    /// directory doesn’t allocate a path for each of its entries.
+    pub path: Cow<'dir, Path>,
 
     /// A cached `metadata` (`stat`) call for this file.
     ///
     /// This too is queried multiple times, and is *not* cached by the OS, as
     /// it could easily change between invocations — but exa is so short-lived
     /// it’s better to just cache it.
-    pub metadata: std::fs::Metadata,
+    pub metadata: Metadata,
 
     /// A reference to the directory that contains this file, if any.
     ///
@@ -101,28 +134,79 @@ pub struct File<'dir> {
     /// The extended attributes of this file.
     extended_attributes: OnceLock<Vec<Attribute>>,
 
+    /// What had been looked up about this file before it was created, if it
+    /// was read from a directory that resolved its files ahead of time. Its
+    /// extended attributes get kept there, rather than in this file.
+    resolved: Option<&'dir Resolved>,
+
     /// The absolute value of this path, used to look up mount points.
     absolute_path: OnceLock<Option<PathBuf>>,
 }
 
 impl<'dir> File<'dir> {
-    pub fn from_args<PD, FN>(
-        path: PathBuf,
+    pub fn from_args<P, PD, FN>(
+        path: P,
         parent_dir: PD,
         filename: FN,
         deref_links: bool,
         total_size: bool,
     ) -> io::Result<File<'dir>>
     where
+        P: Into<Cow<'dir, Path>>,
         PD: Into<Option<&'dir Dir>>,
-        FN: Into<Option<String>>,
+        FN: Into<Option<Cow<'dir, str>>>,
     {
-        let parent_dir = parent_dir.into();
-        let name = filename.into().unwrap_or_else(|| File::filename(&path));
-        let ext = File::ext(&path);
+        let path = path.into();
+        let name = match filename.into() {
+            Some(name) => name,
+            None => Cow::Owned(File::filename(&path).into_owned()),
+        };
 
         debug!("Statting file {:?}", &path);
-        let metadata = std::fs::symlink_metadata(&path)?;
+        let metadata = metadata::symlink_metadata(&path)?;
+        Ok(File::with_metadata(
+            path,
+            parent_dir.into(),
+            name,
+            metadata,
+            None,
+            deref_links,
+            total_size,
+        ))
+    }
+
+    /// Creates one of a directory’s files from what has been looked up about
+    /// it already, only statting it now if it hasn’t been yet.
+    pub(crate) fn from_resolved(
+        path: &'dir Path,
+        parent_dir: &'dir Dir,
+        name: Cow<'dir, str>,
+        resolved: &'dir Resolved,
+        deref_links: bool,
+        total_size: bool,
+    ) -> io::Result<File<'dir>> {
+        let metadata = resolved.metadata(path)?;
+        Ok(File::with_metadata(
+            Cow::Borrowed(path),
+            Some(parent_dir),
+            name,
+            metadata,
+            Some(resolved),
+            deref_links,
+            total_size,
+        ))
+    }
+
+    fn with_metadata(
+        path: Cow<'dir, Path>,
+        parent_dir: Option<&'dir Dir>,
+        name: Cow<'dir, str>,
+        metadata: Metadata,
+        resolved: Option<&'dir Resolved>,
+        deref_links: bool,
+        total_size: bool,
+    ) -> File<'dir> {
+        let ext = File::ext(&path);
         let is_all_all = false;
         let extended_attributes = OnceLock::new();
         let absolute_path = OnceLock::new();
@@ -142,6 +226,7 @@ impl<'dir> File<'dir> {
             deref_links,
             recursive_size,
             extended_attributes,
+            resolved,
             absolute_path,
         };
 
@@ -149,11 +234,28 @@ impl<'dir> File<'dir> {
             file.recursive_size = file.recursive_directory_size();
         }
 
-        Ok(file)
+        file
+    }
+
+    /// Replaces a symlink’s metadata with that of the file it points to, so
+    /// it gets displayed (and listed, if it’s a directory) as the target
+    /// itself, which is how `ls -H` treats its arguments. Broken symlinks are
+    /// left as they are.
+    pub fn follow_symlink(mut self, total_size: bool) -> Self {
+        if self.is_link() {
+            if let Ok(metadata) = metadata::metadata(&self.path) {
+                self.metadata = metadata;
+                if total_size {
+                    self.recursive_size = self.recursive_directory_size();
+                }
+            }
+        }
+
+        self
     }
 
     fn new_aa(
-        path: PathBuf,
+        path: Cow<'dir, Path>,
         parent_dir: &'dir Dir,
         name: &'static str,
         total_size: bool,
@@ -161,7 +263,7 @@ impl<'dir> File<'dir> {
         let ext = File::ext(&path);
 
         debug!("Statting file {:?}", &path);
-        let metadata = std::fs::symlink_metadata(&path)?;
+        let metadata = metadata::symlink_metadata(&path)?;
         let is_all_all = true;
         let parent_dir = Some(parent_dir);
         let extended_attributes = OnceLock::new();
@@ -173,7 +275,7 @@ impl<'dir> File<'dir> {
         };
 
         let mut file = File {
-            name: name.into(),
+            name: Cow::Borrowed(name),
             ext,
             path,
             metadata,
@@ -181,6 +283,7 @@ impl<'dir> File<'dir> {
             is_all_all,
             deref_links: false,
             extended_attributes,
+            resolved: None,
             absolute_path,
             recursive_size,
         };
@@ -193,7 +296,7 @@ impl<'dir> File<'dir> {
     }
 
     pub fn new_aa_current(parent_dir: &'dir Dir, total_size: bool) -> io::Result<File<'dir>> {
-        File::new_aa(parent_dir.path.clone(), parent_dir, ".", total_size)
+        File::new_aa(Cow::Borrowed(&parent_dir.path), parent_dir, ".", total_size)
     }
 
     pub fn new_aa_parent(
@@ -201,19 +304,19 @@ impl<'dir> File<'dir> {
         parent_dir: &'dir Dir,
         total_size: bool,
     ) -> io::Result<File<'dir>> {
-        File::new_aa(path, parent_dir, "..", total_size)
+        File::new_aa(Cow::Owned(path), parent_dir, "..", total_size)
     }
 
     /// A file’s name is derived from its string. This needs to handle directories
     /// such as `/` or `..`, which have no `file_name` component. So instead, just
     /// use the last component as the name.
-    pub fn filename(path: &Path) -> String {
+    pub fn filename(path: &Path) -> Cow<'_, str> {
         if let Some(back) = path.components().next_back() {
-            back.as_os_str().to_string_lossy().to_string()
+            back.as_os_str().to_string_lossy()
         } else {
             // use the path as fallback
             error!("Path {:?} has no last component", path);
-            path.display().to_string()
+            Cow::Owned(path.display().to_string())
         }
     }
 
@@ -252,8 +355,10 @@ impl<'dir> File<'dir> {
 
     /// Get the extended attributes of a file path on demand.
     pub fn extended_attributes(&self) -> &Vec<Attribute> {
-        self.extended_attributes
-            .get_or_init(|| File::gather_extended_attributes(&self.path))
+        let cell = self
+            .resolved
+            .map_or(&self.extended_attributes, |resolved| &resolved.xattrs);
+        cell.get_or_init(|| File::gather_extended_attributes(&self.path))
     }
 
     /// Whether this file is a directory on the filesystem.
@@ -285,7 +390,7 @@ impl<'dir> File<'dir> {
     /// if a `File` is a directory or not! For that, just use `is_directory()`.
     pub fn to_dir(&self) -> io::Result<Dir> {
         trace!("to_dir: reading dir");
-        Dir::read_dir(self.path.clone())
+        Dir::read_dir(self.path.to_path_buf())
     }
 
     /// Whether this file is a regular file on the filesystem — that is, not a
@@ -300,7 +405,7 @@ impl<'dir> File<'dir> {
     #[cfg(unix)]
     pub fn is_executable_file(&self) -> bool {
         let bit = modes::USER_EXECUTE;
-        self.is_file() && (self.metadata.permissions().mode() & bit) == bit
+        self.is_file() && (self.metadata.mode() & bit) == bit
     }
 
     /// Whether this file is a symlink on the filesystem.
@@ -339,18 +444,22 @@ impl<'dir> File<'dir> {
             .as_ref()
     }
 
-    /// Whether this file is a mount point
-    pub fn is_mount_point(&self) -> bool {
-        cfg!(any(target_os = "linux", target_os = "macos"))
-            && self.is_directory()
-            && self
-                .absolute_path()
-                .is_some_and(|p| all_mounts().contains_key(p))
+    /// Whether this file is on a virtual filesystem, such as procfs or
+    /// sysfs, where files have no real size and aren’t worth reading.
+    #[cfg(target_os = "linux")]
+    pub fn is_virtual(&self) -> bool {
+        super::mounts::is_virtual_device(self.metadata.dev())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn is_virtual(&self) -> bool {
+        false
     }
 
-    /// The filesystem device and type for a mount point
+    /// The filesystem device and type for a mount point: a directory that’s
+    /// in the list of mounted filesystems.
     pub fn mount_point_info(&self) -> Option<&MountedFs> {
-        if cfg!(any(target_os = "linux", target_os = "macos")) {
+        if cfg!(any(target_os = "linux", target_os = "macos")) && self.is_directory() {
             return self.absolute_path().and_then(|p| all_mounts().get(p));
         }
         None
@@ -396,26 +505,28 @@ impl<'dir> File<'dir> {
 
         // Use plain `metadata` instead of `symlink_metadata` - we *want* to
         // follow links.
-        match std::fs::metadata(&absolute_path) {
+        match metadata::metadata(&absolute_path) {
             Ok(metadata) => {
                 let ext = File::ext(&path);
-                let name = File::filename(&path);
+                let name = Cow::Owned(File::filename(&path).into_owned());
                 let extended_attributes = OnceLock::new();
                 let absolute_path_cell = OnceLock::from(Some(absolute_path));
                 let file = File {
                     parent_dir: None,
-                    path,
+                    path: Cow::Owned(path),
                     ext,
                     metadata,
                     name,
                     is_all_all: false,
                     deref_links: self.deref_links,
                     extended_attributes,
+                    resolved: None,
                     absolute_path: absolute_path_cell,
                     recursive_size: RecursiveSize::None,
                 };
                 FileTarget::Ok(Box::new(file))
             }
+            Err(_) if self.is_virtual() => FileTarget::Virtual(path),
             Err(e) => {
                 error!("Error following link {:?}: {:#?}", &path, e);
                 FileTarget::Broken(path)
@@ -550,10 +661,10 @@ impl<'dir> File<'dir> {
                 major: unsafe { libc::major(device_id.try_into().unwrap()) } as u32,
                 minor: unsafe { libc::minor(device_id.try_into().unwrap()) } as u32,
             })
-        } else if self.is_file() {
+        } else if self.is_file() && !self.is_virtual() {
             f::Size::Some(self.metadata.len())
         } else {
-            // symlink
+            // symlink, or a file on a virtual filesystem
             f::Size::None
         }
     }
@@ -571,24 +682,54 @@ impl<'dir> File<'dir> {
         }
     }
 
+    /// The space this file takes up on disk, in the same form as its size.
+    /// This can be less than the size for sparse or compressed files, and
+    /// more for files that don’t fill their last block.
+    #[cfg(unix)]
+    pub fn disk_usage(&self) -> f::Size {
+        match (self.size(), self.blocksize()) {
+            (f::Size::Some(_), f::Blocksize::Some(bytes)) => f::Size::Some(bytes),
+            (size, _) => size,
+        }
+    }
+
+    /// Windows doesn’t say how many blocks a file uses, so this is always
+    /// the same as its size.
+    #[cfg(windows)]
+    pub fn disk_usage(&self) -> f::Size {
+        self.size()
+    }
+
     /// Calculate the total directory size recursively.  If not a directory `None`
     /// will be returned.  The directory size is cached for recursive directory
     /// listing.
     #[cfg(unix)]
     fn recursive_directory_size(&self) -> RecursiveSize {
-        if self.is_directory() {
+        if self.is_directory() && self.is_virtual() {
+            // Don’t walk all of /proc or /sys, which can block too.
+            RecursiveSize::Unknown
+        } else if self.is_directory() {
             let key = (self.metadata.dev(), self.metadata.ino());
             if let Some(size) = DIRECTORY_SIZE_CACHE.lock().unwrap().get(&key) {
                 return RecursiveSize::Some(size.0, size.1);
             }
-            Dir::read_dir(self.path.clone()).map_or(RecursiveSize::Unknown, |dir| {
+            Dir::read_dir(self.path.to_path_buf()).map_or(RecursiveSize::Unknown, |dir| {
+                let files = dir
+                    .files(
+                        super::DotFilter::Dotfiles,
+                        super::HiddenMode::Dots,
+                        None,
+                        false,
+                        false,
+                        false,
+                    )
+                    .flatten()
+                    .collect::<Vec<_>>();
+
                 let mut size = 0;
                 let mut blocks = 0;
-                for file in dir
-                    .files(super::DotFilter::Dotfiles, None, false, false, true)
-                    .flatten()
-                {
-                    match file.recursive_directory_size() {
+                for (file, recursive_size) in files.iter().zip(recursive_sizes(&files)) {
+                    match recursive_size {
                         RecursiveSize::Some(bytes, blks) => {
                             size += bytes;
                             blocks += blks;
@@ -684,10 +825,17 @@ impl<'dir> File<'dir> {
     /// make it difficult to get any info about a dir by it's size, so this may be it.
     fn is_empty_directory(&self) -> bool {
         trace!("is_empty_directory: reading dir");
-        match Dir::read_dir(self.path.clone()) {
+        match Dir::read_dir(self.path.to_path_buf()) {
             // . & .. are skipped, if the returned iterator has .next(), it's not empty
             Ok(has_files) => has_files
-                .files(super::DotFilter::Dotfiles, None, false, false, false)
+                .files(
+                    super::DotFilter::Dotfiles,
+                    super::HiddenMode::Dots,
+                    None,
+                    false,
+                    false,
+                    false,
+                )
                 .next()
                 .is_none(),
             Err(_) => false,
@@ -824,6 +972,25 @@ impl<'dir> File<'dir> {
         })
     }
 
+    /// Whether the file has the platform’s own hidden flag set, which is the
+    /// Hidden attribute on Windows and `UF_HIDDEN` on macOS.
+    #[cfg(windows)]
+    pub fn is_natively_hidden(&self) -> bool {
+        self.attributes().hidden
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn is_natively_hidden(&self) -> bool {
+        use std::os::macos::fs::MetadataExt;
+        const UF_HIDDEN: u32 = 0x8000;
+        self.metadata.st_flags() & UF_HIDDEN != 0
+    }
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    pub fn is_natively_hidden(&self) -> bool {
+        false
+    }
+
     #[cfg(windows)]
     pub fn attributes(&self) -> f::Attributes {
         let bits = self.metadata.file_attributes();
@@ -861,6 +1028,46 @@ impl<'a> AsRef<File<'a>> for File<'a> {
     }
 }
 
+/// Calculates the recursive sizes of the files in a directory. The first
+/// directory to be walked has its subdirectories shared out between one
+/// thread per CPU, which then walk everything under them on their own.
+#[cfg(unix)]
+fn recursive_sizes(files: &[File<'_>]) -> Vec<RecursiveSize> {
+    let dirs = files.iter().filter(|f| f.is_directory()).count();
+    if dirs < 2 || WALKING.with(Cell::get) {
+        return files.iter().map(File::recursive_directory_size).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut sizes = vec![RecursiveSize::None; files.len()];
+
+    std::thread::scope(|scope| {
+        let threads = (0..WALK_THREADS.load(Ordering::Relaxed).clamp(1, dirs))
+            .map(|_| {
+                scope.spawn(|| {
+                    WALKING.with(|walking| walking.set(true));
+
+                    let mut sizes = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(file) = files.get(i) else { break };
+                        sizes.push((i, file.recursive_directory_size()));
+                    }
+                    sizes
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            for (i, size) in thread.join().unwrap_or_default() {
+                sizes[i] = size;
+            }
+        }
+    });
+
+    sizes
+}
+
 /// The result of following a symlink.
 pub enum FileTarget<'dir> {
     /// The symlink pointed at a file that exists.
@@ -870,6 +1077,11 @@ pub enum FileTarget<'dir> {
     /// where the file would be, if it existed.
     Broken(PathBuf),
 
+    /// The symlink is on a virtual filesystem, and points at something
+    /// that isn’t a file, such as the `socket:[1234]` targets of the links
+    /// in `/proc/PID/fd`. Holds the target as it was read.
+    Virtual(PathBuf),
+
     /// There was an IO error when following the link. This can happen if the
     /// file isn’t a link to begin with, but also if, say, we don’t have
     /// permission to follow it.
@@ -893,7 +1105,7 @@ impl<'dir> FileTarget<'dir> {
 mod modes {
 
     // The `libc::mode_t` type’s actual type varies, but the value returned
-    // from `metadata.permissions().mode()` is always `u32`.
+    // from `metadata.mode()` is always `u32`.
     pub type Mode = u32;
 
     pub const USER_READ: Mode = libc::S_IRUSR as Mode;
@@ -1,12 +1,21 @@
 //! Filtering and sorting the list of files before displaying them.
 
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::iter::FromIterator;
 #[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
 
-use crate::fs::DotFilter;
+use chrono::NaiveDateTime;
+use regex::Regex;
+
+use crate::fs::fields as f;
+use crate::fs::ignore_file::IgnoreRules;
 use crate::fs::File;
+use crate::fs::{DotFilter, HiddenMode};
+use crate::info::filetype::FileType;
+use crate::output::table::TimeType;
 
 /// Flags used to manage the **file filter** process
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -15,12 +24,6 @@ pub enum FileFilterFlags {
     /// files first, or files starting with Z, or the most-recently-changed
     /// ones, depending on the sort field.
     Reverse,
-
-    /// Whether to only show directories.
-    OnlyDirs,
-
-    /// Whether to only show files.
-    OnlyFiles,
 }
 
 /// The **file filter** processes a list of files before displaying them to
@@ -43,8 +46,11 @@ pub struct FileFilter {
     /// second. Some users prefer it like this.
     pub list_dirs_first: bool,
 
-    /// The metadata field to sort by.
-    pub sort_field: SortField,
+    /// The metadata fields to sort by, in order of importance.
+    pub sort_keys: Vec<SortKey>,
+
+    /// How to order files that are the same in every sort field.
+    pub tie_break: TieBreak,
 
     // Flags that the file filtering process follow
     pub flags: Vec<FileFilterFlags>,
@@ -62,35 +68,95 @@ pub struct FileFilter {
     /// [Linux History: How Dot Files Became Hidden Files](https://linux-audit.com/linux-history-how-dot-files-became-hidden-files/)
     pub dot_filter: DotFilter,
 
+    /// Which entries count as hidden, and so are left out unless the dot
+    /// filter shows dotfiles.
+    pub hidden_mode: HiddenMode,
+
     /// Glob patterns to ignore. Any file name that matches *any* of these
     /// patterns won’t be displayed in the list.
     pub ignore_patterns: IgnorePatterns,
 
+    /// Glob patterns of directories not to recurse into. They still get
+    /// displayed, but nothing inside them gets read.
+    pub prune_patterns: IgnorePatterns,
+
+    /// Regular expressions that file names have to match, or not match, to
+    /// be displayed in the list.
+    pub name_regexes: NameRegexes,
+
+    /// The range of sizes that files have to be in to be displayed.
+    pub size_range: SizeRange,
+
+    /// The range of times that files have to be in to be displayed.
+    pub time_range: TimeRange,
+
+    /// The types of entry to display, with every type being displayed if
+    /// this is empty.
+    pub entry_types: Vec<EntryType>,
+
+    /// Whether directories are being recursed into, in which case they’re
+    /// kept whatever their type, so the entries in them can be listed, but
+    /// they only get listed themselves if they’re one of the types to list.
+    pub recursing: bool,
+
+    /// The categories of file to display, or to hide.
+    pub categories: Categories,
+
+    /// Whether to display symlinks that point to nothing.
+    pub broken_links: BrokenLinks,
+
+    /// The extended attribute that files have to carry to be displayed.
+    pub has_xattr: Option<XattrMatch>,
+
     /// Whether to ignore Git-ignored patterns.
     pub git_ignore: GitIgnore,
 }
 
 impl FileFilter {
+    /// Whether recursion should stop at the given file, because its name
+    /// matches one of the patterns to prune. Directories that were given
+    /// as arguments always get gone into.
+    pub fn is_pruned(&self, file: &File<'_>) -> bool {
+        file.parent_dir.is_some() && self.prune_patterns.is_ignored(&file.name)
+    }
+
+    /// Whether a file that passed the filter gets listed, rather than only
+    /// having been kept so that the entries inside it can be, which is the
+    /// case for directories of a type that isn’t being listed, or with a
+    /// name that doesn’t match `--regex`.
+    pub fn is_listed(&self, file: &File<'_>) -> bool {
+        self.name_regexes.includes(&file.name)
+            && (self.entry_types.is_empty() || self.entry_types.iter().any(|t| t.matches(file)))
+    }
+
     /// Remove every file in the given vector that does *not* pass the
     /// filter predicate for files found inside a directory.
     pub fn filter_child_files(&self, files: &mut Vec<File<'_>>) {
-        use FileFilterFlags::{OnlyDirs, OnlyFiles};
-
-        files.retain(|f| !self.ignore_patterns.is_ignored(&f.name));
+        files.retain(|f| {
+            !self.ignore_patterns.is_ignored(&f.name) && !self.name_regexes.excludes(&f.name)
+        });
 
-        match (
-            self.flags.contains(&OnlyDirs),
-            self.flags.contains(&OnlyFiles),
-        ) {
-            (true, false) => {
-                // On pass -'-only-dirs' flag only
-                files.retain(File::is_directory);
+        if let Some(dir) = files.first().and_then(|f| f.parent_dir) {
+            let rules = IgnoreRules::for_directory(dir);
+            if !rules.is_empty() {
+                files.retain(|f| {
+                    f.name == "." || f.name == ".." || !rules.is_ignored(&f.name, f.is_directory())
+                });
             }
-            (false, true) => {
-                // On pass -'-only-files' flag only
-                files.retain(File::is_file);
-            }
-            _ => {}
+        }
+
+        files.retain(|f| {
+            f.is_directory()
+                || (self.size_range.contains(f.length())
+                    && self.time_range.contains(f)
+                    && self.categories.contains(f)
+                    && self.has_xattr.as_ref().map_or(true, |x| x.matches(f)))
+        });
+
+        files.retain(|f| (self.recursing && f.is_directory()) || self.is_listed(f));
+
+        if self.broken_links != BrokenLinks::Show {
+            files.retain(|f| self.broken_links.allows(f));
         }
     }
 
@@ -104,15 +170,38 @@ impl FileFilter {
     /// `exa -I='*.ogg' music/*` should filter out the ogg files obtained
     /// from the glob, even though the globbing is done by the shell!
     pub fn filter_argument_files(&self, files: &mut Vec<File<'_>>) {
-        files.retain(|f| !self.ignore_patterns.is_ignored(&f.name));
+        files.retain(|f| {
+            !self.ignore_patterns.is_ignored(&f.name)
+                && !self.name_regexes.excludes(&f.name)
+                && ((self.recursing && f.is_directory()) || self.name_regexes.includes(&f.name))
+        });
     }
 
     /// Sort the files in the given vector based on the sort field option.
+    ///
+    /// The sort is stable, so files that compare as equal even after the
+    /// tie-break stay in the order they were read in.
     pub fn sort_files<'a, F>(&self, files: &mut [F])
     where
         F: AsRef<File<'a>>,
     {
-        files.sort_by(|a, b| self.sort_field.compare_files(a.as_ref(), b.as_ref()));
+        if self
+            .sort_keys
+            .iter()
+            .any(|key| key.field != SortField::Unsorted)
+        {
+            // Working out a value can mean lowercasing a name or looking up a
+            // user, so each file’s values are worked out once, rather than
+            // every time it gets compared.
+            let mut values = files
+                .iter()
+                .map(|file| self.sort_values(file.as_ref()))
+                .enumerate()
+                .collect::<Vec<_>>();
+
+            values.sort_by(|(_, a), (_, b)| self.compare_values(a, b));
+            reorder(files, values.into_iter().map(|(index, _)| index).collect());
+        }
 
         if self.flags.contains(&FileFilterFlags::Reverse) {
             files.reverse();
@@ -128,6 +217,121 @@ impl FileFilter {
             });
         }
     }
+
+    /// Works out the value of each sort key for a file, followed by the
+    /// values to break ties with. Files are never tie-broken when they
+    /// aren’t being sorted at all.
+    fn sort_values(&self, file: &File<'_>) -> Vec<SortValue> {
+        let mut values = self
+            .sort_keys
+            .iter()
+            .map(|key| key.field.value(file))
+            .collect::<Vec<_>>();
+
+        match self.sort_keys.last() {
+            Some(key) if key.field != SortField::Unsorted => {
+                values.extend(self.tie_break.values(key.field.case(), file));
+            }
+            _ => {}
+        }
+
+        values
+    }
+
+    /// Compares the values of two files for each sort key in turn, falling
+    /// back to the tie-break values if they’re the same in all of them.
+    fn compare_values(&self, a: &[SortValue], b: &[SortValue]) -> Ordering {
+        let keys = self
+            .sort_keys
+            .iter()
+            .map(Some)
+            .chain(std::iter::repeat(None));
+
+        a.iter()
+            .zip(b)
+            .zip(keys)
+            .map(|((a, b), key)| match key {
+                Some(key) => key.direct(a.cmp(b)),
+                None => a.cmp(b),
+            })
+            .find(|order| order.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Moves each item into its place in the given order, which lists the
+/// index each item is currently at, by following each cycle of moves.
+fn reorder<T>(items: &mut [T], mut order: Vec<usize>) {
+    for start in 0..items.len() {
+        let mut current = start;
+        while order[current] != start {
+            let next = order[current];
+            order[current] = current;
+            items.swap(current, next);
+            current = next;
+        }
+        order[current] = current;
+    }
+}
+
+/// How to order files that are the same in every field being sorted by, so
+/// that listings come out the same every time.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum TieBreak {
+    /// Sort them by name, using the same case rules as the last sort field.
+    Name,
+
+    /// Leave them in the order the operating system listed them in.
+    None,
+
+    /// Sort them by inode.
+    #[cfg(unix)]
+    Inode,
+}
+
+impl TieBreak {
+    /// The values to compare files by when breaking ties. Names compared
+    /// case-insensitively are then compared case-sensitively, so `a` and `A`
+    /// always come out in the same order.
+    fn values(self, case: SortCase, file: &File<'_>) -> Vec<SortValue> {
+        match (self, case) {
+            (Self::Name, SortCase::ABCabc) => vec![SortValue::Name(NaturalKey::new(&file.name))],
+            (Self::Name, SortCase::AaBbCc) => vec![
+                SortValue::Name(NaturalKey::ignoring_case(&file.name)),
+                SortValue::Name(NaturalKey::new(&file.name)),
+            ],
+            (Self::None, _) => Vec::new(),
+            #[cfg(unix)]
+            (Self::Inode, _) => vec![SortValue::Number(file.metadata.ino())],
+        }
+    }
+}
+
+/// One of the fields to sort by, along with whether that field alone should
+/// be sorted in reverse.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct SortKey {
+    pub field: SortField,
+    pub reverse: bool,
+}
+
+impl SortKey {
+    fn direct(self, order: Ordering) -> Ordering {
+        if self.reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+}
+
+impl From<SortField> for SortKey {
+    fn from(field: SortField) -> Self {
+        Self {
+            field,
+            reverse: false,
+        }
+    }
 }
 
 /// User-supplied field to sort by.
@@ -146,11 +350,19 @@ pub enum SortField {
     /// The file’s size, in bytes.
     Size,
 
+    /// The number of characters in the file’s name.
+    NameLength,
+
     /// The file’s inode, which usually corresponds to the order in which
     /// files were created on the filesystem, more or less.
     #[cfg(unix)]
     FileInode,
 
+    /// The number of hard links to the file. For directories, this is two
+    /// more than the number of subdirectories they have on most filesystems.
+    #[cfg(unix)]
+    Links,
+
     /// The time the file was modified (the “mtime”).
     ///
     /// As this is stored as a Unix timestamp, rather than a local time
@@ -200,6 +412,24 @@ pub enum SortField {
     /// The file's name, however if the name of the file begins with `.`
     /// ignore the leading `.` and then sort as Name
     NameMixHidden(SortCase),
+
+    /// The category of the file, as worked out from its name, such as image,
+    /// source code, or build file. Files without a category go last.
+    Category,
+
+    /// The name of the user that owns the file, or its ID if the user can’t
+    /// be looked up.
+    #[cfg(unix)]
+    Owner,
+
+    /// The name of the group that owns the file, or its ID if the group
+    /// can’t be looked up.
+    #[cfg(unix)]
+    Group,
+
+    /// A shuffled order, worked out by hashing each file’s name along with
+    /// the seed, so the same seed always gives the same order.
+    Random(u64),
 }
 
 /// Whether a field should be sorted case-sensitively or case-insensitively.
@@ -222,60 +452,78 @@ pub enum SortCase {
 }
 
 impl SortField {
-    /// Compares two files to determine the order they should be listed in,
-    /// depending on the search field.
+    /// Works out the value of this field for a file, which files are then
+    /// sorted by.
     ///
-    /// The `natord` crate is used here to provide a more *natural* sorting
-    /// order than just sorting character-by-character. This splits filenames
-    /// into groups between letters and numbers, and then sorts those blocks
-    /// together, so `file10` will sort after `file9`, instead of before it
-    /// because of the `1`.
-    pub fn compare_files(self, a: &File<'_>, b: &File<'_>) -> Ordering {
+    /// Names are sorted in a more *natural* order than just sorting
+    /// character-by-character. This splits filenames into groups between
+    /// letters and numbers, and then sorts those blocks together, so `file10`
+    /// will sort after `file9`, instead of before it because of the `1`.
+    fn value(self, file: &File<'_>) -> SortValue {
         use self::SortCase::{ABCabc, AaBbCc};
+        use self::SortValue::*;
 
         #[rustfmt::skip]
         return match self {
-            Self::Unsorted  => Ordering::Equal,
+            Self::Unsorted  => Nothing,
 
-            Self::Name(ABCabc)  => natord::compare(&a.name, &b.name),
-            Self::Name(AaBbCc)  => natord::compare_ignore_case(&a.name, &b.name),
+            Self::Name(ABCabc)  => Name(NaturalKey::new(&file.name)),
+            Self::Name(AaBbCc)  => Name(NaturalKey::ignoring_case(&file.name)),
 
-            Self::Size          => a.length().cmp(&b.length()),
+            Self::Size          => Number(file.length()),
+            Self::NameLength    => Number(file.name.chars().count() as u64),
 
             #[cfg(unix)]
-            Self::FileInode     => a.metadata.ino().cmp(&b.metadata.ino()),
-            Self::ModifiedDate  => a.modified_time().cmp(&b.modified_time()),
-            Self::AccessedDate  => a.accessed_time().cmp(&b.accessed_time()),
-            Self::ChangedDate   => a.changed_time().cmp(&b.changed_time()),
-            Self::CreatedDate   => a.created_time().cmp(&b.created_time()),
-            Self::ModifiedAge   => b.modified_time().cmp(&a.modified_time()),  // flip b and a
-
-            Self::FileType => match a.type_char().cmp(&b.type_char()) { // todo: this recomputes
-                Ordering::Equal  => natord::compare(&a.name, &b.name),
-                order            => order,
-            },
-
-            Self::Extension(ABCabc) => match a.ext.cmp(&b.ext) {
-                Ordering::Equal  => natord::compare(&a.name, &b.name),
-                order            => order,
-            },
-
-            Self::Extension(AaBbCc) => match a.ext.cmp(&b.ext) {
-                Ordering::Equal  => natord::compare_ignore_case(&a.name, &b.name),
-                order            => order,
-            },
-
-            Self::NameMixHidden(ABCabc) => natord::compare(
-                Self::strip_dot(&a.name),
-                Self::strip_dot(&b.name)
-            ),
-            Self::NameMixHidden(AaBbCc) => natord::compare_ignore_case(
-                Self::strip_dot(&a.name),
-                Self::strip_dot(&b.name)
-            ),
+            Self::FileInode     => Number(file.metadata.ino()),
+            #[cfg(unix)]
+            Self::Links         => Number(file.metadata.nlink()),
+            Self::ModifiedDate  => Time(file.modified_time()),
+            Self::AccessedDate  => Time(file.accessed_time()),
+            Self::ChangedDate   => Time(file.changed_time()),
+            Self::CreatedDate   => Time(file.created_time()),
+            Self::ModifiedAge   => Age(Reverse(file.modified_time())),
+
+            Self::FileType      => Type(file.type_char()),
+            Self::Extension(_)  => Text(file.ext.clone()),
+
+            Self::NameMixHidden(ABCabc) => Name(NaturalKey::new(Self::strip_dot(&file.name))),
+            Self::NameMixHidden(AaBbCc) => Name(NaturalKey::ignoring_case(Self::strip_dot(&file.name))),
+
+            Self::Category      => Category(Self::category(file)),
+
+            #[cfg(unix)]
+            Self::Owner         => Name(NaturalKey::new(&owner_name(file))),
+            #[cfg(unix)]
+            Self::Group         => Name(NaturalKey::new(&group_name(file))),
+
+            Self::Random(seed)  => Number(Self::shuffle_key(seed, file)),
         };
     }
 
+    /// Which case rules to compare names with when breaking ties.
+    fn case(self) -> SortCase {
+        match self {
+            Self::Name(case) | Self::Extension(case) | Self::NameMixHidden(case) => case,
+            Self::FileType => SortCase::ABCabc,
+            _ => SortCase::AaBbCc,
+        }
+    }
+
+    /// The sort key for a file in a random order with the given seed.
+    fn shuffle_key(seed: u64, file: &File<'_>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        seed.hash(&mut hasher);
+        file.name.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// The sort key for a file’s category, which puts files without one
+    /// after all the others.
+    fn category(file: &File<'_>) -> (bool, Option<FileType>) {
+        let file_type = FileType::get_file_type(file);
+        (file_type.is_none(), file_type)
+    }
+
     fn strip_dot(n: &str) -> &str {
         match n.strip_prefix('.') {
             Some(s) => s,
@@ -284,6 +532,151 @@ impl SortField {
     }
 }
 
+/// The value of one of the fields to sort by for a file, which is worked out
+/// before sorting so it doesn’t have to be worked out for every comparison.
+///
+/// Only values for the same field ever get compared with each other.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum SortValue {
+    Nothing,
+    Name(NaturalKey),
+    Number(u64),
+    Time(Option<NaiveDateTime>),
+    Age(Reverse<Option<NaiveDateTime>>),
+    Type(f::Type),
+    Text(Option<String>),
+    Category((bool, Option<FileType>)),
+}
+
+/// A name split up so it can be sorted in natural order, in the same way as
+/// the `natord` crate does it: whitespace is skipped, and runs of digits
+/// are compared by their value, unless either starts with a zero, when
+/// they’re compared digit by digit (so `015` comes before `12`).
+#[derive(PartialEq, Eq, Debug)]
+struct NaturalKey(Vec<NaturalChunk>);
+
+#[derive(PartialEq, Eq, Debug)]
+enum NaturalChunk {
+    Char(char),
+    Digits(Box<str>),
+}
+
+impl NaturalKey {
+    fn new(name: &str) -> Self {
+        Self::from_chars(name.chars())
+    }
+
+    fn ignoring_case(name: &str) -> Self {
+        Self::from_chars(name.chars().flat_map(char::to_lowercase))
+    }
+
+    fn from_chars(chars: impl Iterator<Item = char>) -> Self {
+        let mut chunks = Vec::new();
+        let mut digits = String::new();
+
+        for c in chars {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                continue;
+            }
+
+            if !digits.is_empty() {
+                chunks.push(NaturalChunk::Digits(std::mem::take(&mut digits).into()));
+            }
+
+            if !c.is_whitespace() {
+                chunks.push(NaturalChunk::Char(c));
+            }
+        }
+
+        if !digits.is_empty() {
+            chunks.push(NaturalChunk::Digits(digits.into()));
+        }
+
+        Self(chunks)
+    }
+}
+
+impl PartialOrd for NaturalKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NaturalKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl PartialOrd for NaturalChunk {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NaturalChunk {
+    fn cmp(&self, other: &Self) -> Ordering {
+        use self::NaturalChunk::*;
+
+        match (self, other) {
+            (Char(a), Char(b)) => a.cmp(b),
+            (Char(a), Digits(b)) => a.cmp(&first_char(b)),
+            (Digits(a), Char(b)) => first_char(a).cmp(b),
+            (Digits(a), Digits(b)) if a.starts_with('0') || b.starts_with('0') => a.cmp(b),
+            (Digits(a), Digits(b)) => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+        }
+    }
+}
+
+/// The first character of a run of digits, which is never empty.
+fn first_char(digits: &str) -> char {
+    digits.chars().next().unwrap_or('0')
+}
+
+#[cfg(unix)]
+thread_local! {
+    /// Sorting looks up the owner of each file many times, and looking up a
+    /// user or group can mean reading and parsing a file every time.
+    static USERS: uzers::UsersCache = uzers::UsersCache::new();
+}
+
+/// The name of the user that owns the file, or its ID as a string. Files
+/// whose owner can’t be found at all, such as broken links that are being
+/// dereferenced, get an empty name.
+#[cfg(unix)]
+fn owner_name(file: &File<'_>) -> String {
+    use uzers::Users;
+
+    let Some(f::User(uid)) = file.user() else {
+        return String::new();
+    };
+
+    USERS.with(|users| {
+        users.get_user_by_uid(uid).map_or_else(
+            || uid.to_string(),
+            |user| user.name().to_string_lossy().into_owned(),
+        )
+    })
+}
+
+/// The name of the group that owns the file, or its ID as a string.
+#[cfg(unix)]
+fn group_name(file: &File<'_>) -> String {
+    use uzers::Groups;
+
+    let Some(f::Group(gid)) = file.group() else {
+        return String::new();
+    };
+
+    USERS.with(|users| {
+        users.get_group_by_gid(gid).map_or_else(
+            || gid.to_string(),
+            |group| group.name().to_string_lossy().into_owned(),
+        )
+    })
+}
+
 /// The **ignore patterns** are a list of globs that are tested against
 /// each filename, and if any of them match, that file isn’t displayed.
 /// This lets a user hide, say, text files by ignoring `*.txt`.
@@ -339,11 +732,274 @@ impl IgnorePatterns {
     }
 
     /// Test whether the given file should be hidden from the results.
-    fn is_ignored(&self, file: &str) -> bool {
+    pub fn is_ignored(&self, file: &str) -> bool {
         self.patterns.iter().any(|p| p.matches(file))
     }
 }
 
+/// The **size range** hides files that are smaller or larger than the given
+/// sizes, in bytes. Directories are never hidden by it, as their sizes
+/// don’t say much about what’s in them, and their contents still need to be
+/// listed when recursing.
+#[derive(PartialEq, Eq, Default, Debug, Copy, Clone)]
+pub struct SizeRange {
+    /// The smallest size to display, if there is one.
+    pub min: Option<u64>,
+
+    /// The largest size to display, if there is one.
+    pub max: Option<u64>,
+}
+
+impl SizeRange {
+    /// Whether a file of the given size should be displayed.
+    pub fn contains(self, size: u64) -> bool {
+        self.min.map_or(true, |min| size >= min) && self.max.map_or(true, |max| size <= max)
+    }
+}
+
+/// Parses a size such as `300`, `1.5M`, or `4KiB` into a number of bytes.
+/// Prefixes without an `i` are powers of 1000, and those with one are
+/// powers of 1024, and any of them can be followed by a `B`.
+pub fn parse_size(text: &str) -> Option<u64> {
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let unit = unit.to_ascii_lowercase();
+    let unit = unit.strip_suffix('b').unwrap_or(&unit);
+    let (prefix, base) = match unit.strip_suffix('i') {
+        Some(prefix) if !prefix.is_empty() => (prefix, 1024_f64),
+        Some(_) => return None,
+        None => (unit, 1000_f64),
+    };
+
+    let power = match prefix {
+        "" => 0,
+        "k" => 1,
+        "m" => 2,
+        "g" => 3,
+        "t" => 4,
+        "p" => 5,
+        _ => return None,
+    };
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    Some((number * base.powi(power)).round() as u64)
+}
+
+/// The **time range** hides files with one of their timestamps outside the
+/// given times. Like the size range, it never hides directories.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct TimeRange {
+    /// Which of the file’s timestamps to check.
+    pub field: TimeType,
+
+    /// The time files have to be newer than to be displayed, if there is one.
+    pub newer_than: Option<NaiveDateTime>,
+
+    /// The time files have to be older than to be displayed, if there is one.
+    pub older_than: Option<NaiveDateTime>,
+}
+
+impl TimeRange {
+    /// Whether the file should be displayed. Files without the timestamp
+    /// being checked are only displayed when there are no times to check.
+    pub fn contains(&self, file: &File<'_>) -> bool {
+        if self.newer_than.is_none() && self.older_than.is_none() {
+            return true;
+        }
+
+        let time = match self.field {
+            TimeType::Modified => file.modified_time(),
+            TimeType::Changed => file.changed_time(),
+            TimeType::Accessed => file.accessed_time(),
+            TimeType::Created => file.created_time(),
+        };
+
+        time.map_or(false, |time| {
+            self.newer_than.map_or(true, |newer| time > newer)
+                && self.older_than.map_or(true, |older| time < older)
+        })
+    }
+}
+
+/// The **categories** hide files based on what `FileType` they get
+/// classified as, such as images or source code. Like the size range, they
+/// never hide directories.
+#[derive(PartialEq, Eq, Default, Debug, Clone)]
+pub struct Categories {
+    /// The categories to display, with files in any category (or none)
+    /// being displayed if this is empty.
+    pub only: Vec<FileType>,
+
+    /// The categories to hide.
+    pub excluded: Vec<FileType>,
+}
+
+impl Categories {
+    /// Whether the file should be displayed. Files that aren’t in any
+    /// category are only hidden when some categories have been picked.
+    pub fn contains(&self, file: &File<'_>) -> bool {
+        if self.only.is_empty() && self.excluded.is_empty() {
+            return true;
+        }
+
+        match FileType::get_file_type(file) {
+            Some(category) => {
+                (self.only.is_empty() || self.only.contains(&category))
+                    && !self.excluded.contains(&category)
+            }
+            None => self.only.is_empty(),
+        }
+    }
+}
+
+/// Whether to display **broken links**, which point to files that don’t
+/// exist. Finding these means reading every link’s target while filtering,
+/// so it’s only done when they’re being picked out.
+#[derive(PartialEq, Eq, Default, Debug, Copy, Clone)]
+pub enum BrokenLinks {
+    /// Display broken links along with everything else.
+    #[default]
+    Show,
+
+    /// Display only broken links.
+    Only,
+
+    /// Don’t display any broken links.
+    Hide,
+}
+
+impl BrokenLinks {
+    /// Whether the file should be displayed.
+    pub fn allows(self, file: &File<'_>) -> bool {
+        let broken = file.is_link() && file.link_target().is_broken();
+
+        match self {
+            Self::Show => true,
+            Self::Only => broken,
+            Self::Hide => !broken,
+        }
+    }
+}
+
+/// An extended attribute that files have to carry to be displayed with
+/// `--has-xattr`, and the value it has to have, if one was given.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct XattrMatch {
+    /// The name of the attribute, such as `user.comment`.
+    pub name: String,
+
+    /// The value the attribute has to have, or `None` if any value will do.
+    pub value: Option<String>,
+}
+
+impl XattrMatch {
+    /// Whether the file carries the attribute.
+    pub fn matches(&self, file: &File<'_>) -> bool {
+        file.extended_attributes().iter().any(|attr| {
+            attr.name == self.name && self.value.as_ref().map_or(true, |v| *v == attr.value)
+        })
+    }
+}
+
+/// A type of entry that can be picked to be displayed with `--type`.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum EntryType {
+    /// Regular files, of any kind.
+    File,
+
+    /// Directories, but not links to them.
+    Directory,
+
+    /// Symbolic links, whatever they point to.
+    Symlink,
+
+    /// Regular files that the current user can execute.
+    #[cfg(unix)]
+    Executable,
+
+    /// Sockets.
+    #[cfg(unix)]
+    Socket,
+
+    /// Named pipes.
+    #[cfg(unix)]
+    Pipe,
+
+    /// Block devices.
+    #[cfg(unix)]
+    BlockDevice,
+
+    /// Character devices.
+    #[cfg(unix)]
+    CharDevice,
+}
+
+impl EntryType {
+    /// Whether the file is an entry of this type.
+    pub fn matches(self, file: &File<'_>) -> bool {
+        match self {
+            Self::File => file.is_file(),
+            Self::Directory => file.is_directory(),
+            Self::Symlink => file.is_link(),
+            #[cfg(unix)]
+            Self::Executable => file.is_executable_file(),
+            #[cfg(unix)]
+            Self::Socket => file.is_socket(),
+            #[cfg(unix)]
+            Self::Pipe => file.is_pipe(),
+            #[cfg(unix)]
+            Self::BlockDevice => file.is_block_device(),
+            #[cfg(unix)]
+            Self::CharDevice => file.is_char_device(),
+        }
+    }
+}
+
+/// The **name regexes** are a pair of regular expressions tested against
+/// each file name: a file is only displayed if it matches the one to
+/// include, and doesn’t match the one to exclude.
+#[derive(Default, Debug, Clone)]
+pub struct NameRegexes {
+    /// The regular expression names have to match, if there is one.
+    pub include: Option<Regex>,
+
+    /// The regular expression names must not match, if there is one.
+    pub exclude: Option<Regex>,
+}
+
+impl NameRegexes {
+    /// Whether the file with the given name should be hidden.
+    pub fn is_ignored(&self, name: &str) -> bool {
+        !self.includes(name) || self.excludes(name)
+    }
+
+    /// Whether the given name matches the regular expression to include,
+    /// or there isn’t one.
+    pub fn includes(&self, name: &str) -> bool {
+        self.include.as_ref().map_or(true, |re| re.is_match(name))
+    }
+
+    /// Whether the given name matches the regular expression to exclude.
+    pub fn excludes(&self, name: &str) -> bool {
+        self.exclude.as_ref().map_or(false, |re| re.is_match(name))
+    }
+}
+
+// Regexes can’t be compared, but the patterns they were made from can.
+impl PartialEq for NameRegexes {
+    fn eq(&self, other: &Self) -> bool {
+        let pattern = |re: &Option<Regex>| re.as_ref().map(|re| re.as_str().to_owned());
+        pattern(&self.include) == pattern(&other.include)
+            && pattern(&self.exclude) == pattern(&other.exclude)
+    }
+}
+
+impl Eq for NameRegexes {}
+
 /// Whether to ignore or display files that Git would ignore.
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum GitIgnore {
@@ -354,6 +1010,29 @@ pub enum GitIgnore {
     Off,
 }
 
+#[cfg(test)]
+mod test_size_range {
+    use super::*;
+
+    #[test]
+    fn unbounded() {
+        assert!(SizeRange::default().contains(0));
+        assert!(SizeRange::default().contains(u64::MAX));
+    }
+
+    #[test]
+    fn bounded() {
+        let range = SizeRange {
+            min: Some(10),
+            max: Some(20),
+        };
+        assert!(!range.contains(9));
+        assert!(range.contains(10));
+        assert!(range.contains(20));
+        assert!(!range.contains(21));
+    }
+}
+
 #[cfg(test)]
 mod test_ignores {
     use super::*;
@@ -389,3 +1068,61 @@ mod test_ignores {
         assert!(pats.is_ignored("test.mp3"));
     }
 }
+
+#[cfg(test)]
+mod test_natural_keys {
+    use super::*;
+
+    fn sorted(names: &[&'static str], key: fn(&str) -> NaturalKey) -> Vec<&'static str> {
+        let mut names = names.to_vec();
+        names.sort_by_key(|name| key(name));
+        names
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(
+            sorted(&["file10", "file9", "file1", "file"], NaturalKey::new),
+            vec!["file", "file1", "file9", "file10"]
+        );
+    }
+
+    #[test]
+    fn leading_zeroes() {
+        assert_eq!(
+            sorted(&["12", "015", "0150", "2"], NaturalKey::new),
+            vec!["015", "0150", "2", "12"]
+        );
+    }
+
+    #[test]
+    fn whitespace() {
+        assert_eq!(NaturalKey::new("a 1"), NaturalKey::new("a1"));
+        assert!(NaturalKey::new("1 2") < NaturalKey::new("12"));
+    }
+
+    #[test]
+    fn case() {
+        assert_eq!(
+            sorted(&["b", "a", "B", "A"], NaturalKey::new),
+            vec!["A", "B", "a", "b"]
+        );
+        assert_eq!(
+            sorted(&["b", "a", "B", "A"], NaturalKey::ignoring_case),
+            vec!["a", "A", "b", "B"]
+        );
+    }
+
+    #[test]
+    fn type_ties_keep_case() {
+        assert_eq!(SortField::FileType.case(), SortCase::ABCabc);
+        assert_eq!(SortField::Size.case(), SortCase::AaBbCc);
+    }
+
+    #[test]
+    fn reordering() {
+        let mut items = vec!['a', 'b', 'c', 'd', 'e'];
+        reorder(&mut items, vec![3, 0, 4, 1, 2]);
+        assert_eq!(items, vec!['d', 'a', 'e', 'b', 'c']);
+    }
+}
@@ -0,0 +1,305 @@
+//! Reading `.ezaignore` files, which hide entries from every listing using
+//! the same syntax as `.gitignore` files, but without needing Git.
+//!
+//! The rules that apply to a directory come from the `.ezaignore` file in it
+//! and in each of its parents, up to the root of the repository it’s in (the
+//! first parent with a `.git` entry) or the root of the filesystem. Rules
+//! from files further down take precedence over those further up.
+//!
+//! Only the directories that listings start from have their parents searched.
+//! When recursing, each directory inherits its parent’s rules, and only its
+//! own file gets read, if its entries show that it has one.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use log::*;
+use once_cell::sync::Lazy;
+
+use crate::fs::Dir;
+
+/// The name of the file that the rules are read from.
+pub const FILE_NAME: &str = ".ezaignore";
+
+/// The rules of every directory whose entries have been filtered, so the
+/// directories inside them can start from those rather than reading all
+/// their parents’ files again.
+static RULES: Lazy<Mutex<HashMap<PathBuf, Arc<IgnoreRules>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The rules for the entries of one directory.
+#[derive(Debug, Default)]
+pub struct IgnoreRules {
+    /// The rules from each file, outermost first, along with the path of the
+    /// directory being listed relative to the directory the file is in.
+    files: Vec<(String, Arc<[Rule]>)>,
+}
+
+impl IgnoreRules {
+    /// The rules that apply to the entries of the given directory, worked
+    /// out from its parent’s if those have been already.
+    pub fn for_directory(dir: &Dir) -> Arc<Self> {
+        let cached = |path: &Path| RULES.lock().unwrap().get(path).cloned();
+        if let Some(rules) = cached(&dir.path) {
+            return rules;
+        }
+
+        let inherited = dir.path.file_name().zip(dir.path.parent().and_then(cached));
+        let rules = Arc::new(match inherited {
+            Some((name, parent)) => parent.descend(&name.to_string_lossy(), dir),
+            None => Self::read(&dir.path),
+        });
+
+        RULES
+            .lock()
+            .unwrap()
+            .insert(dir.path.clone(), Arc::clone(&rules));
+        rules
+    }
+
+    /// The rules for a directory inside the one these are for, which has its
+    /// own file added, or replaces these entirely if it’s a repository.
+    fn descend(&self, name: &str, dir: &Dir) -> Self {
+        let mut files = if dir.contains(&dir.path.join(".git")) {
+            Vec::new()
+        } else {
+            (self.files.iter())
+                .map(|(prefix, rules)| {
+                    let prefix = if prefix.is_empty() {
+                        name.to_owned()
+                    } else {
+                        format!("{prefix}/{name}")
+                    };
+                    (prefix, Arc::clone(rules))
+                })
+                .collect()
+        };
+
+        let path = dir.path.join(FILE_NAME);
+        if dir.contains(&path) {
+            if let Ok(contents) = fs::read_to_string(&path) {
+                debug!("Reading ignore rules from {:?}", path);
+                files.push((String::new(), parse(&contents)));
+            }
+        }
+
+        Self { files }
+    }
+
+    /// Reads the rules that apply to the entries of the given directory from
+    /// it and each of its parents.
+    fn read(dir: &Path) -> Self {
+        let Ok(dir) = dir.canonicalize() else {
+            return Self::default();
+        };
+
+        let mut files = Vec::new();
+        for base in dir.ancestors() {
+            if let Ok(contents) = fs::read_to_string(base.join(FILE_NAME)) {
+                debug!("Reading ignore rules from {:?}", base.join(FILE_NAME));
+                let prefix = relative_path(dir.strip_prefix(base).unwrap_or(&dir));
+                files.push((prefix, parse(&contents)));
+            }
+
+            if base.join(".git").exists() {
+                break;
+            }
+        }
+
+        files.reverse();
+        Self { files }
+    }
+
+    /// Whether there aren’t any rules, so nothing would be ignored.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Whether the entry in the directory with the given name should be
+    /// hidden. As with Git, the last rule to match an entry decides whether
+    /// it’s hidden, so negated rules can bring entries back.
+    pub fn is_ignored(&self, name: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+
+        for (prefix, rules) in &self.files {
+            let path = if prefix.is_empty() {
+                name.to_owned()
+            } else {
+                format!("{prefix}/{name}")
+            };
+
+            for rule in rules.iter() {
+                if rule.matches(&path, is_dir) {
+                    ignored = !rule.negated;
+                }
+            }
+        }
+
+        ignored
+    }
+}
+
+/// One line of an ignore file.
+#[derive(Debug)]
+struct Rule {
+    /// The glob to match, relative to the directory the file is in.
+    pattern: glob::Pattern,
+
+    /// Whether the line started with `!`, bringing back entries that
+    /// earlier rules hid.
+    negated: bool,
+
+    /// Whether the line ended in `/`, so it only matches directories.
+    dir_only: bool,
+}
+
+impl Rule {
+    /// Parses one line, returning `None` for blank lines, comments, and
+    /// patterns that aren’t valid globs.
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+
+        // A pattern with a slash in is relative to the file’s directory, but
+        // one without matches a name at any depth.
+        let glob = match line.strip_prefix('/') {
+            Some(rest) => rest.to_owned(),
+            None if line.contains('/') => line.to_owned(),
+            None => format!("**/{line}"),
+        };
+
+        match glob::Pattern::new(&glob) {
+            Ok(pattern) => Some(Self {
+                pattern,
+                negated,
+                dir_only,
+            }),
+            Err(e) => {
+                warn!("Invalid ignore pattern {:?}: {}", line, e);
+                None
+            }
+        }
+    }
+
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        let options = glob::MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+
+        (is_dir || !self.dir_only) && self.pattern.matches_with(path, options)
+    }
+}
+
+/// Parses the contents of an ignore file into its rules.
+fn parse(contents: &str) -> Arc<[Rule]> {
+    contents.lines().filter_map(Rule::parse).collect()
+}
+
+/// Joins the components of a relative path with `/`, which is what the
+/// patterns use whatever the platform.
+fn relative_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rules(prefix: &str, contents: &str) -> IgnoreRules {
+        IgnoreRules {
+            files: vec![(prefix.to_owned(), parse(contents))],
+        }
+    }
+
+    #[test]
+    fn names_at_any_depth() {
+        let rules = rules("src/output", "node_modules\n*.log\n");
+        assert!(rules.is_ignored("node_modules", true));
+        assert!(rules.is_ignored("debug.log", false));
+        assert!(!rules.is_ignored("main.rs", false));
+    }
+
+    #[test]
+    fn anchored() {
+        assert!(rules("", "/target").is_ignored("target", true));
+        assert!(!rules("src", "/target").is_ignored("target", true));
+        assert!(rules("src", "src/*.bak").is_ignored("old.bak", false));
+    }
+
+    #[test]
+    fn directories_only() {
+        let rules = rules("", "build/\n");
+        assert!(rules.is_ignored("build", true));
+        assert!(!rules.is_ignored("build", false));
+    }
+
+    #[test]
+    fn negated() {
+        let rules = rules("", "*.md\n!README.md\n");
+        assert!(rules.is_ignored("NOTES.md", false));
+        assert!(!rules.is_ignored("README.md", false));
+    }
+
+    #[test]
+    fn comments_and_escapes() {
+        let rules = rules("", "# a comment\n\n\\#literal\n");
+        assert!(rules.is_ignored("#literal", false));
+        assert!(!rules.is_ignored("# a comment", false));
+    }
+
+    #[test]
+    fn inner_files_win() {
+        let rules = IgnoreRules {
+            files: vec![
+                ("a".to_owned(), parse("*.txt\n")),
+                (String::new(), parse("!keep.txt\n")),
+            ],
+        };
+        assert!(rules.is_ignored("other.txt", false));
+        assert!(!rules.is_ignored("keep.txt", false));
+    }
+
+    #[test]
+    fn inherited() {
+        let root = std::env::temp_dir().join(format!("eza-ignore-{}", std::process::id()));
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::write(root.join(".git"), "").unwrap();
+        fs::write(root.join(FILE_NAME), "*.log\n").unwrap();
+        fs::write(root.join("a").join(FILE_NAME), "!keep.log\nb/*.tmp\n").unwrap();
+
+        let top = Dir::read_dir(root.clone()).unwrap();
+        let a = Dir::read_dir(root.join("a")).unwrap();
+        let b = Dir::read_dir(root.join("a/b")).unwrap();
+        let _ = IgnoreRules::for_directory(&top);
+        let _ = IgnoreRules::for_directory(&a);
+        let rules = IgnoreRules::for_directory(&b);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(rules.is_ignored("debug.log", false));
+        assert!(!rules.is_ignored("keep.log", false));
+        assert!(rules.is_ignored("x.tmp", false));
+        assert_eq!(rules.files.len(), 2);
+    }
+}
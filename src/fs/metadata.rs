@@ -0,0 +1,318 @@
+//! The `stat` information that each file holds on to.
+//!
+//! The standard library’s `symlink_metadata` asks Linux’s `statx` for every
+//! field it knows about, including the file’s birth time. On some filesystems,
+//! NFS in particular, that costs an extra round trip for each file, even
+//! though most listings never show it. On Linux, eza calls `statx` itself
+//! with a mask of only the fields that are going to be used; everywhere else,
+//! this is just the standard library’s own `Metadata`.
+
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+pub use std::fs::Metadata;
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+pub use self::statx::Metadata;
+
+use std::io;
+use std::path::Path;
+
+/// Queries the metadata of a file, without following symbolic links.
+pub fn symlink_metadata(path: &Path) -> io::Result<Metadata> {
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    return statx::stat(path, libc::AT_SYMLINK_NOFOLLOW);
+
+    #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+    return std::fs::symlink_metadata(path);
+}
+
+/// Queries the metadata of a file, following symbolic links.
+pub fn metadata(path: &Path) -> io::Result<Metadata> {
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    return statx::stat(path, 0);
+
+    #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+    return std::fs::metadata(path);
+}
+
+/// Asks for files’ birth times as well as their other fields, which has to
+/// be done before any files get listed if any of them are going to be
+/// displayed, sorted on, or filtered by. Other platforms always have them.
+pub fn request_birth_times() {
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    statx::MASK.fetch_or(libc::STATX_BTIME, std::sync::atomic::Ordering::Relaxed);
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+mod statx {
+    use std::ffi::CString;
+    use std::io;
+    use std::mem::MaybeUninit;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+    use std::path::Path;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    /// The fields to ask `statx` for. Everything eza displays for every file
+    /// is in the basic set; anything more gets added by whatever needs it.
+    pub(super) static MASK: AtomicU32 = AtomicU32::new(libc::STATX_BASIC_STATS);
+
+    /// The result of a `statx` call, with the fields that were asked for.
+    #[derive(Clone)]
+    pub struct Metadata(libc::statx);
+
+    pub(super) fn stat(path: &Path, flags: libc::c_int) -> io::Result<Metadata> {
+        stat_with(path, flags, MASK.load(Ordering::Relaxed))
+    }
+
+    fn stat_with(path: &Path, flags: libc::c_int, mask: u32) -> io::Result<Metadata> {
+        let c_path = CString::new(path.as_os_str().as_bytes())?;
+        let mut buf = MaybeUninit::<libc::statx>::zeroed();
+        let result = unsafe {
+            libc::statx(
+                libc::AT_FDCWD,
+                c_path.as_ptr(),
+                flags | libc::AT_STATX_SYNC_AS_STAT,
+                mask,
+                buf.as_mut_ptr(),
+            )
+        };
+
+        if result == 0 {
+            return Ok(Metadata(unsafe { buf.assume_init() }));
+        }
+
+        // Kernels older than 4.11 don’t have `statx`, and some sandboxes
+        // refuse it, so fall back to the standard library’s `stat`.
+        let error = io::Error::last_os_error();
+        match error.raw_os_error() {
+            Some(libc::ENOSYS | libc::EPERM) if flags == 0 => {
+                std::fs::metadata(path).map(Into::into)
+            }
+            Some(libc::ENOSYS | libc::EPERM) => std::fs::symlink_metadata(path).map(Into::into),
+            _ => Err(error),
+        }
+    }
+
+    impl From<std::fs::Metadata> for Metadata {
+        fn from(metadata: std::fs::Metadata) -> Self {
+            let timestamp = |sec, nsec| libc::statx_timestamp {
+                tv_sec: sec,
+                tv_nsec: nsec as u32,
+                __statx_timestamp_pad1: [0],
+            };
+
+            let mut stx: libc::statx = unsafe { MaybeUninit::zeroed().assume_init() };
+            stx.stx_mask = libc::STATX_BASIC_STATS;
+            stx.stx_blksize = metadata.blksize() as u32;
+            stx.stx_nlink = metadata.nlink() as u32;
+            stx.stx_uid = metadata.uid();
+            stx.stx_gid = metadata.gid();
+            stx.stx_mode = metadata.mode() as u16;
+            stx.stx_ino = metadata.ino();
+            stx.stx_size = metadata.size();
+            stx.stx_blocks = metadata.blocks();
+            stx.stx_atime = timestamp(metadata.atime(), metadata.atime_nsec());
+            stx.stx_ctime = timestamp(metadata.ctime(), metadata.ctime_nsec());
+            stx.stx_mtime = timestamp(metadata.mtime(), metadata.mtime_nsec());
+            unsafe {
+                stx.stx_rdev_major = libc::major(metadata.rdev());
+                stx.stx_rdev_minor = libc::minor(metadata.rdev());
+                stx.stx_dev_major = libc::major(metadata.dev());
+                stx.stx_dev_minor = libc::minor(metadata.dev());
+            }
+
+            if let Ok(created) = metadata.created() {
+                let since = created.duration_since(UNIX_EPOCH).unwrap_or_default();
+                stx.stx_mask |= libc::STATX_BTIME;
+                stx.stx_btime = timestamp(since.as_secs() as i64, i64::from(since.subsec_nanos()));
+            }
+
+            Self(stx)
+        }
+    }
+
+    // These have the same signatures as the standard library’s methods, so
+    // the rest of eza can use either type in the same way.
+    #[allow(clippy::len_without_is_empty, clippy::unnecessary_wraps)]
+    impl Metadata {
+        pub fn file_type(&self) -> FileType {
+            FileType(u32::from(self.0.stx_mode) & libc::S_IFMT)
+        }
+
+        pub fn is_dir(&self) -> bool {
+            self.file_type().is_dir()
+        }
+
+        pub fn is_file(&self) -> bool {
+            self.file_type().is_file()
+        }
+
+        pub fn len(&self) -> u64 {
+            self.0.stx_size
+        }
+
+        pub fn modified(&self) -> io::Result<SystemTime> {
+            Ok(system_time(self.0.stx_mtime))
+        }
+
+        pub fn accessed(&self) -> io::Result<SystemTime> {
+            Ok(system_time(self.0.stx_atime))
+        }
+
+        /// The birth time, which is only there if it was asked for and the
+        /// filesystem keeps track of it.
+        pub fn created(&self) -> io::Result<SystemTime> {
+            if self.0.stx_mask & libc::STATX_BTIME == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "creation time is not available",
+                ));
+            }
+
+            Ok(system_time(self.0.stx_btime))
+        }
+    }
+
+    fn system_time(timestamp: libc::statx_timestamp) -> SystemTime {
+        let nanos = Duration::from_nanos(u64::from(timestamp.tv_nsec));
+        if timestamp.tv_sec >= 0 {
+            UNIX_EPOCH + Duration::from_secs(timestamp.tv_sec as u64) + nanos
+        } else {
+            UNIX_EPOCH - Duration::from_secs(timestamp.tv_sec.unsigned_abs()) + nanos
+        }
+    }
+
+    impl MetadataExt for Metadata {
+        fn dev(&self) -> u64 {
+            libc::makedev(self.0.stx_dev_major, self.0.stx_dev_minor)
+        }
+
+        fn ino(&self) -> u64 {
+            self.0.stx_ino
+        }
+
+        fn mode(&self) -> u32 {
+            u32::from(self.0.stx_mode)
+        }
+
+        fn nlink(&self) -> u64 {
+            u64::from(self.0.stx_nlink)
+        }
+
+        fn uid(&self) -> u32 {
+            self.0.stx_uid
+        }
+
+        fn gid(&self) -> u32 {
+            self.0.stx_gid
+        }
+
+        fn rdev(&self) -> u64 {
+            libc::makedev(self.0.stx_rdev_major, self.0.stx_rdev_minor)
+        }
+
+        fn size(&self) -> u64 {
+            self.0.stx_size
+        }
+
+        fn atime(&self) -> i64 {
+            self.0.stx_atime.tv_sec
+        }
+
+        fn atime_nsec(&self) -> i64 {
+            i64::from(self.0.stx_atime.tv_nsec)
+        }
+
+        fn mtime(&self) -> i64 {
+            self.0.stx_mtime.tv_sec
+        }
+
+        fn mtime_nsec(&self) -> i64 {
+            i64::from(self.0.stx_mtime.tv_nsec)
+        }
+
+        fn ctime(&self) -> i64 {
+            self.0.stx_ctime.tv_sec
+        }
+
+        fn ctime_nsec(&self) -> i64 {
+            i64::from(self.0.stx_ctime.tv_nsec)
+        }
+
+        fn blksize(&self) -> u64 {
+            u64::from(self.0.stx_blksize)
+        }
+
+        fn blocks(&self) -> u64 {
+            self.0.stx_blocks
+        }
+    }
+
+    /// The type bits of a file’s mode.
+    #[derive(PartialEq, Eq, Copy, Clone)]
+    pub struct FileType(u32);
+
+    impl FileType {
+        pub fn is_dir(self) -> bool {
+            self.0 == libc::S_IFDIR
+        }
+
+        pub fn is_file(self) -> bool {
+            self.0 == libc::S_IFREG
+        }
+
+        pub fn is_symlink(self) -> bool {
+            self.0 == libc::S_IFLNK
+        }
+    }
+
+    impl FileTypeExt for FileType {
+        fn is_block_device(&self) -> bool {
+            self.0 == libc::S_IFBLK
+        }
+
+        fn is_char_device(&self) -> bool {
+            self.0 == libc::S_IFCHR
+        }
+
+        fn is_fifo(&self) -> bool {
+            self.0 == libc::S_IFIFO
+        }
+
+        fn is_socket(&self) -> bool {
+            self.0 == libc::S_IFSOCK
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn matches_std() {
+            let path = Path::new("Cargo.toml");
+            let ours = stat(path, libc::AT_SYMLINK_NOFOLLOW).unwrap();
+            let theirs = Metadata::from(std::fs::symlink_metadata(path).unwrap());
+
+            assert!(ours.is_file());
+            assert_eq!(ours.len(), theirs.len());
+            assert_eq!(ours.dev(), theirs.dev());
+            assert_eq!(ours.ino(), theirs.ino());
+            assert_eq!(ours.mode(), theirs.mode());
+            assert_eq!(ours.blocks(), theirs.blocks());
+            assert_eq!(ours.modified().unwrap(), theirs.modified().unwrap());
+            assert_eq!(ours.ctime_nsec(), theirs.ctime_nsec());
+        }
+
+        #[test]
+        fn birth_time_when_asked() {
+            let path = Path::new("Cargo.toml");
+            let mask = libc::STATX_BASIC_STATS | libc::STATX_BTIME;
+            let ours = stat_with(path, 0, mask).unwrap();
+            let theirs = std::fs::metadata(path).unwrap();
+
+            assert_eq!(ours.created().ok(), theirs.created().ok());
+        }
+    }
+}
@@ -1,12 +1,21 @@
+mod arena;
+
 mod dir;
-pub use self::dir::{Dir, DotFilter};
+pub use self::dir::{Dir, DirIdentity, DotFilter, HiddenMode};
 
 mod file;
-pub use self::file::{File, FileTarget};
+pub use self::file::{set_walk_threads, File, FileTarget};
 
+pub mod codeowners;
 pub mod dir_action;
 pub mod feature;
 pub mod fields;
 pub mod filter;
+pub mod ignore_file;
+pub mod metadata;
 pub mod mounts;
+pub mod readahead;
 pub mod recursive_size;
+pub mod snapshot;
+#[cfg(target_os = "linux")]
+pub mod sockets;
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 #[cfg(target_os = "linux")]
@@ -73,3 +73,124 @@ pub(super) fn all_mounts() -> &'static HashMap<PathBuf, MountedFs> {
         mount_map
     })
 }
+
+/// Filesystem types whose files are looked up over the network, where each
+/// lookup waits on a round trip rather than on the local disk.
+const NETWORK_FILESYSTEMS: &[&str] = &[
+    "9p",
+    "afs",
+    "ceph",
+    "cifs",
+    "fuse.sshfs",
+    "glusterfs",
+    "nfs",
+    "nfs4",
+    "smb3",
+    "smbfs",
+    "webdav",
+];
+
+/// Filesystem types whose files the kernel makes up when they’re read,
+/// rather than storing them anywhere. Their sizes mean nothing, and reading
+/// their contents can block.
+const VIRTUAL_FILESYSTEMS: &[&str] = &[
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "efivarfs",
+    "fusectl",
+    "proc",
+    "pstore",
+    "securityfs",
+    "sysfs",
+    "tracefs",
+];
+
+/// Whether the device with the given ID holds a virtual filesystem, such
+/// as procfs or sysfs. The devices are looked up the first time this gets
+/// called.
+#[cfg(target_os = "linux")]
+pub fn is_virtual_device(device: u64) -> bool {
+    use std::collections::HashSet;
+    use std::os::unix::fs::MetadataExt;
+
+    static VIRTUAL_DEVICES: OnceLock<HashSet<u64>> = OnceLock::new();
+
+    VIRTUAL_DEVICES
+        .get_or_init(|| {
+            all_mounts()
+                .values()
+                .filter(|m| VIRTUAL_FILESYSTEMS.contains(&m.fstype.as_str()))
+                .filter_map(|m| std::fs::metadata(&m.dest).ok())
+                .map(|metadata| metadata.dev())
+                .collect()
+        })
+        .contains(&device)
+}
+
+/// The kind of storage a path is on, as far as it can be worked out. This
+/// affects how many files are worth looking up at the same time.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Storage {
+    /// A network filesystem, where lookups are slow but can overlap.
+    Network,
+
+    /// A spinning disk, where lookups at the same time make it seek.
+    Rotational,
+
+    /// Anything else, such as an SSD or a filesystem in memory.
+    Other,
+}
+
+impl Storage {
+    /// Works out the kind of storage the given path is on, from the type of
+    /// the filesystem mounted closest above it and, on Linux, whether the
+    /// device it’s on has rotating parts.
+    pub fn of(path: &Path) -> Self {
+        let Ok(path) = path.canonicalize() else {
+            return Self::Other;
+        };
+
+        let mount = path.ancestors().find_map(|dir| all_mounts().get(dir));
+        if mount.map_or(false, |m| NETWORK_FILESYSTEMS.contains(&m.fstype.as_str())) {
+            return Self::Network;
+        }
+
+        if is_rotational(&path) {
+            Self::Rotational
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Whether the block device the path is on is marked as rotational, which
+/// is found in sysfs under the device, or under its parent for partitions.
+#[cfg(target_os = "linux")]
+fn is_rotational(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+
+    // SAFETY: these only split the device number into its two halves
+    let (major, minor) = unsafe { (libc::major(metadata.dev()), libc::minor(metadata.dev())) };
+    let device = PathBuf::from(format!("/sys/dev/block/{major}:{minor}"));
+    [
+        device.join("queue/rotational"),
+        device.join("../queue/rotational"),
+    ]
+    .iter()
+    .find_map(|flag| std::fs::read_to_string(flag).ok())
+    .map_or(false, |flag| flag.trim() == "1")
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_rotational(_path: &Path) -> bool {
+    false
+}
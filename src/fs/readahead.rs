@@ -0,0 +1,290 @@
+//! Reading the directories beneath the ones being listed ahead of time, on
+//! other threads, so that a recursive listing doesn’t have to wait for the
+//! filesystem one directory at a time.
+//!
+//! The listing still goes through the directories in its own order, and
+//! reads and sorts their files itself, so the output comes out the same as
+//! it would do otherwise: the threads only get there first. Each one takes
+//! the next directory off a shared stack, reads it, looks up the metadata of
+//! everything inside it so it’s cached by the time the listing asks for it,
+//! and puts the directories it finds back on the stack, so the threads that
+//! run out of work take it from the others. When the listing gets to a
+//! directory that’s been read, it takes the contents; when it gets to one
+//! that hasn’t been, it moves it to the top of the stack and waits for it.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::thread;
+
+use log::*;
+
+use crate::fs::filter::IgnorePatterns;
+use crate::fs::Dir;
+
+/// How many directories can be waiting to be listed before the threads stop
+/// reading any more, so a huge tree doesn’t end up read into memory.
+const MAX_WAITING: usize = 4096;
+
+/// Which directories to read ahead, which should be the ones the listing is
+/// going to go into.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// How many levels beneath the paths given to go down.
+    pub max_depth: Option<usize>,
+
+    /// Whether to go into directories whose names start with a dot.
+    pub dotfiles: bool,
+
+    /// The names of the directories not to go into.
+    pub prune: IgnorePatterns,
+
+    /// Whether to stay on the same filesystem as the path they’re beneath.
+    pub one_file_system: bool,
+}
+
+/// Starts reading the directories beneath the given ones with the given
+/// number of threads. This can only be done once.
+pub fn start(roots: Vec<PathBuf>, threads: u32, options: Options) {
+    if threads < 2 || roots.is_empty() {
+        return;
+    }
+
+    let shared = READ_AHEAD.get_or_init(|| Shared {
+        options,
+        state: Mutex::default(),
+        changed: Condvar::new(),
+    });
+
+    {
+        let mut state = shared.lock();
+        for root in roots.into_iter().rev() {
+            let device = device(&root);
+            state.queue(Job {
+                path: root,
+                depth: 0,
+                device,
+            });
+        }
+    }
+
+    debug!("Reading directories ahead with {threads} threads");
+    for _ in 0..threads {
+        thread::spawn(|| shared.work());
+    }
+}
+
+/// The contents of the directory at the given path, if it’s been read
+/// ahead, waiting for it if it’s being read right now.
+pub fn take(path: &Path) -> Option<io::Result<Dir>> {
+    let shared = READ_AHEAD.get()?;
+    let mut state = shared.lock();
+
+    if let Some(Slot::Queued(depth, device)) = state.dirs.get(path) {
+        let job = Job {
+            path: path.to_path_buf(),
+            depth: *depth,
+            device: *device,
+        };
+        state.stack.push(job);
+    }
+
+    state.takers += 1;
+    let read = loop {
+        match state.dirs.remove(path) {
+            None => break None,
+            Some(Slot::Read(read)) => {
+                state.waiting -= 1;
+                break Some(read);
+            }
+            Some(slot) => {
+                state.dirs.insert(path.to_path_buf(), slot);
+                shared.changed.notify_all();
+                state = shared
+                    .changed
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+        }
+    };
+    state.takers -= 1;
+
+    shared.changed.notify_all();
+    read
+}
+
+static READ_AHEAD: OnceLock<Shared> = OnceLock::new();
+
+struct Shared {
+    options: Options,
+    state: Mutex<State>,
+
+    /// Signalled whenever a directory gets read or taken.
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    /// The directories still to be read, with the next one at the end.
+    stack: Vec<Job>,
+
+    /// Where each directory that’s been found has got to.
+    dirs: HashMap<PathBuf, Slot>,
+
+    /// How many directories have been read and not taken yet.
+    waiting: usize,
+
+    /// How many directories are being read right now.
+    reading: usize,
+
+    /// How many times the listing is waiting for a directory, which gets
+    /// read however many others are waiting.
+    takers: usize,
+}
+
+/// A directory to read.
+struct Job {
+    path: PathBuf,
+
+    /// How many levels beneath one of the paths given it is.
+    depth: usize,
+
+    /// The filesystem that the path it’s beneath is on.
+    device: Option<u64>,
+}
+
+enum Slot {
+    /// It’s on the stack, at the given depth and beneath the given device.
+    Queued(usize, Option<u64>),
+    Reading,
+    Read(io::Result<Dir>),
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Reads directories off the stack until there aren’t any left.
+    fn work(&self) {
+        loop {
+            let job = {
+                let mut state = self.lock();
+                loop {
+                    if state.waiting < MAX_WAITING || state.takers > 0 {
+                        if let Some(job) = state.next() {
+                            state.reading += 1;
+                            break job;
+                        }
+
+                        // Nothing more is going to turn up
+                        if state.reading == 0 {
+                            self.changed.notify_all();
+                            return;
+                        }
+                    }
+
+                    state = self
+                        .changed
+                        .wait(state)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            };
+
+            let read = Dir::read_dir_now(job.path.clone());
+            let children = match &read {
+                Ok(dir) => self.children(dir, &job),
+                Err(_) => Vec::new(),
+            };
+
+            let mut state = self.lock();
+            state.reading -= 1;
+            if let Some(slot) = state.dirs.get_mut(&job.path) {
+                *slot = Slot::Read(read);
+                state.waiting += 1;
+            }
+            for child in children.into_iter().rev() {
+                state.queue(child);
+            }
+            self.changed.notify_all();
+        }
+    }
+
+    /// Looks up the metadata of everything in a directory, returning the
+    /// directories inside it that are to be read as well.
+    fn children(&self, dir: &Dir, job: &Job) -> Vec<Job> {
+        let depth = job.depth + 1;
+        let deep_enough = self.options.max_depth.is_some_and(|max| max <= depth);
+
+        let mut children = Vec::new();
+        for path in dir.paths() {
+            let Ok(metadata) = fs::symlink_metadata(path) else {
+                continue;
+            };
+
+            if deep_enough || !metadata.is_dir() || !self.descends(path) {
+                continue;
+            }
+
+            if self.options.one_file_system && job.device != device_of(&metadata) {
+                continue;
+            }
+
+            children.push(Job {
+                path: path.to_path_buf(),
+                depth,
+                device: job.device,
+            });
+        }
+        children
+    }
+
+    /// Whether the listing would go into the directory at the given path.
+    fn descends(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name().and_then(std::ffi::OsStr::to_str) else {
+            return false;
+        };
+
+        (self.options.dotfiles || !name.starts_with('.')) && !self.options.prune.is_ignored(name)
+    }
+}
+
+impl State {
+    fn queue(&mut self, job: Job) {
+        if !self.dirs.contains_key(&job.path) {
+            self.dirs
+                .insert(job.path.clone(), Slot::Queued(job.depth, job.device));
+            self.stack.push(job);
+        }
+    }
+
+    /// The next directory to read, skipping the ones that have been moved
+    /// to the top of the stack and read already.
+    fn next(&mut self) -> Option<Job> {
+        while let Some(job) = self.stack.pop() {
+            if let Some(slot @ Slot::Queued(..)) = self.dirs.get_mut(&job.path) {
+                *slot = Slot::Reading;
+                return Some(job);
+            }
+        }
+        None
+    }
+}
+
+fn device(path: &Path) -> Option<u64> {
+    fs::metadata(path).ok().as_ref().and_then(device_of)
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_wraps)]
+fn device_of(metadata: &fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+}
+
+#[cfg(not(unix))]
+fn device_of(_metadata: &fs::Metadata) -> Option<u64> {
+    None
+}
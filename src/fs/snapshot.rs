@@ -0,0 +1,452 @@
+//! Saving how big everything beneath a directory is to a snapshot file, so
+//! a later listing can show how much each entry has grown or shrunk since.
+//!
+//! A snapshot has one line for every file and directory beneath the paths
+//! that were listed, giving its size in bytes and the number of files in it,
+//! which for a directory counts everything beneath it. Links aren’t followed,
+//! so the sizes are the same whatever options the listing was done with.
+//!
+//! Directories also have the time they were last modified saved, so that a
+//! snapshot can be updated without reading the directories that haven’t had
+//! anything added to or removed from them since. Snapshot files whose names
+//! end in `.zst` are compressed with zstd.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, UNIX_EPOCH};
+
+use log::*;
+
+/// Written at the top of every snapshot file, so the format can be changed.
+const MAGIC: &str = "eza-snapshot 2";
+
+/// Written at the top of the snapshot files saved before directories had
+/// their modification times saved too, which can still be read.
+const MAGIC_V1: &str = "eza-snapshot 1";
+
+/// The bytes that a file compressed with zstd starts with.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// How big a file or directory is, in a snapshot or right now.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub struct Entry {
+    /// The size of the file, or of all the files beneath the directory.
+    pub size: u64,
+
+    /// One for a file, or the number of files beneath the directory.
+    pub files: u64,
+}
+
+/// How much a file or directory has changed since a snapshot was saved.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Delta {
+    /// It wasn’t in the snapshot.
+    New,
+
+    /// It was, and this is how much bigger it’s got, which can be negative.
+    Changed { size: i128, files: i128 },
+}
+
+/// The sizes of everything that was beneath the paths that were listed.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct Snapshot {
+    entries: HashMap<PathBuf, Entry>,
+
+    /// When each directory was last modified, as a time since the epoch.
+    modified: HashMap<PathBuf, Duration>,
+}
+
+impl Snapshot {
+    /// Reads a snapshot from a file that was saved by an earlier listing,
+    /// which gets decompressed first if it was compressed.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "not a snapshot file");
+        let contents = decompress(fs::read(path)?)?;
+        let contents = String::from_utf8(contents).map_err(|_| invalid())?;
+        Self::parse(&contents).ok_or_else(invalid)
+    }
+
+    /// Reads the entries out of a snapshot file, which has to start with
+    /// the right header. Each line has the modification time of a directory
+    /// between its number of files and its path, or a `-` for a file, apart
+    /// from in the first version of the format, which doesn’t have them.
+    fn parse(contents: &str) -> Option<Self> {
+        let mut lines = contents.lines();
+        let fields = match lines.next() {
+            Some(MAGIC) => 4,
+            Some(MAGIC_V1) => 3,
+            _ => return None,
+        };
+
+        let mut snapshot = Self::default();
+        for line in lines {
+            let mut split = line.splitn(fields, '\t');
+            let (Some(size), Some(files)) = (split.next(), split.next()) else {
+                continue;
+            };
+            let modified = if fields == 4 { split.next() } else { Some("-") };
+            let (Some(modified), Some(path)) = (modified, split.next()) else {
+                continue;
+            };
+
+            let (Ok(size), Ok(files)) = (size.parse(), files.parse()) else {
+                continue;
+            };
+            let path = PathBuf::from(path);
+            if let Some(modified) = parse_time(modified) {
+                snapshot.modified.insert(path.clone(), modified);
+            }
+            snapshot.entries.insert(path, Entry { size, files });
+        }
+
+        Some(snapshot)
+    }
+
+    /// Writes the entries of a snapshot, sorted by their paths.
+    fn write(&self, w: &mut impl Write) -> io::Result<()> {
+        let mut entries = self.entries.iter().collect::<Vec<_>>();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+        writeln!(w, "{MAGIC}")?;
+        for (path, entry) in entries {
+            let modified = self.modified.get(path).map_or_else(
+                || String::from("-"),
+                |time| format!("{}.{:09}", time.as_secs(), time.subsec_nanos()),
+            );
+            match path.to_str() {
+                Some(p) if !p.contains('\n') => {
+                    writeln!(w, "{}\t{}\t{modified}\t{p}", entry.size, entry.files)?;
+                }
+                _ => debug!("Leaving {:?} out of the snapshot", path),
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds up the sizes of everything beneath a path, putting an entry for
+    /// each file and directory into the snapshot, and returning the path’s
+    /// own one. A directory that was last modified at the same time as it
+    /// was in the earlier snapshot, if there is one, still has the same
+    /// entries in it, so they get taken from that instead of it being read,
+    /// and only the directories among them get looked at again.
+    fn walk(&mut self, path: &Path, earlier: Option<&Earlier<'_>>) -> Option<Entry> {
+        let metadata = fs::symlink_metadata(path).ok()?;
+
+        let entry = if metadata.is_dir() {
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok());
+            let unchanged = earlier.filter(|e| {
+                modified.is_some() && e.snapshot.modified.get(path) == modified.as_ref()
+            });
+
+            let mut entry = Entry::default();
+            let mut add = |e: Entry| {
+                entry.size += e.size;
+                entry.files += e.files;
+            };
+
+            if let Some(earlier) = unchanged {
+                for child in earlier.children.get(path).into_iter().flatten() {
+                    let file = earlier.snapshot.entries.get(*child);
+                    match file.filter(|_| !earlier.snapshot.modified.contains_key(*child)) {
+                        Some(file) => {
+                            self.entries.insert(child.to_path_buf(), *file);
+                            add(*file);
+                        }
+                        None => self
+                            .walk(child, Some(earlier))
+                            .into_iter()
+                            .for_each(&mut add),
+                    }
+                }
+            } else {
+                for child in fs::read_dir(path).into_iter().flatten().flatten() {
+                    self.walk(&child.path(), earlier)
+                        .into_iter()
+                        .for_each(&mut add);
+                }
+            }
+
+            if let Some(modified) = modified {
+                self.modified.insert(path.to_path_buf(), modified);
+            }
+            entry
+        } else {
+            Entry {
+                size: metadata.len(),
+                files: 1,
+            }
+        };
+
+        self.entries.insert(path.to_path_buf(), entry);
+        Some(entry)
+    }
+
+    /// How much the file at the given path has changed since the snapshot,
+    /// or `None` if it can’t be found now.
+    pub fn delta(&self, path: &Path) -> Option<Delta> {
+        let path = path.canonicalize().ok()?;
+        let now = current(&path)?;
+
+        Some(Delta::between(self.entries.get(&path).copied(), now))
+    }
+}
+
+impl Delta {
+    /// The change from an entry in a snapshot, if there was one, to now.
+    fn between(then: Option<Entry>, now: Entry) -> Self {
+        match then {
+            Some(then) => Self::Changed {
+                size: i128::from(now.size) - i128::from(then.size),
+                files: i128::from(now.files) - i128::from(then.files),
+            },
+            None => Self::New,
+        }
+    }
+}
+
+/// The snapshot at the given path, which is only read once however many
+/// times it gets used. A warning is logged if it can’t be read.
+pub fn load(path: &Path) -> Option<&'static Snapshot> {
+    static SNAPSHOT: OnceLock<Option<Snapshot>> = OnceLock::new();
+
+    SNAPSHOT
+        .get_or_init(|| match Snapshot::load(path) {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                warn!("Could not read snapshot {:?}: {}", path, e);
+                None
+            }
+        })
+        .as_ref()
+}
+
+/// The snapshot that’s being updated, along with the paths of the entries
+/// in each of its directories.
+struct Earlier<'s> {
+    snapshot: &'s Snapshot,
+    children: HashMap<&'s Path, Vec<&'s Path>>,
+}
+
+impl<'s> Earlier<'s> {
+    fn new(snapshot: &'s Snapshot) -> Self {
+        let mut children = HashMap::<_, Vec<_>>::new();
+        for path in snapshot.entries.keys() {
+            if let Some(parent) = path.parent() {
+                children.entry(parent).or_default().push(path.as_path());
+            }
+        }
+
+        Self { snapshot, children }
+    }
+}
+
+/// Saves a snapshot of the given paths, and everything beneath them, to a
+/// file. Paths that can’t be written on one line are left out. When it’s
+/// being updated, the snapshot that’s already in the file is used to skip
+/// reading the directories that haven’t changed since.
+pub fn save(file: &Path, paths: &[PathBuf], update: bool) -> io::Result<()> {
+    let earlier = if update {
+        Snapshot::load(file)
+            .map_err(|e| debug!("Not updating snapshot {:?}: {}", file, e))
+            .ok()
+    } else {
+        None
+    };
+    let earlier = earlier.as_ref().map(Earlier::new);
+
+    let mut snapshot = Snapshot::default();
+    for path in paths {
+        if let Ok(path) = path.canonicalize() {
+            snapshot.walk(&path, earlier.as_ref());
+        }
+    }
+
+    let mut w = BufWriter::new(fs::File::create(file)?);
+    if file.extension().map_or(false, |ext| ext == "zst") {
+        compress(&mut w, &snapshot)
+    } else {
+        snapshot.write(&mut w)?;
+        w.flush()
+    }
+}
+
+/// Writes a snapshot compressed with zstd.
+#[cfg(feature = "zstd")]
+fn compress(w: &mut impl Write, snapshot: &Snapshot) -> io::Result<()> {
+    let mut encoder = zstd::Encoder::new(w, 0)?;
+    snapshot.write(&mut encoder)?;
+    encoder.finish()?.flush()
+}
+
+#[cfg(not(feature = "zstd"))]
+fn compress(_w: &mut impl Write, _snapshot: &Snapshot) -> io::Result<()> {
+    Err(no_zstd())
+}
+
+/// Decompresses the contents of a snapshot file if they were compressed
+/// with zstd, and leaves them as they are otherwise.
+fn decompress(contents: Vec<u8>) -> io::Result<Vec<u8>> {
+    if !contents.starts_with(&ZSTD_MAGIC) {
+        return Ok(contents);
+    }
+
+    #[cfg(feature = "zstd")]
+    return zstd::decode_all(contents.as_slice());
+
+    #[cfg(not(feature = "zstd"))]
+    Err(no_zstd())
+}
+
+#[cfg(not(feature = "zstd"))]
+fn no_zstd() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "compressed snapshots can’t be used because the `zstd` feature was disabled in this build of eza",
+    )
+}
+
+/// Reads the modification time of a directory in a snapshot, which is
+/// written as seconds and nanoseconds, or `-` for a file.
+fn parse_time(time: &str) -> Option<Duration> {
+    let (secs, nanos) = time.split_once('.')?;
+    Some(Duration::new(secs.parse().ok()?, nanos.parse().ok()?))
+}
+
+/// How big the file or directory at the given canonical path is now. The
+/// sizes of directories are remembered, as directories in a tree get asked
+/// about after their parents have been walked through.
+fn current(path: &Path) -> Option<Entry> {
+    static CURRENT: OnceLock<Mutex<HashMap<PathBuf, Entry>>> = OnceLock::new();
+
+    let cache = CURRENT.get_or_init(Mutex::default);
+    if let Some(entry) = cache
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .get(path)
+    {
+        return Some(*entry);
+    }
+
+    let mut snapshot = Snapshot::default();
+    let entry = snapshot.walk(path, None)?;
+    cache
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .extend(snapshot.entries);
+    Some(entry)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut saved = Snapshot::default();
+        saved
+            .entries
+            .insert(PathBuf::from("/p"), Entry { size: 7, files: 2 });
+        saved
+            .entries
+            .insert(PathBuf::from("/p/a\tb"), Entry { size: 5, files: 1 });
+        saved
+            .entries
+            .insert(PathBuf::from("/p/new\nline"), Entry { size: 2, files: 1 });
+        saved
+            .modified
+            .insert(PathBuf::from("/p"), Duration::new(1_700_000_000, 5));
+
+        let mut contents = Vec::new();
+        saved.write(&mut contents).unwrap();
+        let snapshot = Snapshot::parse(&String::from_utf8(contents).unwrap()).unwrap();
+
+        assert_eq!(snapshot.entries.len(), 2);
+        assert_eq!(
+            snapshot.entries.get(Path::new("/p/a\tb")),
+            Some(&Entry { size: 5, files: 1 })
+        );
+        assert_eq!(
+            snapshot.modified.get(Path::new("/p")),
+            Some(&Duration::new(1_700_000_000, 5))
+        );
+        assert_eq!(snapshot.modified.get(Path::new("/p/a\tb")), None);
+    }
+
+    #[test]
+    fn first_version() {
+        let snapshot = Snapshot::parse("eza-snapshot 1\n3\t1\t/p/a\tb\n").unwrap();
+        assert_eq!(
+            snapshot.entries.get(Path::new("/p/a\tb")),
+            Some(&Entry { size: 3, files: 1 })
+        );
+        assert!(snapshot.modified.is_empty());
+    }
+
+    #[test]
+    fn uncompressed() {
+        assert_eq!(decompress(b"eza".to_vec()).unwrap(), b"eza");
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn compressed() {
+        let contents = zstd::encode_all(&b"eza-snapshot 2\n"[..], 0).unwrap();
+        assert_eq!(decompress(contents).unwrap(), b"eza-snapshot 2\n");
+    }
+
+    #[test]
+    fn unchanged_directories_are_not_read() {
+        let dir = std::env::temp_dir().join(format!("eza-snapshot-{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub/file"), "four").unwrap();
+        let dir = dir.canonicalize().unwrap();
+
+        let mut earlier = Snapshot::default();
+        earlier.walk(&dir, None).unwrap();
+
+        // A file that changed in place doesn’t change its directory’s time,
+        // so the size from before gets kept.
+        let file = dir.join("sub/file");
+        earlier.entries.insert(file, Entry { size: 40, files: 1 });
+
+        let mut updated = Snapshot::default();
+        let entry = updated.walk(&dir, Some(&Earlier::new(&earlier)));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(entry, Some(Entry { size: 40, files: 1 }));
+        assert_eq!(updated.modified, earlier.modified);
+    }
+
+    #[test]
+    fn not_a_snapshot() {
+        assert_eq!(Snapshot::parse("something else\n1\t1\t/\n"), None);
+    }
+
+    #[test]
+    fn deltas() {
+        let now = Entry {
+            size: 100,
+            files: 3,
+        };
+        assert_eq!(Delta::between(None, now), Delta::New);
+        assert_eq!(
+            Delta::between(
+                Some(Entry {
+                    size: 250,
+                    files: 1
+                }),
+                now
+            ),
+            Delta::Changed {
+                size: -150,
+                files: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Describing the sockets that the links in `/proc/PID/fd` point to, which
+//! only say `socket:[INODE]`, by looking their inodes up in `/proc/net`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::OnceLock;
+
+/// Describes the socket with the given inode, such as
+/// `tcp 127.0.0.1:8080 (LISTEN)`, if it can be found.
+pub fn describe(inode: u64) -> Option<&'static str> {
+    static SOCKETS: OnceLock<HashMap<u64, String>> = OnceLock::new();
+
+    SOCKETS
+        .get_or_init(|| {
+            let mut sockets = HashMap::new();
+            for (file, protocol) in [
+                ("tcp", "tcp"),
+                ("tcp6", "tcp"),
+                ("udp", "udp"),
+                ("udp6", "udp"),
+            ] {
+                if let Ok(table) = fs::read_to_string(format!("/proc/net/{file}")) {
+                    sockets.extend(parse_inet(&table, protocol));
+                }
+            }
+            if let Ok(table) = fs::read_to_string("/proc/net/unix") {
+                sockets.extend(parse_unix(&table));
+            }
+            sockets
+        })
+        .get(&inode)
+        .map(String::as_str)
+}
+
+/// Gets the inode out of a link target like `socket:[12345]`.
+pub fn socket_inode(target: &str) -> Option<u64> {
+    target
+        .strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Parses one of the `/proc/net/tcp`-style tables, which have a header line
+/// followed by one line per socket.
+fn parse_inet(table: &str, protocol: &str) -> Vec<(u64, String)> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let local = address(fields.get(1)?)?;
+            let remote = address(fields.get(2)?)?;
+            let state = u8::from_str_radix(fields.get(3)?, 16).ok()?;
+            let inode = fields.get(9)?.parse().ok()?;
+
+            let description = if remote.ends_with(":0") {
+                format!("{protocol} {local}")
+            } else {
+                format!("{protocol} {local}->{remote}")
+            };
+
+            match (protocol, tcp_state(state)) {
+                ("tcp", Some(state)) => Some((inode, format!("{description} ({state})"))),
+                _ => Some((inode, description)),
+            }
+        })
+        .collect()
+}
+
+/// Parses `/proc/net/unix`, where the inode is the seventh field, and the
+/// path comes after it for sockets that have one.
+fn parse_unix(table: &str) -> Vec<(u64, String)> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(6);
+            let inode = fields.next()?.parse().ok()?;
+            match fields.next() {
+                Some(path) => Some((inode, format!("unix {path}"))),
+                None => Some((inode, String::from("unix"))),
+            }
+        })
+        .collect()
+}
+
+/// Formats an address written as hexadecimal, like `0100007F:1F90`. The
+/// address is written as 32-bit words in the machine’s byte order, and the
+/// port as a plain number.
+fn address(field: &str) -> Option<String> {
+    let (ip, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+
+    let mut words = Vec::new();
+    for i in (0..ip.len()).step_by(8) {
+        let word = u32::from_str_radix(ip.get(i..i + 8)?, 16).ok()?;
+        words.extend_from_slice(&word.to_ne_bytes());
+    }
+
+    match words.len() {
+        4 => {
+            let ip = Ipv4Addr::new(words[0], words[1], words[2], words[3]);
+            Some(format!("{ip}:{port}"))
+        }
+        16 => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(&words);
+            Some(format!("[{}]:{port}", Ipv6Addr::from(octets)))
+        }
+        _ => None,
+    }
+}
+
+/// The names of the TCP states that are worth showing.
+fn tcp_state(state: u8) -> Option<&'static str> {
+    #[rustfmt::skip]
+    return match state {
+        0x01 => Some("ESTABLISHED"),
+        0x02 => Some("SYN_SENT"),
+        0x03 => Some("SYN_RECV"),
+        0x04 => Some("FIN_WAIT1"),
+        0x05 => Some("FIN_WAIT2"),
+        0x06 => Some("TIME_WAIT"),
+        0x07 => Some("CLOSE"),
+        0x08 => Some("CLOSE_WAIT"),
+        0x09 => Some("LAST_ACK"),
+        0x0A => Some("LISTEN"),
+        0x0B => Some("CLOSING"),
+        _    => None,
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn inodes() {
+        assert_eq!(socket_inode("socket:[187027]"), Some(187_027));
+        assert_eq!(socket_inode("pipe:[187028]"), None);
+    }
+
+    #[test]
+    fn listening_tcp() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   \
+                     0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 12345 1 0000000000000000 100 0 0 10 0\n";
+        assert_eq!(
+            parse_inet(table, "tcp"),
+            vec![(12345, String::from("tcp 127.0.0.1:8080 (LISTEN)"))]
+        );
+    }
+
+    #[test]
+    fn connected_udp() {
+        let table = "header\n   1: 0100007F:0035 0200000A:D431 01 00000000:00000000 00:00000000 00000000     0        0 999 2\n";
+        assert_eq!(
+            parse_inet(table, "udp"),
+            vec![(999, String::from("udp 127.0.0.1:53->10.0.0.2:54321"))]
+        );
+    }
+
+    #[test]
+    fn ipv6() {
+        assert_eq!(
+            address("00000000000000000000000001000000:0050"),
+            Some(String::from("[::1]:80"))
+        );
+    }
+
+    #[test]
+    fn unix() {
+        let table = "Num       RefCount Protocol Flags    Type St Inode Path\n\
+                     0000000000000000: 00000002 00000000 00010000 0001 01 4242 /run/dbus/system_bus_socket\n\
+                     0000000000000000: 00000003 00000000 00000000 0001 03 4343\n";
+        assert_eq!(
+            parse_unix(table),
+            vec![
+                (4242, String::from("unix /run/dbus/system_bus_socket")),
+                (4343, String::from("unix")),
+            ]
+        );
+    }
+}
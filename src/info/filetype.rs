@@ -7,11 +7,12 @@
 //! # Contributors
 //! Please keep these lists sorted. If you're using vim, :sort i
 
-use phf::{phf_map, Map};
+use phf::{phf_map, phf_set, Map, Set};
 
 use crate::fs::File;
 
-#[derive(Debug, Clone)]
+/// The kinds of file, in the order they get sorted in by `--sort=category`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
 pub enum FileType {
     Image,
     Video,
@@ -26,6 +27,38 @@ pub enum FileType {
     // kick off the build of a project. It’s usually only present in directories full of
     // source code.
     Source,
+    Config, // A “config file” sets up one of the tools used to work on a project, such as an
+            // editor, formatter, or linter, rather than being part of the project itself.
+}
+
+/// The tools whose config files are recognised, each of which has a family
+/// of files that can be named in several ways.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub(crate) enum ConfigFamily {
+    EditorConfig,
+    Prettier,
+    ESLint,
+}
+
+/// The names that each family of config files starts with, such as
+/// `.eslintrc` for `.eslintrc.json` and `.eslintrc.yml`.
+const CONFIG_PREFIXES: &[(&str, ConfigFamily)] = &[
+    (".editorconfig", ConfigFamily::EditorConfig),
+    (".eslintrc", ConfigFamily::ESLint),
+    (".prettierrc", ConfigFamily::Prettier),
+    ("eslint.config.", ConfigFamily::ESLint),
+    ("prettier.config.", ConfigFamily::Prettier),
+];
+
+impl ConfigFamily {
+    /// The family of config files that a file with the given name belongs
+    /// to, if it belongs to one.
+    pub(crate) fn of(name: &str) -> Option<Self> {
+        CONFIG_PREFIXES
+            .iter()
+            .find(|(prefix, _)| name.starts_with(prefix))
+            .map(|(_, family)| *family)
+    }
 }
 
 /// Mapping from full filenames to file type.
@@ -365,6 +398,37 @@ const EXTENSION_TYPES: Map<&'static str, FileType> = phf_map! {
     "vsh"        => FileType::Source, // Vertex shader
 };
 
+/// Directories that belong to development tools, such as editors, version
+/// control systems, and CI services, rather than holding the project itself.
+const TOOL_DIRECTORIES: Set<&'static str> = phf_set! {
+    ".cargo",
+    ".circleci",
+    ".devcontainer",
+    ".direnv",
+    ".git",
+    ".github",
+    ".gitlab",
+    ".hg",
+    ".husky",
+    ".idea",
+    ".jj",
+    ".mypy_cache",
+    ".pytest_cache",
+    ".ruff_cache",
+    ".svn",
+    ".tox",
+    ".venv",
+    ".vs",
+    ".vscode",
+    ".zed",
+};
+
+/// Whether the directory is one that belongs to a development tool, such as
+/// `.vscode` or `.github`.
+pub(crate) fn is_tool_directory(file: &File<'_>) -> bool {
+    file.is_directory() && TOOL_DIRECTORIES.contains(&*file.name)
+}
+
 impl FileType {
     /// Lookup the file type based on the file's name, by the file name
     /// lowercase extension, or if the file could be compiled from related
@@ -375,10 +439,13 @@ impl FileType {
             return Some(Self::Build);
         }
         if let Some(file_type) = FILENAME_TYPES.get(&file.name) {
-            return Some(file_type.clone());
+            return Some(*file_type);
+        }
+        if ConfigFamily::of(&file.name).is_some() {
+            return Some(Self::Config);
         }
         if let Some(file_type) = file.ext.as_ref().and_then(|ext| EXTENSION_TYPES.get(ext)) {
-            return Some(file_type.clone());
+            return Some(*file_type);
         }
         if file.name.ends_with('~') || (file.name.starts_with('#') && file.name.ends_with('#')) {
             return Some(Self::Temp);
@@ -395,3 +462,35 @@ impl FileType {
         None
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn config_families() {
+        assert_eq!(
+            ConfigFamily::of(".editorconfig"),
+            Some(ConfigFamily::EditorConfig)
+        );
+        assert_eq!(ConfigFamily::of(".eslintrc"), Some(ConfigFamily::ESLint));
+        assert_eq!(
+            ConfigFamily::of(".eslintrc.yml"),
+            Some(ConfigFamily::ESLint)
+        );
+        assert_eq!(
+            ConfigFamily::of("eslint.config.mjs"),
+            Some(ConfigFamily::ESLint)
+        );
+        assert_eq!(
+            ConfigFamily::of(".prettierrc.json"),
+            Some(ConfigFamily::Prettier)
+        );
+        assert_eq!(
+            ConfigFamily::of("prettier.config.js"),
+            Some(ConfigFamily::Prettier)
+        );
+        assert_eq!(ConfigFamily::of(".prettierignore"), None);
+        assert_eq!(ConfigFamily::of("eslint.js"), None);
+    }
+}
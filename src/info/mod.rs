@@ -5,3 +5,4 @@
 
 pub mod filetype;
 mod sources;
+pub mod suspicious;
@@ -0,0 +1,103 @@
+//! Spotting file names that look like they’re trying to trick whoever is
+//! reading them, such as a downloaded `invoice.pdf.exe`, so they can be
+//! painted with a warning.
+
+/// Extensions of files that run something when they’re opened.
+const RUNNABLE_EXTENSIONS: &[&str] = &[
+    "app", "apk", "bat", "cmd", "com", "exe", "hta", "jar", "js", "jse", "lnk", "msi", "pif",
+    "ps1", "scr", "vbe", "vbs", "wsf",
+];
+
+/// Extensions of harmless-looking files, which a runnable file might be
+/// pretending to be.
+const LURE_EXTENSIONS: &[&str] = &[
+    "avi", "bmp", "csv", "doc", "docx", "gif", "htm", "html", "jpeg", "jpg", "mov", "mp3", "mp4",
+    "pdf", "png", "ppt", "pptx", "rar", "rtf", "txt", "wav", "xls", "xlsx", "zip",
+];
+
+/// Cyrillic and Greek letters that look the same as Latin ones.
+const HOMOGLYPHS: &[char] = &[
+    'а', 'в', 'е', 'і', 'ј', 'к', 'м', 'н', 'о', 'р', 'с', 'т', 'у', 'х', 'ѕ', 'һ', 'ԁ', 'А', 'В',
+    'Е', 'І', 'Ј', 'К', 'М', 'Н', 'О', 'Р', 'С', 'Т', 'Х', 'Ѕ', 'α', 'ε', 'ι', 'κ', 'ν', 'ο', 'ρ',
+    'τ', 'υ', 'χ', 'Α', 'Β', 'Ε', 'Ζ', 'Η', 'Ι', 'Κ', 'Μ', 'Ν', 'Ο', 'Ρ', 'Τ', 'Υ', 'Χ',
+];
+
+/// Whether the file name looks deceptive: it ends in whitespace, contains
+/// characters that change the direction of the text, mixes Latin letters
+/// with lookalikes from other alphabets, or hides a runnable extension
+/// behind a harmless one.
+pub fn is_suspicious(name: &str) -> bool {
+    name.ends_with(char::is_whitespace)
+        || name.chars().any(is_bidi_control)
+        || has_homoglyphs(name)
+        || has_double_extension(name)
+}
+
+/// Whether the character overrides or embeds a text direction, which can
+/// make the end of a name display in the middle of it.
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+/// Whether any word in the name is made of Latin letters with lookalike
+/// letters mixed in. Words written entirely in another alphabet are fine.
+fn has_homoglyphs(name: &str) -> bool {
+    name.split(|c: char| !c.is_alphanumeric()).any(|word| {
+        word.chars().any(|c| c.is_ascii_alphabetic())
+            && word.chars().any(|c| HOMOGLYPHS.contains(&c))
+    })
+}
+
+/// Whether the name ends in a runnable extension that comes straight after
+/// a harmless one, such as `invoice.pdf.exe`.
+fn has_double_extension(name: &str) -> bool {
+    let mut parts = name.rsplit('.');
+    let (Some(last), Some(lure)) = (parts.next(), parts.next()) else {
+        return false;
+    };
+
+    // There has to be something before the lure for it to be an extension.
+    if parts.all(str::is_empty) {
+        return false;
+    }
+
+    let last = last.to_ascii_lowercase();
+    let lure = lure.trim_end().to_ascii_lowercase();
+    RUNNABLE_EXTENSIONS.contains(&last.as_str()) && LURE_EXTENSIONS.contains(&lure.as_str())
+}
+
+#[cfg(test)]
+mod test {
+    use super::is_suspicious;
+
+    #[test]
+    fn normal_names() {
+        assert!(!is_suspicious("invoice.pdf"));
+        assert!(!is_suspicious("setup.exe"));
+        assert!(!is_suspicious("archive.tar.gz"));
+        assert!(!is_suspicious(".pdf.exe"));
+    }
+
+    #[test]
+    fn trailing_space() {
+        assert!(is_suspicious("notes.txt "));
+    }
+
+    #[test]
+    fn bidi_override() {
+        assert!(is_suspicious("invoice\u{202E}fdp.exe"));
+    }
+
+    #[test]
+    fn homoglyphs() {
+        assert!(is_suspicious("p\u{0430}ypal.html"));
+        assert!(!is_suspicious("документ.txt"));
+    }
+
+    #[test]
+    fn double_extension() {
+        assert!(is_suspicious("invoice.pdf.exe"));
+        assert!(is_suspicious("Photo.JPG.scr"));
+        assert!(is_suspicious("invoice.pdf    .exe"));
+    }
+}
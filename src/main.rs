@@ -24,18 +24,26 @@
 use std::env;
 use std::ffi::{OsStr, OsString};
 use std::io::{self, ErrorKind, IsTerminal, Write};
-use std::path::{Component, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::process::exit;
+use std::sync::{Mutex, PoisonError};
 
 use ansiterm::{ANSIStrings, Style};
 
 use log::*;
+use scoped_threadpool::Pool;
 
+use crate::fs::dir_action::{Descent, RecurseOptions};
 use crate::fs::feature::git::GitCache;
+use crate::fs::fields as f;
 use crate::fs::filter::GitIgnore;
-use crate::fs::{Dir, File};
+use crate::fs::{readahead, snapshot, Dir, DirIdentity, File};
 use crate::options::{vars, Options, OptionsResult, Vars};
-use crate::output::{details, escape, file_name, grid, grid_details, lines, Mode, View};
+use crate::output::table::{self, SizeFormat};
+use crate::output::{
+    details, diff, escape, file_name, grid, grid_details, lines, prompt, Flush, Mode, Tally,
+    TreeStyle, Unreadable, View,
+};
 use crate::theme::Theme;
 
 mod fs;
@@ -60,7 +68,23 @@ fn main() {
 
     let stdout_istty = io::stdout().is_terminal();
 
-    let args: Vec<_> = env::args_os().skip(1).collect();
+    let args = match options::subcommand::expand(env::args_os().skip(1).collect()) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("eza: {error}");
+            exit(exits::OPTIONS_ERROR);
+        }
+    };
+    let args = match options::preset::expand(args, &LiveVars) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("eza: {error}");
+            eprintln!("{}", error.suggestion());
+            exit(exits::OPTIONS_ERROR);
+        }
+    };
+
+    let args = options::remember::apply(args, &LiveVars);
     match Options::parse(args.iter().map(std::convert::AsRef::as_ref), &LiveVars) {
         OptionsResult::Ok(options, mut input_paths) => {
             // List the current directory by default.
@@ -69,11 +93,28 @@ fn main() {
                 input_paths = vec![OsStr::new(".")];
             }
 
+            for warning in &options.warnings {
+                eprintln!("eza: warning: {warning}");
+
+                if let Some(s) = warning.suggestion() {
+                    eprintln!("{s}");
+                }
+            }
+
             let git = git_options(&options, &input_paths);
-            let writer = io::stdout();
+            let writer: Box<dyn Write> = match options.view.flush {
+                Flush::Line => Box::new(io::stdout()),
+                Flush::Dir | Flush::End => Box::new(io::BufWriter::new(io::stdout().lock())),
+            };
 
             let console_width = options.view.width.actual_terminal_width();
             let theme = options.theme.to_theme(stdout_istty);
+            let unreadable = Unreadable::new(options.view.inline_errors);
+            let threads = options.threads.count(Path::new(input_paths[0]));
+            fs::set_walk_threads(threads);
+            if options.reads_birth_times() {
+                fs::metadata::request_birth_times();
+            }
             let exa = Exa {
                 options,
                 writer,
@@ -81,6 +122,9 @@ fn main() {
                 theme,
                 console_width,
                 git,
+                unreadable,
+                threads,
+                tally: None,
             };
 
             info!("matching on exa.run");
@@ -128,8 +172,9 @@ pub struct Exa<'args> {
     /// List of command-line options, having been successfully parsed.
     pub options: Options,
 
-    /// The output handle that we write to.
-    pub writer: io::Stdout,
+    /// The output handle that we write to, which is buffered unless it gets
+    /// flushed after every line.
+    pub writer: Box<dyn Write>,
 
     /// List of the free command-line arguments that should correspond to file
     /// names (anything that isn’t an option).
@@ -148,6 +193,18 @@ pub struct Exa<'args> {
     /// This has to last the lifetime of the program, because the user might
     /// want to list several directories in the same repository.
     pub git: Option<GitCache>,
+
+    /// The directories that couldn’t be read while recursing, which get
+    /// listed once everything else has been.
+    pub unreadable: Unreadable,
+
+    /// How many threads to look up files with, worked out once from the
+    /// first path given.
+    pub threads: u32,
+
+    /// The totals for `--summary` and `--git-summary`, which get added to
+    /// as the entries are listed.
+    pub tally: Option<Mutex<Tally>>,
 }
 
 /// The “real” environment variables type.
@@ -164,7 +221,9 @@ impl Vars for LiveVars {
 /// listed before they’re actually listed, if the options demand it.
 fn git_options(options: &Options, args: &[&OsStr]) -> Option<GitCache> {
     if options.should_scan_for_git() {
-        Some(args.iter().map(PathBuf::from).collect())
+        let disk_cache = env::var_os(vars::EZA_GIT_CACHE).map_or(false, |v| !v.is_empty());
+        let git: GitCache = args.iter().map(PathBuf::from).collect();
+        Some(git.with_disk_cache(disk_cache))
     } else {
         None
     }
@@ -177,8 +236,29 @@ impl<'args> Exa<'args> {
     pub fn run(mut self) -> io::Result<i32> {
         debug!("Running with options: {:#?}", self.options);
 
+        // These tell the terminal where the output of the command is, with
+        // the exit status at the end, so it can be treated as one block.
+        if self.options.view.semantic_markers {
+            write!(self.writer, "\x1B]133;C\x07")?;
+        }
+
+        let mut exit_status = self.list()?;
+        if exit_status == exits::SUCCESS && !self.unreadable.is_empty() {
+            exit_status = exits::UNREADABLE_DIRS;
+        }
+
+        if self.options.view.semantic_markers {
+            write!(self.writer, "\x1B]133;D;{exit_status}\x07")?;
+        }
+
+        self.writer.flush()?;
+        self.unreadable.print_summary(&mut io::stderr())?;
+        Ok(exit_status)
+    }
+
+    fn list(&mut self) -> io::Result<i32> {
         let mut files = Vec::new();
-        let mut dirs = Vec::new();
+        let mut dir_args = Vec::new();
         let mut exit_status = 0;
 
         for file_path in &self.input_paths {
@@ -194,17 +274,15 @@ impl<'args> Exa<'args> {
                     writeln!(io::stderr(), "{file_path:?}: {e}")?;
                 }
 
-                Ok(f) => {
-                    if f.points_to_directory() && !self.options.dir_action.treat_dirs_as_files() {
-                        trace!("matching on to_dir");
-                        match f.to_dir() {
-                            Ok(d) => dirs.push(d),
-                            Err(e) if e.kind() == ErrorKind::PermissionDenied => {
-                                eprintln!("{file_path:?}: {e}");
-                                exit(exits::PERMISSION_DENIED);
-                            }
-                            Err(e) => writeln!(io::stderr(), "{file_path:?}: {e}")?,
-                        }
+                Ok(mut f) => {
+                    if self.options.view.deref_command_line {
+                        f = f.follow_symlink(self.options.view.total_size);
+                    }
+
+                    if f.points_to_directory()
+                        && !self.options.dir_action.treat_arg_as_file(&f.path)
+                    {
+                        dir_args.push((*file_path, f));
                     } else {
                         files.push(f);
                     }
@@ -212,6 +290,20 @@ impl<'args> Exa<'args> {
             }
         }
 
+        self.read_ahead(&files, &dir_args);
+
+        let mut dirs = Vec::new();
+        for (file_path, result) in self.read_dirs(dir_args) {
+            match result {
+                Ok(d) => dirs.push(d),
+                Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+                    eprintln!("{file_path:?}: {e}");
+                    exit(exits::PERMISSION_DENIED);
+                }
+                Err(e) => writeln!(io::stderr(), "{file_path:?}: {e}")?,
+            }
+        }
+
         // We want to print a directory’s name before we list it, *except* in
         // the case where it’s the only directory, *except* if there are any
         // files to print as well. (It’s a double negative)
@@ -220,15 +312,130 @@ impl<'args> Exa<'args> {
         let is_only_dir = dirs.len() == 1 && no_files;
 
         self.options.filter.filter_argument_files(&mut files);
-        self.print_files(None, files)?;
 
-        self.print_dirs(dirs, no_files, is_only_dir, exit_status)
+        if self.options.view.count_only {
+            return self.print_count(&files, &dirs, exit_status);
+        }
+
+        if self.options.view.prompt {
+            return self.print_prompt(files, &dirs, exit_status);
+        }
+
+        if let Some(recurse) = self.options.dir_action.recurse_options() {
+            if recurse.tree && self.options.view.diff {
+                return self.print_diff(&files, recurse, exit_status);
+            }
+        }
+
+        let git_summary = self.options.view.git_summary && self.options.shows_git_column();
+        if self.options.view.summary || git_summary {
+            self.tally = Some(Mutex::new(self.new_tally()));
+        }
+
+        self.print_files(None, files, false)?;
+        self.flush_directory()?;
+
+        let exit_status =
+            self.print_dirs(dirs, &[], &mut { no_files }, is_only_dir, exit_status)?;
+
+        if let Some(tally) = self.tally.take() {
+            let tally = tally.into_inner().unwrap_or_else(PoisonError::into_inner);
+            writeln!(&mut self.writer)?;
+            if self.options.view.summary {
+                self.print_summary(&tally)?;
+            }
+            if git_summary {
+                self.print_git_summary(&tally)?;
+            }
+        }
+
+        if let Some(snapshot_file) = &self.options.view.save_snapshot {
+            let paths = self
+                .input_paths
+                .iter()
+                .map(PathBuf::from)
+                .collect::<Vec<_>>();
+            if let Err(e) = snapshot::save(snapshot_file, &paths, self.options.view.update_snapshot)
+            {
+                writeln!(io::stderr(), "{snapshot_file:?}: {e}")?;
+            }
+        }
+
+        Ok(exit_status)
+    }
+
+    /// Starts reading the directories beneath the ones given as arguments
+    /// on other threads, when they’re going to be recursed into, so they
+    /// don’t have to be read one after another. Trees get their directories
+    /// given as files.
+    fn read_ahead(&self, files: &[File<'_>], dir_args: &[(&OsStr, File<'_>)]) {
+        let Some(recurse) = self.options.dir_action.recurse_options() else {
+            return;
+        };
+
+        let roots = files
+            .iter()
+            .filter(|file| recurse.tree && file.points_to_directory())
+            .chain(dir_args.iter().map(|(_, file)| file))
+            .map(|file| file.path.to_path_buf())
+            .collect::<Vec<_>>();
+
+        let filter = &self.options.filter;
+        let options = readahead::Options {
+            max_depth: recurse.max_depth,
+            dotfiles: filter.dot_filter.shows_dotfiles(),
+            prune: filter.prune_patterns.clone(),
+            one_file_system: recurse.one_file_system,
+        };
+        readahead::start(roots, self.threads, options);
+    }
+
+    /// Reads the directories given as arguments. When there are several,
+    /// as there often are when eza is run from a script, they get read at
+    /// the same time, though the results stay in the order they were given.
+    fn read_dirs(
+        &self,
+        dir_args: Vec<(&'args OsStr, File<'_>)>,
+    ) -> Vec<(&'args OsStr, io::Result<Dir>)> {
+        trace!("matching on to_dir");
+        let threads = self.threads.min(dir_args.len() as u32);
+
+        let mut results = dir_args
+            .iter()
+            .map(|_| Err(ErrorKind::Other.into()))
+            .collect::<Vec<_>>();
+
+        if threads > 1 {
+            debug!(
+                "Reading {} directories with {threads} threads",
+                dir_args.len()
+            );
+            Pool::new(threads).scoped(|scoped| {
+                for ((_, file), result) in dir_args.iter().zip(&mut results) {
+                    scoped.execute(move || *result = file.to_dir());
+                }
+            });
+        } else {
+            for ((_, file), result) in dir_args.iter().zip(&mut results) {
+                *result = file.to_dir();
+            }
+        }
+
+        dir_args
+            .into_iter()
+            .map(|(file_path, _)| file_path)
+            .zip(results)
+            .collect()
     }
 
+    /// Lists the directories, and the ones inside them when recursing. The
+    /// ancestors are the directories that have been descended into to get
+    /// to them, which are only kept track of when links are being followed.
     fn print_dirs(
         &mut self,
         dir_files: Vec<Dir>,
-        mut first: bool,
+        ancestors: &[DirIdentity],
+        first: &mut bool,
         is_only_dir: bool,
         exit_status: i32,
     ) -> io::Result<i32> {
@@ -237,67 +444,93 @@ impl<'args> Exa<'args> {
             ..
         } = self.options.view;
         for dir in dir_files {
-            // Put a gap between directories, or between the list of files and
-            // the first directory.
-            if first {
-                first = false;
-            } else {
-                writeln!(&mut self.writer)?;
-            }
-
-            if !is_only_dir {
-                let mut bits = Vec::new();
-                escape(
-                    dir.path.display().to_string(),
-                    &mut bits,
-                    Style::default(),
-                    Style::default(),
-                    quote_style,
-                );
-                writeln!(&mut self.writer, "{}:", ANSIStrings(&bits))?;
-            }
+            let depth = dir
+                .path
+                .components()
+                .filter(|&c| c != Component::CurDir)
+                .count()
+                + 1;
+
+            // Directories above the minimum depth only get recursed into.
+            // (Trees leave out those levels when they get rendered instead.)
+            let listed = self
+                .options
+                .dir_action
+                .recurse_options()
+                .map_or(true, |r| r.tree || !r.is_too_shallow(depth));
+
+            if listed {
+                // Put a gap between directories, or between the list of files
+                // and the first directory.
+                if *first {
+                    *first = false;
+                } else {
+                    writeln!(&mut self.writer)?;
+                }
 
-            let mut children = Vec::new();
-            let git_ignore = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
-            for file in dir.files(
-                self.options.filter.dot_filter,
-                self.git.as_ref(),
-                git_ignore,
-                self.options.view.deref_links,
-                self.options.view.total_size,
-            ) {
-                match file {
-                    Ok(file) => children.push(file),
-                    Err((path, e)) => writeln!(io::stderr(), "[{}: {}]", path.display(), e)?,
+                if !is_only_dir {
+                    let mut bits = Vec::new();
+                    escape(
+                        dir.path.display().to_string(),
+                        &mut bits,
+                        Style::default(),
+                        Style::default(),
+                        quote_style,
+                    );
+                    writeln!(&mut self.writer, "{}:", ANSIStrings(&bits))?;
                 }
             }
 
-            self.options.filter.filter_child_files(&mut children);
+            let mut children = self.read_children(&dir)?;
             self.options.filter.sort_files(&mut children);
 
             if let Some(recurse_opts) = self.options.dir_action.recurse_options() {
-                let depth = dir
-                    .path
-                    .components()
-                    .filter(|&c| c != Component::CurDir)
-                    .count()
-                    + 1;
                 if !recurse_opts.tree && !recurse_opts.is_too_deep(depth) {
+                    // Directories left out by the limit aren’t recursed into
+                    // either, but the ones that are only there to be recursed
+                    // into don’t count towards it.
+                    let filter = &self.options.filter;
+                    let mut shown = self.options.view.limit.map_or(usize::MAX, |l| l.count);
+                    let within_limit = |f: &&File<'_>| {
+                        if !filter.is_listed(f) {
+                            return true;
+                        }
+                        let within = shown > 0;
+                        shown = shown.saturating_sub(1);
+                        within
+                    };
+                    let ancestors = if recurse_opts.tracks_ancestors() {
+                        let mut ancestors = ancestors.to_vec();
+                        ancestors.extend(Dir::identity(&dir.path));
+                        ancestors
+                    } else {
+                        Vec::new()
+                    };
+
                     let mut child_dirs = Vec::new();
-                    for child_dir in children
-                        .iter()
-                        .filter(|f| f.is_directory() && !f.is_all_all)
-                    {
+                    let descended = |f: &&File<'_>| !f.is_all_all && !filter.is_pruned(f);
+                    for child_dir in children.iter().filter(within_limit).filter(descended) {
+                        match recurse_opts.descent(child_dir, &ancestors) {
+                            Descent::Skip => continue,
+                            Descent::Cycle => {
+                                writeln!(io::stderr(), "{}: [cycle]", child_dir.path.display())?;
+                                continue;
+                            }
+                            Descent::Into(_) => {}
+                        }
+
                         match child_dir.to_dir() {
                             Ok(d) => child_dirs.push(d),
-                            Err(e) => {
-                                writeln!(io::stderr(), "{}: {}", child_dir.path.display(), e)?;
-                            }
+                            Err(e) => self.unreadable.add(&child_dir.path, &e)?,
                         }
                     }
 
-                    self.print_files(Some(&dir), children)?;
-                    match self.print_dirs(child_dirs, false, false, exit_status) {
+                    if listed {
+                        children.retain(|f| self.options.filter.is_listed(f));
+                        self.print_files(Some(&dir), children, true)?;
+                        self.flush_directory()?;
+                    }
+                    match self.print_dirs(child_dirs, &ancestors, first, false, exit_status) {
                         Ok(_) => (),
                         Err(e) => return Err(e),
                     }
@@ -305,18 +538,348 @@ impl<'args> Exa<'args> {
                 }
             }
 
-            self.print_files(Some(&dir), children)?;
+            if listed {
+                // At the depth limit, the directories that were only kept to
+                // be recursed into aren’t going to be, so they go too.
+                if (self.options.dir_action.recurse_options()).is_some_and(|r| !r.tree) {
+                    children.retain(|f| self.options.filter.is_listed(f));
+                }
+                self.print_files(Some(&dir), children, false)?;
+                self.flush_directory()?;
+            }
+        }
+
+        Ok(exit_status)
+    }
+
+    /// Sends what’s been written so far on its way if the output gets
+    /// flushed after each directory, as it’s otherwise kept in a buffer.
+    fn flush_directory(&mut self) -> io::Result<()> {
+        if self.options.view.flush == Flush::Dir {
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Prints the number of entries that would have been listed, and their
+    /// total size, without rendering any of them.
+    fn print_count(
+        &mut self,
+        files: &[File<'_>],
+        dirs: &[Dir],
+        exit_status: i32,
+    ) -> io::Result<i32> {
+        let tally = self.tally(files, dirs)?;
+        writeln!(&mut self.writer, "{} {}", tally.count, tally.size)?;
+        Ok(exit_status)
+    }
+
+    /// Prints the number of files and directories that were listed, their
+    /// total size, and how many have changes in Git, after the listing.
+    fn print_summary(&mut self, tally: &Tally) -> io::Result<()> {
+        let numeric =
+            locale::Numeric::load_user_locale().unwrap_or_else(|_| locale::Numeric::english());
+        let plural = |count: usize, one: &str, many: &str| {
+            let noun = if count == 1 { one } else { many };
+            format!("{} {noun}", numeric.format_int(count))
+        };
+
+        let size_format = self.table_options().map(|t| t.size_format);
+        let size = f::Size::Some(tally.size).render(
+            &self.theme,
+            size_format.unwrap_or(SizeFormat::DecimalBytes),
+            &numeric,
+        );
+
+        let mut parts = vec![
+            plural(tally.count - tally.dirs, "file", "files"),
+            plural(tally.dirs, "directory", "directories"),
+            ANSIStrings(&size.contents).to_string(),
+        ];
+
+        if self.options.shows_git_column() {
+            parts.push(format!("{} modified", numeric.format_int(tally.modified)));
+            parts.push(format!("{} untracked", numeric.format_int(tally.untracked)));
+        }
+
+        writeln!(&mut self.writer, "{}", parts.join(", "))
+    }
+
+    /// Prints how many of the entries that were listed have each kind of
+    /// change in Git, after the listing.
+    fn print_git_summary(&mut self, tally: &Tally) -> io::Result<()> {
+        let numeric =
+            locale::Numeric::load_user_locale().unwrap_or_else(|_| locale::Numeric::english());
+
+        let parts = [
+            (tally.unstaged, "modified"),
+            (tally.untracked, "untracked"),
+            (tally.staged, "staged"),
+            (tally.conflicted, "conflicted"),
+        ]
+        .into_iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, kind)| format!("{} {kind}", numeric.format_int(count)))
+        .collect::<Vec<_>>();
+
+        if parts.is_empty() {
+            writeln!(&mut self.writer, "No changes")
+        } else {
+            writeln!(&mut self.writer, "{}", parts.join(", "))
+        }
+    }
+
+    /// Adds up the entries that would be listed, in the same way that the
+    /// listing would go through them, for when nothing actually is.
+    fn tally(&self, files: &[File<'_>], dirs: &[Dir]) -> io::Result<Tally> {
+        let mut tally = self.new_tally();
+        let tree = self
+            .options
+            .dir_action
+            .recurse_options()
+            .map_or(false, |r| r.tree);
+
+        for file in files {
+            // In tree mode, directory arguments are listed as the roots of
+            // their trees rather than as directories of their own.
+            if tree && file.points_to_directory() {
+                tally.add_unsized(file, self.git.as_ref());
+                match file.to_dir() {
+                    Ok(d) => self.count_dir(&d, 0, &mut tally)?,
+                    Err(e) => writeln!(io::stderr(), "{}: {}", file.path.display(), e)?,
+                }
+            } else {
+                tally.add(file, self.git.as_ref());
+            }
+        }
+
+        for dir in dirs {
+            self.count_dir(dir, 0, &mut tally)?;
+        }
+
+        Ok(tally)
+    }
+
+    /// The options for the table, if the view has one.
+    fn table_options(&self) -> Option<&table::Options> {
+        match &self.options.view.mode {
+            Mode::Details(opts) => opts.table.as_ref(),
+            Mode::GridDetails(opts) => opts.details.table.as_ref(),
+            Mode::Grid(_) | Mode::Lines => None,
+        }
+    }
+
+    /// An empty tally, which counts Git statuses when they would be shown.
+    fn new_tally(&self) -> Tally {
+        let disk_usage = self.table_options().map_or(false, |t| t.disk_usage);
+        Tally::new(disk_usage, self.options.shows_git_column())
+    }
+
+    /// Prints a one-line summary of the argument files and the contents of
+    /// the argument directories, for use in shell prompts.
+    fn print_prompt<'dir>(
+        &mut self,
+        mut files: Vec<File<'dir>>,
+        dirs: &'dir [Dir],
+        exit_status: i32,
+    ) -> io::Result<i32> {
+        for dir in dirs {
+            files.extend(self.read_children(dir)?);
         }
 
+        let git = self.git.as_ref().and_then(|g| {
+            dirs.iter()
+                .find(|d| g.has_anything_for(&d.path))
+                .map(|d| g.get(&d.path, true))
+        });
+
+        let r = prompt::Render {
+            files,
+            theme: &self.theme,
+            git,
+        };
+        r.render(&mut self.writer)?;
+        Ok(exit_status)
+    }
+
+    /// Prints one tree comparing the two directories that were given as
+    /// arguments, which have to be the only arguments. (Trees list their
+    /// arguments as files, to put them at the root.)
+    fn print_diff(
+        &mut self,
+        files: &[File<'_>],
+        recurse: RecurseOptions,
+        exit_status: i32,
+    ) -> io::Result<i32> {
+        let [a, b] = files else {
+            writeln!(io::stderr(), "eza: --diff needs two directories to compare")?;
+            return Ok(exits::OPTIONS_ERROR);
+        };
+        let (a, b) = match (a.to_dir(), b.to_dir()) {
+            (Ok(a), Ok(b)) => (a, b),
+            (Err(e), _) => {
+                writeln!(io::stderr(), "{}: {}", a.path.display(), e)?;
+                return Ok(exits::OPTIONS_ERROR);
+            }
+            (_, Err(e)) => {
+                writeln!(io::stderr(), "{}: {}", b.path.display(), e)?;
+                return Ok(exits::OPTIONS_ERROR);
+            }
+        };
+
+        let tree_style = match &self.options.view.mode {
+            Mode::Details(opts) => opts.tree_style,
+            Mode::GridDetails(opts) => opts.details.tree_style,
+            Mode::Grid(_) | Mode::Lines => TreeStyle::default(),
+        };
+
+        let r = diff::Render {
+            dirs: (&a, &b),
+            theme: &self.theme,
+            file_style: &self.options.view.file_style,
+            filter: &self.options.filter,
+            recurse,
+            tree_style,
+            git: self.git.as_ref(),
+            deref_links: self.options.view.deref_links,
+            unreadable: &self.unreadable,
+            only_changes: self.options.view.only_changes,
+        };
+        r.render(&mut self.writer)?;
         Ok(exit_status)
     }
 
-    /// Prints the list of files using whichever view is selected.
-    fn print_files(&mut self, dir: Option<&Dir>, files: Vec<File<'_>>) -> io::Result<()> {
+    /// Adds the entries of a directory to the tally, recursing into its
+    /// subdirectories if the listing would have done so.
+    fn count_dir(&self, dir: &Dir, depth: usize, tally: &mut Tally) -> io::Result<()> {
+        let children = self.read_children(dir)?;
+
+        let recurse = self
+            .options
+            .dir_action
+            .recurse_options()
+            .filter(|r| !r.is_too_deep(depth + 1));
+
+        for child in &children {
+            let descended = !child.is_all_all && !self.options.filter.is_pruned(child);
+            if recurse.is_some() && child.is_directory() && descended {
+                // Directories that get descended into aren’t sized, so that
+                // their contents don’t get counted twice with --total-size.
+                if self.options.filter.is_listed(child) {
+                    tally.add_unsized(child, self.git.as_ref());
+                }
+                match child.to_dir() {
+                    Ok(d) => self.count_dir(&d, depth + 1, tally)?,
+                    Err(e) => self.unreadable.add(&child.path, &e)?,
+                }
+            } else if self.options.filter.is_listed(child) {
+                tally.add(child, self.git.as_ref());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the files in a directory that pass the filter, printing any
+    /// errors encountered along the way.
+    fn read_children<'dir>(&self, dir: &'dir Dir) -> io::Result<Vec<File<'dir>>> {
+        let mut children = Vec::new();
+        let git_ignore = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
+        for file in dir.files(
+            self.options.filter.dot_filter,
+            self.options.filter.hidden_mode,
+            self.git.as_ref(),
+            git_ignore,
+            self.options.view.deref_links,
+            self.options.view.total_size,
+        ) {
+            match file {
+                Ok(file) => children.push(file),
+                Err((path, e)) => writeln!(io::stderr(), "[{}: {}]", path.display(), e)?,
+            }
+        }
+
+        self.options.filter.filter_child_files(&mut children);
+        Ok(children)
+    }
+
+    /// Prints the list of files using whichever view is selected, adding
+    /// them to the tally if there is one. When recursing, the directories
+    /// among them are going to be listed next.
+    fn print_files(
+        &mut self,
+        dir: Option<&Dir>,
+        mut files: Vec<File<'_>>,
+        recursing: bool,
+    ) -> io::Result<()> {
         if files.is_empty() {
+            // Only directories get a placeholder: an empty list of
+            // command-line arguments isn’t an empty directory.
+            if dir.is_some() && self.options.view.empty_placeholder {
+                let placeholder = self.theme.ui.empty_placeholder.paint("<empty>");
+                writeln!(&mut self.writer, "{placeholder}")?;
+            }
             return Ok(());
         }
 
+        // The views sort the files themselves, but they have to be sorted
+        // first here to know which ones to leave out.
+        let mut hidden = 0;
+        if let Some(limit) = self.options.view.limit {
+            if files.len() > limit.count {
+                self.options.filter.sort_files(&mut files);
+                hidden = files.len() - limit.count;
+                files.truncate(limit.count);
+            }
+        }
+
+        // Trees count their entries as they add them, as most of them are
+        // beneath these ones.
+        let tree = (self.options.dir_action.recurse_options()).map_or(false, |r| r.tree);
+        if let Some(tally) = self.tally.as_mut().filter(|_| !tree) {
+            let tally = tally.get_mut().unwrap_or_else(PoisonError::into_inner);
+            let filter = &self.options.filter;
+            for file in &files {
+                // Directories that get descended into aren’t sized, so that
+                // their contents don’t get counted twice with --total-size.
+                if recursing && file.is_directory() && !file.is_all_all && !filter.is_pruned(file) {
+                    tally.add_unsized(file, self.git.as_ref());
+                } else {
+                    tally.add(file, self.git.as_ref());
+                }
+            }
+        }
+
+        match self.options.view.group_by {
+            Some(group_by) => {
+                for (i, group) in group_by.split(files).into_iter().enumerate() {
+                    if i > 0 {
+                        writeln!(&mut self.writer)?;
+                    }
+                    let header = self.theme.ui.group_header.paint(group.header());
+                    writeln!(&mut self.writer, "{header}")?;
+                    self.render_files(dir, group.files)?;
+                }
+            }
+            None => self.render_files(dir, files)?,
+        }
+
+        if hidden > 0 && self.options.view.limit.map_or(false, |l| l.marker) {
+            let numeric =
+                locale::Numeric::load_user_locale().unwrap_or_else(|_| locale::Numeric::english());
+            let marker = format!("… and {} more", numeric.format_int(hidden));
+            writeln!(
+                &mut self.writer,
+                "{}",
+                self.theme.ui.punctuation.paint(marker)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Renders the list of files using whichever view is selected.
+    fn render_files(&mut self, dir: Option<&Dir>, files: Vec<File<'_>>) -> io::Result<()> {
         let theme = &self.theme;
         let View {
             ref mode,
@@ -365,6 +928,13 @@ impl<'args> Exa<'args> {
                     filter,
                     git_ignoring,
                     git,
+                    unreadable: Some(&self.unreadable),
+                    tally: self
+                        .tally
+                        .as_ref()
+                        .filter(|_| recurse.map_or(false, |r| r.tree)),
+                    console_width: self.console_width,
+                    threads: self.threads,
                 };
                 r.render(&mut self.writer)
             }
@@ -390,6 +960,7 @@ impl<'args> Exa<'args> {
                     git_ignoring,
                     git,
                     console_width,
+                    threads: self.threads,
                 };
                 r.render(&mut self.writer)
             }
@@ -411,6 +982,13 @@ impl<'args> Exa<'args> {
                     filter,
                     git_ignoring,
                     git,
+                    unreadable: Some(&self.unreadable),
+                    tally: self
+                        .tally
+                        .as_ref()
+                        .filter(|_| recurse.map_or(false, |r| r.tree)),
+                    console_width: self.console_width,
+                    threads: self.threads,
                 };
                 r.render(&mut self.writer)
             }
@@ -431,4 +1009,8 @@ mod exits {
 
     /// Exit code for missing file permissions
     pub const PERMISSION_DENIED: i32 = 13;
+
+    /// Exit code for when some of the directories beneath the ones being
+    /// listed couldn’t be read, but everything else was listed.
+    pub const UNREADABLE_DIRS: i32 = 4;
 }
@@ -0,0 +1,190 @@
+//! Spotting options that get ignored because of other options, so the user
+//! can be told about them instead of the option silently doing nothing.
+//!
+//! Options from an alias come before the ones typed on the command-line, so
+//! an option is only warned about when it comes *after* the one that causes
+//! it to be ignored: `alias eza="eza --across"` shouldn’t make every
+//! `eza --long` complain. In strict mode, the combination is an error
+//! whichever order it comes in.
+
+use std::fmt;
+
+use crate::options::parser::{Arg, MatchedFlags};
+use crate::options::{flags, OptionsError};
+use crate::output::{Mode, View};
+
+/// An option that was given, but that won’t do anything because of another
+/// option that came before it.
+#[derive(PartialEq, Eq, Debug)]
+pub struct OptionsWarning {
+    /// The option that gets ignored.
+    pub ignored: &'static Arg,
+
+    /// The option that causes it to be ignored.
+    pub because: &'static Arg,
+}
+
+impl fmt::Display for OptionsWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Option {} is ignored given option {}",
+            self.ignored, self.because
+        )
+    }
+}
+
+impl OptionsWarning {
+    /// Suggests what the user might have wanted instead.
+    pub fn suggestion(&self) -> Option<&'static str> {
+        suggestion(self.ignored, self.because)
+    }
+}
+
+/// The options about how files are listed, which the summaries printed by
+/// `--count-only` and `--prompt` replace.
+static VIEW_FLAGS: &[&Arg] = &[
+    &flags::LONG,
+    &flags::GRID,
+    &flags::TREE,
+    &flags::ACROSS,
+    &flags::GRID_ROWS,
+    &flags::ONE_LINE,
+    &flags::SUMMARY,
+    &flags::GIT_SUMMARY,
+];
+
+/// Finds the options that the view that was picked ignores, returning an
+/// error for the first one in strict mode.
+pub fn check(matches: &MatchedFlags<'_>, view: &View) -> Result<Vec<OptionsWarning>, OptionsError> {
+    let mut warnings = Vec::new();
+
+    // --count-only gets checked before --prompt, so it wins
+    let summary = if view.count_only {
+        warnings.extend(ignored(matches, &flags::PROMPT, &flags::COUNT_ONLY)?);
+        Some(&flags::COUNT_ONLY)
+    } else if view.prompt {
+        Some(&flags::PROMPT)
+    } else {
+        None
+    };
+
+    if let Some(summary) = summary {
+        for option in VIEW_FLAGS {
+            warnings.extend(ignored(matches, option, summary)?);
+        }
+    } else if let Mode::Details(_) = view.mode {
+        // There are no columns to go across without --grid
+        let because = if matches.position(&flags::TREE) > matches.position(&flags::LONG) {
+            &flags::TREE
+        } else {
+            &flags::LONG
+        };
+        warnings.extend(ignored(matches, &flags::ACROSS, because)?);
+        warnings.extend(ignored(matches, &flags::GRID_ROWS, because)?);
+    }
+
+    Ok(warnings)
+}
+
+/// Checks whether an option that gets ignored because of another was given.
+fn ignored(
+    matches: &MatchedFlags<'_>,
+    ignored: &'static Arg,
+    because: &'static Arg,
+) -> Result<Option<OptionsWarning>, OptionsError> {
+    let (Some(i), Some(b)) = (matches.position(ignored), matches.position(because)) else {
+        return Ok(None);
+    };
+
+    if matches.is_strict() {
+        Err(OptionsError::Useless(ignored, true, because))
+    } else if i > b {
+        Ok(Some(OptionsWarning { ignored, because }))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Suggests what the user might have wanted when one option is ignored
+/// because of another, whether as a warning or as an error in strict mode.
+pub fn suggestion(ignored: &'static Arg, because: &'static Arg) -> Option<&'static str> {
+    if ignored == &flags::ACROSS && because == &flags::LONG {
+        Some("To show the details view in columns across, add --grid")
+    } else if ignored == &flags::GRID_ROWS && because == &flags::LONG {
+        Some("To show the details view in columns, add --grid")
+    } else if ignored == &flags::PROMPT {
+        Some("To print the prompt summary instead of the count, leave out --count-only")
+    } else if because == &flags::COUNT_ONLY {
+        Some("To list the files instead of counting them, leave out --count-only")
+    } else if because == &flags::PROMPT {
+        Some("To list the files instead of summarising them, leave out --prompt")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ffi::OsString;
+
+    use crate::options::test::parse_for_test;
+    use crate::options::test::Strictnesses::*;
+
+    static TEST_ARGS: &[&Arg] = &[
+        &flags::LONG,
+        &flags::GRID,
+        &flags::TREE,
+        &flags::ACROSS,
+        &flags::GRID_ROWS,
+        &flags::ONE_LINE,
+        &flags::COUNT_ONLY,
+        &flags::PROMPT,
+        &flags::SUMMARY,
+        &flags::GIT_SUMMARY,
+    ];
+
+    macro_rules! test {
+        ($name:ident: $inputs:expr; $stricts:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
+                    View::deduce(mf, &None::<OsString>).and_then(|view| check(mf, &view))
+                }) {
+                    assert_eq!(result, $result);
+                }
+            }
+        };
+    }
+
+    fn warning(ignored: &'static Arg, because: &'static Arg) -> OptionsWarning {
+        OptionsWarning { ignored, because }
+    }
+
+    // Nothing to warn about
+    test!(nothing:          [];                               Both => Ok(vec![]));
+    test!(grid_across:      ["--grid", "--across"];           Both => Ok(vec![]));
+    test!(long_grid_across: ["--long", "--grid", "--across"]; Both => Ok(vec![]));
+    test!(long_grid_rows:   ["-lG", "--grid-rows=5"];         Both => Ok(vec![]));
+    test!(overridden_view:  ["--long", "--oneline"];          Both => Ok(vec![]));
+
+    // Ignored options that come last
+    test!(long_across:      ["--long", "--across"];           Last => Ok(vec![warning(&flags::ACROSS, &flags::LONG)]));
+    test!(tree_across:      ["--tree", "--across"];           Last => Ok(vec![warning(&flags::ACROSS, &flags::TREE)]));
+    test!(long_rows:        ["--long", "--grid-rows=5"];      Last => Ok(vec![warning(&flags::GRID_ROWS, &flags::LONG)]));
+    test!(count_prompt:     ["--count-only", "--prompt"];     Last => Ok(vec![warning(&flags::PROMPT, &flags::COUNT_ONLY)]));
+    test!(prompt_long:      ["--prompt", "--long"];           Last => Ok(vec![warning(&flags::LONG, &flags::PROMPT)]));
+    test!(count_summary:    ["--count-only", "--summary"];    Last => Ok(vec![warning(&flags::SUMMARY, &flags::COUNT_ONLY)]));
+    test!(prompt_git:       ["--prompt", "--git-summary"];    Last => Ok(vec![warning(&flags::GIT_SUMMARY, &flags::PROMPT)]));
+
+    // Ignored options that come first, as though from an alias
+    test!(across_long:      ["--across", "--long"];           Last => Ok(vec![]));
+    test!(prompt_count:     ["--prompt", "--count-only"];     Last => Ok(vec![]));
+    test!(long_prompt:      ["--long", "--prompt"];           Last => Ok(vec![]));
+
+    // Errors in strict mode, whichever order they’re in
+    test!(tree_across_2:    ["--tree", "--across"];           Complain => Err(OptionsError::Useless(&flags::ACROSS, true, &flags::TREE)));
+    test!(prompt_count_2:   ["--prompt", "--count-only"];     Complain => Err(OptionsError::Useless(&flags::PROMPT, true, &flags::COUNT_ONLY)));
+    test!(long_prompt_2:    ["--long", "--prompt"];           Complain => Err(OptionsError::Useless(&flags::LONG, true, &flags::PROMPT)));
+}
@@ -1,6 +1,6 @@
 //! Parsing the options for `DirAction`.
 
-use crate::options::parser::MatchedFlags;
+use crate::options::parser::{Arg, MatchedFlags};
 use crate::options::{flags, NumberSource, OptionsError};
 
 use crate::fs::dir_action::{DirAction, RecurseOptions};
@@ -10,10 +10,18 @@ impl DirAction {
     /// There are three possible actions, and they overlap somewhat: the
     /// `--tree` flag is another form of recursion, so those two are allowed
     /// to both be present, but the `--list-dirs` flag is used separately.
+    /// When they’re mixed, `--list-dirs` only wins if it comes last.
     pub fn deduce(matches: &MatchedFlags<'_>, can_tree: bool) -> Result<Self, OptionsError> {
         let recurse = matches.has(&flags::RECURSE)?;
-        let as_file = matches.has(&flags::LIST_DIRS)?;
         let tree = matches.has(&flags::TREE)?;
+        let flat = matches.has(&flags::FLAT)?;
+
+        let list_dirs_auto = match matches.get(&flags::LIST_DIRS)? {
+            None => false,
+            Some(word) if word == "auto" => true,
+            Some(word) => return Err(OptionsError::BadArgument(&flags::LIST_DIRS, word.into())),
+        };
+        let as_file = list_dirs_auto || matches.has(&flags::LIST_DIRS)?;
 
         if matches.is_strict() {
             // Early check for --level when it wouldn’t do anything
@@ -23,6 +31,74 @@ impl DirAction {
                     &flags::RECURSE,
                     &flags::TREE,
                 ));
+            } else if !recurse && !tree && matches.count(&flags::MIN_DEPTH) > 0 {
+                return Err(OptionsError::Useless2(
+                    &flags::MIN_DEPTH,
+                    &flags::RECURSE,
+                    &flags::TREE,
+                ));
+            } else if !recurse && !tree && matches.has(&flags::FOLLOW_LINKS)? {
+                return Err(OptionsError::Useless2(
+                    &flags::FOLLOW_LINKS,
+                    &flags::RECURSE,
+                    &flags::TREE,
+                ));
+            } else if !recurse && !tree && flat {
+                return Err(OptionsError::Useless2(
+                    &flags::FLAT,
+                    &flags::RECURSE,
+                    &flags::TREE,
+                ));
+            } else if !recurse && !tree && matches.has(&flags::ONE_FILE_SYSTEM)? {
+                return Err(OptionsError::Useless2(
+                    &flags::ONE_FILE_SYSTEM,
+                    &flags::RECURSE,
+                    &flags::TREE,
+                ));
+            } else if !recurse && !tree && matches.has(&flags::INLINE_ERRORS)? {
+                return Err(OptionsError::Useless2(
+                    &flags::INLINE_ERRORS,
+                    &flags::RECURSE,
+                    &flags::TREE,
+                ));
+            } else if !recurse && !tree && matches.get(&flags::PRUNE_GLOB)?.is_some() {
+                return Err(OptionsError::Useless2(
+                    &flags::PRUNE_GLOB,
+                    &flags::RECURSE,
+                    &flags::TREE,
+                ));
+            } else if !tree && matches.has(&flags::COLLAPSE_CONFIGS)? {
+                return Err(OptionsError::Useless(
+                    &flags::COLLAPSE_CONFIGS,
+                    false,
+                    &flags::TREE,
+                ));
+            } else if !tree && matches.has(&flags::TREE_COUNTS)? {
+                return Err(OptionsError::Useless(
+                    &flags::TREE_COUNTS,
+                    false,
+                    &flags::TREE,
+                ));
+            } else if !tree && matches.has(&flags::COMPACT_TREE)? {
+                return Err(OptionsError::Useless(
+                    &flags::COMPACT_TREE,
+                    false,
+                    &flags::TREE,
+                ));
+            } else if !tree && matches.get(&flags::TREE_STYLE)?.is_some() {
+                return Err(OptionsError::Useless(
+                    &flags::TREE_STYLE,
+                    false,
+                    &flags::TREE,
+                ));
+            } else if !tree && matches.get(&flags::TREE_LIMIT)?.is_some() {
+                return Err(OptionsError::Useless(
+                    &flags::TREE_LIMIT,
+                    false,
+                    &flags::TREE,
+                ));
+            } else if !tree && matches.has(&flags::DIFF)? {
+                return Err(OptionsError::Useless(&flags::DIFF, false, &flags::TREE));
             } else if recurse && as_file {
                 return Err(OptionsError::Conflict(&flags::RECURSE, &flags::LIST_DIRS));
             } else if tree && as_file {
@@ -30,12 +106,27 @@ impl DirAction {
             }
         }
 
-        if tree && can_tree {
+        let as_file_last = matches
+            .last_where_any(|f| {
+                f.matches(&flags::RECURSE)
+                    || f.matches(&flags::TREE)
+                    || f.matches(&flags::LIST_DIRS)
+            })
+            .map_or(false, |f| f.matches(&flags::LIST_DIRS));
+
+        if as_file_last && list_dirs_auto {
+            Ok(Self::AsFileUnlessSlash)
+        } else if as_file_last {
+            Ok(Self::AsFile)
+        } else if (tree || recurse && flat) && can_tree {
             // Tree is only appropriate in details mode, so this has to
-            // examine the View, which should have already been deduced by now
+            // examine the View, which should have already been deduced by now.
+            // Flat listings are trees without their branches.
             Ok(Self::Recurse(RecurseOptions::deduce(matches, true)?))
         } else if recurse {
             Ok(Self::Recurse(RecurseOptions::deduce(matches, false)?))
+        } else if as_file && list_dirs_auto {
+            Ok(Self::AsFileUnlessSlash)
         } else if as_file {
             Ok(Self::AsFile)
         } else {
@@ -46,27 +137,37 @@ impl DirAction {
 
 impl RecurseOptions {
     /// Determine which files should be recursed into, based on the `--level`
-    /// flag’s value, and whether the `--tree` flag was passed, which was
-    /// determined earlier. The maximum level should be a number, and this
-    /// will fail with an `Err` if it isn’t.
+    /// and `--min-depth` flags’ values, whether links should be followed or
+    /// other filesystems left alone,
+    /// whether the `--tree` flag was passed, which was determined earlier,
+    /// and whether to draw the tree flat. The levels should be numbers, and
+    /// this will fail with an `Err` if they aren’t.
     pub fn deduce(matches: &MatchedFlags<'_>, tree: bool) -> Result<Self, OptionsError> {
-        if let Some(level) = matches.get(&flags::LEVEL)? {
-            let arg_str = level.to_string_lossy();
-            match arg_str.parse() {
-                Ok(l) => Ok(Self {
-                    tree,
-                    max_depth: Some(l),
-                }),
-                Err(e) => {
-                    let source = NumberSource::Arg(&flags::LEVEL);
-                    Err(OptionsError::FailedParse(arg_str.to_string(), source, e))
-                }
+        Ok(Self {
+            tree,
+            max_depth: Self::deduce_level(matches, &flags::LEVEL)?,
+            min_depth: Self::deduce_level(matches, &flags::MIN_DEPTH)?,
+            follow_links: matches.has(&flags::FOLLOW_LINKS)?,
+            one_file_system: matches.has(&flags::ONE_FILE_SYSTEM)?,
+            flat: tree && matches.has(&flags::FLAT)?,
+        })
+    }
+
+    fn deduce_level(
+        matches: &MatchedFlags<'_>,
+        flag: &'static Arg,
+    ) -> Result<Option<usize>, OptionsError> {
+        let Some(level) = matches.get(flag)? else {
+            return Ok(None);
+        };
+
+        let arg_str = level.to_string_lossy();
+        match arg_str.parse() {
+            Ok(l) => Ok(Some(l)),
+            Err(e) => {
+                let source = NumberSource::Arg(flag);
+                Err(OptionsError::FailedParse(arg_str.to_string(), source, e))
             }
-        } else {
-            Ok(Self {
-                tree,
-                max_depth: None,
-            })
         }
     }
 }
@@ -90,6 +191,18 @@ mod test {
                     &flags::LIST_DIRS,
                     &flags::TREE,
                     &flags::LEVEL,
+                    &flags::MIN_DEPTH,
+                    &flags::COLLAPSE_CONFIGS,
+                    &flags::TREE_COUNTS,
+                    &flags::COMPACT_TREE,
+                    &flags::TREE_STYLE,
+                    &flags::TREE_LIMIT,
+                    &flags::DIFF,
+                    &flags::FOLLOW_LINKS,
+                    &flags::FLAT,
+                    &flags::PRUNE_GLOB,
+                    &flags::ONE_FILE_SYSTEM,
+                    &flags::INLINE_ERRORS,
                 ];
                 for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
                     $type::deduce(mf, true)
@@ -106,29 +219,81 @@ mod test {
     // Listing files as directories
     test!(dirs_short:      DirAction <- ["-d"];           Both => Ok(DirAction::AsFile));
     test!(dirs_long:       DirAction <- ["--list-dirs"];  Both => Ok(DirAction::AsFile));
+    test!(dirs_auto:       DirAction <- ["--list-dirs=auto"];  Both => Ok(DirAction::AsFileUnlessSlash));
+    test!(dirs_auto_short: DirAction <- ["-d=auto"];           Both => Ok(DirAction::AsFileUnlessSlash));
+    test!(dirs_auto_next:  DirAction <- ["--list-dirs", "auto"];  Both => Ok(DirAction::AsFile));
+    test!(dirs_bad:        DirAction <- ["--list-dirs=always"];   Both => Err(OptionsError::BadArgument(&flags::LIST_DIRS, "always".into())));
 
     // Recursing
     use self::DirAction::Recurse;
-    test!(rec_short:       DirAction <- ["-R"];                           Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(rec_long:        DirAction <- ["--recurse"];                    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(rec_lim_short:   DirAction <- ["-RL4"];                         Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(4) })));
-    test!(rec_lim_short_2: DirAction <- ["-RL=5"];                        Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(5) })));
-    test!(rec_lim_long:    DirAction <- ["--recurse", "--level", "666"];  Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(666) })));
-    test!(rec_lim_long_2:  DirAction <- ["--recurse", "--level=0118"];    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(118) })));
-    test!(tree:            DirAction <- ["--tree"];                       Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
-    test!(rec_tree:        DirAction <- ["--recurse", "--tree"];          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
-    test!(rec_short_tree:  DirAction <- ["-TR"];                          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
+    test!(rec_short:       DirAction <- ["-R"];                           Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(rec_long:        DirAction <- ["--recurse"];                    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(rec_lim_short:   DirAction <- ["-RL4"];                         Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(4), min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(rec_lim_short_2: DirAction <- ["-RL=5"];                        Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(5), min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(rec_lim_long:    DirAction <- ["--recurse", "--level", "666"];  Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(666), min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(rec_lim_long_2:  DirAction <- ["--recurse", "--level=0118"];    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(118), min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(tree:            DirAction <- ["--tree"];                       Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(rec_tree:        DirAction <- ["--recurse", "--tree"];          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(rec_short_tree:  DirAction <- ["-TR"];                          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
 
     // Overriding --list-dirs, --recurse, and --tree
-    test!(dirs_recurse:    DirAction <- ["--list-dirs", "--recurse"];     Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(dirs_tree:       DirAction <- ["--list-dirs", "--tree"];        Last => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
+    test!(dirs_recurse:    DirAction <- ["--list-dirs", "--recurse"];     Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(dirs_tree:       DirAction <- ["--list-dirs", "--tree"];        Last => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
     test!(just_level:      DirAction <- ["--level=4"];                    Last => Ok(DirAction::List));
+    test!(recurse_dirs:    DirAction <- ["--recurse", "--list-dirs"];     Last => Ok(DirAction::AsFile));
+    test!(tree_dirs:       DirAction <- ["--tree", "--list-dirs"];        Last => Ok(DirAction::AsFile));
+    test!(tree_dirs_auto:  DirAction <- ["--tree", "--list-dirs=auto"];   Last => Ok(DirAction::AsFileUnlessSlash));
+    test!(dirs_auto_tree:  DirAction <- ["--list-dirs=auto", "--tree"];   Last => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
 
     test!(dirs_recurse_2:  DirAction <- ["--list-dirs", "--recurse"]; Complain => Err(OptionsError::Conflict(&flags::RECURSE, &flags::LIST_DIRS)));
     test!(dirs_tree_2:     DirAction <- ["--list-dirs", "--tree"];    Complain => Err(OptionsError::Conflict(&flags::TREE,    &flags::LIST_DIRS)));
     test!(just_level_2:    DirAction <- ["--level=4"];                Complain => Err(OptionsError::Useless2(&flags::LEVEL, &flags::RECURSE, &flags::TREE)));
 
+    // Minimum depths
+    test!(min_depth:       DirAction <- ["-R", "--min-depth=2"];          Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, min_depth: Some(2), follow_links: false, one_file_system: false, flat: false })));
+    test!(min_and_max:     DirAction <- ["-T", "--min-depth", "2", "-L3"];  Both => Ok(Recurse(RecurseOptions { tree: true, max_depth: Some(3), min_depth: Some(2), follow_links: false, one_file_system: false, flat: false })));
+    test!(min_depth_bad:   DirAction <- ["-R", "--min-depth=deep"];       Both => Err(OptionsError::FailedParse("deep".into(), NumberSource::Arg(&flags::MIN_DEPTH), "deep".parse::<usize>().unwrap_err())));
+    test!(just_min_depth:  DirAction <- ["--min-depth=2"];            Complain => Err(OptionsError::Useless2(&flags::MIN_DEPTH, &flags::RECURSE, &flags::TREE)));
+
+    // Following links
+    test!(follow_tree:     DirAction <- ["-T", "--follow-links"];         Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None, follow_links: true, one_file_system: false, flat: false })));
+    test!(follow_recurse:  DirAction <- ["-R", "--follow-links"];         Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, min_depth: None, follow_links: true, one_file_system: false, flat: false })));
+    test!(just_follow:     DirAction <- ["--follow-links"];               Last => Ok(DirAction::List));
+    test!(just_follow_2:   DirAction <- ["--follow-links"];           Complain => Err(OptionsError::Useless2(&flags::FOLLOW_LINKS, &flags::RECURSE, &flags::TREE)));
+
+    // Flat listings
+    test!(flat_recurse:    DirAction <- ["-R", "--flat"];                 Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: true })));
+    test!(flat_tree:       DirAction <- ["--tree", "--flat"];             Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: true })));
+    test!(just_flat:       DirAction <- ["--flat"];                       Last => Ok(DirAction::List));
+    test!(just_flat_2:     DirAction <- ["--flat"];                   Complain => Err(OptionsError::Useless2(&flags::FLAT, &flags::RECURSE, &flags::TREE)));
+
+    // Staying on one filesystem
+    test!(one_fs_tree:     DirAction <- ["-T", "--one-file-system"];      Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None, follow_links: false, one_file_system: true, flat: false })));
+    test!(one_fs_recurse:  DirAction <- ["-R", "--one-file-system"];      Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, min_depth: None, follow_links: false, one_file_system: true, flat: false })));
+    test!(just_one_fs_2:   DirAction <- ["--one-file-system"];        Complain => Err(OptionsError::Useless2(&flags::ONE_FILE_SYSTEM, &flags::RECURSE, &flags::TREE)));
+    test!(inline_errors:   DirAction <- ["-R", "--inline-errors"];        Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(just_inline_2:   DirAction <- ["--inline-errors"];          Complain => Err(OptionsError::Useless2(&flags::INLINE_ERRORS, &flags::RECURSE, &flags::TREE)));
+
+    // Pruning directories
+    test!(prune_recurse:   DirAction <- ["-R", "--prune-glob=target"];    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(just_prune_2:    DirAction <- ["--prune-glob=target"];      Complain => Err(OptionsError::Useless2(&flags::PRUNE_GLOB, &flags::RECURSE, &flags::TREE)));
+
+    // Collapsing config files
+    test!(tree_collapse:   DirAction <- ["--tree", "--collapse-configs"]; Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(just_collapse:   DirAction <- ["--collapse-configs"];           Last => Ok(DirAction::List));
+    test!(just_collapse_2: DirAction <- ["--collapse-configs"];       Complain => Err(OptionsError::Useless(&flags::COLLAPSE_CONFIGS, false, &flags::TREE)));
+
+    // Counting what’s beneath directories
+    test!(tree_counts:     DirAction <- ["-T", "--tree-counts"];          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(just_counts_2:   DirAction <- ["-R", "--tree-counts"];      Complain => Err(OptionsError::Useless(&flags::TREE_COUNTS, false, &flags::TREE)));
+    test!(compact_tree:    DirAction <- ["-T", "--compact-tree"];         Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(just_compact_2:  DirAction <- ["--compact-tree"];           Complain => Err(OptionsError::Useless(&flags::COMPACT_TREE, false, &flags::TREE)));
+    test!(just_style_2:    DirAction <- ["--tree-style=ascii"];       Complain => Err(OptionsError::Useless(&flags::TREE_STYLE, false, &flags::TREE)));
+    test!(just_limit_2:    DirAction <- ["--tree-limit=5"];           Complain => Err(OptionsError::Useless(&flags::TREE_LIMIT, false, &flags::TREE)));
+    test!(tree_diff:       DirAction <- ["-T", "--diff"];                 Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None, follow_links: false, one_file_system: false, flat: false })));
+    test!(just_diff_2:     DirAction <- ["--diff"];                   Complain => Err(OptionsError::Useless(&flags::DIFF, false, &flags::TREE)));
+
     // Overriding levels
-    test!(overriding_1:    DirAction <- ["-RL=6", "-L=7"];                Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(7) })));
+    test!(overriding_1:    DirAction <- ["-RL=6", "-L=7"];                Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(7), min_depth: None, follow_links: false, one_file_system: false, flat: false })));
     test!(overriding_2:    DirAction <- ["-RL=6", "-L=7"];            Complain => Err(OptionsError::Duplicate(Flag::Short(b'L'), Flag::Short(b'L'))));
 }
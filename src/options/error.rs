@@ -2,8 +2,8 @@ use std::ffi::OsString;
 use std::fmt;
 use std::num::ParseIntError;
 
-use crate::options::flags;
 use crate::options::parser::{Arg, Flag, ParseError};
+use crate::options::{conflicts, flags};
 
 /// Something wrong with the combination of options the user has picked.
 #[derive(PartialEq, Eq, Debug)]
@@ -39,6 +39,9 @@ pub enum OptionsError {
 
     /// A glob ignore was given that failed to be parsed as a pattern.
     FailedGlobPattern(String),
+
+    /// A regular expression was given that failed to be parsed.
+    FailedRegexPattern(&'static Arg, String),
 }
 
 /// The source of a string that failed to be parsed as a number.
@@ -96,6 +99,7 @@ impl fmt::Display for OptionsError {
             Self::TreeAllAll                 => write!(f, "Option --tree is useless given --all --all"),
             Self::FailedParse(s, n, e)       => write!(f, "Value {s:?} not valid for {n}: {e}"),
             Self::FailedGlobPattern(ref e)   => write!(f, "Failed to parse glob pattern: {e}"),
+            Self::FailedRegexPattern(a, e)   => write!(f, "Failed to parse regular expression for option {a}: {e}"),
         };
     }
 }
@@ -112,6 +116,7 @@ impl OptionsError {
             Self::Parse(ParseError::NeedsValue { ref flag, .. }) if *flag == Flag::Short(b't') => {
                 Some("To sort newest files last, try \"--sort newest\", or just \"-snew\"")
             }
+            Self::Useless(a, true, b) => conflicts::suggestion(a, b),
             _ => None,
         }
     }
@@ -2,7 +2,10 @@ use crate::options::parser::MatchedFlags;
 use crate::options::vars::{self, Vars};
 use crate::options::{flags, NumberSource, OptionsError};
 
-use crate::output::file_name::{Classify, EmbedHyperlinks, Options, QuoteStyle, ShowIcons};
+use crate::output::file_name::{
+    Classify, EmbedHyperlinks, IconColour, Options, QuoteStyle, ShowIcons,
+};
+use crate::output::icons::IconSet;
 
 impl Options {
     pub fn deduce<V: Vars>(
@@ -12,6 +15,8 @@ impl Options {
     ) -> Result<Self, OptionsError> {
         let classify = Classify::deduce(matches)?;
         let show_icons = ShowIcons::deduce(matches, vars)?;
+        let icon_colour = IconColour::deduce(matches)?;
+        let icon_set = IconSet::deduce(matches)?;
 
         let quote_style = QuoteStyle::deduce(matches)?;
         let embed_hyperlinks = EmbedHyperlinks::deduce(matches)?;
@@ -19,6 +24,8 @@ impl Options {
         Ok(Self {
             classify,
             show_icons,
+            icon_colour,
+            icon_set,
             quote_style,
             embed_hyperlinks,
             is_a_tty,
@@ -86,6 +93,46 @@ impl ShowIcons {
     }
 }
 
+impl IconColour {
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(word) =
+            matches.get_where(|f| f.matches(&flags::ICON_COLOR) || f.matches(&flags::ICON_COLOUR))?
+        else {
+            return Ok(Self::default());
+        };
+
+        if word == "file" {
+            Ok(Self::File)
+        } else if word == "name" {
+            Ok(Self::Name)
+        } else if word == "fixed" {
+            Ok(Self::Fixed)
+        } else {
+            Err(OptionsError::BadArgument(&flags::ICON_COLOR, word.into()))
+        }
+    }
+}
+
+impl IconSet {
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(word) = matches.get(&flags::ICON_SET)? else {
+            return Ok(Self::default());
+        };
+
+        if word == "nerd3" {
+            Ok(Self::Nerd3)
+        } else if word == "nerd2" {
+            Ok(Self::Nerd2)
+        } else if word == "unicode" {
+            Ok(Self::Unicode)
+        } else if word == "emoji" {
+            Ok(Self::Emoji)
+        } else {
+            Err(OptionsError::BadArgument(&flags::ICON_SET, word.into()))
+        }
+    }
+}
+
 impl QuoteStyle {
     pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
         if matches.has(&flags::NO_QUOTES)? {
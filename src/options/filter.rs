@@ -1,24 +1,32 @@
 //! Parsing the options for `FileFilter`.
 
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use regex::Regex;
+
 use crate::fs::filter::{
-    FileFilter, FileFilterFlags, GitIgnore, IgnorePatterns, SortCase, SortField,
+    parse_size, BrokenLinks, Categories, EntryType, FileFilter, FileFilterFlags, GitIgnore,
+    IgnorePatterns, NameRegexes, SizeRange, SortCase, SortField, SortKey, TieBreak, TimeRange,
+    XattrMatch,
 };
-use crate::fs::DotFilter;
+use crate::fs::{DotFilter, HiddenMode};
+use crate::info::filetype::FileType;
 
-use crate::options::parser::MatchedFlags;
-use crate::options::{flags, OptionsError};
+use crate::options::parser::{Arg, Flag, MatchedFlags};
+use crate::options::{flags, NumberSource, OptionsError};
+use crate::output::table::TimeType;
 
 impl FileFilter {
-    /// Determines which of all the file filter options to use.
-    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+    /// Determines which of all the file filter options to use, given
+    /// whether directories are going to be recursed into, which depends on
+    /// the directory action that was deduced earlier.
+    pub fn deduce(matches: &MatchedFlags<'_>, recursing: bool) -> Result<Self, OptionsError> {
         use FileFilterFlags as FFF;
         let mut filter_flags: Vec<FileFilterFlags> = vec![];
 
-        for (has, flag) in &[
-            (matches.has(&flags::REVERSE)?, FFF::Reverse),
-            (matches.has(&flags::ONLY_DIRS)?, FFF::OnlyDirs),
-            (matches.has(&flags::ONLY_FILES)?, FFF::OnlyFiles),
-        ] {
+        for (has, flag) in &[(matches.has(&flags::REVERSE)?, FFF::Reverse)] {
             if *has {
                 filter_flags.push(flag.clone());
             }
@@ -28,22 +36,34 @@ impl FileFilter {
         return Ok(Self {
             list_dirs_first:  matches.has(&flags::DIRS_FIRST)?,
             flags: filter_flags,
-            sort_field:       SortField::deduce(matches)?,
+            sort_keys:        SortKey::deduce(matches)?,
+            tie_break:        TieBreak::deduce(matches)?,
             dot_filter:       DotFilter::deduce(matches)?,
+            hidden_mode:      HiddenMode::deduce(matches)?,
             ignore_patterns:  IgnorePatterns::deduce(matches)?,
+            prune_patterns:   IgnorePatterns::deduce_prune(matches)?,
+            name_regexes:     NameRegexes::deduce(matches)?,
+            size_range:       SizeRange::deduce(matches)?,
+            time_range:       TimeRange::deduce(matches)?,
+            entry_types:      EntryType::deduce(matches)?,
+            recursing,
+            categories:       Categories::deduce(matches)?,
+            broken_links:     BrokenLinks::deduce(matches)?,
+            has_xattr:        XattrMatch::deduce(matches)?,
             git_ignore:       GitIgnore::deduce(matches)?,
         });
     }
 }
 
-impl SortField {
-    /// Determines which sort field to use based on the `--sort` argument.
-    /// This argument’s value can be one of several flags, listed above.
-    /// Returns the default sort field if none is given, or `Err` if the
-    /// value doesn’t correspond to a sort field we know about.
-    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+impl SortKey {
+    /// Determines which sort fields to use based on the `--sort` argument,
+    /// which is a comma-separated list of fields, each of which can end in
+    /// `:asc` or `:desc` to pick its direction. Returns the default sort
+    /// field if none is given, or `Err` if any part of the value doesn’t
+    /// correspond to a sort field we know about.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Vec<Self>, OptionsError> {
         let Some(word) = matches.get(&flags::SORT)? else {
-            return Ok(Self::default());
+            return Ok(vec![SortField::default().into()]);
         };
 
         // Get String because we can’t match an OsStr
@@ -51,12 +71,37 @@ impl SortField {
             return Err(OptionsError::BadArgument(&flags::SORT, word.into()));
         };
 
+        word.split(',')
+            .map(|key| Self::parse(key, matches))
+            .collect()
+    }
+
+    /// Parses a single field of the `--sort` argument.
+    fn parse(key: &str, matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let (word, reverse) = match key.rsplit_once(':') {
+            Some((word, "asc")) => (word, false),
+            Some((word, "desc")) => (word, true),
+            Some(_) => return Err(OptionsError::BadArgument(&flags::SORT, key.into())),
+            None => (key, false),
+        };
+
+        let field = SortField::parse(word, matches)?;
+        Ok(Self { field, reverse })
+    }
+}
+
+impl SortField {
+    /// Determines which sort field a word of the `--sort` argument refers
+    /// to. It can be one of several words, listed above, or `Err` if it
+    /// doesn’t correspond to a sort field we know about.
+    fn parse(word: &str, matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
         let field = match word {
             "name" | "filename" => Self::Name(SortCase::AaBbCc),
             "Name" | "Filename" => Self::Name(SortCase::ABCabc),
             ".name" | ".filename" => Self::NameMixHidden(SortCase::AaBbCc),
             ".Name" | ".Filename" => Self::NameMixHidden(SortCase::ABCabc),
             "size" | "filesize" => Self::Size,
+            "name-length" => Self::NameLength,
             "ext" | "extension" => Self::Extension(SortCase::AaBbCc),
             "Ext" | "Extension" => Self::Extension(SortCase::ABCabc),
 
@@ -76,7 +121,15 @@ impl SortField {
             "cr" | "created" => Self::CreatedDate,
             #[cfg(unix)]
             "inode" => Self::FileInode,
+            #[cfg(unix)]
+            "links" => Self::Links,
             "type" => Self::FileType,
+            "category" => Self::Category,
+            #[cfg(unix)]
+            "owner" | "user" => Self::Owner,
+            #[cfg(unix)]
+            "group" => Self::Group,
+            "random" => Self::Random(Self::deduce_seed(matches)?),
             "none" => Self::Unsorted,
             _ => {
                 return Err(OptionsError::BadArgument(&flags::SORT, word.into()));
@@ -85,6 +138,26 @@ impl SortField {
 
         Ok(field)
     }
+
+    /// Determines the seed for the random sort order from the `--seed`
+    /// argument, picking a different one each time if none is given.
+    fn deduce_seed(matches: &MatchedFlags<'_>) -> Result<u64, OptionsError> {
+        let Some(seed) = matches.get(&flags::SEED)? else {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.subsec_nanos());
+            return Ok(u64::from(nanos) ^ u64::from(process::id()));
+        };
+
+        let arg_str = seed.to_string_lossy();
+        match arg_str.parse() {
+            Ok(seed) => Ok(seed),
+            Err(e) => {
+                let source = NumberSource::Arg(&flags::SEED);
+                Err(OptionsError::FailedParse(arg_str.to_string(), source, e))
+            }
+        }
+    }
 }
 
 // I’ve gone back and forth between whether to sort case-sensitively or
@@ -106,11 +179,11 @@ impl SortField {
 // them in the first case, and the shell in the second case, so they wouldn’t
 // be exactly the same if exa does something non-conventional.
 //
-// However, exa already sorts files differently: it uses natural sorting from
-// the natord crate, sorting the string “2” before “10” because the number’s
-// smaller, because that’s usually what the user expects to happen. Users will
-// name their files with numbers expecting them to be treated like numbers,
-// rather than lists of numeric characters.
+// However, exa already sorts files differently: it uses natural sorting, the
+// same as the natord crate, sorting the string “2” before “10” because the
+// number’s smaller, because that’s usually what the user expects to happen.
+// Users will name their files with numbers expecting them to be treated like
+// numbers, rather than lists of numeric characters.
 //
 // In the same way, users will name their files with letters expecting the
 // order of the letters to matter, rather than each letter’s character’s ASCII
@@ -159,14 +232,41 @@ impl DotFilter {
     }
 }
 
+impl HiddenMode {
+    /// Determines which entries count as hidden from the `--hidden-mode`
+    /// argument, using the platform’s default if it isn’t given.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(word) = matches.get(&flags::HIDDEN_MODE)? else {
+            return Ok(Self::default());
+        };
+
+        match word.to_str() {
+            Some("dots") => Ok(Self::Dots),
+            Some("native") => Ok(Self::Native),
+            Some("both") => Ok(Self::Both),
+            _ => Err(OptionsError::BadArgument(&flags::HIDDEN_MODE, word.into())),
+        }
+    }
+}
+
 impl IgnorePatterns {
     /// Determines the set of glob patterns to use based on the
     /// `--ignore-glob` argument’s value. This is a list of strings
     /// separated by pipe (`|`) characters, given in any order.
     pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        Self::deduce_flag(matches, &flags::IGNORE_GLOB)
+    }
+
+    /// Determines the set of glob patterns for directories not to recurse
+    /// into, from the `--prune-glob` argument, which works the same way.
+    pub fn deduce_prune(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        Self::deduce_flag(matches, &flags::PRUNE_GLOB)
+    }
+
+    fn deduce_flag(matches: &MatchedFlags<'_>, flag: &'static Arg) -> Result<Self, OptionsError> {
         // If there are no inputs, we return a set of patterns that doesn’t
         // match anything, rather than, say, `None`.
-        let Some(inputs) = matches.get(&flags::IGNORE_GLOB)? else {
+        let Some(inputs) = matches.get(flag)? else {
             return Ok(Self::empty());
         };
 
@@ -183,6 +283,345 @@ impl IgnorePatterns {
     }
 }
 
+impl NameRegexes {
+    /// Determines the regular expressions to match file names against from
+    /// the `--regex` and `--ignore-regex` arguments, or their
+    /// case-insensitive `--iregex` and `--ignore-iregex` versions.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        Ok(Self {
+            include: Self::deduce_regex(matches, &flags::REGEX, &flags::IREGEX)?,
+            exclude: Self::deduce_regex(matches, &flags::IGNORE_REGEX, &flags::IGNORE_IREGEX)?,
+        })
+    }
+
+    /// Builds the regular expression from whichever of the two arguments
+    /// was given last, ignoring case if it was the insensitive one.
+    fn deduce_regex(
+        matches: &MatchedFlags<'_>,
+        sensitive: &'static Arg,
+        insensitive: &'static Arg,
+    ) -> Result<Option<Regex>, OptionsError> {
+        let either = |flag: &Flag| flag.matches(sensitive) || flag.matches(insensitive);
+        let Some(pattern) = matches.get_where(either)? else {
+            return Ok(None);
+        };
+
+        let pattern = pattern.to_string_lossy();
+        let (arg, pattern) = match matches.last_where_any(either) {
+            Some(flag) if flag.matches(insensitive) => (insensitive, format!("(?i){pattern}")),
+            _ => (sensitive, pattern.into_owned()),
+        };
+
+        match Regex::new(&pattern) {
+            Ok(regex) => Ok(Some(regex)),
+            Err(e) => Err(OptionsError::FailedRegexPattern(arg, e.to_string())),
+        }
+    }
+}
+
+impl TieBreak {
+    /// Determines how to break ties between files based on the
+    /// `--tie-break` argument, breaking them by name if it isn’t given.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(word) = matches.get(&flags::TIE_BREAK)? else {
+            return Ok(Self::Name);
+        };
+
+        match word.to_str() {
+            Some("name") => Ok(Self::Name),
+            Some("none") => Ok(Self::None),
+            #[cfg(unix)]
+            Some("inode") => Ok(Self::Inode),
+            _ => Err(OptionsError::BadArgument(&flags::TIE_BREAK, word.into())),
+        }
+    }
+}
+
+impl SizeRange {
+    /// Determines the range of sizes to display from the `--size` argument,
+    /// which can be a minimum such as `+10M`, a maximum such as `-4k`, a
+    /// range such as `1M..100M` (where either end can be left out), or an
+    /// exact size. Every file is displayed if it isn’t given.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(value) = matches.get(&flags::SIZE)? else {
+            return Ok(Self::default());
+        };
+
+        let bad_argument = || OptionsError::BadArgument(&flags::SIZE, value.into());
+        let text = value.to_str().ok_or_else(bad_argument)?;
+        let bound = |size: &str| parse_size(size).ok_or_else(bad_argument);
+        let optional_bound = |size: &str| match size {
+            "" => Ok(None),
+            size => bound(size).map(Some),
+        };
+
+        let (min, max) = if let Some((min, max)) = text.split_once("..") {
+            (optional_bound(min)?, optional_bound(max)?)
+        } else if let Some(min) = text.strip_prefix('+') {
+            (Some(bound(min)?), None)
+        } else if let Some(max) = text.strip_prefix('-') {
+            (None, Some(bound(max)?))
+        } else {
+            let size = bound(text)?;
+            (Some(size), Some(size))
+        };
+
+        Ok(Self { min, max })
+    }
+}
+
+impl TimeRange {
+    /// Determines the range of times to display from the `--newer-than` and
+    /// `--older-than` arguments, which can each be an amount of time ago such
+    /// as `2 days`, or a date such as `2024-01-01`. The timestamp that gets
+    /// checked is the one picked with `--time`, `--accessed`, `--created`,
+    /// or `--changed`, and is the modified time otherwise.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let now = Utc::now().naive_utc();
+        let newer_than = Self::deduce_time(matches, &flags::NEWER_THAN, now)?;
+        let older_than = Self::deduce_time(matches, &flags::OLDER_THAN, now)?;
+
+        let field = if let Some(word) = matches.get(&flags::TIME)? {
+            match word.to_str() {
+                Some("mod" | "modified") => TimeType::Modified,
+                Some("ch" | "changed") => TimeType::Changed,
+                Some("acc" | "accessed") => TimeType::Accessed,
+                Some("cr" | "created") => TimeType::Created,
+                _ => return Err(OptionsError::BadArgument(&flags::TIME, word.into())),
+            }
+        } else if matches.has(&flags::ACCESSED)? {
+            TimeType::Accessed
+        } else if matches.has(&flags::CREATED)? {
+            TimeType::Created
+        } else if matches.has(&flags::CHANGED)? {
+            TimeType::Changed
+        } else {
+            TimeType::Modified
+        };
+
+        Ok(Self {
+            field,
+            newer_than,
+            older_than,
+        })
+    }
+
+    fn deduce_time(
+        matches: &MatchedFlags<'_>,
+        flag: &'static Arg,
+        now: NaiveDateTime,
+    ) -> Result<Option<NaiveDateTime>, OptionsError> {
+        let Some(value) = matches.get(flag)? else {
+            return Ok(None);
+        };
+
+        let time = value.to_str().and_then(|text| {
+            parse_duration(text)
+                .map(|ago| now - ago)
+                .or_else(|| parse_date(text))
+        });
+
+        match time {
+            Some(time) => Ok(Some(time)),
+            None => Err(OptionsError::BadArgument(flag, value.into())),
+        }
+    }
+}
+
+/// Parses an amount of time such as `30s`, `2 days`, or `1week` into a
+/// duration. Months are counted as 30 days, and years as 365.
+fn parse_duration(text: &str) -> Option<Duration> {
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: i64 = number.parse().ok()?;
+
+    #[rustfmt::skip]
+    let seconds = match unit.trim_start() {
+        "s" | "sec" | "secs" | "second" | "seconds"   => 1,
+        "m" | "min" | "mins" | "minute" | "minutes"   => 60,
+        "h" | "hour" | "hours"                        => 60 * 60,
+        "d" | "day" | "days"                          => 60 * 60 * 24,
+        "w" | "week" | "weeks"                        => 60 * 60 * 24 * 7,
+        "mo" | "month" | "months"                     => 60 * 60 * 24 * 30,
+        "y" | "year" | "years"                        => 60 * 60 * 24 * 365,
+        _                                             => return None,
+    };
+
+    number.checked_mul(seconds).map(Duration::seconds)
+}
+
+/// Parses a date such as `2024-01-01`, or a date and time such as
+/// `2024-01-01 12:30`, in the local time zone, returning it in UTC like
+/// the timestamps of files.
+fn parse_date(text: &str) -> Option<NaiveDateTime> {
+    let local = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+    })?;
+
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|time| time.naive_utc())
+}
+
+impl EntryType {
+    /// Determines which types of entry to display from the `--type`
+    /// argument, a comma-separated list of the letters `find` uses for them,
+    /// along with the `--only-dirs`, `--only-files`, and `--only-links`
+    /// shorthands. Every type is displayed if none of them are given.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Vec<Self>, OptionsError> {
+        let mut types = Vec::new();
+        for (flag, entry_type) in [
+            (&flags::ONLY_DIRS, Self::Directory),
+            (&flags::ONLY_FILES, Self::File),
+            (&flags::ONLY_LINKS, Self::Symlink),
+        ] {
+            if matches.has(flag)? {
+                types.push(entry_type);
+            }
+        }
+
+        let Some(value) = matches.get(&flags::TYPE)? else {
+            return Ok(types);
+        };
+
+        let Some(text) = value.to_str() else {
+            return Err(OptionsError::BadArgument(&flags::TYPE, value.into()));
+        };
+
+        for entry_type in Self::deduce_letters(text)? {
+            if !types.contains(&entry_type) {
+                types.push(entry_type);
+            }
+        }
+
+        Ok(types)
+    }
+
+    /// Parses the letters given to `--type`.
+    fn deduce_letters(text: &str) -> Result<Vec<Self>, OptionsError> {
+        text.split(',')
+            .map(|letter| match letter {
+                "f" => Ok(Self::File),
+                "d" => Ok(Self::Directory),
+                "l" => Ok(Self::Symlink),
+                #[cfg(unix)]
+                "x" => Ok(Self::Executable),
+                #[cfg(unix)]
+                "s" => Ok(Self::Socket),
+                #[cfg(unix)]
+                "p" => Ok(Self::Pipe),
+                #[cfg(unix)]
+                "b" => Ok(Self::BlockDevice),
+                #[cfg(unix)]
+                "c" => Ok(Self::CharDevice),
+                _ => Err(OptionsError::BadArgument(&flags::TYPE, letter.into())),
+            })
+            .collect()
+    }
+}
+
+impl Categories {
+    /// Determines which categories of file to display from the `--only`
+    /// and `--exclude-category` arguments, which are both comma-separated
+    /// lists of category names.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        Ok(Self {
+            only: Self::deduce_list(matches, &flags::ONLY)?,
+            excluded: Self::deduce_list(matches, &flags::EXCLUDE_CATEGORY)?,
+        })
+    }
+
+    fn deduce_list(
+        matches: &MatchedFlags<'_>,
+        flag: &'static Arg,
+    ) -> Result<Vec<FileType>, OptionsError> {
+        let Some(value) = matches.get(flag)? else {
+            return Ok(Vec::new());
+        };
+
+        let Some(text) = value.to_str() else {
+            return Err(OptionsError::BadArgument(flag, value.into()));
+        };
+
+        #[rustfmt::skip]
+        return text.split(',')
+            .map(|word| match word {
+                "image" | "images"          => Ok(FileType::Image),
+                "video" | "videos"          => Ok(FileType::Video),
+                "music"                     => Ok(FileType::Music),
+                "lossless"                  => Ok(FileType::Lossless),
+                "crypto"                    => Ok(FileType::Crypto),
+                "document" | "documents"    => Ok(FileType::Document),
+                "compressed"                => Ok(FileType::Compressed),
+                "temp"                      => Ok(FileType::Temp),
+                "compiled"                  => Ok(FileType::Compiled),
+                "build"                     => Ok(FileType::Build),
+                "source"                    => Ok(FileType::Source),
+                "config" | "configs"        => Ok(FileType::Config),
+                _ => Err(OptionsError::BadArgument(flag, word.into())),
+            })
+            .collect();
+    }
+}
+
+impl BrokenLinks {
+    /// Determines whether to pick out broken links from the
+    /// `--broken-links` and `--no-broken-links` arguments, with the last one
+    /// given winning.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let flag = matches
+            .has_where(|f| f.matches(&flags::BROKEN_LINKS) || f.matches(&flags::NO_BROKEN_LINKS))?;
+
+        match flag {
+            Some(f) if f.matches(&flags::BROKEN_LINKS) => Ok(Self::Only),
+            Some(_) => Ok(Self::Hide),
+            None => Ok(Self::Show),
+        }
+    }
+}
+
+impl XattrMatch {
+    /// Determines which extended attribute files have to carry from the
+    /// `--has-xattr` argument, which is a name, optionally followed by `=`
+    /// and the value it has to have. The name can’t be empty.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(input) = matches.get(&flags::HAS_XATTR)? else {
+            return Ok(None);
+        };
+
+        let input = input.to_string_lossy();
+        let (name, value) = match input.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_owned())),
+            None => (&*input, None),
+        };
+
+        if name.is_empty() {
+            return Err(OptionsError::BadArgument(
+                &flags::HAS_XATTR,
+                input.into_owned().into(),
+            ));
+        }
+
+        Ok(Some(Self {
+            name: name.to_owned(),
+            value,
+        }))
+    }
+}
+
 impl GitIgnore {
     pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
         if matches.has(&flags::GIT_IGNORE)? {
@@ -210,10 +649,32 @@ mod test {
 
                 static TEST_ARGS: &[&Arg] = &[
                     &flags::SORT,
+                    &flags::SEED,
+                    &flags::TIE_BREAK,
+                    &flags::SIZE,
+                    &flags::NEWER_THAN,
+                    &flags::OLDER_THAN,
+                    &flags::TIME,
+                    &flags::ACCESSED,
+                    &flags::TYPE,
+                    &flags::ONLY_DIRS,
+                    &flags::ONLY_FILES,
+                    &flags::ONLY_LINKS,
+                    &flags::ONLY,
+                    &flags::EXCLUDE_CATEGORY,
+                    &flags::BROKEN_LINKS,
+                    &flags::NO_BROKEN_LINKS,
+                    &flags::HAS_XATTR,
+                    &flags::HIDDEN_MODE,
+                    &flags::REGEX,
+                    &flags::IREGEX,
+                    &flags::IGNORE_REGEX,
+                    &flags::IGNORE_IREGEX,
                     &flags::ALL,
                     &flags::ALMOST_ALL,
                     &flags::TREE,
                     &flags::IGNORE_GLOB,
+                    &flags::PRUNE_GLOB,
                     &flags::GIT_IGNORE,
                 ];
                 for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
@@ -229,31 +690,177 @@ mod test {
         use super::*;
 
         // Default behaviour
-        test!(empty:         SortField <- [];                  Both => Ok(SortField::default()));
+        test!(empty:         SortKey <- [];                  Both => Ok(vec![SortField::default().into()]));
 
         // Sort field arguments
-        test!(one_arg:       SortField <- ["--sort=mod"];      Both => Ok(SortField::ModifiedDate));
-        test!(one_long:      SortField <- ["--sort=size"];     Both => Ok(SortField::Size));
-        test!(one_short:     SortField <- ["-saccessed"];      Both => Ok(SortField::AccessedDate));
-        test!(lowercase:     SortField <- ["--sort", "name"];  Both => Ok(SortField::Name(SortCase::AaBbCc)));
-        test!(uppercase:     SortField <- ["--sort", "Name"];  Both => Ok(SortField::Name(SortCase::ABCabc)));
-        test!(old:           SortField <- ["--sort", "new"];   Both => Ok(SortField::ModifiedDate));
-        test!(oldest:        SortField <- ["--sort=newest"];   Both => Ok(SortField::ModifiedDate));
-        test!(new:           SortField <- ["--sort", "old"];   Both => Ok(SortField::ModifiedAge));
-        test!(newest:        SortField <- ["--sort=oldest"];   Both => Ok(SortField::ModifiedAge));
-        test!(age:           SortField <- ["-sage"];           Both => Ok(SortField::ModifiedAge));
-
-        test!(mix_hidden_lowercase:     SortField <- ["--sort", ".name"];  Both => Ok(SortField::NameMixHidden(SortCase::AaBbCc)));
-        test!(mix_hidden_uppercase:     SortField <- ["--sort", ".Name"];  Both => Ok(SortField::NameMixHidden(SortCase::ABCabc)));
+        test!(one_arg:       SortKey <- ["--sort=mod"];      Both => Ok(vec![SortField::ModifiedDate.into()]));
+        test!(one_long:      SortKey <- ["--sort=size"];     Both => Ok(vec![SortField::Size.into()]));
+        test!(one_short:     SortKey <- ["-saccessed"];      Both => Ok(vec![SortField::AccessedDate.into()]));
+        test!(lowercase:     SortKey <- ["--sort", "name"];  Both => Ok(vec![SortField::Name(SortCase::AaBbCc).into()]));
+        test!(uppercase:     SortKey <- ["--sort", "Name"];  Both => Ok(vec![SortField::Name(SortCase::ABCabc).into()]));
+        test!(old:           SortKey <- ["--sort", "new"];   Both => Ok(vec![SortField::ModifiedDate.into()]));
+        test!(oldest:        SortKey <- ["--sort=newest"];   Both => Ok(vec![SortField::ModifiedDate.into()]));
+        test!(new:           SortKey <- ["--sort", "old"];   Both => Ok(vec![SortField::ModifiedAge.into()]));
+        test!(newest:        SortKey <- ["--sort=oldest"];   Both => Ok(vec![SortField::ModifiedAge.into()]));
+        test!(age:           SortKey <- ["-sage"];           Both => Ok(vec![SortField::ModifiedAge.into()]));
+
+        test!(mix_hidden_lowercase:     SortKey <- ["--sort", ".name"];  Both => Ok(vec![SortField::NameMixHidden(SortCase::AaBbCc).into()]));
+        test!(mix_hidden_uppercase:     SortKey <- ["--sort", ".Name"];  Both => Ok(vec![SortField::NameMixHidden(SortCase::ABCabc).into()]));
+
+        test!(category:      SortKey <- ["--sort=category"]; Both => Ok(vec![SortField::Category.into()]));
+        test!(name_length:   SortKey <- ["--sort=name-length"]; Both => Ok(vec![SortField::NameLength.into()]));
+        #[cfg(unix)]
+        test!(links:         SortKey <- ["--sort", "links"]; Both => Ok(vec![SortField::Links.into()]));
+        #[cfg(unix)]
+        test!(owner:         SortKey <- ["--sort=owner"];    Both => Ok(vec![SortField::Owner.into()]));
+        #[cfg(unix)]
+        test!(user:          SortKey <- ["--sort", "user"];  Both => Ok(vec![SortField::Owner.into()]));
+        #[cfg(unix)]
+        test!(group:         SortKey <- ["-sgroup"];         Both => Ok(vec![SortField::Group.into()]));
+
+        test!(random:        SortKey <- ["--sort=random", "--seed=42"]; Both => Ok(vec![SortField::Random(42).into()]));
+        test!(seed_unused:   SortKey <- ["--sort=size", "--seed=42"];   Both => Ok(vec![SortField::Size.into()]));
+
+        // Several fields
+        test!(two_keys:      SortKey <- ["--sort=size,name"];          Both => Ok(vec![SortField::Size.into(), SortField::Name(SortCase::AaBbCc).into()]));
+        test!(directions:    SortKey <- ["--sort=size:desc,Name:asc"]; Both => Ok(vec![SortKey { field: SortField::Size, reverse: true }, SortField::Name(SortCase::ABCabc).into()]));
 
         // Errors
-        test!(error:         SortField <- ["--sort=colour"];   Both => Err(OptionsError::BadArgument(&flags::SORT, OsString::from("colour"))));
+        test!(error:         SortKey <- ["--sort=colour"];   Both => Err(OptionsError::BadArgument(&flags::SORT, OsString::from("colour"))));
+        test!(error_in_list: SortKey <- ["--sort=size,colour"];   Both => Err(OptionsError::BadArgument(&flags::SORT, OsString::from("colour"))));
+        test!(bad_direction: SortKey <- ["--sort=size:up"];       Both => Err(OptionsError::BadArgument(&flags::SORT, OsString::from("size:up"))));
 
         // Overriding
-        test!(overridden:    SortField <- ["--sort=cr",       "--sort", "mod"];     Last => Ok(SortField::ModifiedDate));
-        test!(overridden_2:  SortField <- ["--sort", "none",  "--sort=Extension"];  Last => Ok(SortField::Extension(SortCase::ABCabc)));
-        test!(overridden_3:  SortField <- ["--sort=cr",       "--sort", "mod"];     Complain => Err(OptionsError::Duplicate(Flag::Long("sort"), Flag::Long("sort"))));
-        test!(overridden_4:  SortField <- ["--sort", "none",  "--sort=Extension"];  Complain => Err(OptionsError::Duplicate(Flag::Long("sort"), Flag::Long("sort"))));
+        test!(overridden:    SortKey <- ["--sort=cr",       "--sort", "mod"];     Last => Ok(vec![SortField::ModifiedDate.into()]));
+        test!(overridden_2:  SortKey <- ["--sort", "none",  "--sort=Extension"];  Last => Ok(vec![SortField::Extension(SortCase::ABCabc).into()]));
+        test!(overridden_3:  SortKey <- ["--sort=cr",       "--sort", "mod"];     Complain => Err(OptionsError::Duplicate(Flag::Long("sort"), Flag::Long("sort"))));
+        test!(overridden_4:  SortKey <- ["--sort", "none",  "--sort=Extension"];  Complain => Err(OptionsError::Duplicate(Flag::Long("sort"), Flag::Long("sort"))));
+    }
+
+    mod hidden_modes {
+        use super::*;
+
+        test!(empty:        HiddenMode <- [];                          Both => Ok(HiddenMode::default()));
+        test!(dots:         HiddenMode <- ["--hidden-mode=dots"];      Both => Ok(HiddenMode::Dots));
+        test!(native:       HiddenMode <- ["--hidden-mode", "native"]; Both => Ok(HiddenMode::Native));
+        test!(both:         HiddenMode <- ["--hidden-mode=both"];      Both => Ok(HiddenMode::Both));
+        test!(error:        HiddenMode <- ["--hidden-mode=all"];       Both => Err(OptionsError::BadArgument(&flags::HIDDEN_MODE, OsString::from("all"))));
+    }
+
+    mod tie_breaks {
+        use super::*;
+
+        test!(empty:        TieBreak <- [];                         Both => Ok(TieBreak::Name));
+        test!(name:         TieBreak <- ["--tie-break=name"];       Both => Ok(TieBreak::Name));
+        test!(none:         TieBreak <- ["--tie-break", "none"];    Both => Ok(TieBreak::None));
+        #[cfg(unix)]
+        test!(inode:        TieBreak <- ["--tie-break=inode"];      Both => Ok(TieBreak::Inode));
+        test!(error:        TieBreak <- ["--tie-break=size"];       Both => Err(OptionsError::BadArgument(&flags::TIE_BREAK, OsString::from("size"))));
+    }
+
+    mod size_ranges {
+        use super::*;
+
+        test!(empty:        SizeRange <- [];                     Both => Ok(SizeRange::default()));
+        test!(min:          SizeRange <- ["--size=+10M"];        Both => Ok(SizeRange { min: Some(10_000_000), max: None }));
+        test!(max:          SizeRange <- ["--size", "-4KiB"];    Both => Ok(SizeRange { min: None, max: Some(4096) }));
+        test!(range:        SizeRange <- ["--size=1k..1.5M"];    Both => Ok(SizeRange { min: Some(1000), max: Some(1_500_000) }));
+        test!(open_range:   SizeRange <- ["--size=100.."];       Both => Ok(SizeRange { min: Some(100), max: None }));
+        test!(exact:        SizeRange <- ["--size=512B"];        Both => Ok(SizeRange { min: Some(512), max: Some(512) }));
+
+        // Errors
+        test!(no_number:    SizeRange <- ["--size=+M"];          Both => Err(OptionsError::BadArgument(&flags::SIZE, OsString::from("+M"))));
+        test!(bad_unit:     SizeRange <- ["--size=10X"];         Both => Err(OptionsError::BadArgument(&flags::SIZE, OsString::from("10X"))));
+        test!(just_i:       SizeRange <- ["--size=10i"];         Both => Err(OptionsError::BadArgument(&flags::SIZE, OsString::from("10i"))));
+    }
+
+    mod time_ranges {
+        use super::*;
+
+        test!(empty:        TimeRange <- [];                          Both => Ok(TimeRange { field: TimeType::Modified, newer_than: None, older_than: None }));
+        test!(accessed:     TimeRange <- ["-u"];                      Both => Ok(TimeRange { field: TimeType::Accessed, newer_than: None, older_than: None }));
+        test!(time_created: TimeRange <- ["--time=created"];          Both => Ok(TimeRange { field: TimeType::Created, newer_than: None, older_than: None }));
+
+        // Errors
+        test!(bad_newer:    TimeRange <- ["--newer-than=soon"];       Both => Err(OptionsError::BadArgument(&flags::NEWER_THAN, OsString::from("soon"))));
+        test!(bad_unit:     TimeRange <- ["--older-than", "2 ages"];  Both => Err(OptionsError::BadArgument(&flags::OLDER_THAN, OsString::from("2 ages"))));
+        test!(bad_date:     TimeRange <- ["--older-than=2024-13-01"]; Both => Err(OptionsError::BadArgument(&flags::OLDER_THAN, OsString::from("2024-13-01"))));
+
+        #[test]
+        fn durations() {
+            assert_eq!(parse_duration("30s"), Some(Duration::seconds(30)));
+            assert_eq!(parse_duration("2 days"), Some(Duration::days(2)));
+            assert_eq!(parse_duration("1week"), Some(Duration::weeks(1)));
+            assert_eq!(parse_duration("3 mo"), Some(Duration::days(90)));
+            assert_eq!(parse_duration("days"), None);
+            assert_eq!(parse_duration("5 fortnights"), None);
+        }
+
+        #[test]
+        fn dates() {
+            assert!(parse_date("2024-01-01").is_some());
+            assert!(parse_date("2024-01-01 12:30").is_some());
+            assert!(parse_date("2024-01-01T12:30:15").is_some());
+            assert_eq!(parse_date("01/01/2024"), None);
+        }
+    }
+
+    mod entry_types {
+        use super::*;
+
+        test!(empty:        EntryType <- [];                 Both => Ok(vec![]));
+        test!(one:          EntryType <- ["--type=d"];       Both => Ok(vec![EntryType::Directory]));
+        test!(several:      EntryType <- ["--type", "f,l"];  Both => Ok(vec![EntryType::File, EntryType::Symlink]));
+        #[cfg(unix)]
+        test!(special:      EntryType <- ["--type=x,s,p"];   Both => Ok(vec![EntryType::Executable, EntryType::Socket, EntryType::Pipe]));
+
+        // Errors
+        test!(bad_letter:   EntryType <- ["--type=f,q"];     Both => Err(OptionsError::BadArgument(&flags::TYPE, OsString::from("q"))));
+        test!(word:         EntryType <- ["--type=file"];    Both => Err(OptionsError::BadArgument(&flags::TYPE, OsString::from("file"))));
+
+        // Shorthands
+        test!(only_dirs:    EntryType <- ["--only-dirs"];    Both => Ok(vec![EntryType::Directory]));
+        test!(only_files:   EntryType <- ["-f"];             Both => Ok(vec![EntryType::File]));
+        test!(only_links:   EntryType <- ["--only-links"];   Both => Ok(vec![EntryType::Symlink]));
+        test!(dirs_links:   EntryType <- ["-D", "--only-links"];  Both => Ok(vec![EntryType::Directory, EntryType::Symlink]));
+        test!(with_type:    EntryType <- ["-f", "--type=f,l"];    Both => Ok(vec![EntryType::File, EntryType::Symlink]));
+    }
+
+    mod categories {
+        use super::*;
+
+        test!(empty:        Categories <- [];                                 Both => Ok(Categories::default()));
+        test!(only:         Categories <- ["--only=images,video"];            Both => Ok(Categories { only: vec![FileType::Image, FileType::Video], excluded: vec![] }));
+        test!(exclude:      Categories <- ["--exclude-category", "temp"];     Both => Ok(Categories { only: vec![], excluded: vec![FileType::Temp] }));
+        test!(both:         Categories <- ["--only=source", "--exclude-category=build,compiled"];
+                                                                              Both => Ok(Categories { only: vec![FileType::Source], excluded: vec![FileType::Build, FileType::Compiled] }));
+
+        // Errors
+        test!(bad_name:     Categories <- ["--only=images,cats"];             Both => Err(OptionsError::BadArgument(&flags::ONLY, OsString::from("cats"))));
+    }
+
+    mod broken_links {
+        use super::*;
+
+        test!(empty:        BrokenLinks <- [];                                          Both => Ok(BrokenLinks::Show));
+        test!(only:         BrokenLinks <- ["--broken-links"];                          Both => Ok(BrokenLinks::Only));
+        test!(hide:         BrokenLinks <- ["--no-broken-links"];                       Both => Ok(BrokenLinks::Hide));
+        test!(last_wins:    BrokenLinks <- ["--broken-links", "--no-broken-links"];     Last => Ok(BrokenLinks::Hide));
+
+        // Errors
+        test!(both:         BrokenLinks <- ["--no-broken-links", "--broken-links"];     Complain => Err(OptionsError::Duplicate(Flag::Long("no-broken-links"), Flag::Long("broken-links"))));
+    }
+
+    mod has_xattr {
+        use super::*;
+
+        test!(empty:        XattrMatch <- [];                                         Both => Ok(None));
+        test!(name:         XattrMatch <- ["--has-xattr", "user.comment"];            Both => Ok(Some(XattrMatch { name: "user.comment".into(), value: None })));
+        test!(value:        XattrMatch <- ["--has-xattr=user.tag=red"];               Both => Ok(Some(XattrMatch { name: "user.tag".into(), value: Some("red".into()) })));
+        test!(empty_value:  XattrMatch <- ["--has-xattr=user.tag="];                  Both => Ok(Some(XattrMatch { name: "user.tag".into(), value: Some(String::new()) })));
+
+        // Errors
+        test!(no_name:      XattrMatch <- ["--has-xattr==red"];                       Both => Err(OptionsError::BadArgument(&flags::HAS_XATTR, OsString::from("=red"))));
     }
 
     mod dot_filters {
@@ -300,6 +907,64 @@ mod test {
         test!(overridden_2: IgnorePatterns <- ["-I", "*.OGG", "-I*.MP3"];          Last => Ok(IgnorePatterns::from_iter(vec![ pat("*.MP3") ])));
         test!(overridden_3: IgnorePatterns <- ["-I=*.ogg",    "-I", "*.mp3"];  Complain => Err(OptionsError::Duplicate(Flag::Short(b'I'), Flag::Short(b'I'))));
         test!(overridden_4: IgnorePatterns <- ["-I", "*.OGG", "-I*.MP3"];      Complain => Err(OptionsError::Duplicate(Flag::Short(b'I'), Flag::Short(b'I'))));
+
+        // Pruning is separate from ignoring
+        test!(not_pruned:   IgnorePatterns <- ["--prune-glob=target"];             Both => Ok(IgnorePatterns::empty()));
+
+        #[test]
+        fn pruned() {
+            use crate::options::test::parse_for_test;
+            use crate::options::test::Strictnesses::*;
+
+            static TEST_ARGS: &[&Arg] = &[&flags::IGNORE_GLOB, &flags::PRUNE_GLOB];
+            let inputs = ["-I*.o", "--prune-glob=node_modules|.git"];
+            for result in parse_for_test(&inputs, TEST_ARGS, Both, IgnorePatterns::deduce_prune) {
+                let expected = IgnorePatterns::from_iter(vec![pat("node_modules"), pat(".git")]);
+                assert_eq!(result, Ok(expected));
+            }
+        }
+    }
+
+    mod name_regexes {
+        use super::*;
+
+        fn regexes(include: Option<&str>, exclude: Option<&str>) -> NameRegexes {
+            NameRegexes {
+                include: include.map(|re| Regex::new(re).unwrap()),
+                exclude: exclude.map(|re| Regex::new(re).unwrap()),
+            }
+        }
+
+        test!(none:         NameRegexes <- [];                                    Both => Ok(regexes(None, None)));
+        test!(include:      NameRegexes <- ["--regex", "^a.*z$"];                 Both => Ok(regexes(Some("^a.*z$"), None)));
+        test!(include_i:    NameRegexes <- ["--iregex=^a"];                       Both => Ok(regexes(Some("(?i)^a"), None)));
+        test!(exclude:      NameRegexes <- ["--ignore-regex=~$"];                 Both => Ok(regexes(None, Some("~$"))));
+        test!(exclude_i:    NameRegexes <- ["--ignore-iregex", "\\.bak$"];       Both => Ok(regexes(None, Some("(?i)\\.bak$"))));
+        test!(both:         NameRegexes <- ["--regex=rs$", "--ignore-regex=^m"];  Both => Ok(regexes(Some("rs$"), Some("^m"))));
+
+        // Overriding
+        test!(overridden:   NameRegexes <- ["--regex=a", "--iregex=b"];           Last => Ok(regexes(Some("(?i)b"), None)));
+        test!(overridden_2: NameRegexes <- ["--regex=a", "--iregex=b"];           Complain => Err(OptionsError::Duplicate(Flag::Long("regex"), Flag::Long("iregex"))));
+
+        // Errors
+        test!(bad:          NameRegexes <- ["--regex=("];                         Both => Err(OptionsError::FailedRegexPattern(&flags::REGEX, Regex::new("(").unwrap_err().to_string())));
+
+        #[test]
+        fn matching() {
+            let filter = regexes(Some("\\.rs$"), Some("^test"));
+            assert!(!filter.is_ignored("main.rs"));
+            assert!(filter.is_ignored("main.c"));
+            assert!(filter.is_ignored("test.rs"));
+        }
+
+        #[test]
+        fn included_and_excluded() {
+            let filter = regexes(Some("\\.rs$"), Some("^test"));
+            assert!(filter.includes("main.rs"));
+            assert!(!filter.includes("main.c"));
+            assert!(filter.excludes("test.c"));
+            assert!(regexes(None, None).includes("main.c"));
+        }
     }
 
     mod git_ignores {
@@ -308,4 +973,34 @@ mod test {
         test!(off:  GitIgnore <- [];                Both => Ok(GitIgnore::Off));
         test!(on:   GitIgnore <- ["--git-ignore"];  Both => Ok(GitIgnore::CheckAndIgnore));
     }
+
+    mod recursing {
+        use crate::options::{Options, OptionsResult};
+        use std::ffi::OsStr;
+
+        fn recursing(args: &[&str]) -> bool {
+            let os_args = args.iter().map(OsStr::new).collect::<Vec<_>>();
+            match Options::parse(os_args, &None) {
+                OptionsResult::Ok(options, _) => options.filter.recursing,
+                _ => panic!("invalid options: {args:?}"),
+            }
+        }
+
+        #[test]
+        fn listing() {
+            assert!(!recursing(&["-f"]));
+        }
+
+        #[test]
+        fn recurse() {
+            assert!(recursing(&["-R", "-f"]));
+            assert!(recursing(&["-l", "--tree", "-f"]));
+        }
+
+        #[test]
+        fn overridden_by_list_dirs() {
+            assert!(!recursing(&["-R", "--list-dirs", "-f"]));
+            assert!(recursing(&["--list-dirs", "-R", "-f"]));
+        }
+    }
 }
@@ -1,4 +1,6 @@
 use crate::options::parser::{Arg, Args, TakesValue, Values};
+use crate::output::table::{COLUMN_NAMES, HIDE_NAMES};
+use crate::theme::THEME_NAMES;
 
 // exa options
 pub static VERSION: Arg = Arg { short: Some(b'v'), long: "version",  takes_value: TakesValue::Forbidden };
@@ -9,12 +11,26 @@ pub static ONE_LINE:    Arg = Arg { short: Some(b'1'), long: "oneline",     take
 pub static LONG:        Arg = Arg { short: Some(b'l'), long: "long",        takes_value: TakesValue::Forbidden };
 pub static GRID:        Arg = Arg { short: Some(b'G'), long: "grid",        takes_value: TakesValue::Forbidden };
 pub static ACROSS:      Arg = Arg { short: Some(b'x'), long: "across",      takes_value: TakesValue::Forbidden };
+pub static GRID_ROWS:   Arg = Arg { short: None,       long: "grid-rows",   takes_value: TakesValue::Necessary(None) };
 pub static RECURSE:     Arg = Arg { short: Some(b'R'), long: "recurse",     takes_value: TakesValue::Forbidden };
 pub static TREE:        Arg = Arg { short: Some(b'T'), long: "tree",        takes_value: TakesValue::Forbidden };
 pub static CLASSIFY:    Arg = Arg { short: Some(b'F'), long: "classify",    takes_value: TakesValue::Forbidden };
 pub static DEREF_LINKS: Arg = Arg { short: Some(b'X'), long: "dereference", takes_value: TakesValue::Forbidden };
+pub static DEREF_ARGS:  Arg = Arg { short: None,       long: "dereference-command-line", takes_value: TakesValue::Forbidden };
 pub static WIDTH:       Arg = Arg { short: Some(b'w'), long: "width",       takes_value: TakesValue::Necessary(None) };
 pub static NO_QUOTES:   Arg = Arg { short: None,       long: "no-quotes",   takes_value: TakesValue::Forbidden };
+pub static EMPTY_PLACEHOLDER: Arg = Arg { short: None, long: "empty-placeholder", takes_value: TakesValue::Forbidden };
+pub static COUNT_ONLY:  Arg = Arg { short: None,       long: "count-only",  takes_value: TakesValue::Forbidden };
+pub static PROMPT:      Arg = Arg { short: None,       long: "prompt",      takes_value: TakesValue::Forbidden };
+pub static SUMMARY:     Arg = Arg { short: None,       long: "summary",     takes_value: TakesValue::Forbidden };
+pub static LIMIT:       Arg = Arg { short: None,       long: "limit",       takes_value: TakesValue::Necessary(None) };
+pub static LIMIT_MARKER: Arg = Arg { short: None,      long: "limit-marker", takes_value: TakesValue::Forbidden };
+pub static FORGET:      Arg = Arg { short: None,       long: "forget",      takes_value: TakesValue::Forbidden };
+pub static SEMANTIC_MARKERS: Arg = Arg { short: None,  long: "semantic-markers", takes_value: TakesValue::Forbidden };
+pub static FLUSH:       Arg = Arg { short: None,       long: "flush",       takes_value: TakesValue::Necessary(Some(FLUSHES)) };
+const FLUSHES: &[&str] = &["line", "dir", "end"];
+pub static GROUP_BY:    Arg = Arg { short: None,       long: "group-by",    takes_value: TakesValue::Necessary(Some(GROUP_BYS)) };
+const GROUP_BYS: &[&str] = &["category", "extension", "first-letter", "none"];
 
 pub static COLOR:  Arg = Arg { short: None, long: "color",  takes_value: TakesValue::Optional(Some(WHEN)) };
 pub static COLOUR: Arg = Arg { short: None, long: "colour", takes_value: TakesValue::Optional(Some(WHEN)) };
@@ -23,21 +39,68 @@ const WHEN: &[&str] = &["always", "auto", "never"];
 pub static COLOR_SCALE:  Arg = Arg { short: None, long: "color-scale",  takes_value: TakesValue::Forbidden };
 pub static COLOUR_SCALE: Arg = Arg { short: None, long: "colour-scale", takes_value: TakesValue::Forbidden };
 
+pub static COLOR_COLUMNS:  Arg = Arg { short: None, long: "color-columns",  takes_value: TakesValue::Necessary(Some(COLUMN_WHEN)) };
+pub static COLOUR_COLUMNS: Arg = Arg { short: None, long: "colour-columns", takes_value: TakesValue::Necessary(Some(COLUMN_WHEN)) };
+const COLUMN_WHEN: &[&str] = &["always", "dim", "never"];
+
+pub static COLOR_OWNERS:  Arg = Arg { short: None, long: "color-owners",  takes_value: TakesValue::Forbidden };
+pub static COLOUR_OWNERS: Arg = Arg { short: None, long: "colour-owners", takes_value: TakesValue::Forbidden };
+
+pub static THEME: Arg = Arg { short: None, long: "theme", takes_value: TakesValue::Necessary(Some(THEME_NAMES)) };
+
 // filtering and sorting options
 pub static ALL:         Arg = Arg { short: Some(b'a'), long: "all",         takes_value: TakesValue::Forbidden };
 pub static ALMOST_ALL:  Arg = Arg { short: Some(b'A'), long: "almost-all",  takes_value: TakesValue::Forbidden };
-pub static LIST_DIRS:   Arg = Arg { short: Some(b'd'), long: "list-dirs",   takes_value: TakesValue::Forbidden };
+pub static HIDDEN_MODE: Arg = Arg { short: None,       long: "hidden-mode", takes_value: TakesValue::Necessary(Some(HIDDEN_MODES)) };
+pub static LIST_DIRS:   Arg = Arg { short: Some(b'd'), long: "list-dirs",   takes_value: TakesValue::OptionalAttached(Some(LIST_DIRS_MODES)) };
 pub static LEVEL:       Arg = Arg { short: Some(b'L'), long: "level",       takes_value: TakesValue::Necessary(None) };
+pub static MIN_DEPTH:   Arg = Arg { short: None,       long: "min-depth",   takes_value: TakesValue::Necessary(None) };
+pub static FOLLOW_LINKS: Arg = Arg { short: None,      long: "follow-links", takes_value: TakesValue::Forbidden };
+pub static FLAT:         Arg = Arg { short: None,      long: "flat",         takes_value: TakesValue::Forbidden };
+pub static PRUNE_GLOB:   Arg = Arg { short: None,      long: "prune-glob",   takes_value: TakesValue::Necessary(None) };
+pub static ONE_FILE_SYSTEM: Arg = Arg { short: None,   long: "one-file-system", takes_value: TakesValue::Forbidden };
+pub static INLINE_ERRORS: Arg = Arg { short: None,     long: "inline-errors", takes_value: TakesValue::Forbidden };
+pub static COLLAPSE_CONFIGS: Arg = Arg { short: None,  long: "collapse-configs", takes_value: TakesValue::Forbidden };
+pub static TREE_COUNTS: Arg = Arg { short: None,       long: "tree-counts", takes_value: TakesValue::Forbidden };
+pub static COMPACT_TREE: Arg = Arg { short: None,      long: "compact-tree", takes_value: TakesValue::Forbidden };
+pub static TREE_STYLE:  Arg = Arg { short: None,       long: "tree-style",  takes_value: TakesValue::Necessary(Some(TREE_STYLES)) };
+pub static TREE_LIMIT:  Arg = Arg { short: None,       long: "tree-limit",  takes_value: TakesValue::Necessary(None) };
+pub static DIFF:        Arg = Arg { short: None,       long: "diff",        takes_value: TakesValue::Forbidden };
+pub static ONLY_CHANGES: Arg = Arg { short: None,      long: "only-changes", takes_value: TakesValue::Forbidden };
 pub static REVERSE:     Arg = Arg { short: Some(b'r'), long: "reverse",     takes_value: TakesValue::Forbidden };
 pub static SORT:        Arg = Arg { short: Some(b's'), long: "sort",        takes_value: TakesValue::Necessary(Some(SORTS)) };
+pub static SEED:        Arg = Arg { short: None,       long: "seed",        takes_value: TakesValue::Necessary(None) };
+pub static TIE_BREAK:   Arg = Arg { short: None,       long: "tie-break",   takes_value: TakesValue::Necessary(Some(TIE_BREAKS)) };
 pub static IGNORE_GLOB: Arg = Arg { short: Some(b'I'), long: "ignore-glob", takes_value: TakesValue::Necessary(None) };
+pub static REGEX:       Arg = Arg { short: None,       long: "regex",       takes_value: TakesValue::Necessary(None) };
+pub static IREGEX:      Arg = Arg { short: None,       long: "iregex",      takes_value: TakesValue::Necessary(None) };
+pub static IGNORE_REGEX:  Arg = Arg { short: None,     long: "ignore-regex",   takes_value: TakesValue::Necessary(None) };
+pub static IGNORE_IREGEX: Arg = Arg { short: None,     long: "ignore-iregex",  takes_value: TakesValue::Necessary(None) };
+pub static SIZE:        Arg = Arg { short: None,       long: "size",        takes_value: TakesValue::Necessary(None) };
+pub static NEWER_THAN:  Arg = Arg { short: None,       long: "newer-than",  takes_value: TakesValue::Necessary(None) };
+pub static OLDER_THAN:  Arg = Arg { short: None,       long: "older-than",  takes_value: TakesValue::Necessary(None) };
 pub static GIT_IGNORE:  Arg = Arg { short: None, long: "git-ignore",           takes_value: TakesValue::Forbidden };
 pub static DIRS_FIRST:  Arg = Arg { short: None, long: "group-directories-first",  takes_value: TakesValue::Forbidden };
 pub static ONLY_DIRS:   Arg = Arg { short: Some(b'D'), long: "only-dirs", takes_value: TakesValue::Forbidden };
 pub static ONLY_FILES:  Arg = Arg { short: Some(b'f'), long: "only-files", takes_value: TakesValue::Forbidden };
-const SORTS: Values = &[ "name", "Name", "size", "extension",
+pub static ONLY_LINKS:  Arg = Arg { short: None,       long: "only-links", takes_value: TakesValue::Forbidden };
+pub static TYPE:        Arg = Arg { short: None,       long: "type",       takes_value: TakesValue::Necessary(Some(TYPES)) };
+const TYPES: Values = &["f", "d", "l", "x", "s", "p", "b", "c"];
+pub static BROKEN_LINKS:     Arg = Arg { short: None, long: "broken-links",     takes_value: TakesValue::Forbidden };
+pub static NO_BROKEN_LINKS:  Arg = Arg { short: None, long: "no-broken-links",  takes_value: TakesValue::Forbidden };
+pub static HAS_XATTR:        Arg = Arg { short: None, long: "has-xattr",        takes_value: TakesValue::Necessary(None) };
+pub static ONLY:             Arg = Arg { short: None, long: "only",             takes_value: TakesValue::Necessary(Some(CATEGORIES)) };
+pub static EXCLUDE_CATEGORY: Arg = Arg { short: None, long: "exclude-category", takes_value: TakesValue::Necessary(Some(CATEGORIES)) };
+const CATEGORIES: Values = &["image", "video", "music", "lossless", "crypto", "document",
+                             "compressed", "temp", "compiled", "build", "source", "config"];
+const LIST_DIRS_MODES: &[&str] = &["auto"];
+const HIDDEN_MODES: &[&str] = &["dots", "native", "both"];
+const TREE_STYLES: &[&str] = &["unicode", "ascii", "rounded", "bold"];
+const SORTS: Values = &[ "name", "Name", "name-length", "size", "extension",
                          "Extension", "modified", "changed", "accessed",
-                         "created", "inode", "type", "none" ];
+                         "created", "inode", "links", "type", "category", "owner",
+                         "group", "random", "none" ];
+const TIE_BREAKS: Values = &[ "name", "none", "inode" ];
 
 // display options
 pub static BINARY:      Arg = Arg { short: Some(b'b'), long: "binary",      takes_value: TakesValue::Forbidden };
@@ -45,20 +108,34 @@ pub static BYTES:       Arg = Arg { short: Some(b'B'), long: "bytes",       take
 pub static GROUP:       Arg = Arg { short: Some(b'g'), long: "group",       takes_value: TakesValue::Forbidden };
 pub static NUMERIC:     Arg = Arg { short: Some(b'n'), long: "numeric",     takes_value: TakesValue::Forbidden };
 pub static HEADER:      Arg = Arg { short: Some(b'h'), long: "header",      takes_value: TakesValue::Forbidden };
+pub static HEADER_INTERVAL: Arg = Arg { short: None,   long: "header-interval", takes_value: TakesValue::Necessary(None) };
 pub static ICONS:       Arg = Arg { short: None,       long: "icons",       takes_value: TakesValue::Optional(Some(WHEN))};
+pub static ICON_COLOR:  Arg = Arg { short: None,       long: "icon-color",  takes_value: TakesValue::Necessary(Some(ICON_COLOURS)) };
+pub static ICON_COLOUR: Arg = Arg { short: None,       long: "icon-colour", takes_value: TakesValue::Necessary(Some(ICON_COLOURS)) };
+const ICON_COLOURS: &[&str] = &["file", "name", "fixed"];
+pub static ICON_SET:    Arg = Arg { short: None,       long: "icon-set",    takes_value: TakesValue::Necessary(Some(ICON_SETS)) };
+const ICON_SETS: &[&str] = &["nerd3", "nerd2", "unicode", "emoji"];
 pub static INODE:       Arg = Arg { short: Some(b'i'), long: "inode",       takes_value: TakesValue::Forbidden };
 pub static LINKS:       Arg = Arg { short: Some(b'H'), long: "links",       takes_value: TakesValue::Forbidden };
 pub static MODIFIED:    Arg = Arg { short: Some(b'm'), long: "modified",    takes_value: TakesValue::Forbidden };
 pub static CHANGED:     Arg = Arg { short: None,       long: "changed",     takes_value: TakesValue::Forbidden };
 pub static BLOCKSIZE:   Arg = Arg { short: Some(b'S'), long: "blocksize",   takes_value: TakesValue::Forbidden };
 pub static TOTAL_SIZE:  Arg = Arg { short: None,       long: "total-size",  takes_value: TakesValue::Forbidden };
+pub static DISK_USAGE:  Arg = Arg { short: None,       long: "disk-usage",  takes_value: TakesValue::Forbidden };
+pub static BLOCK_SIZE:  Arg = Arg { short: None,       long: "block-size",  takes_value: TakesValue::Necessary(None) };
+pub static SIZE_STYLE:  Arg = Arg { short: None,       long: "size-style",  takes_value: TakesValue::Necessary(Some(SIZE_STYLES)) };
+const SIZE_STYLES: Values = &["default", "binary", "bytes", "grouped"];
 pub static TIME:        Arg = Arg { short: Some(b't'), long: "time",        takes_value: TakesValue::Necessary(Some(TIMES)) };
 pub static ACCESSED:    Arg = Arg { short: Some(b'u'), long: "accessed",    takes_value: TakesValue::Forbidden };
 pub static CREATED:     Arg = Arg { short: Some(b'U'), long: "created",     takes_value: TakesValue::Forbidden };
 pub static TIME_STYLE:  Arg = Arg { short: None,       long: "time-style",  takes_value: TakesValue::Necessary(Some(TIME_STYLES)) };
 pub static HYPERLINK:   Arg = Arg { short: None,       long: "hyperlink",   takes_value: TakesValue::Forbidden };
 pub static MOUNTS:      Arg = Arg { short: Some(b'M'), long: "mounts",      takes_value: TakesValue::Forbidden };
+pub static THREADS:     Arg = Arg { short: None,       long: "threads",     takes_value: TakesValue::Necessary(Some(THREAD_COUNTS)) };
+const THREAD_COUNTS: Values = &["auto"];
 pub static SMART_GROUP: Arg = Arg { short: None,       long: "smart-group", takes_value: TakesValue::Forbidden };
+pub static AGE:         Arg = Arg { short: None,       long: "age",         takes_value: TakesValue::Optional(Some(AGE_UNITS)) };
+const AGE_UNITS: Values = &["seconds", "minutes", "hours", "days"];
 const TIMES: Values = &["modified", "changed", "accessed", "created"];
 const TIME_STYLES: Values = &["default", "long-iso", "full-iso", "iso", "relative"];
 
@@ -67,29 +144,40 @@ pub static NO_PERMISSIONS: Arg = Arg { short: None, long: "no-permissions", take
 pub static NO_FILESIZE: Arg = Arg { short: None, long: "no-filesize", takes_value: TakesValue::Forbidden };
 pub static NO_USER: Arg = Arg { short: None, long: "no-user", takes_value: TakesValue::Forbidden };
 pub static NO_TIME: Arg = Arg { short: None, long: "no-time", takes_value: TakesValue::Forbidden };
+pub static COLUMNS: Arg = Arg { short: None, long: "columns", takes_value: TakesValue::Necessary(Some(COLUMN_NAMES)) };
+pub static WRAP_NAMES: Arg = Arg { short: None, long: "wrap-names", takes_value: TakesValue::Forbidden };
+pub static HIDE: Arg = Arg { short: None, long: "hide", takes_value: TakesValue::Necessary(Some(HIDE_NAMES)) };
+pub static TABLE_STYLE: Arg = Arg { short: None, long: "table-style", takes_value: TakesValue::Necessary(Some(TABLE_STYLES)) };
+const TABLE_STYLES: &[&str] = &["borders", "compact", "none"];
+pub static ALIGN: Arg = Arg { short: None, long: "align", takes_value: TakesValue::Necessary(None) };
 
 // optional feature options
 pub static GIT:               Arg = Arg { short: None,       long: "git",                  takes_value: TakesValue::Forbidden };
 pub static NO_GIT:            Arg = Arg { short: None,       long: "no-git",               takes_value: TakesValue::Forbidden };
 pub static GIT_REPOS:         Arg = Arg { short: None,       long: "git-repos",            takes_value: TakesValue::Forbidden };
 pub static GIT_REPOS_NO_STAT: Arg = Arg { short: None,       long: "git-repos-no-status",  takes_value: TakesValue::Forbidden };
+pub static GIT_SUMMARY:       Arg = Arg { short: None,       long: "git-summary",          takes_value: TakesValue::Forbidden };
 pub static EXTENDED:          Arg = Arg { short: Some(b'@'), long: "extended",             takes_value: TakesValue::Forbidden };
 pub static OCTAL:             Arg = Arg { short: Some(b'o'), long: "octal-permissions",    takes_value: TakesValue::Forbidden };
 pub static SECURITY_CONTEXT:  Arg = Arg { short: Some(b'Z'), long: "context",              takes_value: TakesValue::Forbidden };
+pub static CODEOWNERS:        Arg = Arg { short: None,       long: "codeowners",           takes_value: TakesValue::Forbidden };
+pub static SAVE_SNAPSHOT:     Arg = Arg { short: None,       long: "save-snapshot",        takes_value: TakesValue::Necessary(None) };
+pub static UPDATE_SNAPSHOT:   Arg = Arg { short: None,       long: "update-snapshot",      takes_value: TakesValue::Necessary(None) };
+pub static DELTA:             Arg = Arg { short: None,       long: "delta",                takes_value: TakesValue::Necessary(None) };
 
 pub static ALL_ARGS: Args = Args(&[
     &VERSION, &HELP,
 
-    &ONE_LINE, &LONG, &GRID, &ACROSS, &RECURSE, &TREE, &CLASSIFY, &DEREF_LINKS,
-    &COLOR, &COLOUR, &COLOR_SCALE, &COLOUR_SCALE, &WIDTH, &NO_QUOTES,
+    &ONE_LINE, &LONG, &GRID, &ACROSS, &GRID_ROWS, &RECURSE, &TREE, &CLASSIFY, &DEREF_LINKS, &DEREF_ARGS,
+    &COLOR, &COLOUR, &COLOR_SCALE, &COLOUR_SCALE, &COLOR_COLUMNS, &COLOUR_COLUMNS, &COLOR_OWNERS, &COLOUR_OWNERS, &THEME, &WIDTH, &NO_QUOTES, &EMPTY_PLACEHOLDER, &COUNT_ONLY, &PROMPT, &SUMMARY, &LIMIT, &LIMIT_MARKER, &FORGET, &SEMANTIC_MARKERS, &FLUSH, &GROUP_BY,
 
-    &ALL, &ALMOST_ALL, &LIST_DIRS, &LEVEL, &REVERSE, &SORT, &DIRS_FIRST,
-    &IGNORE_GLOB, &GIT_IGNORE, &ONLY_DIRS, &ONLY_FILES,
+    &ALL, &ALMOST_ALL, &HIDDEN_MODE, &LIST_DIRS, &LEVEL, &MIN_DEPTH, &FOLLOW_LINKS, &FLAT, &PRUNE_GLOB, &ONE_FILE_SYSTEM, &INLINE_ERRORS, &COLLAPSE_CONFIGS, &TREE_COUNTS, &COMPACT_TREE, &TREE_STYLE, &TREE_LIMIT, &DIFF, &ONLY_CHANGES, &REVERSE, &SORT, &SEED, &TIE_BREAK, &DIRS_FIRST,
+    &IGNORE_GLOB, &REGEX, &IREGEX, &IGNORE_REGEX, &IGNORE_IREGEX, &SIZE, &NEWER_THAN, &OLDER_THAN, &GIT_IGNORE, &ONLY_DIRS, &ONLY_FILES, &ONLY_LINKS, &TYPE, &ONLY, &EXCLUDE_CATEGORY, &BROKEN_LINKS, &NO_BROKEN_LINKS, &HAS_XATTR,
 
-    &BINARY, &BYTES, &GROUP, &NUMERIC, &HEADER, &ICONS, &INODE, &LINKS, &MODIFIED, &CHANGED,
-    &BLOCKSIZE, &TOTAL_SIZE, &TIME, &ACCESSED, &CREATED, &TIME_STYLE, &HYPERLINK, &MOUNTS,
-    &NO_PERMISSIONS, &NO_FILESIZE, &NO_USER, &NO_TIME, &SMART_GROUP,
+    &BINARY, &BYTES, &GROUP, &NUMERIC, &HEADER, &HEADER_INTERVAL, &ICONS, &ICON_COLOR, &ICON_COLOUR, &ICON_SET, &INODE, &LINKS, &MODIFIED, &CHANGED,
+    &BLOCKSIZE, &TOTAL_SIZE, &DISK_USAGE, &BLOCK_SIZE, &SIZE_STYLE, &TIME, &ACCESSED, &CREATED, &TIME_STYLE, &AGE, &HYPERLINK, &MOUNTS, &THREADS,
+    &NO_PERMISSIONS, &NO_FILESIZE, &NO_USER, &NO_TIME, &COLUMNS, &HIDE, &WRAP_NAMES, &TABLE_STYLE, &ALIGN, &SMART_GROUP,
 
-    &GIT, &NO_GIT, &GIT_REPOS, &GIT_REPOS_NO_STAT,
-    &EXTENDED, &OCTAL, &SECURITY_CONTEXT
+    &GIT, &NO_GIT, &GIT_REPOS, &GIT_REPOS_NO_STAT, &GIT_SUMMARY,
+    &EXTENDED, &OCTAL, &SECURITY_CONTEXT, &CODEOWNERS, &SAVE_SNAPSHOT, &UPDATE_SNAPSHOT, &DELTA
 ]);
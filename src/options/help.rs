@@ -5,7 +5,15 @@ use crate::options::flags;
 use crate::options::parser::MatchedFlags;
 
 static USAGE_PART1: &str = "Usage:
-  eza [options] [files...]
+  eza [options] [@preset...] [files...]
+  eza SUBCOMMAND [options] [@preset...] [files...]
+
+SUBCOMMANDS
+  list               list files (the default)
+  tree               list files as a tree, like --tree
+  du                 list files by their total size, like du
+  find PATTERN       list everything beneath with a name matching PATTERN
+  diff OLD NEW       compare two directories in one tree
 
 META OPTIONS
   --help             show list of command-line options
@@ -16,15 +24,35 @@ DISPLAY OPTIONS
   -l, --long         display extended file metadata as a table
   -G, --grid         display entries as a grid (default)
   -x, --across       sort the grid across, rather than downwards
+  --grid-rows N      aim for N rows in the grid, rather than as few as fit
   -R, --recurse      recurse into directories
   -T, --tree         recurse into directories as a tree
   -X, --dereference  dereference symbolic links when displaying information
+  --dereference-command-line  dereference only symbolic links given as arguments
   -F, --classify     display type indicator by file names
   --colo[u]r=WHEN    when to use terminal colours (always, auto, never)
   --colo[u]r-scale   highlight levels of file sizes distinctly
+  --colo[u]r-columns=WHEN  when to colour the long view's columns (always, dim, never)
+  --colo[u]r-owners  colour each user and group by its name
+  --theme=NAME       which built-in colour theme to use (auto, light, dark, gruvbox, ...)
   --icons=WHEN       when to display icons (always, auto, never)
+  --icon-colo[u]r=MODE  how to colour icons (file, name, fixed)
+  --icon-set=SET     which icons to use (nerd3, nerd2, unicode, emoji)
   --no-quotes        don't quote file names with spaces
+  --empty-placeholder  show a placeholder line for directories with no entries
+  --count-only       print the number and total size of entries instead of listing them
+  --prompt           print a one-line summary of the directory for use in shell prompts
+  --summary          print the number and total size of entries after listing them
+  --limit N          list only the first N entries, once they've been sorted
+  --limit-marker     end a limited listing with how many entries were left out
+  --save-snapshot=FILE  save the sizes of everything beneath the listed paths, for --delta
+  --update-snapshot=FILE  update a saved snapshot, only reading the directories that changed
+  --forget           forget the options remembered for the directory (see EZA_REMEMBER)
   --hyperlink        display entries as hyperlinks
+  --semantic-markers  mark where the output starts and ends for the terminal
+  --flush=WHEN       when to write out the output (line, dir, end)
+  --group-by=WHAT    split the listing into sections, each with a header
+                     (category, extension, first-letter, none)
   -w, --width COLS   set screen width in columns
   --smart-group      only show group if it has a different name from owner
 
@@ -32,50 +60,102 @@ DISPLAY OPTIONS
 FILTERING AND SORTING OPTIONS
   -a, --all                  show hidden and 'dot' files. Use this twice to also show the '.' and '..' directories
   -A, --almost-all           equivalent to --all; included for compatibility with `ls -A`
-  -d, --list-dirs            list directories as files; don't list their contents
+  --hidden-mode MODE         which files are hidden (dots, native, both)
+  -d, --list-dirs[=auto]     list directories as files; don't list their contents
+                             (with auto, only paths ending in '/' are listed)
   -L, --level DEPTH          limit the depth of recursion
+  --min-depth DEPTH          skip the first levels of recursion
+  --follow-links             recurse into the directories that links point to
+  --flat                     list everything beneath the directories in one view, by path
+  --prune-glob GLOBS         glob patterns (pipe-separated) of directories not to recurse into
+  --one-file-system          don't recurse into directories on other filesystems
+  --inline-errors            print errors about unreadable directories where they happen
+  --collapse-configs         list a tree's tool config files on one line per directory
+  --tree-counts              show how many files are beneath each directory in a tree
+  --compact-tree             list chains of single directories in a tree on one line
+  --tree-style STYLE         which characters to draw trees with (unicode, ascii, rounded, bold)
+  --tree-limit N             list at most N entries in each directory of a tree
+  --diff                     compare two directories in one tree
+  --only-changes             collapse the entries that are the same in a --diff
   -r, --reverse              reverse the sort order
   -s, --sort SORT_FIELD      which field to sort by
+  --seed SEED                seed for the random sort order, to repeat a shuffle
+  --tie-break FIELD          how to order files that sort the same (name, none, inode)
   --group-directories-first  list directories before other files
   -D, --only-dirs            list only directories
   -f, --only-files           list only files
-  -I, --ignore-glob GLOBS    glob patterns (pipe-separated) of files to ignore";
+  --only-links               list only symbolic links
+  --type TYPES               list only these types (comma-separated): f(ile),
+                             d(irectory), l(ink), x (executable), s(ocket),
+                             p(ipe), b(lock device), and c(haracter device)
+  --only CATEGORIES          list only files in these categories (comma-separated),
+                             such as image, video, music, document, or source
+  --exclude-category CATEGORIES  don't list files in these categories
+  --broken-links             list only symlinks that point to nothing
+  --no-broken-links          don't list symlinks that point to nothing
+  --has-xattr NAME[=VALUE]   list only files with this extended attribute
+  -I, --ignore-glob GLOBS    glob patterns (pipe-separated) of files to ignore
+  --regex PATTERN            only list files with names matching a regex
+  --ignore-regex PATTERN     don't list files with names matching a regex
+                             (--iregex and --ignore-iregex ignore case)
+  --size SIZE                only list files of at least (+10M), at most (-4k),
+                             or between (1M..100M) these sizes
+  --newer-than TIME          only list files newer than a time ago or a date
+  --older-than TIME          only list files older than a time ago or a date";
 
 static GIT_FILTER_HELP: &str = "  \
   --git-ignore               ignore files mentioned in '.gitignore'";
 
 static USAGE_PART2: &str = "  \
-  Valid sort fields:         name, Name, extension, Extension, size, type,
-                             modified, accessed, created, inode, and none.
+  Valid sort fields:         name, Name, name-length, extension, Extension,
+                             size, type, modified, accessed, created, inode,
+                             links, category, owner, group, random, and none.
                              date, time, old, and new all refer to modified.
+                             Separate fields with commas to break ties, and
+                             add :asc or :desc to a field to pick its order.
 
 LONG VIEW OPTIONS
   -b, --binary             list file sizes with binary prefixes
   -B, --bytes              list file sizes in bytes, without any prefixes
+  --block-size SIZE        list file sizes as a number of blocks of SIZE, such as K or 1M
+  --size-style STYLE       how to format file sizes (default, binary, bytes, grouped)
   -g, --group              list each file's group
   -h, --header             add a header row to each column
+  --header-interval N      repeat the header row after every N rows
   -H, --links              list each file's number of hard links
   -i, --inode              list each file's inode number
   -m, --modified           use the modified timestamp field
   -M, --mounts             show mount details (Linux and MacOS only)
   -n, --numeric            list numeric user and group IDs
   -S, --blocksize          show size of allocated file system blocks
+  --disk-usage             show the space files take up on disk as their size
   -t, --time FIELD         which timestamp field to list (modified, accessed, created)
   -u, --accessed           use the accessed timestamp field
   -U, --created            use the created timestamp field
   --changed                use the changed timestamp field
   --time-style             how to format timestamps (default, iso, long-iso, full-iso, relative, or a custom style with '+' as prefix. Ex: '+%Y/%m/%d')
+  --age[=UNIT]             show how long ago each file's timestamp was (seconds, minutes, hours, days)
   --total-size             show the size of a directory as the size of all files and directories inside
+  --codeowners             list each file's owners from the repository's CODEOWNERS file
+  --delta=FILE             show how much each entry has grown since a saved snapshot
+  --threads COUNT          how many threads to look up file details with (a number, or auto)
   --no-permissions         suppress the permissions field
   -o, --octal-permissions  list each file's permission in octal format
   --no-filesize            suppress the filesize field
   --no-user                suppress the user field
-  --no-time                suppress the time field";
+  --no-time                suppress the time field
+  --columns COLUMNS        which columns to list, in order (comma-separated),
+                           such as perms,size,user,git,name
+  --hide COLUMNS           which columns not to list (comma-separated)
+  --wrap-names             carry names too long for the terminal onto the next lines
+  --table-style STYLE      lines to draw around the columns (borders, compact, none)
+  --align COLUMNS          which side to align columns to, such as inode=right,name=right";
 
 static GIT_VIEW_HELP:   &str = "  \
   --git                    list each file's Git status, if tracked or ignored
   --no-git                 suppress Git status (always overrides --git, --git-repos, --git-repos-no-status)
-  --git-repos              list root of git-tree status";
+  --git-repos              list root of git-tree status
+  --git-summary            print how many files have each kind of Git change after listing them";
 static EXTENDED_HELP: &str = "  \
   -@, --extended           list each file's extended attributes and sizes";
 static SECATTR_HELP: &str = "  \
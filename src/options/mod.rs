@@ -71,10 +71,13 @@
 use std::ffi::OsStr;
 
 use crate::fs::dir_action::DirAction;
-use crate::fs::filter::{FileFilter, GitIgnore};
+use crate::fs::filter::{FileFilter, GitIgnore, SortField};
+use crate::output::details::Threads;
+use crate::output::table::TimeType;
 use crate::output::{details, grid_details, Mode, View};
 use crate::theme::Options as ThemeOptions;
 
+mod conflicts;
 mod dir_action;
 mod file_name;
 mod filter;
@@ -84,6 +87,7 @@ mod theme;
 mod view;
 
 mod error;
+pub use self::conflicts::OptionsWarning;
 pub use self::error::{NumberSource, OptionsError};
 
 mod help;
@@ -92,6 +96,12 @@ use self::help::HelpString;
 mod parser;
 use self::parser::MatchedFlags;
 
+pub mod preset;
+
+pub mod remember;
+
+pub mod subcommand;
+
 pub mod vars;
 pub use self::vars::Vars;
 
@@ -115,8 +125,16 @@ pub struct Options {
     /// width, then the view will be downgraded.
     pub view: View,
 
+    /// How many threads to look up files and read directories with, which
+    /// every view uses.
+    pub threads: Threads,
+
     /// The options to make up the styles of the UI and file names.
     pub theme: ThemeOptions,
+
+    /// The options that were given but will be ignored because of others,
+    /// which the user should be warned about.
+    pub warnings: Vec<OptionsWarning>,
 }
 
 impl Options {
@@ -157,14 +175,18 @@ impl Options {
         }
     }
 
-    /// Whether the View specified in this set of options includes a Git
-    /// status column. It’s only worth trying to discover a repository if the
-    /// results will end up being displayed.
+    /// Whether anything in this set of options uses Git statuses. It’s only
+    /// worth trying to discover a repository if the results will end up being
+    /// used.
     pub fn should_scan_for_git(&self) -> bool {
-        if self.filter.git_ignore == GitIgnore::CheckAndIgnore {
-            return true;
-        }
+        self.filter.git_ignore == GitIgnore::CheckAndIgnore
+            || self.view.prompt
+            || self.shows_git_column()
+    }
 
+    /// Whether the View specified in this set of options includes a Git
+    /// status column.
+    pub fn shows_git_column(&self) -> bool {
         match self.view.mode {
             Mode::Details(details::Options {
                 table: Some(ref table),
@@ -182,6 +204,30 @@ impl Options {
         }
     }
 
+    /// Whether files’ birth times get displayed, sorted on, or filtered by,
+    /// and so have to be asked for when statting them.
+    pub fn reads_birth_times(&self) -> bool {
+        let shown = match self.view.mode {
+            Mode::Details(details::Options {
+                table: Some(ref table),
+                ..
+            })
+            | Mode::GridDetails(grid_details::Options {
+                details:
+                    details::Options {
+                        table: Some(ref table),
+                        ..
+                    },
+                ..
+            }) => table.columns.time_types.created,
+            _ => false,
+        };
+
+        shown
+            || self.filter.time_range.field == TimeType::Created
+            || (self.filter.sort_keys.iter()).any(|key| key.field == SortField::CreatedDate)
+    }
+
     /// Determines the complete set of options based on the given command-line
     /// arguments, after they’ve been parsed.
     fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
@@ -197,14 +243,18 @@ impl Options {
 
         let view = View::deduce(matches, vars)?;
         let dir_action = DirAction::deduce(matches, matches!(view.mode, Mode::Details(_)))?;
-        let filter = FileFilter::deduce(matches)?;
+        let filter = FileFilter::deduce(matches, dir_action.recurse_options().is_some())?;
+        let threads = Threads::deduce(matches, vars)?;
         let theme = ThemeOptions::deduce(matches, vars)?;
+        let warnings = conflicts::check(matches, &view)?;
 
         Ok(Self {
             dir_action,
             filter,
             view,
+            threads,
             theme,
+            warnings,
         })
     }
 }
@@ -252,6 +302,7 @@ pub mod test {
     ///
     /// It returns a vector with one or two elements in.
     /// These elements can then be tested with `assert_eq` or what have you.
+    #[allow(clippy::needless_pass_by_value)]
     pub fn parse_for_test<T, F>(
         inputs: &[&str],
         args: &'static [&'static Arg],
@@ -100,6 +100,10 @@ pub enum TakesValue {
 
     /// This flag may be followed by a value to override its defaults
     Optional(Option<Values>),
+
+    /// This flag may be given a value to override its defaults, but only
+    /// after an equals sign, as what comes after it is usually a file name.
+    OptionalAttached(Option<Values>),
 }
 
 /// An **argument** can be matched by one of the user’s input strings.
@@ -176,7 +180,9 @@ impl Args {
                     let arg = self.lookup_long(before)?;
                     let flag = Flag::Long(arg.long);
                     match arg.takes_value {
-                        TakesValue::Necessary(_) | TakesValue::Optional(_) => {
+                        TakesValue::Necessary(_)
+                        | TakesValue::Optional(_)
+                        | TakesValue::OptionalAttached(_) => {
                             result_flags.push((flag, Some(after)));
                         }
                         TakesValue::Forbidden => return Err(ParseError::ForbiddenValue { flag }),
@@ -188,7 +194,7 @@ impl Args {
                     let arg = self.lookup_long(long_arg_name)?;
                     let flag = Flag::Long(arg.long);
                     match arg.takes_value {
-                        TakesValue::Forbidden => {
+                        TakesValue::Forbidden | TakesValue::OptionalAttached(_) => {
                             result_flags.push((flag, None));
                         }
                         TakesValue::Necessary(values) => {
@@ -233,7 +239,9 @@ impl Args {
                         let arg = self.lookup_short(*byte)?;
                         let flag = Flag::Short(*byte);
                         match arg.takes_value {
-                            TakesValue::Forbidden | TakesValue::Optional(_) => {
+                            TakesValue::Forbidden
+                            | TakesValue::Optional(_)
+                            | TakesValue::OptionalAttached(_) => {
                                 result_flags.push((flag, None));
                             }
                             TakesValue::Necessary(values) => {
@@ -246,7 +254,9 @@ impl Args {
                     let arg = self.lookup_short(*arg_with_value)?;
                     let flag = Flag::Short(arg.short.unwrap());
                     match arg.takes_value {
-                        TakesValue::Necessary(_) | TakesValue::Optional(_) => {
+                        TakesValue::Necessary(_)
+                        | TakesValue::Optional(_)
+                        | TakesValue::OptionalAttached(_) => {
                             result_flags.push((flag, Some(after)));
                         }
                         TakesValue::Forbidden => {
@@ -271,10 +281,15 @@ impl Args {
                         let arg = self.lookup_short(*byte)?;
                         let flag = Flag::Short(*byte);
                         match arg.takes_value {
-                            TakesValue::Forbidden => {
+                            // Short arguments can only be given an optional
+                            // value after an equals sign, so that ‘-d dir’
+                            // doesn’t take the directory as its value.
+                            TakesValue::Forbidden
+                            | TakesValue::Optional(_)
+                            | TakesValue::OptionalAttached(_) => {
                                 result_flags.push((flag, None));
                             }
-                            TakesValue::Necessary(values) | TakesValue::Optional(values) => {
+                            TakesValue::Necessary(values) => {
                                 if index < bytes.len() - 1 {
                                     let remnants = &bytes[index + 1..];
                                     result_flags.push((flag, Some(bytes_to_os_str(remnants))));
@@ -282,17 +297,7 @@ impl Args {
                                 } else if let Some(next_arg) = inputs.next() {
                                     result_flags.push((flag, Some(next_arg)));
                                 } else {
-                                    match arg.takes_value {
-                                        TakesValue::Forbidden => {
-                                            unreachable!()
-                                        }
-                                        TakesValue::Necessary(_) => {
-                                            return Err(ParseError::NeedsValue { flag, values });
-                                        }
-                                        TakesValue::Optional(_) => {
-                                            result_flags.push((flag, None));
-                                        }
-                                    }
+                                    return Err(ParseError::NeedsValue { flag, values });
                                 }
                             }
                         }
@@ -415,6 +420,28 @@ impl<'a> MatchedFlags<'a> {
             .map(|tuple| &tuple.0)
     }
 
+    /// Returns the last found argument that satisfies the predicate, whether
+    /// or not it was given a value, with strict mode having no effect.
+    ///
+    /// You’ll have to test the resulting flag to see which argument it was.
+    pub fn last_where_any<P>(&self, predicate: P) -> Option<&Flag>
+    where
+        P: Fn(&Flag) -> bool,
+    {
+        self.flags
+            .iter()
+            .rev()
+            .find(|tuple| predicate(&tuple.0))
+            .map(|tuple| &tuple.0)
+    }
+
+    /// Returns the position of the last occurrence of the given argument,
+    /// whether or not it was given a value, with strict mode having no
+    /// effect. This is used to tell which of two options came later.
+    pub fn position(&self, arg: &Arg) -> Option<usize> {
+        self.flags.iter().rposition(|tuple| tuple.0.matches(arg))
+    }
+
     // This code could probably be better.
     // Both ‘has’ and ‘get’ immediately begin with a conditional, which makes
     // me think the functionality could be moved to inside Strictness.
@@ -639,7 +666,9 @@ mod parse_test {
         &Arg { short: Some(b'l'), long: "long",     takes_value: TakesValue::Forbidden },
         &Arg { short: Some(b'v'), long: "verbose",  takes_value: TakesValue::Forbidden },
         &Arg { short: Some(b'c'), long: "count",    takes_value: TakesValue::Necessary(None) },
-        &Arg { short: Some(b't'), long: "type",     takes_value: TakesValue::Necessary(Some(SUGGESTIONS)) }
+        &Arg { short: Some(b't'), long: "type",     takes_value: TakesValue::Necessary(Some(SUGGESTIONS)) },
+        &Arg { short: Some(b'o'), long: "optional", takes_value: TakesValue::Optional(None) },
+        &Arg { short: Some(b'a'), long: "attached", takes_value: TakesValue::OptionalAttached(None) },
     ];
 
     // Just filenames
@@ -690,6 +719,31 @@ mod parse_test {
     test!(short_two_equals_s:   ["-t=exa"]     => frees: [],  flags: [(Flag::Short(b't'), Some(OsStr::new("exa"))) ]);
     test!(short_two_next_s:     ["-t", "exa"]  => frees: [],  flags: [(Flag::Short(b't'), Some(OsStr::new("exa"))) ]);
 
+    // Short args with optional values
+    test!(short_opt:          ["-o"]          => frees: [],         flags: [(Flag::Short(b'o'), None) ]);
+    test!(short_opt_then:     ["-o", "dir"]   => frees: [ "dir" ],  flags: [(Flag::Short(b'o'), None) ]);
+    test!(short_opt_together: ["-ol"]         => frees: [],         flags: [(Flag::Short(b'o'), None), (Flag::Short(b'l'), None) ]);
+    test!(short_opt_equals:   ["-o=auto"]     => frees: [],         flags: [(Flag::Short(b'o'), Some(OsStr::new("auto"))) ]);
+
+    // Args with optional values that have to come after an equals sign
+    test!(long_att:          ["--attached"]          => frees: [],          flags: [(Flag::Long("attached"), None) ]);
+    test!(long_att_then:     ["--attached", "auto"]  => frees: [ "auto" ],  flags: [(Flag::Long("attached"), None) ]);
+    test!(long_att_equals:   ["--attached=auto"]     => frees: [],          flags: [(Flag::Long("attached"), Some(OsStr::new("auto"))) ]);
+    test!(short_att_then:    ["-a", "auto"]          => frees: [ "auto" ],  flags: [(Flag::Short(b'a'), None) ]);
+    test!(short_att_equals:  ["-a=auto"]             => frees: [],          flags: [(Flag::Short(b'a'), Some(OsStr::new("auto"))) ]);
+
+    #[test]
+    fn list_dirs_then_dir() {
+        use crate::options::flags;
+
+        static ARGS: &[&Arg] = &[&flags::LIST_DIRS];
+        let inputs = [OsStr::new("--list-dirs"), OsStr::new("auto")];
+        let got = Args(ARGS).parse(inputs.iter().copied(), Strictness::UseLastArguments);
+        let matches = got.unwrap();
+        assert_eq!(matches.frees, vec![OsStr::new("auto")]);
+        assert_eq!(matches.flags.flags, vec![(Flag::Long("list-dirs"), None)]);
+    }
+
     // Unknown args
     test!(unknown_long:          ["--quiet"]      => error UnknownArgument      { attempt: OsString::from("quiet") });
     test!(unknown_long_eq:       ["--quiet=shhh"] => error UnknownArgument      { attempt: OsString::from("quiet") });
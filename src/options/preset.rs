@@ -0,0 +1,252 @@
+//! Expanding presets, which are named bundles of options defined in the
+//! config file and used by giving their name after an `@`, so `eza @dev` can
+//! take the place of a shell alias.
+//!
+//! Presets are defined with lines such as:
+//!
+//! ```toml
+//! preset.dev = ["-l", "--git", "--icons"]
+//! ```
+//!
+//! or in a `[preset]` table with just `dev = [...]`. Anything else in the
+//! file is left alone, as are presets that use other presets.
+
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::*;
+
+use crate::options::{vars, Vars};
+
+/// The name of the file that presets are read from, in the config directory.
+pub const FILE_NAME: &str = "config.toml";
+
+/// Something wrong with one of the presets that was used.
+#[derive(PartialEq, Eq, Debug)]
+pub enum PresetError {
+    /// The preset isn’t in the config file, or there isn’t a config file,
+    /// which is where it was looked for.
+    Unknown(String, Option<PathBuf>),
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(name, _) => write!(f, "Preset @{name} is not defined"),
+        }
+    }
+}
+
+impl PresetError {
+    /// Tells the user where the presets should be defined.
+    pub fn suggestion(&self) -> String {
+        match self {
+            Self::Unknown(_, Some(path)) => format!("Presets are defined in {}", path.display()),
+            Self::Unknown(_, None) => {
+                format!("Presets are defined in eza/{FILE_NAME} in the config directory")
+            }
+        }
+    }
+}
+
+/// Replaces each preset in the arguments with the options it stands for.
+/// Arguments after `--` are left alone, as are ones that look like a preset
+/// but name a file that exists.
+pub fn expand<V: Vars>(args: Vec<OsString>, vars: &V) -> Result<Vec<OsString>, PresetError> {
+    let is_preset = |arg: &OsStr| {
+        arg.to_str()
+            .map_or(false, |a| a.len() > 1 && a.starts_with('@'))
+    };
+
+    let end = args
+        .iter()
+        .position(|arg| arg == "--")
+        .unwrap_or(args.len());
+    if !args[..end].iter().any(|arg| is_preset(arg)) {
+        return Ok(args);
+    }
+
+    let file = config_file(vars);
+    let presets = file
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|contents| parse(&contents))
+        .unwrap_or_default();
+
+    let mut expanded = Vec::with_capacity(args.len());
+    for (i, arg) in args.into_iter().enumerate() {
+        if i >= end || !is_preset(&arg) {
+            expanded.push(arg);
+            continue;
+        }
+
+        let name = &arg.to_str().unwrap_or_default()[1..];
+        match presets.iter().find(|(n, _)| n == name) {
+            Some((_, options)) => expanded.extend(options.iter().map(OsString::from)),
+            None if Path::new(&arg).exists() => expanded.push(arg),
+            None => return Err(PresetError::Unknown(name.to_owned(), file)),
+        }
+    }
+
+    Ok(expanded)
+}
+
+/// The config file in the config directory.
+fn config_file<V: Vars>(vars: &V) -> Option<PathBuf> {
+    config_dir(vars).map(|dir| dir.join(FILE_NAME))
+}
+
+/// The directory that the config files are read from, which is
+/// `$EZA_CONFIG_DIR`, or `$XDG_CONFIG_HOME/eza`, falling back to
+/// `~/.config/eza`.
+pub fn config_dir<V: Vars>(vars: &V) -> Option<PathBuf> {
+    if let Some(dir) = vars.get(vars::EZA_CONFIG_DIR).filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|config| !config.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config.join("eza"))
+}
+
+/// Reads the presets out of the config file, in the order they’re defined.
+/// Lines that can’t be read are skipped with a warning.
+fn parse(contents: &str) -> Vec<(String, Vec<String>)> {
+    let mut presets = Vec::new();
+    let mut table = String::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            table = name.trim().to_owned();
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+
+        let key = key.trim();
+        let name = if table == "preset" {
+            key
+        } else if let Some(name) = key.strip_prefix("preset.").filter(|_| table.is_empty()) {
+            name
+        } else {
+            continue;
+        };
+
+        if let Some(options) = parse_array(value.trim()) {
+            presets.push((name.to_owned(), options));
+        } else {
+            warn!("Invalid options for preset {:?}: {}", name, value.trim());
+        }
+    }
+
+    presets
+}
+
+/// Parses an array of strings, which can be in either double quotes (with
+/// backslash escapes) or single quotes (without).
+fn parse_array(value: &str) -> Option<Vec<String>> {
+    let mut chars = value.strip_prefix('[')?.chars();
+    let mut strings = Vec::new();
+
+    loop {
+        match chars.find(|c| !c.is_whitespace())? {
+            ']' => break,
+            ',' if !strings.is_empty() => continue,
+            '"' => {
+                let mut string = String::new();
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            'n' => string.push('\n'),
+                            't' => string.push('\t'),
+                            c => string.push(c),
+                        },
+                        c => string.push(c),
+                    }
+                }
+                strings.push(string);
+            }
+            '\'' => {
+                let mut string = String::new();
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        c => string.push(c),
+                    }
+                }
+                strings.push(string);
+            }
+            _ => return None,
+        }
+    }
+
+    match chars.find(|c| !c.is_whitespace()) {
+        None | Some('#') => Some(strings),
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn strings(strings: &[&str]) -> Vec<String> {
+        strings.iter().map(|s| (*s).to_owned()).collect()
+    }
+
+    #[test]
+    fn dotted_keys() {
+        let presets = parse("# presets\npreset.dev = [\"-l\", \"--git\", \"--icons\"]\n");
+        assert_eq!(
+            presets,
+            vec![(String::from("dev"), strings(&["-l", "--git", "--icons"]))]
+        );
+    }
+
+    #[test]
+    fn table() {
+        let presets = parse("[preset]\nbig = ['-l', '--sort=size']\n\n[other]\nsmall = ['-1']\n");
+        assert_eq!(
+            presets,
+            vec![(String::from("big"), strings(&["-l", "--sort=size"]))]
+        );
+    }
+
+    #[test]
+    fn arrays() {
+        assert_eq!(parse_array("[]"), Some(vec![]));
+        assert_eq!(
+            parse_array("[ \"a\\\"b\" , 'c\\d', ]  # comment"),
+            Some(strings(&["a\"b", "c\\d"]))
+        );
+        assert_eq!(parse_array("[\"unterminated"), None);
+        assert_eq!(parse_array("['unterminated]"), None);
+        assert_eq!(parse_array("\"-l\""), None);
+        assert_eq!(parse_array("[-l]"), None);
+    }
+
+    #[test]
+    fn no_presets() {
+        let args = vec![OsString::from("-l"), OsString::from("src")];
+        assert_eq!(expand(args.clone(), &None::<OsString>), Ok(args));
+    }
+
+    #[test]
+    fn after_dashes() {
+        let args = vec![OsString::from("--"), OsString::from("@dev")];
+        assert_eq!(expand(args.clone(), &None::<OsString>), Ok(args));
+    }
+}
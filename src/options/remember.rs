@@ -0,0 +1,233 @@
+//! Remembering the options used to list each directory, so they can be used
+//! again the next time it’s listed without any.
+//!
+//! This is only done when the `EZA_REMEMBER` variable is set, and only when
+//! a single directory (or none, for the current one) is being listed. The
+//! options are saved whenever some are given, and `--forget` deletes them.
+//!
+//! Remembered options are put *before* the ones on the command-line, in the
+//! same way as options set in an alias, so any options that are given can
+//! still override them.
+
+use std::collections::hash_map::DefaultHasher;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use log::*;
+
+use crate::options::parser::Strictness;
+use crate::options::{flags, vars, Vars};
+
+/// Written at the top of every state file, so the format can be changed.
+const MAGIC: &[u8] = b"eza-dir-state 1\n";
+
+/// Puts the options remembered for the directory being listed in front of
+/// the given arguments, or remembers the options in them, depending on
+/// whether there are any. Anything that can’t be worked out is left for the
+/// actual parsing to complain about.
+pub fn apply<V: Vars>(args: Vec<OsString>, vars: &V) -> Vec<OsString> {
+    if vars.get(vars::EZA_REMEMBER).map_or(true, |v| v.is_empty()) {
+        return args;
+    }
+
+    let (dir, options, forget) = {
+        let Ok(matches) =
+            flags::ALL_ARGS.parse(args.iter().map(OsString::as_os_str), Strictness::UseLastArguments)
+        else {
+            return args;
+        };
+
+        // Asking for help shouldn’t make every listing afterwards do it.
+        let meta = matches.flags.has(&flags::HELP).unwrap_or(false)
+            || matches.flags.has(&flags::VERSION).unwrap_or(false);
+
+        if meta || matches.frees.len() > 1 {
+            return args;
+        }
+
+        let dir = matches.frees.first().copied().unwrap_or(OsStr::new("."));
+        let Ok(dir) = Path::new(dir).canonicalize() else {
+            return args;
+        };
+
+        let options = args
+            .iter()
+            .filter(|arg| {
+                *arg != "--"
+                    && !matches
+                        .frees
+                        .iter()
+                        .any(|f| std::ptr::eq(*f, arg.as_os_str()))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let forget = matches.flags.has(&flags::FORGET).unwrap_or(false);
+        (dir, options, forget)
+    };
+
+    if !dir.is_dir() {
+        return args;
+    }
+
+    if forget {
+        forget_options(&dir);
+        args
+    } else if options.is_empty() {
+        match load(&dir) {
+            Some(mut remembered) => {
+                remembered.extend(args);
+                remembered
+            }
+            None => args,
+        }
+    } else {
+        save(&dir, &options);
+        args
+    }
+}
+
+/// Loads the options remembered for the directory, if there are any.
+fn load(dir: &Path) -> Option<Vec<OsString>> {
+    let path = state_file(dir)?;
+    let contents = fs::read(&path).ok()?;
+    let options = decode(&contents, dir);
+    if options.is_none() {
+        debug!("State file {:?} is for a different directory", path);
+    }
+    options
+}
+
+/// Remembers the options for the directory. Errors are only logged, as
+/// listing the directory matters more.
+fn save(dir: &Path, options: &[OsString]) {
+    let Some(path) = state_file(dir) else {
+        return;
+    };
+
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(&path, encode(dir, options)));
+
+    if let Err(e) = result {
+        warn!("Error remembering options in {:?}: {:?}", path, e);
+    }
+}
+
+/// Deletes the options remembered for the directory.
+fn forget_options(dir: &Path) {
+    let Some(path) = state_file(dir) else {
+        return;
+    };
+
+    if let Err(e) = fs::remove_file(&path) {
+        debug!("Error forgetting options in {:?}: {:?}", path, e);
+    }
+}
+
+/// The state file for a directory, in `$XDG_STATE_HOME/eza/dirs`, falling
+/// back to `~/.local/state/eza/dirs`.
+fn state_file(dir: &Path) -> Option<PathBuf> {
+    let state = std::env::var_os("XDG_STATE_HOME")
+        .filter(|state| !state.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+        })?;
+
+    let mut hasher = DefaultHasher::new();
+    dir.hash(&mut hasher);
+    Some(
+        state
+            .join("eza")
+            .join("dirs")
+            .join(format!("{:016x}", hasher.finish())),
+    )
+}
+
+/// Serialises the options: the magic line, then the directory and each
+/// option as NUL-terminated records.
+fn encode(dir: &Path, options: &[OsString]) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(os_to_bytes(dir.as_os_str()));
+    bytes.push(b'\0');
+
+    for option in options {
+        bytes.extend(os_to_bytes(option));
+        bytes.push(b'\0');
+    }
+
+    bytes
+}
+
+/// Deserialises the options, returning `None` if they were written for a
+/// different directory (which could end up with the same file name), or
+/// can’t be read at all.
+fn decode(bytes: &[u8], dir: &Path) -> Option<Vec<OsString>> {
+    let bytes = bytes.strip_prefix(MAGIC)?;
+    let bytes = bytes.strip_suffix(b"\0")?;
+
+    let mut records = bytes.split(|&b| b == b'\0');
+    if records.next()? != os_to_bytes(dir.as_os_str()) {
+        return None;
+    }
+
+    Some(records.map(os_from_bytes).collect())
+}
+
+#[cfg(unix)]
+fn os_to_bytes(string: &OsStr) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    string.as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn os_to_bytes(string: &OsStr) -> Vec<u8> {
+    string.to_string_lossy().into_owned().into_bytes()
+}
+
+#[cfg(unix)]
+fn os_from_bytes(bytes: &[u8]) -> OsString {
+    use std::os::unix::ffi::OsStrExt;
+    OsStr::from_bytes(bytes).to_owned()
+}
+
+#[cfg(not(unix))]
+fn os_from_bytes(bytes: &[u8]) -> OsString {
+    OsString::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn example() -> Vec<OsString> {
+        vec!["-l".into(), "--sort".into(), "size".into()]
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes = encode(Path::new("/home/me/src"), &example());
+        assert_eq!(decode(&bytes, Path::new("/home/me/src")), Some(example()));
+    }
+
+    #[test]
+    fn different_dir() {
+        let bytes = encode(Path::new("/home/me/src"), &example());
+        assert_eq!(decode(&bytes, Path::new("/home/me")), None);
+    }
+
+    #[test]
+    fn garbage() {
+        assert_eq!(decode(b"not a state file", Path::new("/")), None);
+    }
+
+    #[test]
+    fn off_by_default() {
+        let args = vec![OsString::from("-l")];
+        assert_eq!(apply(args.clone(), &None::<OsString>), args);
+    }
+}
@@ -0,0 +1,127 @@
+//! Subcommands, which give names to the modes that eza can run in, such as
+//! `eza tree` for `eza --tree`.
+//!
+//! A subcommand can only come first, and is replaced by the options it
+//! stands for before anything else is parsed, so the options after it can
+//! still override them. A first argument that names a file that exists is
+//! always listed as a file, so running eza without a subcommand works the
+//! same as it always has.
+
+use std::ffi::OsString;
+use std::fmt;
+use std::path::Path;
+
+/// The subcommands, and the options they stand for.
+pub static SUBCOMMANDS: &[(&str, &[&str])] = &[
+    ("list", &[]),
+    ("tree", &["--tree"]),
+    (
+        "du",
+        &[
+            "--long",
+            "--total-size",
+            "--sort=size",
+            "--no-permissions",
+            "--no-user",
+            "--no-time",
+        ],
+    ),
+    ("find", &["--recurse", "--flat", "--regex"]),
+    ("diff", &["--tree", "--diff"]),
+];
+
+/// Something that went wrong while expanding a subcommand.
+#[derive(PartialEq, Eq, Debug)]
+pub enum SubcommandError {
+    /// `eza find` was run without a pattern after it, which would otherwise
+    /// be reported as `--regex` needing a value.
+    NoPattern,
+}
+
+impl fmt::Display for SubcommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoPattern => write!(f, "Subcommand find needs a PATTERN to match names with"),
+        }
+    }
+}
+
+/// Replaces the subcommand at the start of the arguments, if there is one,
+/// with the options it stands for.
+pub fn expand(mut args: Vec<OsString>) -> Result<Vec<OsString>, SubcommandError> {
+    let Some(first) = args.first().and_then(|arg| arg.to_str()) else {
+        return Ok(args);
+    };
+
+    let Some((name, options)) = SUBCOMMANDS.iter().find(|(name, _)| *name == first) else {
+        return Ok(args);
+    };
+
+    if Path::new(first).exists() {
+        return Ok(args);
+    }
+
+    if *name == "find" && args.len() == 1 {
+        return Err(SubcommandError::NoPattern);
+    }
+
+    drop(args.splice(..1, options.iter().map(OsString::from)));
+    Ok(args)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn tree() {
+        assert_eq!(
+            expand(args(&["tree", "--level=2", "src"])).unwrap(),
+            args(&["--tree", "--level=2", "src"])
+        );
+    }
+
+    #[test]
+    fn find() {
+        assert_eq!(
+            expand(args(&["find", "\\.rs$", "src"])).unwrap(),
+            args(&["--recurse", "--flat", "--regex", "\\.rs$", "src"])
+        );
+    }
+
+    #[test]
+    fn diff() {
+        assert_eq!(
+            expand(args(&["diff", "old", "new"])).unwrap(),
+            args(&["--tree", "--diff", "old", "new"])
+        );
+    }
+
+    #[test]
+    fn list() {
+        assert_eq!(expand(args(&["list", "-l"])).unwrap(), args(&["-l"]));
+    }
+
+    #[test]
+    fn not_first() {
+        assert_eq!(
+            expand(args(&["-l", "tree"])).unwrap(),
+            args(&["-l", "tree"])
+        );
+    }
+
+    #[test]
+    fn not_a_subcommand() {
+        assert_eq!(expand(args(&["trees"])).unwrap(), args(&["trees"]));
+        assert_eq!(expand(args(&[])).unwrap(), args(&[]));
+    }
+
+    #[test]
+    fn find_without_pattern() {
+        assert_eq!(expand(args(&["find"])), Err(SubcommandError::NoPattern));
+    }
+}
@@ -1,11 +1,21 @@
+use std::fs;
+
 use crate::options::parser::MatchedFlags;
-use crate::options::{flags, vars, OptionsError, Vars};
-use crate::theme::{ColourScale, Definitions, Options, UseColours};
+use crate::options::{flags, preset, vars, OptionsError, Vars};
+use crate::theme::{
+    Background, BaseTheme, ColourScale, ColumnColours, Definitions, NamedTheme, Options,
+    OwnerColours, ThemeFile, UseColours,
+};
 
 impl Options {
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         let use_colours = UseColours::deduce(matches, vars)?;
         let colour_scale = ColourScale::deduce(matches)?;
+        let truecolour = has_truecolour(vars);
+        let column_colours = ColumnColours::deduce(matches)?;
+        let owner_colours = OwnerColours::deduce(matches)?;
+        let base = BaseTheme::deduce(matches, vars)?;
+        let background = Background::deduce(matches, vars)?;
 
         let definitions = if use_colours == UseColours::Never {
             Definitions::default()
@@ -16,6 +26,11 @@ impl Options {
         Ok(Self {
             use_colours,
             colour_scale,
+            truecolour,
+            column_colours,
+            owner_colours,
+            base,
+            background,
             definitions,
         })
     }
@@ -59,6 +74,102 @@ impl ColourScale {
     }
 }
 
+impl OwnerColours {
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        if matches
+            .has_where(|f| f.matches(&flags::COLOR_OWNERS) || f.matches(&flags::COLOUR_OWNERS))?
+            .is_some()
+        {
+            Ok(Self::Hashed)
+        } else {
+            Ok(Self::Fixed)
+        }
+    }
+}
+
+/// Whether the `COLORTERM` variable says the terminal can show 24-bit
+/// colours.
+fn has_truecolour<V: Vars>(vars: &V) -> bool {
+    vars.get(vars::COLORTERM)
+        .map_or(false, |term| term == "truecolor" || term == "24bit")
+}
+
+impl ColumnColours {
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(word) = matches
+            .get_where(|f| f.matches(&flags::COLOR_COLUMNS) || f.matches(&flags::COLOUR_COLUMNS))?
+        else {
+            return Ok(Self::Always);
+        };
+
+        if word == "always" {
+            Ok(Self::Always)
+        } else if word == "dim" {
+            Ok(Self::Dim)
+        } else if word == "never" {
+            Ok(Self::Never)
+        } else {
+            Err(OptionsError::BadArgument(
+                &flags::COLOR_COLUMNS,
+                word.into(),
+            ))
+        }
+    }
+}
+
+impl BaseTheme {
+    /// Picks the theme named by the `--theme` option, falling back to the
+    /// `EZA_THEME` variable. An unknown name in the variable is ignored,
+    /// rather than making every invocation fail.
+    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        if let Some(word) = matches.get(&flags::THEME)? {
+            return Self::named(word.to_string_lossy().as_ref())
+                .ok_or_else(|| OptionsError::BadArgument(&flags::THEME, word.into()));
+        }
+
+        let Some(word) = vars.get(vars::EZA_THEME) else {
+            return Ok(Self::Named(NamedTheme::DEFAULT));
+        };
+
+        Ok(
+            Self::named(word.to_string_lossy().as_ref()).unwrap_or_else(|| {
+                log::warn!("Unknown theme {:?} in {}", word, vars::EZA_THEME);
+                Self::Named(NamedTheme::DEFAULT)
+            }),
+        )
+    }
+
+    fn named(name: &str) -> Option<Self> {
+        if name == "auto" || name == "light" || name == "dark" {
+            Some(Self::ForBackground)
+        } else {
+            NamedTheme::find(name).map(Self::Named)
+        }
+    }
+}
+
+impl Background {
+    /// Picks the background given by `--theme=light` or `--theme=dark`, or
+    /// the same in the `EZA_THEME` variable, falling back to the one in the
+    /// `COLORFGBG` variable.
+    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Option<Self>, OptionsError> {
+        let word = match matches.get(&flags::THEME)? {
+            Some(word) => Some(word.to_os_string()),
+            None => vars.get(vars::EZA_THEME),
+        };
+
+        if word.as_deref() == Some("light".as_ref()) {
+            Ok(Some(Self::Light))
+        } else if word.as_deref() == Some("dark".as_ref()) {
+            Ok(Some(Self::Dark))
+        } else {
+            Ok(vars
+                .get(vars::COLORFGBG)
+                .and_then(|v| Self::from_colorfgbg(&v.to_string_lossy())))
+        }
+    }
+}
+
 impl Definitions {
     fn deduce<V: Vars>(vars: &V) -> Self {
         let ls = vars
@@ -67,7 +178,10 @@ impl Definitions {
         let exa = vars
             .get_with_fallback(vars::EZA_COLORS, vars::EXA_COLORS)
             .map(|e| e.to_string_lossy().to_string());
-        Self { ls, exa }
+        let file = preset::config_dir(vars)
+            .and_then(|dir| fs::read_to_string(dir.join(ThemeFile::FILE_NAME)).ok())
+            .map(|contents| ThemeFile::parse(&contents));
+        Self { ls, exa, file }
     }
 }
 
@@ -86,6 +200,11 @@ mod terminal_test {
         &flags::COLOUR,
         &flags::COLOR_SCALE,
         &flags::COLOUR_SCALE,
+        &flags::COLOR_COLUMNS,
+        &flags::COLOUR_COLUMNS,
+        &flags::COLOR_OWNERS,
+        &flags::COLOUR_OWNERS,
+        &flags::THEME,
     ];
 
     macro_rules! test {
@@ -140,6 +259,9 @@ mod terminal_test {
         ls: &'static str,
         exa: &'static str,
         no_color: &'static str,
+        theme: &'static str,
+        colorfgbg: &'static str,
+        colorterm: &'static str,
     }
 
     impl MockVars {
@@ -148,13 +270,33 @@ mod terminal_test {
                 ls: "",
                 exa: "",
                 no_color: "",
+                theme: "",
+                colorfgbg: "",
+                colorterm: "",
             }
         }
         fn with_no_color() -> MockVars {
             MockVars {
-                ls: "",
-                exa: "",
                 no_color: "true",
+                ..MockVars::empty()
+            }
+        }
+        fn with_theme(theme: &'static str) -> MockVars {
+            MockVars {
+                theme,
+                ..MockVars::empty()
+            }
+        }
+        fn with_colorfgbg(colorfgbg: &'static str) -> MockVars {
+            MockVars {
+                colorfgbg,
+                ..MockVars::empty()
+            }
+        }
+        fn with_colorterm(colorterm: &'static str) -> MockVars {
+            MockVars {
+                colorterm,
+                ..MockVars::empty()
             }
         }
     }
@@ -169,6 +311,12 @@ mod terminal_test {
                 Some(OsString::from(self.exa))
             } else if name == vars::NO_COLOR && !self.no_color.is_empty() {
                 Some(OsString::from(self.no_color))
+            } else if name == vars::EZA_THEME && !self.theme.is_empty() {
+                Some(OsString::from(self.theme))
+            } else if name == vars::COLORFGBG && !self.colorfgbg.is_empty() {
+                Some(OsString::from(self.colorfgbg))
+            } else if name == vars::COLORTERM && !self.colorterm.is_empty() {
+                Some(OsString::from(self.colorterm))
             } else {
                 None
             }
@@ -213,4 +361,45 @@ mod terminal_test {
     test!(scale_6:  ColourScale <- ["--color-scale",                 ];   Complain => Ok(ColourScale::Gradient));
     test!(scale_7:  ColourScale <- [                 "--colour-scale"];   Complain => Ok(ColourScale::Gradient));
     test!(scale_8:  ColourScale <- [                                 ];   Complain => Ok(ColourScale::Fixed));
+
+    #[test]
+    fn truecolour() {
+        assert!(has_truecolour(&MockVars::with_colorterm("truecolor")));
+        assert!(has_truecolour(&MockVars::with_colorterm("24bit")));
+        assert!(!has_truecolour(&MockVars::with_colorterm("256")));
+        assert!(!has_truecolour(&MockVars::empty()));
+    }
+
+    test!(owners_1: OwnerColours <- [];                                  Both => Ok(OwnerColours::Fixed));
+    test!(owners_2: OwnerColours <- ["--color-owners"];                  Both => Ok(OwnerColours::Hashed));
+    test!(owners_3: OwnerColours <- ["--color-owners", "--colour-owners"]; Last => Ok(OwnerColours::Hashed));
+    test!(owners_4: OwnerColours <- ["--color-owners", "--colour-owners"]; Complain => err OptionsError::Duplicate(Flag::Long("color-owners"), Flag::Long("colour-owners")));
+
+    test!(columns_1: ColumnColours <- [];                               Both => Ok(ColumnColours::Always));
+    test!(columns_2: ColumnColours <- ["--color-columns=dim"];          Both => Ok(ColumnColours::Dim));
+    test!(columns_3: ColumnColours <- ["--colour-columns=never"];       Both => Ok(ColumnColours::Never));
+    test!(columns_4: ColumnColours <- ["--color-columns=loud"];         Both => err OptionsError::BadArgument(&flags::COLOR_COLUMNS, OsString::from("loud")));
+
+    // --theme
+    test!(theme_none:     BaseTheme <- [], MockVars::empty();                                    Both => Ok(BaseTheme::Named(NamedTheme::DEFAULT)));
+    test!(theme_named:    BaseTheme <- ["--theme=gruvbox"], MockVars::empty();                   Both => Ok(BaseTheme::Named(NamedTheme::find("gruvbox").unwrap())));
+    test!(theme_env:      BaseTheme <- [], MockVars::with_theme("catppuccin");                   Both => Ok(BaseTheme::Named(NamedTheme::find("catppuccin").unwrap())));
+    test!(theme_over_env: BaseTheme <- ["--theme", "high-contrast"], MockVars::with_theme("catppuccin"); Both => Ok(BaseTheme::Named(NamedTheme::find("high-contrast").unwrap())));
+    test!(theme_bad_env:  BaseTheme <- [], MockVars::with_theme("neon");                         Both => Ok(BaseTheme::Named(NamedTheme::DEFAULT)));
+    test!(theme_bad:      BaseTheme <- ["--theme=neon"], MockVars::empty();                      Both => err OptionsError::BadArgument(&flags::THEME, OsString::from("neon")));
+
+    // --theme=auto
+    test!(auto_theme:     BaseTheme <- ["--theme=auto"], MockVars::empty();                      Both => Ok(BaseTheme::ForBackground));
+    test!(auto_unknown:   Background <- ["--theme=auto"], MockVars::empty();                     Both => Ok(None));
+    test!(auto_dark:      Background <- ["--theme=auto"], MockVars::with_colorfgbg("15;0");      Both => Ok(Some(Background::Dark)));
+    test!(auto_light:     Background <- ["--theme=auto"], MockVars::with_colorfgbg("0;15");      Both => Ok(Some(Background::Light)));
+    test!(auto_rxvt:      Background <- ["--theme=auto"], MockVars::with_colorfgbg("0;default;7"); Both => Ok(Some(Background::Light)));
+    test!(auto_grey:      Background <- ["--theme=auto"], MockVars::with_colorfgbg("0;8");       Both => Ok(Some(Background::Dark)));
+
+    // --theme=light and --theme=dark
+    test!(light_theme:    BaseTheme <- ["--theme=light"], MockVars::empty();                     Both => Ok(BaseTheme::ForBackground));
+    test!(light:          Background <- ["--theme=light"], MockVars::with_colorfgbg("15;0");     Both => Ok(Some(Background::Light)));
+    test!(dark:           Background <- ["--theme=dark"], MockVars::with_colorfgbg("0;15");      Both => Ok(Some(Background::Dark)));
+    test!(dark_env:       Background <- [], MockVars::with_theme("dark");                        Both => Ok(Some(Background::Dark)));
+    test!(named_colorfgbg: Background <- ["--theme=gruvbox"], MockVars::with_colorfgbg("0;15");  Both => Ok(Some(Background::Light)));
 }
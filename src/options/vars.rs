@@ -14,6 +14,16 @@ pub static COLUMNS: &str = "COLUMNS";
 /// Environment variable used to datetime format.
 pub static TIME_STYLE: &str = "TIME_STYLE";
 
+/// Environment variables used by GNU `ls` to pick the units that sizes are
+/// shown in when no option does: `LS_BLOCK_SIZE` for every size, and
+/// `BLOCK_SIZE` for just the blocksize column.
+pub static LS_BLOCK_SIZE: &str = "LS_BLOCK_SIZE";
+pub static BLOCK_SIZE: &str = "BLOCK_SIZE";
+
+/// Environment variable used to ask for POSIX behaviour, which makes the
+/// blocksize column count 512-byte blocks. Any value turns it on.
+pub static POSIXLY_CORRECT: &str = "POSIXLY_CORRECT";
+
 /// Environment variable used to disable colors.
 /// See: <https://no-color.org/>
 pub static NO_COLOR: &str = "NO_COLOR";
@@ -26,6 +36,19 @@ pub static NO_COLOR: &str = "NO_COLOR";
 pub static EXA_COLORS: &str = "EXA_COLORS";
 pub static EZA_COLORS: &str = "EZA_COLORS";
 
+/// Environment variable used to pick one of the built-in themes, in the same
+/// way as the `--theme` option, which overrides it.
+pub static EZA_THEME: &str = "EZA_THEME";
+
+/// Environment variable set by some terminals to the numbers of their
+/// foreground and background colours, which is used to tell whether the
+/// background is light or dark.
+pub static COLORFGBG: &str = "COLORFGBG";
+
+/// Environment variable set by terminals that can show 24-bit colours to
+/// `truecolor` or `24bit`.
+pub static COLORTERM: &str = "COLORTERM";
+
 /// Environment variable used to switch on strict argument checking, such as
 /// complaining if an argument was specified twice, or if two conflict.
 /// This is meant to be so you don’t accidentally introduce the wrong
@@ -55,6 +78,23 @@ pub static EZA_ICON_SPACING: &str = "EZA_ICON_SPACING";
 pub static EXA_OVERRIDE_GIT: &str = "EXA_OVERRIDE_GIT";
 pub static EZA_OVERRIDE_GIT: &str = "EZA_OVERRIDE_GIT";
 
+/// Environment variable used to keep Git statuses in an on-disk cache
+/// between invocations, which only gets invalidated when HEAD or the index
+/// changes. Any non-empty value will turn the cache on.
+pub static EZA_GIT_CACHE: &str = "EZA_GIT_CACHE";
+
+/// Environment variable used to set how many threads to look up files’
+/// details with, as a number or `auto`.
+pub static EZA_THREADS: &str = "EZA_THREADS";
+
+/// Environment variable used to remember the options used to list each
+/// directory, and use them again when it’s listed without any.
+pub static EZA_REMEMBER: &str = "EZA_REMEMBER";
+
+/// Environment variable used to set the directory that the config file, with
+/// the presets in, and the theme file are read from.
+pub static EZA_CONFIG_DIR: &str = "EZA_CONFIG_DIR";
+
 /// Mockable wrapper for `std::env::var_os`.
 pub trait Vars {
     fn get(&self, name: &'static str) -> Option<OsString>;
@@ -1,19 +1,63 @@
+use std::num::{NonZeroU32, NonZeroUsize};
+use std::path::PathBuf;
+
 use crate::fs::feature::xattr;
-use crate::options::parser::MatchedFlags;
+use crate::options::parser::{Arg, Flag, MatchedFlags};
 use crate::options::{flags, NumberSource, OptionsError, Vars};
+use crate::output::details::Threads;
 use crate::output::file_name::Options as FileStyle;
 use crate::output::grid_details::{self, RowThreshold};
 use crate::output::table::{
-    Columns, GroupFormat, Options as TableOptions, SizeFormat, TimeTypes, UserFormat,
+    AgeUnit, Alignment, BlockSize, Columns, GroupFormat, Options as TableOptions, SizeFormat,
+    TimeTypes, UserFormat, COLUMN_NAMES, HIDE_NAMES,
 };
 use crate::output::time::TimeFormat;
-use crate::output::{details, grid, Mode, TerminalWidth, View};
+use crate::output::{
+    details, grid, Flush, GroupBy, Limit, Mode, TableStyle, TerminalWidth, TreeStyle, View,
+};
 
 impl View {
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         let mode = Mode::deduce(matches, vars)?;
         let deref_links = matches.has(&flags::DEREF_LINKS)?;
+        let deref_command_line = matches.has(&flags::DEREF_ARGS)?;
         let total_size = matches.has(&flags::TOTAL_SIZE)?;
+        let empty_placeholder = matches.has(&flags::EMPTY_PLACEHOLDER)?;
+        let count_only = matches.has(&flags::COUNT_ONLY)?;
+        let prompt = matches.has(&flags::PROMPT)?;
+        let summary = matches.has(&flags::SUMMARY)?;
+        let git_summary = matches.has(&flags::GIT_SUMMARY)?;
+        if git_summary && matches.is_strict() && !matches.has(&flags::GIT)? {
+            return Err(OptionsError::Useless(
+                &flags::GIT_SUMMARY,
+                false,
+                &flags::GIT,
+            ));
+        }
+        let limit = Limit::deduce(matches)?;
+        let semantic_markers = matches.has(&flags::SEMANTIC_MARKERS)?;
+        let fresh = matches.get(&flags::SAVE_SNAPSHOT)?;
+        let updated = matches.get(&flags::UPDATE_SNAPSHOT)?;
+        if matches.is_strict() && fresh.is_some() && updated.is_some() {
+            return Err(OptionsError::Conflict(
+                &flags::SAVE_SNAPSHOT,
+                &flags::UPDATE_SNAPSHOT,
+            ));
+        }
+        let update_snapshot = updated.is_some();
+        let save_snapshot = updated.or(fresh).map(PathBuf::from);
+        let diff = matches.has(&flags::DIFF)?;
+        let only_changes = matches.has(&flags::ONLY_CHANGES)?;
+        if only_changes && matches.is_strict() && !diff {
+            return Err(OptionsError::Useless(
+                &flags::ONLY_CHANGES,
+                false,
+                &flags::DIFF,
+            ));
+        }
+        let flush = Flush::deduce(matches)?;
+        let inline_errors = matches.has(&flags::INLINE_ERRORS)?;
+        let group_by = GroupBy::deduce(matches)?;
         let width = TerminalWidth::deduce(matches, vars)?;
         let file_style = FileStyle::deduce(matches, vars, width.actual_terminal_width().is_some())?;
         Ok(Self {
@@ -21,11 +65,49 @@ impl View {
             width,
             file_style,
             deref_links,
+            deref_command_line,
             total_size,
+            empty_placeholder,
+            count_only,
+            prompt,
+            summary,
+            git_summary,
+            limit,
+            semantic_markers,
+            save_snapshot,
+            update_snapshot,
+            diff,
+            only_changes,
+            flush,
+            inline_errors,
+            group_by,
         })
     }
 }
 
+impl Limit {
+    /// Determines how many entries to display from the `--limit` argument,
+    /// which has to be a positive number, and whether to say how many more
+    /// there were from `--limit-marker`.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(count) = matches.get(&flags::LIMIT)? else {
+            return Ok(None);
+        };
+
+        let count = count.to_string_lossy();
+        match count.parse::<NonZeroUsize>() {
+            Ok(n) => Ok(Some(Self {
+                count: n.get(),
+                marker: matches.has(&flags::LIMIT_MARKER)?,
+            })),
+            Err(e) => {
+                let source = NumberSource::Arg(&flags::LIMIT);
+                Err(OptionsError::FailedParse(count.to_string(), source, e))
+            }
+        }
+    }
+}
+
 impl Mode {
     /// Determine which viewing mode to use based on the user’s options.
     ///
@@ -36,11 +118,16 @@ impl Mode {
     /// This is complicated a little by the fact that `--grid` and `--tree`
     /// can also combine with `--long`, so care has to be taken to use the
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        // A flat recursive listing is drawn as a tree without branches
+        let recurse_flat = matches.has(&flags::RECURSE)? && matches.has(&flags::FLAT)?;
+        let is_tree =
+            |f: &Flag| f.matches(&flags::TREE) || (recurse_flat && f.matches(&flags::FLAT));
+
         let flag = matches.has_where_any(|f| {
             f.matches(&flags::LONG)
                 || f.matches(&flags::ONE_LINE)
                 || f.matches(&flags::GRID)
-                || f.matches(&flags::TREE)
+                || is_tree(f)
         });
 
         let Some(flag) = flag else {
@@ -50,14 +137,13 @@ impl Mode {
         };
 
         if flag.matches(&flags::LONG)
-            || (flag.matches(&flags::TREE) && matches.has(&flags::LONG)?)
+            || (is_tree(flag) && matches.has(&flags::LONG)?)
             || (flag.matches(&flags::GRID) && matches.has(&flags::LONG)?)
         {
             let _ = matches.has(&flags::LONG)?;
             let details = details::Options::deduce_long(matches, vars)?;
 
-            let flag =
-                matches.has_where_any(|f| f.matches(&flags::GRID) || f.matches(&flags::TREE));
+            let flag = matches.has_where_any(|f| f.matches(&flags::GRID) || is_tree(f));
 
             if flag.is_some() && flag.unwrap().matches(&flags::GRID) {
                 let _ = matches.has(&flags::GRID)?;
@@ -77,7 +163,7 @@ impl Mode {
 
         Self::strict_check_long_flags(matches)?;
 
-        if flag.matches(&flags::TREE) {
+        if is_tree(flag) {
             let _ = matches.has(&flags::TREE)?;
             let details = details::Options::deduce_tree(matches)?;
             return Ok(Self::Details(details));
@@ -85,6 +171,13 @@ impl Mode {
 
         if flag.matches(&flags::ONE_LINE) {
             let _ = matches.has(&flags::ONE_LINE)?;
+
+            // A flat listing already has one path on each line
+            if recurse_flat {
+                let details = details::Options::deduce_tree(matches)?;
+                return Ok(Self::Details(details));
+            }
+
             return Ok(Self::Lines);
         }
 
@@ -103,16 +196,39 @@ impl Mode {
                 &flags::LINKS,
                 &flags::HEADER,
                 &flags::BLOCKSIZE,
+                &flags::DISK_USAGE,
                 &flags::TIME,
                 &flags::GROUP,
                 &flags::NUMERIC,
                 &flags::MOUNTS,
+                &flags::CODEOWNERS,
+                &flags::AGE,
+                &flags::COLOR_OWNERS,
+                &flags::COLOUR_OWNERS,
             ] {
                 if matches.has(option)? {
                     return Err(OptionsError::Useless(option, false, &flags::LONG));
                 }
             }
 
+            for option in &[
+                &flags::BLOCK_SIZE,
+                &flags::SIZE_STYLE,
+                &flags::DELTA,
+                &flags::AGE,
+                &flags::COLUMNS,
+                &flags::HIDE,
+                &flags::TABLE_STYLE,
+                &flags::HEADER_INTERVAL,
+                &flags::ALIGN,
+                &flags::COLOR_COLUMNS,
+                &flags::COLOUR_COLUMNS,
+            ] {
+                if matches.get(option)?.is_some() {
+                    return Err(OptionsError::Useless(option, false, &flags::LONG));
+                }
+            }
+
             if matches.has(&flags::GIT)? && !matches.has(&flags::NO_GIT)? {
                 return Err(OptionsError::Useless(&flags::GIT, false, &flags::LONG));
             } else if matches.has(&flags::LEVEL)?
@@ -124,6 +240,15 @@ impl Mode {
                     &flags::RECURSE,
                     &flags::TREE,
                 ));
+            } else if matches.has(&flags::WRAP_NAMES)?
+                && !matches.has(&flags::TREE)?
+                && !(matches.has(&flags::RECURSE)? && matches.has(&flags::FLAT)?)
+            {
+                return Err(OptionsError::Useless2(
+                    &flags::WRAP_NAMES,
+                    &flags::LONG,
+                    &flags::TREE,
+                ));
             }
         }
 
@@ -135,10 +260,28 @@ impl grid::Options {
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
         let grid = grid::Options {
             across: matches.has(&flags::ACROSS)?,
+            rows: Self::deduce_rows(matches)?,
         };
 
         Ok(grid)
     }
+
+    /// Determines how many rows to aim for from the `--grid-rows` argument,
+    /// which has to be a positive number.
+    fn deduce_rows(matches: &MatchedFlags<'_>) -> Result<Option<usize>, OptionsError> {
+        let Some(count) = matches.get(&flags::GRID_ROWS)? else {
+            return Ok(None);
+        };
+
+        let count = count.to_string_lossy();
+        match count.parse::<NonZeroUsize>() {
+            Ok(n) => Ok(Some(n.get())),
+            Err(e) => {
+                let source = NumberSource::Arg(&flags::GRID_ROWS);
+                Err(OptionsError::FailedParse(count.to_string(), source, e))
+            }
+        }
+    }
 }
 
 impl details::Options {
@@ -146,9 +289,17 @@ impl details::Options {
         let details = details::Options {
             table: None,
             header: false,
+            header_interval: None,
             xattr: xattr::ENABLED && matches.has(&flags::EXTENDED)?,
             secattr: xattr::ENABLED && matches.has(&flags::SECURITY_CONTEXT)?,
             mounts: matches.has(&flags::MOUNTS)?,
+            collapse_configs: matches.has(&flags::COLLAPSE_CONFIGS)?,
+            tree_counts: matches.has(&flags::TREE_COUNTS)?,
+            compact_tree: matches.has(&flags::COMPACT_TREE)?,
+            tree_style: TreeStyle::deduce(matches)?,
+            tree_limit: Self::deduce_tree_limit(matches)?,
+            wrap_names: matches.has(&flags::WRAP_NAMES)?,
+            table_style: TableStyle::Plain,
         };
 
         Ok(details)
@@ -166,11 +317,62 @@ impl details::Options {
         Ok(details::Options {
             table: Some(TableOptions::deduce(matches, vars)?),
             header: matches.has(&flags::HEADER)?,
+            header_interval: Self::deduce_header_interval(matches)?,
             xattr: xattr::ENABLED && matches.has(&flags::EXTENDED)?,
             secattr: xattr::ENABLED && matches.has(&flags::SECURITY_CONTEXT)?,
             mounts: matches.has(&flags::MOUNTS)?,
+            collapse_configs: matches.has(&flags::COLLAPSE_CONFIGS)?,
+            tree_counts: matches.has(&flags::TREE_COUNTS)?,
+            compact_tree: matches.has(&flags::COMPACT_TREE)?,
+            tree_style: TreeStyle::deduce(matches)?,
+            tree_limit: Self::deduce_tree_limit(matches)?,
+            wrap_names: matches.has(&flags::WRAP_NAMES)?,
+            table_style: TableStyle::deduce(matches)?,
         })
     }
+
+    /// Determines how often to repeat the header from the
+    /// `--header-interval` argument, which has to be a positive number, and
+    /// is only any use with a header.
+    fn deduce_header_interval(matches: &MatchedFlags<'_>) -> Result<Option<usize>, OptionsError> {
+        let Some(count) = matches.get(&flags::HEADER_INTERVAL)? else {
+            return Ok(None);
+        };
+
+        if matches.is_strict() && !matches.has(&flags::HEADER)? {
+            return Err(OptionsError::Useless(
+                &flags::HEADER_INTERVAL,
+                false,
+                &flags::HEADER,
+            ));
+        }
+
+        let count = count.to_string_lossy();
+        match count.parse::<NonZeroUsize>() {
+            Ok(n) => Ok(Some(n.get())),
+            Err(e) => {
+                let source = NumberSource::Arg(&flags::HEADER_INTERVAL);
+                Err(OptionsError::FailedParse(count.to_string(), source, e))
+            }
+        }
+    }
+
+    /// Determines how many entries to display in each directory of a tree
+    /// from the `--tree-limit` argument, which has to be a positive number.
+    fn deduce_tree_limit(matches: &MatchedFlags<'_>) -> Result<Option<usize>, OptionsError> {
+        let Some(count) = matches.get(&flags::TREE_LIMIT)? else {
+            return Ok(None);
+        };
+
+        let count = count.to_string_lossy();
+        match count.parse::<NonZeroUsize>() {
+            Ok(n) => Ok(Some(n.get())),
+            Err(e) => {
+                let source = NumberSource::Arg(&flags::TREE_LIMIT);
+                Err(OptionsError::FailedParse(count.to_string(), source, e))
+            }
+        }
+    }
 }
 
 impl TerminalWidth {
@@ -230,50 +432,328 @@ impl RowThreshold {
     }
 }
 
+impl AgeUnit {
+    /// Determines which unit to show ages in from the `--age` argument,
+    /// which counts in seconds if it isn’t given a unit, or `None` if there
+    /// shouldn’t be an age column at all.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(word) = matches.get(&flags::AGE)? else {
+            return Ok(matches.has(&flags::AGE)?.then_some(Self::Seconds));
+        };
+
+        match word.to_str() {
+            Some("seconds") => Ok(Some(Self::Seconds)),
+            Some("minutes") => Ok(Some(Self::Minutes)),
+            Some("hours") => Ok(Some(Self::Hours)),
+            Some("days") => Ok(Some(Self::Days)),
+            _ => Err(OptionsError::BadArgument(&flags::AGE, word.into())),
+        }
+    }
+}
+
+impl Flush {
+    /// Determines when to flush the output from the `--flush` argument,
+    /// flushing after every line if it isn’t given.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(word) = matches.get(&flags::FLUSH)? else {
+            return Ok(Self::default());
+        };
+
+        match word.to_str() {
+            Some("line") => Ok(Self::Line),
+            Some("dir") => Ok(Self::Dir),
+            Some("end") => Ok(Self::End),
+            _ => Err(OptionsError::BadArgument(&flags::FLUSH, word.into())),
+        }
+    }
+}
+
+impl GroupBy {
+    /// Determines what to split the listing into sections by from the
+    /// `--group-by` argument, not splitting it up if it isn’t given.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(word) = matches.get(&flags::GROUP_BY)? else {
+            return Ok(None);
+        };
+
+        match word.to_str() {
+            Some("category") => Ok(Some(Self::Category)),
+            Some("extension") => Ok(Some(Self::Extension)),
+            Some("first-letter") => Ok(Some(Self::FirstLetter)),
+            Some("none") => Ok(None),
+            _ => Err(OptionsError::BadArgument(&flags::GROUP_BY, word.into())),
+        }
+    }
+}
+
+impl TableStyle {
+    /// Determines whether to draw lines around and between the columns of
+    /// the long view from the `--table-style` argument, not drawing any if
+    /// it isn’t given.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(word) = matches.get(&flags::TABLE_STYLE)? else {
+            return Ok(Self::default());
+        };
+
+        match word.to_str() {
+            Some("borders") => Ok(Self::Borders),
+            Some("compact") => Ok(Self::Compact),
+            Some("none") => Ok(Self::Plain),
+            _ => Err(OptionsError::BadArgument(&flags::TABLE_STYLE, word.into())),
+        }
+    }
+}
+
+impl TreeStyle {
+    /// Determines which characters to draw trees with from the
+    /// `--tree-style` argument, using box-drawing characters if it isn’t
+    /// given.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(word) = matches.get(&flags::TREE_STYLE)? else {
+            return Ok(Self::default());
+        };
+
+        match word.to_str() {
+            Some("unicode") => Ok(Self::Unicode),
+            Some("ascii") => Ok(Self::Ascii),
+            Some("rounded") => Ok(Self::Rounded),
+            Some("bold") => Ok(Self::Bold),
+            _ => Err(OptionsError::BadArgument(&flags::TREE_STYLE, word.into())),
+        }
+    }
+}
+
+impl Threads {
+    /// Determines how many threads to use from the `--threads` argument,
+    /// or the `EZA_THREADS` environment variable if it isn’t given. Either
+    /// can be a positive number or `auto`, which is the default.
+    pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        use crate::options::vars;
+
+        let (value, source) = if let Some(arg) = matches.get(&flags::THREADS)? {
+            (
+                arg.to_string_lossy().into_owned(),
+                NumberSource::Arg(&flags::THREADS),
+            )
+        } else if let Some(env) = vars.get(vars::EZA_THREADS) {
+            (
+                env.to_string_lossy().into_owned(),
+                NumberSource::Env(vars::EZA_THREADS),
+            )
+        } else {
+            return Ok(Self::Automatic);
+        };
+
+        if value == "auto" {
+            return Ok(Self::Automatic);
+        }
+
+        match value.parse::<NonZeroU32>() {
+            Ok(count) => Ok(Self::Fixed(count.get())),
+            Err(e) => Err(OptionsError::FailedParse(value, source, e)),
+        }
+    }
+}
+
 impl TableOptions {
     fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         let time_format = TimeFormat::deduce(matches, vars)?;
-        let size_format = SizeFormat::deduce(matches)?;
+        let (size_format, blocksize_format) = SizeFormat::deduce_with_env(matches, vars)?;
+        let disk_usage = matches.has(&flags::DISK_USAGE)?;
         let user_format = UserFormat::deduce(matches)?;
         let group_format = GroupFormat::deduce(matches)?;
-        let columns = Columns::deduce(matches, vars)?;
+        let order = Self::deduce_order(matches)?;
+        let hidden = Self::deduce_hidden(matches)?;
+        let columns = Columns::deduce(matches, vars, order.as_deref(), &hidden)?;
+        let snapshot = matches.get(&flags::DELTA)?.map(PathBuf::from);
+        let align = Self::deduce_align(matches)?;
         Ok(Self {
             size_format,
+            blocksize_format,
+            disk_usage,
             time_format,
             user_format,
             group_format,
             columns,
+            snapshot,
+            order,
+            align,
         })
     }
+
+    /// Determines which columns to display, in which order, from the
+    /// comma-separated names given to `--columns`. The file name can be
+    /// named as well, but only at the end, as that’s where it always goes.
+    fn deduce_order(matches: &MatchedFlags<'_>) -> Result<Option<Vec<&'static str>>, OptionsError> {
+        let Some(names) = Self::column_names(matches, &flags::COLUMNS)? else {
+            return Ok(None);
+        };
+
+        if names.iter().rev().skip(1).any(|name| *name == "name") {
+            return Err(OptionsError::Unsupported(
+                "The name column has to come last in --columns".into(),
+            ));
+        }
+
+        let mut order = Vec::new();
+        for name in names {
+            if !order.contains(&name) {
+                order.push(name);
+            }
+        }
+
+        Ok(Some(order))
+    }
+
+    /// Determines which columns not to display from the comma-separated
+    /// names given to `--hide`, which can be any of them except the name,
+    /// with `time` hiding every timestamp.
+    fn deduce_hidden(matches: &MatchedFlags<'_>) -> Result<Vec<&'static str>, OptionsError> {
+        let Some(list) = matches.get(&flags::HIDE)? else {
+            return Ok(Vec::new());
+        };
+
+        let mut names = Vec::new();
+        for word in list.to_string_lossy().split(',').filter(|w| !w.is_empty()) {
+            match word {
+                "name" => {
+                    return Err(OptionsError::Unsupported(
+                        "The name column can’t be hidden".into(),
+                    ))
+                }
+                "time" => names.extend(["modified", "changed", "created", "accessed"]),
+                _ => match HIDE_NAMES.iter().find(|name| **name == word) {
+                    Some(name) => names.push(*name),
+                    None => return Err(OptionsError::BadArgument(&flags::HIDE, word.into())),
+                },
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// Determines which columns to align differently from usual from the
+    /// comma-separated list given to `--align`, with each column named
+    /// followed by `=left` or `=right`, such as `inode=right`.
+    fn deduce_align(
+        matches: &MatchedFlags<'_>,
+    ) -> Result<Vec<(&'static str, Alignment)>, OptionsError> {
+        let Some(list) = matches.get(&flags::ALIGN)? else {
+            return Ok(Vec::new());
+        };
+
+        let mut align = Vec::new();
+        for word in list.to_string_lossy().split(',').filter(|w| !w.is_empty()) {
+            let bad = || OptionsError::BadArgument(&flags::ALIGN, word.into());
+            let (name, side) = word.split_once('=').ok_or_else(bad)?;
+            let name = COLUMN_NAMES.iter().find(|n| **n == name).ok_or_else(bad)?;
+            let alignment = match side {
+                "left" => Alignment::Left,
+                "right" => Alignment::Right,
+                _ => return Err(bad()),
+            };
+            align.push((*name, alignment));
+        }
+
+        Ok(align)
+    }
+
+    /// The names of the columns in the comma-separated list given to an
+    /// option, if it was given.
+    fn column_names(
+        matches: &MatchedFlags<'_>,
+        flag: &'static Arg,
+    ) -> Result<Option<Vec<&'static str>>, OptionsError> {
+        let Some(list) = matches.get(flag)? else {
+            return Ok(None);
+        };
+
+        let mut names = Vec::new();
+        for word in list.to_string_lossy().split(',').filter(|w| !w.is_empty()) {
+            match COLUMN_NAMES.iter().find(|name| **name == word) {
+                Some(name) => names.push(*name),
+                None => return Err(OptionsError::BadArgument(flag, word.into())),
+            }
+        }
+
+        Ok(Some(names))
+    }
 }
 
 impl Columns {
-    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+    /// Determines which columns to display, which are the ones named in the
+    /// order if there is one, rather than the ones turned on by the options
+    /// for each column, without the hidden ones.
+    fn deduce<V: Vars>(
+        matches: &MatchedFlags<'_>,
+        vars: &V,
+        order: Option<&[&str]>,
+        hidden: &[&str],
+    ) -> Result<Self, OptionsError> {
         use crate::options::vars;
-        let time_types = TimeTypes::deduce(matches)?;
+        let pick = |name: &str, shown: bool| {
+            order.map_or(shown, |o| o.contains(&name)) && !hidden.contains(&name)
+        };
+
+        let time_types = if order.is_some() {
+            TimeTypes {
+                modified: false,
+                changed: false,
+                accessed: false,
+                created: false,
+            }
+        } else {
+            TimeTypes::deduce(matches)?
+        };
+        let time_types = TimeTypes {
+            modified: pick("modified", time_types.modified),
+            changed: pick("changed", time_types.changed),
+            accessed: pick("accessed", time_types.accessed),
+            created: pick("created", time_types.created),
+        };
 
         let no_git_env = vars
             .get_with_fallback(vars::EXA_OVERRIDE_GIT, vars::EZA_OVERRIDE_GIT)
             .is_some();
 
-        let git = matches.has(&flags::GIT)? && !matches.has(&flags::NO_GIT)? && !no_git_env;
-        let subdir_git_repos =
-            matches.has(&flags::GIT_REPOS)? && !matches.has(&flags::NO_GIT)? && !no_git_env;
+        let git =
+            pick("git", matches.has(&flags::GIT)?) && !matches.has(&flags::NO_GIT)? && !no_git_env;
+        let subdir_git_repos = pick("repos", matches.has(&flags::GIT_REPOS)?)
+            && !matches.has(&flags::NO_GIT)?
+            && !no_git_env;
         let subdir_git_repos_no_stat = !subdir_git_repos
-            && matches.has(&flags::GIT_REPOS_NO_STAT)?
+            && pick("repos-no-status", matches.has(&flags::GIT_REPOS_NO_STAT)?)
             && !matches.has(&flags::NO_GIT)?
             && !no_git_env;
 
-        let blocksize = matches.has(&flags::BLOCKSIZE)?;
-        let group = matches.has(&flags::GROUP)?;
-        let inode = matches.has(&flags::INODE)?;
-        let links = matches.has(&flags::LINKS)?;
-        let octal = matches.has(&flags::OCTAL)?;
-        let security_context = xattr::ENABLED && matches.has(&flags::SECURITY_CONTEXT)?;
+        let blocksize = pick("blocksize", matches.has(&flags::BLOCKSIZE)?);
+        let group = pick("group", matches.has(&flags::GROUP)?);
+        let inode = pick("inode", matches.has(&flags::INODE)?);
+        let links = pick("links", matches.has(&flags::LINKS)?);
+        let octal = pick("octal", matches.has(&flags::OCTAL)?);
+        let security_context =
+            xattr::ENABLED && pick("context", matches.has(&flags::SECURITY_CONTEXT)?);
+        let codeowners = pick("codeowners", matches.has(&flags::CODEOWNERS)?);
+
+        // The delta column needs a snapshot to compare against.
+        let has_snapshot = matches.get(&flags::DELTA)?.is_some();
+        let delta = pick("delta", has_snapshot);
+        if delta && !has_snapshot {
+            return Err(OptionsError::Useless(&flags::COLUMNS, false, &flags::DELTA));
+        }
 
-        let permissions = !matches.has(&flags::NO_PERMISSIONS)?;
-        let filesize = !matches.has(&flags::NO_FILESIZE)?;
-        let user = !matches.has(&flags::NO_USER)?;
+        // The unit of the age column still comes from --age.
+        let age = AgeUnit::deduce(matches)?;
+        let age = if order.is_some() {
+            Some(age.unwrap_or(AgeUnit::Seconds))
+        } else {
+            age
+        };
+        let age = age.filter(|_| pick("age", true));
+
+        let permissions = pick("perms", !matches.has(&flags::NO_PERMISSIONS)?);
+        let filesize = pick("size", !matches.has(&flags::NO_FILESIZE)?);
+        let user = pick("user", !matches.has(&flags::NO_USER)?);
 
         Ok(Self {
             time_types,
@@ -286,6 +766,9 @@ impl Columns {
             subdir_git_repos_no_stat,
             octal,
             security_context,
+            codeowners,
+            delta,
+            age,
             permissions,
             filesize,
             user,
@@ -300,15 +783,192 @@ impl SizeFormat {
     /// The default mode is to use the decimal prefixes, as they are the
     /// most commonly-understood, and don’t involve trying to parse large
     /// strings of digits in your head. Changing the format to anything else
-    /// involves the `--binary` or `--bytes` flags, and these conflict with
-    /// each other.
+    /// involves the `--binary`, `--bytes`, `--block-size`, or `--size-style`
+    /// options, and these conflict with each other.
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
-        let flag = matches.has_where(|f| f.matches(&flags::BINARY) || f.matches(&flags::BYTES))?;
+        let mut formats = Vec::new();
+
+        match matches.has_where(|f| f.matches(&flags::BINARY) || f.matches(&flags::BYTES))? {
+            Some(f) if f.matches(&flags::BINARY) => {
+                formats.push((&flags::BINARY, *f, Self::BinaryBytes));
+            }
+            Some(f) if f.matches(&flags::BYTES) => {
+                formats.push((&flags::BYTES, *f, Self::JustBytes));
+            }
+            _ => {}
+        }
+
+        if let Some(word) = matches.get(&flags::BLOCK_SIZE)? {
+            let format = word
+                .to_str()
+                .and_then(Self::from_block_size)
+                .ok_or_else(|| OptionsError::BadArgument(&flags::BLOCK_SIZE, word.into()))?;
+
+            formats.push((
+                &flags::BLOCK_SIZE,
+                Flag::Long(flags::BLOCK_SIZE.long),
+                format,
+            ));
+        }
+
+        if let Some(word) = matches.get(&flags::SIZE_STYLE)? {
+            let format = match word.to_str() {
+                Some("default") => Self::DecimalBytes,
+                Some("binary") => Self::BinaryBytes,
+                Some("bytes") => Self::JustBytes,
+                Some("grouped") => Self::Blocks(BlockSize {
+                    bytes: 1,
+                    suffix: None,
+                    separators: true,
+                }),
+                _ => return Err(OptionsError::BadArgument(&flags::SIZE_STYLE, word.into())),
+            };
+
+            formats.push((
+                &flags::SIZE_STYLE,
+                Flag::Long(flags::SIZE_STYLE.long),
+                format,
+            ));
+        }
+
+        // The options that take values have to be checked against the
+        // others separately, with the last one being used.
+        formats.sort_by_key(|(arg, _, _)| matches.position(arg));
+        if let [(_, a, _), (_, b, _), ..] = formats[..] {
+            if matches.is_strict() {
+                return Err(OptionsError::Duplicate(a, b));
+            }
+        }
+
+        Ok(formats
+            .last()
+            .map_or(Self::DecimalBytes, |(_, _, format)| *format))
+    }
 
-        Ok(match flag {
-            Some(f) if f.matches(&flags::BINARY) => Self::BinaryBytes,
-            Some(f) if f.matches(&flags::BYTES) => Self::JustBytes,
-            _ => Self::DecimalBytes,
+    /// Determines the formats for the size column and the blocksize column.
+    /// When none of the size options are given, these follow the same
+    /// environment variables as GNU `ls`, so it can be swapped for eza in
+    /// scripts: `LS_BLOCK_SIZE` for both columns, then `BLOCK_SIZE` for just
+    /// the blocksize one, with `POSIXLY_CORRECT` making it count 512-byte
+    /// blocks. Values that can’t be used are ignored, as they are by `ls`.
+    fn deduce_with_env<V: Vars>(
+        matches: &MatchedFlags<'_>,
+        vars: &V,
+    ) -> Result<(Self, Self), OptionsError> {
+        use crate::options::vars;
+
+        let format = Self::deduce(matches)?;
+        let given = matches
+            .last_where_any(|f| {
+                f.matches(&flags::BINARY)
+                    || f.matches(&flags::BYTES)
+                    || f.matches(&flags::BLOCK_SIZE)
+                    || f.matches(&flags::SIZE_STYLE)
+            })
+            .is_some();
+        if given {
+            return Ok((format, format));
+        }
+
+        let from_var = |name| {
+            vars.get(name)
+                .and_then(|value| value.to_str().and_then(Self::from_block_size))
+        };
+
+        if let Some(format) = from_var(vars::LS_BLOCK_SIZE) {
+            return Ok((format, format));
+        }
+
+        let blocksize_format = from_var(vars::BLOCK_SIZE)
+            .or_else(|| {
+                vars.get(vars::POSIXLY_CORRECT).map(|_| {
+                    Self::Blocks(BlockSize {
+                        bytes: 512,
+                        suffix: None,
+                        separators: false,
+                    })
+                })
+            })
+            .unwrap_or(format);
+
+        Ok((format, blocksize_format))
+    }
+
+    /// Parses a block size in the same format as GNU `ls`, which can also be
+    /// `human-readable` for binary prefixes, or `si` for decimal ones.
+    fn from_block_size(word: &str) -> Option<Self> {
+        match word {
+            "human-readable" => Some(Self::BinaryBytes),
+            "si" => Some(Self::DecimalBytes),
+            _ => BlockSize::deduce(word).map(Self::Blocks),
+        }
+    }
+}
+
+/// The units that can be given to `--block-size`, in order of size. Each
+/// one is a power of 1024 when written alone or with `iB`, and a power of
+/// 1000 when written with just `B`.
+static BLOCK_UNITS: &[[&str; 3]] = &[
+    ["K", "KiB", "KB"],
+    ["M", "MiB", "MB"],
+    ["G", "GiB", "GB"],
+    ["T", "TiB", "TB"],
+    ["P", "PiB", "PB"],
+    ["E", "EiB", "EB"],
+];
+
+impl BlockSize {
+    /// Parses a block size in the same format as GNU `ls`: an optional `'`
+    /// to group the digits, then a number, a unit, or a number and a unit,
+    /// such as `K`, `'1`, or `4MB`. The unit is only shown after each size
+    /// when there isn’t a number.
+    fn deduce(word: &str) -> Option<Self> {
+        let (separators, word) = match word.strip_prefix('\'') {
+            Some(rest) => (true, rest),
+            None => (false, word),
+        };
+
+        let digits = word
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(word.len());
+        let (number, unit) = word.split_at(digits);
+
+        let number = if number.is_empty() {
+            None
+        } else {
+            Some(number.parse::<u64>().ok()?)
+        };
+
+        let (multiplier, suffix) = if unit.is_empty() {
+            (1, None)
+        } else {
+            // The first letter of the unit can be in either case, like `k`.
+            let matches = |form: &str| {
+                form.len() == unit.len()
+                    && form[..1].eq_ignore_ascii_case(&unit[..1])
+                    && form[1..] == unit[1..]
+            };
+
+            BLOCK_UNITS.iter().zip(1..).find_map(|(forms, power)| {
+                let index = forms.iter().position(|form| matches(form))?;
+                let base: u64 = if index == 2 { 1000 } else { 1024 };
+                Some((base.pow(power), Some(forms[index])))
+            })?
+        };
+
+        if number.is_none() && suffix.is_none() {
+            return None;
+        }
+
+        let bytes = number.unwrap_or(1).checked_mul(multiplier)?;
+        if bytes == 0 {
+            return None;
+        }
+
+        Some(Self {
+            bytes,
+            suffix: suffix.filter(|_| number.is_none()),
+            separators,
         })
     }
 }
@@ -442,13 +1102,45 @@ mod test {
         &flags::GIT,
         &flags::LINKS,
         &flags::BLOCKSIZE,
+        &flags::DISK_USAGE,
+        &flags::BLOCK_SIZE,
+        &flags::SIZE_STYLE,
+        &flags::GIT_SUMMARY,
+        &flags::CODEOWNERS,
+        &flags::DELTA,
+        &flags::SAVE_SNAPSHOT,
+        &flags::UPDATE_SNAPSHOT,
+        &flags::DIFF,
+        &flags::ONLY_CHANGES,
         &flags::LONG,
         &flags::LEVEL,
         &flags::GRID,
         &flags::ACROSS,
+        &flags::GRID_ROWS,
         &flags::ONE_LINE,
         &flags::TREE,
+        &flags::RECURSE,
+        &flags::FLAT,
         &flags::NUMERIC,
+        &flags::THREADS,
+        &flags::LIMIT,
+        &flags::LIMIT_MARKER,
+        &flags::TREE_STYLE,
+        &flags::TREE_LIMIT,
+        &flags::AGE,
+        &flags::FLUSH,
+        &flags::GROUP_BY,
+        &flags::TABLE_STYLE,
+        &flags::HEADER_INTERVAL,
+        &flags::ALIGN,
+        &flags::COLOR_COLUMNS,
+        &flags::COLOUR_COLUMNS,
+        &flags::COLOR_OWNERS,
+        &flags::COLOUR_OWNERS,
+        &flags::COLUMNS,
+        &flags::HIDE,
+        &flags::NO_USER,
+        &flags::WRAP_NAMES,
     ];
 
     #[allow(unused_macro_rules)]
@@ -545,6 +1237,92 @@ mod test {
         test!(both_6:  SizeFormat <- ["--bytes",  "--binary"];  Complain => err OptionsError::Duplicate(Flag::Long("bytes"),  Flag::Long("binary")));
         test!(both_7:  SizeFormat <- ["--binary", "--bytes"];   Complain => err OptionsError::Duplicate(Flag::Long("binary"), Flag::Long("bytes")));
         test!(both_8:  SizeFormat <- ["--bytes",  "--bytes"];   Complain => err OptionsError::Duplicate(Flag::Long("bytes"),  Flag::Long("bytes")));
+
+        // Block sizes
+        test!(kibi:    SizeFormat <- ["--block-size=K"];        Both => Ok(SizeFormat::Blocks(BlockSize { bytes: 1024,        suffix: Some("K"),  separators: false })));
+        test!(kilo:    SizeFormat <- ["--block-size=kB"];       Both => Ok(SizeFormat::Blocks(BlockSize { bytes: 1000,        suffix: Some("KB"), separators: false })));
+        test!(mebi:    SizeFormat <- ["--block-size=MiB"];      Both => Ok(SizeFormat::Blocks(BlockSize { bytes: 1_048_576,   suffix: Some("MiB"), separators: false })));
+        test!(number:  SizeFormat <- ["--block-size=4M"];       Both => Ok(SizeFormat::Blocks(BlockSize { bytes: 4_194_304,   suffix: None,       separators: false })));
+        test!(grouped: SizeFormat <- ["--block-size='1"];       Both => Ok(SizeFormat::Blocks(BlockSize { bytes: 1,           suffix: None,       separators: true })));
+        test!(exa:     SizeFormat <- ["--block-size='EB"];      Both => Ok(SizeFormat::Blocks(BlockSize { bytes: 10_u64.pow(18), suffix: Some("EB"), separators: true })));
+
+        test!(zero:    SizeFormat <- ["--block-size=0"];        Both => err OptionsError::BadArgument(&flags::BLOCK_SIZE, OsString::from("0")));
+        test!(huge:    SizeFormat <- ["--block-size=20EiB"];    Both => err OptionsError::BadArgument(&flags::BLOCK_SIZE, OsString::from("20EiB")));
+        test!(unit:    SizeFormat <- ["--block-size=1Q"];       Both => err OptionsError::BadArgument(&flags::BLOCK_SIZE, OsString::from("1Q")));
+        test!(quote:   SizeFormat <- ["--block-size='"];        Both => err OptionsError::BadArgument(&flags::BLOCK_SIZE, OsString::from("'")));
+
+        // Block sizes and the other flags
+        test!(both_9:  SizeFormat <- ["--bytes", "--block-size=K"];  Last => Ok(SizeFormat::Blocks(BlockSize { bytes: 1024, suffix: Some("K"), separators: false })));
+        test!(both_10: SizeFormat <- ["--block-size=K", "--binary"]; Last => Ok(SizeFormat::BinaryBytes));
+        test!(both_11: SizeFormat <- ["--bytes", "--block-size=K"];  Complain => err OptionsError::Duplicate(Flag::Long("bytes"), Flag::Long("block-size")));
+
+        test!(human:   SizeFormat <- ["--block-size=human-readable"];  Both => Ok(SizeFormat::BinaryBytes));
+        test!(si:      SizeFormat <- ["--block-size=si"];       Both => Ok(SizeFormat::DecimalBytes));
+
+        // Size styles
+        test!(style_1: SizeFormat <- ["--size-style=binary"];   Both => Ok(SizeFormat::BinaryBytes));
+        test!(style_2: SizeFormat <- ["--size-style=grouped"];  Both => Ok(SizeFormat::Blocks(BlockSize { bytes: 1, suffix: None, separators: true })));
+        test!(style_3: SizeFormat <- ["--size-style=kilo"];     Both => err OptionsError::BadArgument(&flags::SIZE_STYLE, OsString::from("kilo")));
+        test!(style_4: SizeFormat <- ["--size-style=grouped", "--bytes"];  Last => Ok(SizeFormat::JustBytes));
+        test!(style_5: SizeFormat <- ["--bytes", "--size-style=default"];  Last => Ok(SizeFormat::DecimalBytes));
+        test!(style_6: SizeFormat <- ["--block-size=K", "--size-style=grouped"];  Complain => err OptionsError::Duplicate(Flag::Long("block-size"), Flag::Long("size-style")));
+
+        // Environment variables, which the test ones give every variable
+        // the same value for
+        fn with_env(
+            inputs: &[&str],
+            value: Option<&str>,
+        ) -> Vec<Result<(SizeFormat, SizeFormat), OptionsError>> {
+            let vars = value.map(OsString::from);
+            parse_for_test(inputs, TEST_ARGS, Both, |mf| {
+                SizeFormat::deduce_with_env(mf, &vars)
+            })
+        }
+
+        const KIBI: SizeFormat = SizeFormat::Blocks(BlockSize {
+            bytes: 1024,
+            suffix: Some("K"),
+            separators: false,
+        });
+        const HALF_KIBI: SizeFormat = SizeFormat::Blocks(BlockSize {
+            bytes: 512,
+            suffix: None,
+            separators: false,
+        });
+
+        #[test]
+        fn no_env() {
+            for result in with_env(&[], None) {
+                assert_eq!(
+                    result,
+                    Ok((SizeFormat::DecimalBytes, SizeFormat::DecimalBytes))
+                );
+            }
+        }
+
+        #[test]
+        fn ls_block_size() {
+            for result in with_env(&[], Some("K")) {
+                assert_eq!(result, Ok((KIBI, KIBI)));
+            }
+        }
+
+        #[test]
+        fn posixly_correct() {
+            for result in with_env(&[], Some("yes")) {
+                assert_eq!(result, Ok((SizeFormat::DecimalBytes, HALF_KIBI)));
+            }
+        }
+
+        #[test]
+        fn options_override_env() {
+            for result in with_env(&["--size-style=default"], Some("K")) {
+                assert_eq!(
+                    result,
+                    Ok((SizeFormat::DecimalBytes, SizeFormat::DecimalBytes))
+                );
+            }
+        }
     }
 
     mod time_formats {
@@ -583,6 +1361,113 @@ mod test {
         test!(override_env:     TimeFormat <- ["--time-style=full-iso"], Some("long-iso".into());  Both => like Ok(TimeFormat::FullISO));
     }
 
+    mod threads {
+        use super::*;
+
+        test!(empty:       Threads <- [], None;                            Both => like Ok(Threads::Automatic));
+        test!(auto:        Threads <- ["--threads=auto"], None;            Both => like Ok(Threads::Automatic));
+        test!(four:        Threads <- ["--threads", "4"], None;            Both => like Ok(Threads::Fixed(4)));
+        test!(env:         Threads <- [], Some("2".into());                Both => like Ok(Threads::Fixed(2)));
+        test!(env_auto:    Threads <- [], Some("auto".into());             Both => like Ok(Threads::Automatic));
+        test!(overrides:   Threads <- ["--threads=8"], Some("2".into());   Both => like Ok(Threads::Fixed(8)));
+        test!(zero:        Threads <- ["--threads=0"], None;               Both => like Err(OptionsError::FailedParse(_, NumberSource::Arg(_), _)));
+        test!(bad_env:     Threads <- [], Some("many".into());             Both => like Err(OptionsError::FailedParse(_, NumberSource::Env(_), _)));
+
+        fn threads(args: &[&str]) -> Result<Threads, OptionsError> {
+            use crate::options::{Options, OptionsResult};
+            use std::ffi::OsStr;
+
+            let os_args = args.iter().map(OsStr::new).collect::<Vec<_>>();
+            match Options::parse(os_args, &None) {
+                OptionsResult::Ok(options, _) => Ok(options.threads),
+                OptionsResult::InvalidOptions(e) => Err(e),
+                _ => panic!("unexpected result: {args:?}"),
+            }
+        }
+
+        #[test]
+        fn every_view() {
+            for view in ["-1", "--grid", "--long", "--tree", "--long --grid"] {
+                let mut args = view.split(' ').collect::<Vec<_>>();
+                args.push("--threads=3");
+                assert_eq!(threads(&args), Ok(Threads::Fixed(3)), "{view}");
+            }
+        }
+
+        #[test]
+        fn invalid_in_every_view() {
+            for view in ["-1", "--grid", "--long", "--tree", "--long --grid"] {
+                let mut args = view.split(' ').collect::<Vec<_>>();
+                args.push("--threads=abc");
+                assert!(
+                    matches!(threads(&args), Err(OptionsError::FailedParse(..))),
+                    "{view}"
+                );
+            }
+        }
+    }
+
+    mod age_units {
+        use super::*;
+
+        test!(empty:       AgeUnit <- [];                   Both => Ok(None));
+        test!(seconds:     AgeUnit <- ["--age"];            Both => Ok(Some(AgeUnit::Seconds)));
+        test!(days:        AgeUnit <- ["--age=days"];       Both => Ok(Some(AgeUnit::Days)));
+        test!(error:       AgeUnit <- ["--age=weeks"];      Both => Err(OptionsError::BadArgument(&flags::AGE, OsString::from("weeks"))));
+    }
+
+    mod tree_styles {
+        use super::*;
+
+        test!(empty:       TreeStyle <- [];                              Both => Ok(TreeStyle::Unicode));
+        test!(ascii:       TreeStyle <- ["--tree-style=ascii"];          Both => Ok(TreeStyle::Ascii));
+        test!(rounded:     TreeStyle <- ["--tree-style", "rounded"];     Both => Ok(TreeStyle::Rounded));
+        test!(bold:        TreeStyle <- ["--tree-style=bold"];           Both => Ok(TreeStyle::Bold));
+        test!(overridden:  TreeStyle <- ["--tree-style=bold", "--tree-style=unicode"];  Last => Ok(TreeStyle::Unicode));
+        test!(error:       TreeStyle <- ["--tree-style=fancy"];          Both => Err(OptionsError::BadArgument(&flags::TREE_STYLE, OsString::from("fancy"))));
+    }
+
+    mod table_styles {
+        use super::*;
+
+        test!(empty:       TableStyle <- [];                             Both => Ok(TableStyle::Plain));
+        test!(borders:     TableStyle <- ["--table-style=borders"];      Both => Ok(TableStyle::Borders));
+        test!(compact:     TableStyle <- ["--table-style", "compact"];   Both => Ok(TableStyle::Compact));
+        test!(overridden:  TableStyle <- ["--table-style=borders", "--table-style=none"];  Last => Ok(TableStyle::Plain));
+        test!(error:       TableStyle <- ["--table-style=fancy"];        Both => Err(OptionsError::BadArgument(&flags::TABLE_STYLE, OsString::from("fancy"))));
+    }
+
+    mod flushes {
+        use super::*;
+
+        test!(empty:       Flush <- [];                                  Both => Ok(Flush::Line));
+        test!(dir:         Flush <- ["--flush=dir"];                     Both => Ok(Flush::Dir));
+        test!(end:         Flush <- ["--flush", "end"];                  Both => Ok(Flush::End));
+        test!(overridden:  Flush <- ["--flush=end", "--flush=line"];     Last => Ok(Flush::Line));
+        test!(error:       Flush <- ["--flush=never"];                   Both => Err(OptionsError::BadArgument(&flags::FLUSH, OsString::from("never"))));
+    }
+
+    mod group_bys {
+        use super::*;
+
+        test!(empty:       GroupBy <- [];                                Both => Ok(None));
+        test!(category:    GroupBy <- ["--group-by=category"];           Both => Ok(Some(GroupBy::Category)));
+        test!(letter:      GroupBy <- ["--group-by", "first-letter"];    Both => Ok(Some(GroupBy::FirstLetter)));
+        test!(none:        GroupBy <- ["--group-by=extension", "--group-by=none"];  Last => Ok(None));
+        test!(error:       GroupBy <- ["--group-by=size"];               Both => Err(OptionsError::BadArgument(&flags::GROUP_BY, OsString::from("size"))));
+    }
+
+    mod limits {
+        use super::*;
+
+        test!(empty:       Limit <- [];                                  Both => Ok(None));
+        test!(twenty:      Limit <- ["--limit=20"];                      Both => Ok(Some(Limit { count: 20, marker: false })));
+        test!(marker:      Limit <- ["--limit", "5", "--limit-marker"];  Both => Ok(Some(Limit { count: 5, marker: true })));
+        test!(no_limit:    Limit <- ["--limit-marker"];                  Both => Ok(None));
+        test!(zero:        Limit <- ["--limit=0"];                       Both => like Err(OptionsError::FailedParse(_, NumberSource::Arg(_), _)));
+        test!(words:       Limit <- ["--limit=lots"];                    Both => like Err(OptionsError::FailedParse(_, NumberSource::Arg(_), _)));
+    }
+
     mod time_types {
         use super::*;
 
@@ -627,6 +1512,62 @@ mod test {
         test!(overridden_2: TimeTypes <- ["-tcr", "-tmod"];    Complain => err OptionsError::Duplicate(Flag::Short(b't'), Flag::Short(b't')));
     }
 
+    mod columns {
+        use super::*;
+
+        test!(picked:        TableOptions <- ["--columns=size,perms"], None;           Both => like Ok(TableOptions { columns: Columns { filesize: true, permissions: true, user: false, group: false, time_types: TimeTypes { modified: false, .. }, .. }, .. }));
+        test!(overriding:    TableOptions <- ["--columns=user", "--no-user"], None;    Both => like Ok(TableOptions { columns: Columns { user: true, filesize: false, .. }, .. }));
+        test!(ages:          TableOptions <- ["--columns=age,name"], None;             Both => like Ok(TableOptions { columns: Columns { age: Some(AgeUnit::Seconds), .. }, .. }));
+        test!(age_unit:      TableOptions <- ["--columns=age", "--age=days"], None;    Both => like Ok(TableOptions { columns: Columns { age: Some(AgeUnit::Days), .. }, .. }));
+        test!(no_age:        TableOptions <- ["--columns=size", "--age=days"], None;   Both => like Ok(TableOptions { columns: Columns { age: None, .. }, .. }));
+        test!(not_picked:    TableOptions <- [], None;                                 Both => like Ok(TableOptions { order: None, columns: Columns { filesize: true, user: true, .. }, .. }));
+
+        test!(unknown:       TableOptions <- ["--columns=size,sizes"], None;           Both => err OptionsError::BadArgument(&flags::COLUMNS, OsString::from("sizes")));
+        test!(name_first:    TableOptions <- ["--columns=name,size"], None;            Both => err OptionsError::Unsupported("The name column has to come last in --columns".into()));
+        test!(no_snapshot:   TableOptions <- ["--columns=delta"], None;                Both => err OptionsError::Useless(&flags::COLUMNS, false, &flags::DELTA));
+
+        test!(hidden:        TableOptions <- ["--hide=user,modified"], None;            Both => like Ok(TableOptions { columns: Columns { user: false, filesize: true, time_types: TimeTypes { modified: false, .. }, .. }, .. }));
+        test!(hide_picked:   TableOptions <- ["--columns=size,user", "--hide=user"], None; Both => like Ok(TableOptions { columns: Columns { user: false, filesize: true, permissions: false, .. }, .. }));
+        test!(hide_age:      TableOptions <- ["--age", "--hide=age"], None;              Both => like Ok(TableOptions { columns: Columns { age: None, .. }, .. }));
+        test!(hide_unknown:  TableOptions <- ["--hide=users"], None;                     Both => err OptionsError::BadArgument(&flags::HIDE, OsString::from("users")));
+        test!(hide_time:     TableOptions <- ["--hide=user,time", "-um"], None;         Both => like Ok(TableOptions { columns: Columns { user: false, time_types: TimeTypes { modified: false, accessed: false, changed: false, created: false }, .. }, .. }));
+        test!(hide_name:     TableOptions <- ["--hide=size,name"], None;                 Both => err OptionsError::Unsupported("The name column can’t be hidden".into()));
+
+        test!(bad_side:      TableOptions <- ["--align=size=middle"], None;            Both => err OptionsError::BadArgument(&flags::ALIGN, OsString::from("size=middle")));
+        test!(bad_column:    TableOptions <- ["--align=sizes=left"], None;             Both => err OptionsError::BadArgument(&flags::ALIGN, OsString::from("sizes=left")));
+        test!(no_side:       TableOptions <- ["--align=size"], None;                   Both => err OptionsError::BadArgument(&flags::ALIGN, OsString::from("size")));
+
+        #[test]
+        fn aligned() {
+            for result in parse_for_test(
+                &["--align=inode=right,,name=right"],
+                TEST_ARGS,
+                Both,
+                |mf| TableOptions::deduce_align(mf),
+            ) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        ("inode", Alignment::Right),
+                        ("name", Alignment::Right)
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn order() {
+            for result in parse_for_test(
+                &["--columns=size,perms,user,size,name"],
+                TEST_ARGS,
+                Both,
+                |mf| TableOptions::deduce_order(mf),
+            ) {
+                assert_eq!(result, Ok(Some(vec!["size", "perms", "user", "name"])));
+            }
+        }
+    }
+
     mod views {
         use super::*;
 
@@ -640,6 +1581,8 @@ mod test {
         test!(grid:          Mode <- ["--grid"], None;    Both => like Ok(Mode::Grid(GridOptions { across: false, .. })));
         test!(across:        Mode <- ["--across"], None;  Both => like Ok(Mode::Grid(GridOptions { across: true,  .. })));
         test!(gracross:      Mode <- ["-xG"], None;       Both => like Ok(Mode::Grid(GridOptions { across: true,  .. })));
+        test!(grid_rows:     Mode <- ["--grid-rows=8"], None;  Both => like Ok(Mode::Grid(GridOptions { rows: Some(8), .. })));
+        test!(zero_rows:     Mode <- ["--grid-rows=0"], None;  Both => like Err(OptionsError::FailedParse(_, NumberSource::Arg(_), _)));
 
         // Lines views
         test!(lines:         Mode <- ["--oneline"], None;     Both => like Ok(Mode::Lines));
@@ -674,6 +1617,19 @@ mod test {
         test!(just_inode_2:  Mode <- ["--inode"],     None;  Complain => err OptionsError::Useless(&flags::INODE,   false, &flags::LONG));
         test!(just_links_2:  Mode <- ["--links"],     None;  Complain => err OptionsError::Useless(&flags::LINKS,   false, &flags::LONG));
         test!(just_blocks_2: Mode <- ["--blocksize"], None;  Complain => err OptionsError::Useless(&flags::BLOCKSIZE,  false, &flags::LONG));
+        test!(just_usage_2:  Mode <- ["--disk-usage"], None; Complain => err OptionsError::Useless(&flags::DISK_USAGE, false, &flags::LONG));
+        test!(just_block_2:  Mode <- ["--block-size=K"], None; Complain => err OptionsError::Useless(&flags::BLOCK_SIZE, false, &flags::LONG));
+        test!(just_owners_2: Mode <- ["--codeowners"],   None; Complain => err OptionsError::Useless(&flags::CODEOWNERS, false, &flags::LONG));
+        test!(just_age_2:    Mode <- ["--age"],          None; Complain => err OptionsError::Useless(&flags::AGE, false, &flags::LONG));
+        test!(just_age_3:    Mode <- ["--age=hours"],    None; Complain => err OptionsError::Useless(&flags::AGE, false, &flags::LONG));
+        test!(just_delta_2:  Mode <- ["--delta=snap"],   None; Complain => err OptionsError::Useless(&flags::DELTA, false, &flags::LONG));
+        test!(just_columns:  Mode <- ["--columns=size"], None; Complain => err OptionsError::Useless(&flags::COLUMNS, false, &flags::LONG));
+        test!(just_hide:     Mode <- ["--hide=group"],   None; Complain => err OptionsError::Useless(&flags::HIDE, false, &flags::LONG));
+        test!(just_owners_3: Mode <- ["--colour-owners"], None; Complain => err OptionsError::Useless(&flags::COLOUR_OWNERS, false, &flags::LONG));
+        test!(just_table:    Mode <- ["--table-style=borders"],  None; Complain => err OptionsError::Useless(&flags::TABLE_STYLE, false, &flags::LONG));
+        test!(just_wrap:     Mode <- ["--wrap-names"],   None; Complain => err OptionsError::Useless2(&flags::WRAP_NAMES, &flags::LONG, &flags::TREE));
+        test!(just_git_sum:  View <- ["--git-summary"],  None; Complain => err OptionsError::Useless(&flags::GIT_SUMMARY, false, &flags::GIT));
+        test!(just_changes:  View <- ["--only-changes"], None; Complain => err OptionsError::Useless(&flags::ONLY_CHANGES, false, &flags::DIFF));
         test!(just_binary_2: Mode <- ["--binary"],    None;  Complain => err OptionsError::Useless(&flags::BINARY,  false, &flags::LONG));
         test!(just_bytes_2:  Mode <- ["--bytes"],     None;  Complain => err OptionsError::Useless(&flags::BYTES,   false, &flags::LONG));
         test!(just_numeric2: Mode <- ["--numeric"],   None;  Complain => err OptionsError::Useless(&flags::NUMERIC, false, &flags::LONG));
@@ -689,5 +1645,23 @@ mod test {
         test!(ot:            Mode <- ["--oneline", "--tree"],           None;  Both => like Ok(Mode::Details(_)));
         test!(og:            Mode <- ["--oneline", "--grid"],           None;  Both => like Ok(Mode::Grid(_)));
         test!(tg:            Mode <- ["--tree", "--grid"],              None;  Both => like Ok(Mode::Grid(_)));
+
+        // Flat recursive listings are drawn as trees
+        test!(rec_flat:      Mode <- ["-R", "--flat"],                  None;  Both => like Ok(Mode::Details(details::Options { table: None, .. })));
+        test!(rec_flat_long: Mode <- ["-lR", "--flat"],                 None;  Both => like Ok(Mode::Details(details::Options { table: Some(_), .. })));
+        test!(flat_grid:     Mode <- ["-R", "--flat", "--grid"],        None;  Last => like Ok(Mode::Grid(_)));
+        test!(flat_lines:    Mode <- ["-R", "--flat", "-1"],            None;  Both => like Ok(Mode::Details(details::Options { table: None, .. })));
+        test!(lines_flat:    Mode <- ["-1", "-R", "--flat"],            None;  Both => like Ok(Mode::Details(details::Options { table: None, .. })));
+        test!(just_flat:     Mode <- ["--flat"],                        None;  Last => like Ok(Mode::Grid(_)));
+
+        // Limiting the entries in each directory of a tree
+        test!(interval:      Mode <- ["-lh", "--header-interval=40"],   None;  Both => like Ok(Mode::Details(details::Options { header: true, header_interval: Some(40), .. })));
+        test!(zero_interval: Mode <- ["-lh", "--header-interval=0"],    None;  Both => like Err(OptionsError::FailedParse(_, NumberSource::Arg(_), _)));
+        test!(no_header:     Mode <- ["-l", "--header-interval=40"],    None;  Complain => err OptionsError::Useless(&flags::HEADER_INTERVAL, false, &flags::HEADER));
+        test!(tree_limit:    Mode <- ["--tree", "--tree-limit=3"],      None;  Both => like Ok(Mode::Details(details::Options { tree_limit: Some(3), .. })));
+        test!(long_limit:    Mode <- ["-lT", "--tree-limit", "9"],      None;  Both => like Ok(Mode::Details(details::Options { tree_limit: Some(9), .. })));
+        test!(wrap_long:     Mode <- ["-l", "--wrap-names"],            None;  Both => like Ok(Mode::Details(details::Options { wrap_names: true, .. })));
+        test!(wrap_tree:     Mode <- ["--tree", "--wrap-names"],        None;  Both => like Ok(Mode::Details(details::Options { wrap_names: true, table: None, .. })));
+        test!(zero_limit:    Mode <- ["--tree", "--tree-limit=0"],      None;  Both => like Err(OptionsError::FailedParse(_, NumberSource::Arg(_), _)));
     }
 }
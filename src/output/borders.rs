@@ -0,0 +1,220 @@
+//! Drawing lines around and between the columns of the table in the long
+//! view, for `--table-style`.
+//!
+//! The table gets put together the same way whatever the style is, with
+//! the cells of each row joined by a separator and the name after them;
+//! the decoration picks the separator, then closes each row off and draws
+//! the rules above, beneath, and in between the rows once they’re all done,
+//! as only then can it know how wide the names get.
+
+use ansiterm::Style;
+
+use crate::output::cell::TextCell;
+use crate::output::table::Alignment;
+
+/// How the table in the long view gets drawn.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum TableStyle {
+    /// Columns separated by spaces, without any lines.
+    #[default]
+    Plain,
+
+    /// A line in between the columns in place of the space, and one
+    /// beneath the header if there is one.
+    Compact,
+
+    /// A box around the whole table, with lines between the columns and
+    /// beneath the header.
+    Borders,
+}
+
+/// Which of the rules across the table to draw.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Rule {
+    Top,
+    Header,
+    Bottom,
+}
+
+/// The lines for a table with the given column widths.
+pub struct Decoration {
+    style: TableStyle,
+    lines: Style,
+
+    /// The widths of the columns, not counting the name.
+    widths: Vec<usize>,
+
+    /// Which side of their column the names go on.
+    names: Alignment,
+
+    /// The width of the longest name, once the rows have been closed off.
+    name_width: usize,
+}
+
+impl Decoration {
+    pub fn new(style: TableStyle, lines: Style, widths: &[usize], names: Alignment) -> Self {
+        Self {
+            style,
+            lines,
+            widths: widths.to_vec(),
+            names,
+            name_width: 0,
+        }
+    }
+
+    /// What goes at the start of each row, before its first cell.
+    pub fn start(&self) -> TextCell {
+        match self.style {
+            TableStyle::Plain | TableStyle::Compact => TextCell::default(),
+            TableStyle::Borders => TextCell::paint_str(self.lines, "│ "),
+        }
+    }
+
+    /// What goes after each cell, including the one before the name.
+    pub fn separator(&self) -> TextCell {
+        match self.style {
+            TableStyle::Plain => TextCell::paint_str(Style::default(), " "),
+            TableStyle::Compact => TextCell::paint_str(self.lines, "│"),
+            TableStyle::Borders => TextCell::paint_str(self.lines, " │ "),
+        }
+    }
+
+    /// Pads the names in the rows out to the longest one, on whichever side
+    /// they aren’t aligned to, and puts the right border after them, if
+    /// there is one.
+    pub fn close(&mut self, rows: &mut [TextCell]) {
+        let cells_width = *self.start().width
+            + self
+                .widths
+                .iter()
+                .map(|width| width + *self.separator().width)
+                .sum::<usize>();
+
+        self.name_width = rows
+            .iter()
+            .map(|row| row.width.saturating_sub(cells_width))
+            .max()
+            .unwrap_or(0);
+
+        for row in rows {
+            let padding = (cells_width + self.name_width).saturating_sub(*row.width);
+            if self.names == Alignment::Right {
+                row.insert_spaces(cells_width, padding);
+            } else if self.style == TableStyle::Borders {
+                row.add_spaces(padding);
+            }
+
+            if self.style == TableStyle::Borders {
+                row.push(self.lines.paint(" │"), 2);
+            }
+        }
+    }
+
+    /// One of the rules across the table, if this style has it.
+    pub fn rule(&self, rule: Rule) -> Option<TextCell> {
+        #[rustfmt::skip]
+        let (left, cross, right) = match (self.style, rule) {
+            (TableStyle::Plain, _)                   => return None,
+            (TableStyle::Compact, Rule::Header)      => ("", "┼", ""),
+            (TableStyle::Compact, _)                 => return None,
+            (TableStyle::Borders, Rule::Top)         => ("┌─", "─┬─", "─┐"),
+            (TableStyle::Borders, Rule::Header)      => ("├─", "─┼─", "─┤"),
+            (TableStyle::Borders, Rule::Bottom)      => ("└─", "─┴─", "─┘"),
+        };
+
+        let mut text = String::from(left);
+        for width in &self.widths {
+            text.push_str(&"─".repeat(*width));
+            text.push_str(cross);
+        }
+        text.push_str(&"─".repeat(self.name_width));
+        text.push_str(right);
+
+        Some(TextCell::paint(self.lines, text))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn row(decoration: &Decoration, cells: &[&'static str], name: &'static str) -> TextCell {
+        let mut row = decoration.start();
+        for (cell, width) in cells.iter().zip(&decoration.widths) {
+            row.append(TextCell::paint_str(Style::default(), cell));
+            row.add_spaces(width - cell.len());
+            row.append(decoration.separator());
+        }
+        row.append(TextCell::paint_str(Style::default(), name));
+        row
+    }
+
+    fn draw(style: TableStyle) -> Vec<String> {
+        draw_aligned(style, Alignment::Left)
+    }
+
+    fn draw_aligned(style: TableStyle, names: Alignment) -> Vec<String> {
+        let mut decoration = Decoration::new(style, Style::default(), &[4, 2], names);
+        let mut rows = vec![
+            row(&decoration, &["Size", "Us"], "Name"),
+            row(&decoration, &["12", "me"], "a.txt"),
+        ];
+        decoration.close(&mut rows);
+
+        let mut lines = Vec::new();
+        lines.extend(decoration.rule(Rule::Top));
+        lines.push(rows[0].clone());
+        lines.extend(decoration.rule(Rule::Header));
+        lines.push(rows[1].clone());
+        lines.extend(decoration.rule(Rule::Bottom));
+
+        lines.iter().map(|l| l.strings().to_string()).collect()
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(draw(TableStyle::Plain), ["Size Us Name", "12   me a.txt"]);
+    }
+
+    #[test]
+    fn plain_right() {
+        assert_eq!(
+            draw_aligned(TableStyle::Plain, Alignment::Right),
+            ["Size Us  Name", "12   me a.txt"]
+        );
+    }
+
+    #[test]
+    fn compact() {
+        assert_eq!(
+            draw(TableStyle::Compact),
+            ["Size│Us│Name", "────┼──┼─────", "12  │me│a.txt"]
+        );
+    }
+
+    #[test]
+    fn borders() {
+        assert_eq!(
+            draw(TableStyle::Borders),
+            [
+                "┌──────┬────┬───────┐",
+                "│ Size │ Us │ Name  │",
+                "├──────┼────┼───────┤",
+                "│ 12   │ me │ a.txt │",
+                "└──────┴────┴───────┘",
+            ]
+        );
+    }
+
+    #[test]
+    fn borders_right() {
+        assert_eq!(
+            draw_aligned(TableStyle::Borders, Alignment::Right)[1..4],
+            [
+                "│ Size │ Us │  Name │",
+                "├──────┼────┼───────┤",
+                "│ 12   │ me │ a.txt │"
+            ]
+        );
+    }
+}
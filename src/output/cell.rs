@@ -1,10 +1,11 @@
 //! The `TextCell` type for the details and lines views.
 
 use std::iter::Sum;
+use std::mem;
 use std::ops::{Add, Deref, DerefMut};
 
-use ansiterm::{ANSIString, ANSIStrings, Style};
-use unicode_width::UnicodeWidthStr;
+use ansiterm::{ANSIString, ANSIStrings, Colour, Style};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// An individual cell that holds text in a table, used in the details and
 /// lines views to store ANSI-terminal-formatted data before it is printed.
@@ -80,6 +81,27 @@ impl TextCell {
         self.contents.0.push(Style::default().paint(spaces));
     }
 
+    /// Adds the given number of unstyled spaces into this cell at the given
+    /// width, which should come between two of its strings, or at the end
+    /// if it doesn’t.
+    pub fn insert_spaces(&mut self, at: usize, count: usize) {
+        let mut width = 0;
+        let index = self
+            .contents
+            .0
+            .iter()
+            .position(|string| {
+                let before = width;
+                width += UnicodeWidthStr::width(&**string);
+                before >= at
+            })
+            .unwrap_or(self.contents.0.len());
+
+        (*self.width) += count;
+        let spaces = Style::default().paint(" ".repeat(count));
+        self.contents.0.insert(index, spaces);
+    }
+
     /// Adds the contents of another `ANSIString` to the end of this cell.
     pub fn push(&mut self, string: ANSIString<'static>, extra_width: usize) {
         self.contents.0.push(string);
@@ -91,6 +113,67 @@ impl TextCell {
         (*self.width) += *other.width;
         self.contents.0.extend(other.contents.0);
     }
+
+    /// Gives everything in this cell the given background, including the
+    /// spaces used as padding, apart from the parts that have a background
+    /// of their own.
+    pub fn stripe(&mut self, background: Colour) {
+        for string in &mut self.contents.0 {
+            let style = string.style_ref_mut();
+            if style.background.is_none() {
+                style.background = Some(background);
+            }
+        }
+    }
+
+    /// Paints everything in this cell in the given style, in place of the
+    /// styles it had.
+    pub fn restyle(&mut self, style: Style) {
+        for string in &mut self.contents.0 {
+            *string.style_ref_mut() = style;
+        }
+    }
+
+    /// Splits this cell into lines that are each at most the given width,
+    /// breaking between characters, so a wide character never gets split
+    /// across two lines. Cells with escape sequences in them, such as
+    /// hyperlinks, are left as they are, as the sequences can’t be split.
+    pub fn wrap(self, width: usize) -> Vec<Self> {
+        let escaped = self.contents.0.iter().any(|s| s.contains('\x1B'));
+        if *self.width <= width || width == 0 || escaped {
+            return vec![self];
+        }
+
+        let mut lines = Vec::new();
+        let mut line = Self::default();
+        for string in self.contents.0 {
+            let style = *string.style_ref();
+            let mut piece = String::new();
+            let mut piece_width = 0;
+
+            for c in string.chars() {
+                let char_width = c.width().unwrap_or(0);
+                let line_width = *line.width + piece_width;
+                if line_width + char_width > width && line_width > 0 {
+                    if !piece.is_empty() {
+                        line.push(style.paint(mem::take(&mut piece)), piece_width);
+                    }
+                    lines.push(mem::take(&mut line));
+                    piece_width = 0;
+                }
+
+                piece.push(c);
+                piece_width += char_width;
+            }
+
+            if !piece.is_empty() {
+                line.push(style.paint(piece), piece_width);
+            }
+        }
+
+        lines.push(line);
+        lines
+    }
 }
 
 // I’d like to eventually abstract cells so that instead of *every* cell
@@ -268,3 +351,93 @@ mod width_unit_test {
         assert_eq!(*(cell + 8), 17);
     }
 }
+
+#[cfg(test)]
+mod stripe_test {
+    use super::TextCell;
+    use ansiterm::Colour::*;
+    use ansiterm::Style;
+
+    #[test]
+    fn padding_too() {
+        let mut cell = TextCell::paint_str(Blue.normal(), "abc");
+        cell.add_spaces(2);
+        cell.append(TextCell::paint_str(Red.on(Yellow), "d"));
+        cell.stripe(Fixed(236));
+
+        let styles = cell.iter().map(|s| *s.style_ref()).collect::<Vec<_>>();
+        assert_eq!(
+            styles,
+            [
+                Blue.on(Fixed(236)),
+                Style::default().on(Fixed(236)),
+                Red.on(Yellow)
+            ]
+        );
+        assert_eq!(*cell.width, 6);
+    }
+}
+
+#[cfg(test)]
+mod restyle_test {
+    use super::TextCell;
+    use ansiterm::Colour::*;
+
+    #[test]
+    fn every_part() {
+        let mut cell = TextCell::paint_str(Blue.bold(), "12");
+        cell.append(TextCell::paint_str(Red.on(Yellow), "k"));
+        cell.restyle(Fixed(244).dimmed());
+
+        let styles = cell.iter().map(|s| *s.style_ref()).collect::<Vec<_>>();
+        assert_eq!(styles, [Fixed(244).dimmed(), Fixed(244).dimmed()]);
+        assert_eq!(cell.strings().to_string(), "\x1b[2;38;5;244m12k\x1b[0m");
+    }
+}
+
+#[cfg(test)]
+mod wrap_test {
+    use super::TextCell;
+    use ansiterm::ANSIStrings;
+    use ansiterm::Colour::*;
+
+    fn contents(lines: &[TextCell]) -> Vec<String> {
+        lines.iter().map(|l| l.strings().to_string()).collect()
+    }
+
+    #[test]
+    fn fits() {
+        let cell = TextCell::paint_str(Blue.normal(), "short");
+        assert_eq!(cell.clone().wrap(5), vec![cell]);
+    }
+
+    #[test]
+    fn across_styles() {
+        let mut cell = TextCell::paint_str(Blue.normal(), "abcd");
+        cell.append(TextCell::paint_str(Red.normal(), "efg"));
+
+        let lines = cell.wrap(3);
+        assert_eq!(
+            lines.iter().map(|l| *l.width).collect::<Vec<_>>(),
+            [3, 3, 1]
+        );
+        assert_eq!(
+            contents(&lines),
+            [
+                Blue.paint("abc").to_string(),
+                ANSIStrings(&[Blue.paint("d"), Red.paint("ef")]).to_string(),
+                Red.paint("g").to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn double_width() {
+        let cell = TextCell::paint_str(Blue.normal(), "日本語の名前");
+        let lines = cell.wrap(5);
+        assert_eq!(
+            lines.iter().map(|l| *l.width).collect::<Vec<_>>(),
+            [4, 4, 4]
+        );
+    }
+}
@@ -58,10 +58,22 @@
 //! Each column in the table needs to be resized to fit its widest argument. This
 //! means that we must wait until every row has been added to the table before it
 //! can be displayed, in order to make sure that every column is wide enough.
+//! A tree without a table has no columns to wait for, so its rows get written
+//! out as each directory is read instead, unless something about a directory’s
+//! row can only be worked out once the rows beneath it are in.
+//!
+//! Building the rows is done in two stages. First, each file’s details are
+//! looked up on a pool of threads: its cells, and if it’s going to be recursed
+//! into, the metadata and extended attributes of the files inside it. Then the
+//! rows get put together in order on the main thread, which by then doesn’t
+//! have to wait on the filesystem for anything. Git statuses take the longest
+//! to query, so they start being queried as soon as rendering begins.
 
 use std::io::{self, Write};
 use std::mem::MaybeUninit;
-use std::path::PathBuf;
+use std::ops::{Index, IndexMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
 use std::vec::IntoIter as VecIntoIter;
 
 use ansiterm::Style;
@@ -69,16 +81,21 @@ use scoped_threadpool::Pool;
 
 use log::*;
 
-use crate::fs::dir_action::RecurseOptions;
+use crate::fs::dir_action::{Descent, RecurseOptions};
 use crate::fs::feature::git::GitCache;
 use crate::fs::feature::xattr::Attribute;
 use crate::fs::fields::SecurityContextType;
 use crate::fs::filter::FileFilter;
-use crate::fs::{Dir, File};
-use crate::output::cell::TextCell;
-use crate::output::file_name::Options as FileStyle;
-use crate::output::table::{Options as TableOptions, Row as TableRow, Table};
-use crate::output::tree::{TreeDepth, TreeParams, TreeTrunk};
+use crate::fs::mounts::Storage;
+use crate::fs::{Dir, DirIdentity, File};
+use crate::info::filetype::FileType;
+use crate::output::borders::{Decoration, Rule, TableStyle};
+use crate::output::cell::{DisplayWidth, TextCell};
+use crate::output::file_name::{Classify, Options as FileStyle};
+use crate::output::table::{Alignment, Options as TableOptions, Row as TableRow, Table};
+use crate::output::tally::Tally;
+use crate::output::tree::{self, TreeDepth, TreeParams, TreePart, TreeStyle, TreeTrunk};
+use crate::output::unreadable::Unreadable;
 use crate::theme::Theme;
 
 /// With the **Details** view, the output gets formatted into columns, with
@@ -105,6 +122,9 @@ pub struct Options {
     /// Whether to show a header line or not.
     pub header: bool,
 
+    /// How many rows to list before the header gets repeated, if it does.
+    pub header_interval: Option<usize>,
+
     /// Whether to show each file’s extended attributes.
     pub xattr: bool,
 
@@ -113,6 +133,62 @@ pub struct Options {
 
     /// Whether to show a directory's mounted filesystem details
     pub mounts: bool,
+
+    /// Whether to collapse the tool config files in each directory of a
+    /// tree into one row, instead of giving each one a row of its own.
+    pub collapse_configs: bool,
+
+    /// Whether to say how many files and directories are beneath each
+    /// directory in a tree, including the ones too deep to be listed.
+    pub tree_counts: bool,
+
+    /// Whether to list a chain of directories that each have just one
+    /// directory inside them on one line, such as `src/main/java`.
+    pub compact_tree: bool,
+
+    /// Which characters to draw the tree with.
+    pub tree_style: TreeStyle,
+
+    /// How many entries to display in each directory of a tree, with a row
+    /// saying how many more there are after them.
+    pub tree_limit: Option<usize>,
+
+    /// Whether names too long to fit in the terminal carry on onto the
+    /// lines beneath them, underneath where they start.
+    pub wrap_names: bool,
+
+    /// Whether to draw lines around and between the columns of the table.
+    pub table_style: TableStyle,
+}
+
+/// The number of threads to use when looking up the details of files, which
+/// mostly spend their time waiting for the filesystem.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Threads {
+    /// Work it out from the number of CPUs and the storage being listed.
+    Automatic,
+
+    /// Use exactly this many threads.
+    Fixed(u32),
+}
+
+impl Threads {
+    /// The number of threads to use for listing files at the given path.
+    /// Network filesystems get more threads than there are CPUs, as most
+    /// of them will be waiting for replies, and spinning disks get fewer, so
+    /// they don’t spend their time seeking.
+    pub fn count(self, path: &Path) -> u32 {
+        let cpus = match self {
+            Self::Fixed(count) => return count,
+            Self::Automatic => num_cpus::get().max(1) as u32,
+        };
+
+        match Storage::of(path) {
+            Storage::Network => cpus * 4,
+            Storage::Rotational => cpus.min(2),
+            Storage::Other => cpus,
+        }
+    }
 }
 
 pub struct Render<'a> {
@@ -134,6 +210,21 @@ pub struct Render<'a> {
     pub git_ignoring: bool,
 
     pub git: Option<&'a GitCache>,
+
+    /// Where to note the directories in a tree that can’t be read, which
+    /// get listed at the end instead of beneath them unless it’s inline.
+    pub unreadable: Option<&'a Unreadable>,
+
+    /// Where to count the entries of a tree as they get added, for
+    /// `--summary` and `--git-summary`.
+    pub tally: Option<&'a Mutex<Tally>>,
+
+    /// The width of the terminal, which names get wrapped at if they’re
+    /// being wrapped.
+    pub console_width: Option<usize>,
+
+    /// How many threads to look up files’ details with.
+    pub threads: u32,
 }
 
 #[rustfmt::skip]
@@ -141,10 +232,45 @@ struct Egg<'a> {
     table_row: Option<TableRow>,
     xattrs:    &'a [Attribute],
     errors:    Vec<(io::Error, Option<PathBuf>)>,
-    dir:       Option<Dir>,
+    dir:       Option<Box<Dir>>,
+    descent:   Descent,
+    beneath:   Option<Counts>,
     file:      &'a File<'a>,
 }
 
+/// What each row added for the files in a directory shows.
+enum Entry<'a> {
+    File(Egg<'a>),
+
+    /// The config files that got collapsed into one row.
+    Configs,
+
+    /// How many entries got left out by `--tree-limit`.
+    More,
+}
+
+/// The number of files and directories beneath a directory in a tree.
+#[derive(Default, Debug, Copy, Clone)]
+struct Counts {
+    files: usize,
+    dirs: usize,
+}
+
+impl Counts {
+    fn add(&mut self, other: Self) {
+        self.files += other.files;
+        self.dirs += other.dirs;
+    }
+
+    fn add_file(&mut self, file: &File<'_>) {
+        if file.is_directory() {
+            self.dirs += 1;
+        } else {
+            self.files += 1;
+        }
+    }
+}
+
 impl<'a> AsRef<File<'a>> for Egg<'a> {
     fn as_ref(&self) -> &File<'a> {
         self.file
@@ -153,12 +279,8 @@ impl<'a> AsRef<File<'a>> for Egg<'a> {
 
 impl<'a> Render<'a> {
     pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
-        let n_cpus = match num_cpus::get() as u32 {
-            0 => 1,
-            n => n,
-        };
-        let mut pool = Pool::new(n_cpus);
-        let mut rows = Vec::new();
+        debug!("Looking up details with {} threads", self.threads);
+        let mut pool = Pool::new(self.threads);
 
         if let Some(ref table) = self.opts.table {
             match (self.git, self.dir) {
@@ -175,7 +297,11 @@ impl<'a> Render<'a> {
                 (None, _) => { /* Keep Git how it is */ }
             }
 
+            // The rows of a table never get streamed, even in a tree, as the
+            // width of each column depends on every row that’s in it, and
+            // guessing would leave the columns out of line.
             let mut table = Table::new(table, self.git, self.theme);
+            let mut rows = Rows::buffered();
 
             if self.opts.header {
                 let header = table.header_row();
@@ -183,28 +309,102 @@ impl<'a> Render<'a> {
                 rows.push(self.render_header(header));
             }
 
+            if let Some(root) = self.render_root() {
+                rows.push_root(root);
+            }
+
             // This is weird, but I can’t find a way around it:
             // https://internals.rust-lang.org/t/should-option-mut-t-implement-copy/3715/6
             let mut table = Some(table);
-            self.add_files_to_table(
-                &mut pool,
-                &mut table,
-                &mut rows,
-                &self.files,
-                TreeDepth::root(),
-            );
+            std::thread::scope(|scope| {
+                if let Some(git) = self.git {
+                    scope.spawn(move || git.prefetch());
+                }
 
-            for row in self.iterate_with_table(table.unwrap(), rows) {
-                writeln!(w, "{}", row.strings())?;
+                self.add_files_to_table(
+                    &mut pool,
+                    &mut table,
+                    &mut rows,
+                    &self.files,
+                    TreeDepth::root(),
+                    &[],
+                );
+            });
+            let top = rows.top_depth();
+            let mut rows = rows.finish()?;
+            self.skip_shallow_rows(&mut rows, top);
+
+            let table = table.unwrap();
+            let style = self.opts.table_style;
+            let names = table.name_alignment();
+            let mut decoration =
+                Decoration::new(style, self.theme.ui.punctuation, table.widths(), names);
+            let mut lines = self
+                .iterate_with_table(table, rows, style)
+                .collect::<Vec<_>>();
+            decoration.close(&mut lines);
+
+            // Every other row below the header gets striped, if the theme
+            // gives the stripes a background.
+            let stripe = self.theme.ui.zebra.and_then(|style| style.background);
+            let header = self.opts.header.then(|| lines[0].clone());
+            let header_rule = decoration.rule(Rule::Header);
+            let skip = usize::from(header.is_some());
+
+            if let Some(rule) = decoration.rule(Rule::Top) {
+                writeln!(w, "{}", rule.strings())?;
+            }
+            for (i, mut line) in lines.into_iter().enumerate() {
+                // How many rows beneath the header this one is
+                let Some(n) = i.checked_sub(skip) else {
+                    writeln!(w, "{}", line.strings())?;
+                    if let Some(rule) = &header_rule {
+                        writeln!(w, "{}", rule.strings())?;
+                    }
+                    continue;
+                };
+
+                if let (Some(header), Some(interval)) = (&header, self.opts.header_interval) {
+                    if n > 0 && n % interval == 0 {
+                        writeln!(w, "{}", header.strings())?;
+                        if let Some(rule) = &header_rule {
+                            writeln!(w, "{}", rule.strings())?;
+                        }
+                    }
+                }
+
+                if let Some(background) = stripe {
+                    if n % 2 == 1 {
+                        line.stripe(background);
+                    }
+                }
+                writeln!(w, "{}", line.strings())?;
+            }
+            if let Some(rule) = decoration.rule(Rule::Bottom) {
+                writeln!(w, "{}", rule.strings())?;
             }
         } else {
+            let mut rows = if self.can_stream() {
+                Rows::streamed(w, self.branches())
+            } else {
+                Rows::buffered()
+            };
+
+            if let Some(root) = self.render_root() {
+                rows.push_root(root);
+            }
+
             self.add_files_to_table(
                 &mut pool,
                 &mut None,
                 &mut rows,
                 &self.files,
                 TreeDepth::root(),
+                &[],
             );
+            let top = rows.top_depth();
+            let mut rows = rows.finish()?;
+            self.skip_shallow_rows(&mut rows, top);
 
             for row in self.iterate(rows) {
                 writeln!(w, "{}", row.strings())?;
@@ -214,6 +414,36 @@ impl<'a> Render<'a> {
         Ok(())
     }
 
+    /// Whether the rows can be written out as soon as nothing else is going
+    /// to change them, which they can’t be when a directory’s counts only
+    /// get added to its row after the rows beneath it, or when levels of
+    /// the tree get taken out once all the rows are in.
+    fn can_stream(&self) -> bool {
+        !self.opts.tree_counts
+            && self
+                .recurse
+                .and_then(|r| r.min_depth)
+                .map_or(true, |min_depth| min_depth <= 1)
+    }
+
+    /// Removes the rows of a tree that are above the minimum depth, apart
+    /// from the ones at its root, and moves the deeper rows up to where
+    /// they would have been. The root is at the given depth, which is only
+    /// below zero when the arguments go beneath a row of their own.
+    fn skip_shallow_rows(&self, rows: &mut Vec<Row>, top: usize) {
+        let Some(min_depth) = self.recurse.filter(|r| r.tree).and_then(|r| r.min_depth) else {
+            return;
+        };
+
+        if min_depth > 1 {
+            rows.retain(|row| {
+                let depth = row.tree.depth().0;
+                depth <= top || depth >= min_depth + top
+            });
+            tree::skip_levels(rows.iter_mut().map(|row| &mut row.tree), min_depth - 1);
+        }
+    }
+
     /// Whether to show the extended attribute hint
     pub fn show_xattr_hint(&self, file: &File<'_>) -> bool {
         // Do not show the hint '@' if the only extended attribute is the security
@@ -228,17 +458,22 @@ impl<'a> Render<'a> {
     }
 
     /// Adds files to the table, possibly recursively. This is easily
-    /// parallelisable, and uses a pool of threads.
+    /// parallelisable, and uses a pool of threads to look each file up
+    /// before the rows get put together in order. Returns how many files
+    /// and directories were added, including the ones beneath them.
     fn add_files_to_table<'dir>(
         &self,
         pool: &mut Pool,
         table: &mut Option<Table<'a>>,
-        rows: &mut Vec<Row>,
+        rows: &mut Rows<'_>,
         src: &[File<'dir>],
         depth: TreeDepth,
-    ) {
+        ancestors: &[DirIdentity],
+    ) -> Counts {
         use crate::fs::feature::xattr;
-        use std::sync::{Arc, Mutex};
+        use std::sync::Arc;
+
+        let (src, hidden) = self.limit_entries(src, depth);
 
         let mut file_eggs = (0..src.len())
             .map(|_| MaybeUninit::uninit())
@@ -248,7 +483,7 @@ impl<'a> Render<'a> {
             let file_eggs = Arc::new(Mutex::new(&mut file_eggs));
             let table = table.as_ref();
 
-            for (idx, file) in src.iter().enumerate() {
+            for (idx, &file) in src.iter().enumerate() {
                 let file_eggs = Arc::clone(&file_eggs);
 
                 scoped.execute(move || {
@@ -282,30 +517,49 @@ impl<'a> Render<'a> {
                         &[]
                     };
 
-                    let table_row = table
-                        .as_ref()
-                        .map(|t| t.row_for_file(file, self.show_xattr_hint(file)));
-
                     let mut dir = None;
-                    if let Some(r) = self.recurse {
-                        if file.is_directory() && r.tree && !r.is_too_deep(depth.0) {
+                    let mut descent = Descent::Skip;
+                    let mut beneath = None;
+                    if let Some(r) = self.recurse.filter(|r| r.tree) {
+                        let pruned = self.filter.is_pruned(file);
+                        if !r.is_too_deep(depth.0) && !pruned {
+                            descent = r.descent(file, ancestors);
+                        }
+
+                        if let Descent::Into(_) = descent {
                             trace!("matching on to_dir");
                             match file.to_dir() {
                                 Ok(d) => {
-                                    dir = Some(d);
+                                    self.resolve_ahead(&d, file, table.is_some());
+                                    dir = Some(Box::new(d));
                                 }
                                 Err(e) => {
-                                    errors.push((e, None));
+                                    if let Some(unreadable) = self.unreadable {
+                                        unreadable.note(&file.path, &e);
+                                    }
+                                    if self.unreadable.map_or(true, Unreadable::is_inline) {
+                                        errors.push((e, None));
+                                    }
                                 }
                             }
+                        } else if file.is_directory() && self.opts.tree_counts && !pruned {
+                            beneath = file.to_dir().ok().map(|d| self.count_beneath(&d, file));
                         }
                     };
 
+                    // This comes after the directory has been read, so the
+                    // Git statuses have had as long as possible to load.
+                    let table_row = table
+                        .as_ref()
+                        .map(|t| t.row_for_file(file, self.show_xattr_hint(file)));
+
                     let egg = Egg {
                         table_row,
                         xattrs,
                         errors,
                         dir,
+                        descent,
+                        beneath,
                         file,
                     };
                     unsafe { std::ptr::write(file_eggs.lock().unwrap()[idx].as_mut_ptr(), egg) }
@@ -317,33 +571,112 @@ impl<'a> Render<'a> {
         let mut file_eggs = unsafe { std::mem::transmute::<_, Vec<Egg<'_>>>(file_eggs) };
         self.filter.sort_files(&mut file_eggs);
 
-        for (tree_params, egg) in depth.iterate_over(file_eggs.into_iter()) {
+        // The config files inside a directory get taken out, and listed in
+        // a row of their own after the rest of its files.
+        let mut collapsed = None;
+        if self.opts.collapse_configs && depth.0 > 0 {
+            let (configs, rest): (Vec<_>, Vec<_>) = file_eggs.into_iter().partition(|egg| {
+                !egg.file.is_directory()
+                    && FileType::get_file_type(egg.file) == Some(FileType::Config)
+            });
+            file_eggs = rest;
+            if !configs.is_empty() {
+                collapsed = Some(configs);
+            }
+        }
+
+        let entries = file_eggs
+            .into_iter()
+            .map(Entry::File)
+            .chain(collapsed.is_some().then_some(Entry::Configs))
+            .chain((!hidden.is_empty()).then_some(Entry::More))
+            .collect::<Vec<_>>();
+
+        // Entries above the minimum depth get removed later, so they
+        // shouldn’t make the columns any wider.
+        let too_shallow = depth.0 > 0
+            && self
+                .recurse
+                .map_or(false, |r| r.tree && r.is_too_shallow(depth.0));
+
+        let mut counts = Counts::default();
+        for (tree_params, entry) in depth.iterate_over(entries.into_iter()) {
+            // Nothing changes the rows before this one any more.
+            rows.stream();
+
+            let egg = match entry {
+                Entry::File(egg) => egg,
+                Entry::Configs => {
+                    let configs = collapsed.take().unwrap_or_default();
+                    counts.files += configs.len();
+                    if !too_shallow {
+                        for egg in &configs {
+                            self.tally(egg.file, false);
+                        }
+                    }
+                    rows.push(self.render_configs(&configs, tree_params));
+                    continue;
+                }
+                Entry::More => {
+                    for file in &hidden {
+                        counts.add_file(file);
+                    }
+                    rows.push(self.render_more(hidden.len(), tree_params));
+                    continue;
+                }
+            };
+
+            counts.add_file(egg.file);
+
             let mut files = Vec::new();
             let mut errors = egg.errors;
 
-            if let (Some(ref mut t), Some(row)) = (table.as_mut(), egg.table_row.as_ref()) {
-                t.add_widths(row);
+            // In a flat listing, the directories that are only there to be
+            // recursed into don’t get rows of their own.
+            let has_row = !self.is_flat() || self.filter.is_listed(egg.file);
+            if !too_shallow && has_row {
+                self.tally(egg.file, egg.dir.is_some());
+            }
+
+            if !too_shallow && has_row {
+                if let (Some(ref mut t), Some(row)) = (table.as_mut(), egg.table_row.as_ref()) {
+                    t.add_widths(row);
+                }
             }
 
-            let file_name = self
+            let mut file_name = self
                 .file_style
                 .for_file(egg.file, self.theme)
                 .with_link_paths()
                 .with_mount_details(self.opts.mounts)
+                .with_parent_path(self.is_flat())
                 .paint()
                 .promote();
 
+            if let Some(beneath) = egg.beneath {
+                self.add_counts(&mut file_name, beneath);
+                counts.add(beneath);
+            }
+
+            if egg.descent == Descent::Cycle {
+                file_name.append(TextCell::paint_str(self.theme.ui.punctuation, " [cycle]"));
+            }
+
+            let row_index = rows.len();
             let row = Row {
                 tree: tree_params,
                 cells: egg.table_row,
                 name: file_name,
             };
 
-            rows.push(row);
+            if has_row {
+                rows.push(row);
+            }
 
             if let Some(ref dir) = egg.dir {
                 for file_to_add in dir.files(
                     self.filter.dot_filter,
+                    self.filter.hidden_mode,
                     self.git,
                     self.git_ignoring,
                     egg.file.deref_links,
@@ -360,6 +693,27 @@ impl<'a> Render<'a> {
                 }
 
                 self.filter.filter_child_files(&mut files);
+                let ancestors = egg.descent.ancestors(ancestors);
+
+                // A followed link’s target is at the end of its row, so
+                // nothing can be added to it.
+                if self.opts.compact_tree
+                    && has_row
+                    && egg.xattrs.is_empty()
+                    && errors.is_empty()
+                    && !egg.file.is_link()
+                {
+                    if let Some((folded, beneath)) =
+                        self.add_compacted(pool, table, rows, row_index, &files, depth, &ancestors)
+                    {
+                        if self.opts.tree_counts {
+                            self.add_counts(&mut rows[row_index].name, beneath);
+                        }
+                        counts.dirs += folded;
+                        counts.add(beneath);
+                        continue;
+                    }
+                }
 
                 if !files.is_empty() {
                     for xattr in egg.xattrs {
@@ -374,7 +728,18 @@ impl<'a> Render<'a> {
                         ));
                     }
 
-                    self.add_files_to_table(pool, table, rows, &files, depth.deeper());
+                    let beneath = self.add_files_to_table(
+                        pool,
+                        table,
+                        rows,
+                        &files,
+                        depth.deeper(),
+                        &ancestors,
+                    );
+                    if self.opts.tree_counts && has_row {
+                        self.add_counts(&mut rows[row_index].name, beneath);
+                    }
+                    counts.add(beneath);
                     continue;
                 }
             }
@@ -394,6 +759,114 @@ impl<'a> Render<'a> {
                 rows.push(r);
             }
         }
+
+        counts
+    }
+
+    /// The entries in a directory of a tree to display, which with
+    /// `--tree-limit` are the first ones once they’ve been sorted, and the
+    /// ones that get left out. The arguments at the root are all displayed.
+    fn limit_entries<'f, 'dir>(
+        &self,
+        files: &'f [File<'dir>],
+        depth: TreeDepth,
+    ) -> (Vec<&'f File<'dir>>, Vec<&'f File<'dir>>) {
+        let mut files = files.iter().collect::<Vec<_>>();
+        match self.opts.tree_limit {
+            Some(limit) if depth.0 > 0 && files.len() > limit => {
+                self.filter.sort_files(&mut files);
+                let hidden = files.split_off(limit);
+                (files, hidden)
+            }
+            _ => (files, Vec::new()),
+        }
+    }
+
+    /// Follows a chain of directories that each have just one directory
+    /// inside them, adding each one’s name to the row of the first, then
+    /// adds the files inside the last one beneath it. Returns how many
+    /// directories got folded into the row, and how much is beneath the last
+    /// one, or `None` without adding anything if there’s no chain to fold.
+    #[allow(clippy::too_many_arguments)]
+    fn add_compacted<'dir>(
+        &self,
+        pool: &mut Pool,
+        table: &mut Option<Table<'a>>,
+        rows: &mut Rows<'_>,
+        row_index: usize,
+        files: &[File<'dir>],
+        depth: TreeDepth,
+        ancestors: &[DirIdentity],
+    ) -> Option<(usize, Counts)> {
+        let [child] = files else {
+            return None;
+        };
+
+        if !child.is_directory() || self.filter.is_pruned(child) {
+            return None;
+        }
+
+        let descent = self.recurse?.descent(child, ancestors);
+        if descent == Descent::Cycle {
+            return None;
+        }
+        let ancestors = descent.ancestors(ancestors);
+
+        // Directories that can’t be read, or that have files inside that
+        // can’t be, keep a row of their own to show the errors under.
+        let dir = child.to_dir().ok()?;
+        let mut children = dir
+            .files(
+                self.filter.dot_filter,
+                self.filter.hidden_mode,
+                self.git,
+                self.git_ignoring,
+                child.deref_links,
+                child.is_recursive_size(),
+            )
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+        self.filter.filter_child_files(&mut children);
+
+        // With --classify, the name being added to already ends in a slash.
+        let style = self.file_style.for_file(child, self.theme).style();
+        let name = &mut rows[row_index].name;
+        if let Classify::AddFileIndicators = self.file_style.classify {
+            name.append(TextCell::paint(style, child.name.to_string()));
+            name.push(Style::default().paint("/"), 1);
+        } else {
+            name.push(self.theme.ui.punctuation.paint("/"), 1);
+            name.append(TextCell::paint(style, child.name.to_string()));
+        }
+
+        let (folded, beneath) = match self
+            .add_compacted(pool, table, rows, row_index, &children, depth, &ancestors)
+        {
+            Some(chain) => chain,
+            None if children.is_empty() => (0, Counts::default()),
+            None => (
+                0,
+                self.add_files_to_table(pool, table, rows, &children, depth.deeper(), &ancestors),
+            ),
+        };
+
+        self.tally(child, true);
+        Some((folded + 1, beneath))
+    }
+
+    /// Counts an entry that got added to the tree, without its size if it
+    /// got descended into.
+    fn tally(&self, file: &File<'_>, descended: bool) {
+        let Some(tally) = self.tally else {
+            return;
+        };
+
+        let mut tally = tally.lock().unwrap_or_else(PoisonError::into_inner);
+        if descended {
+            tally.add_unsized(file, self.git);
+        } else {
+            tally.add(file, self.git);
+        }
     }
 
     pub fn render_header(&self, header: TableRow) -> Row {
@@ -423,6 +896,141 @@ impl<'a> Render<'a> {
         }
     }
 
+    /// Looks up the metadata of the files in a directory that’s going to be
+    /// listed, and their extended attributes if a table is going to show
+    /// them, so the main thread doesn’t have to when it gets to them.
+    fn resolve_ahead(&self, dir: &Dir, file: &File<'_>, table: bool) {
+        use crate::fs::feature::xattr;
+
+        dir.resolve_ahead();
+        let files = dir.files(
+            self.filter.dot_filter,
+            self.filter.hidden_mode,
+            self.git,
+            self.git_ignoring,
+            file.deref_links,
+            file.is_recursive_size(),
+        );
+
+        for file in files.flatten() {
+            if xattr::ENABLED && (table || self.opts.xattr) {
+                file.extended_attributes();
+            }
+        }
+    }
+
+    /// Counts the files and directories beneath a directory that’s too deep
+    /// in the tree to have its contents listed, as they would be listed.
+    fn count_beneath(&self, dir: &Dir, file: &File<'_>) -> Counts {
+        let mut files = dir
+            .files(
+                self.filter.dot_filter,
+                self.filter.hidden_mode,
+                self.git,
+                self.git_ignoring,
+                file.deref_links,
+                false,
+            )
+            .flatten()
+            .collect::<Vec<_>>();
+        self.filter.filter_child_files(&mut files);
+
+        let mut counts = Counts::default();
+        for child in &files {
+            counts.add_file(child);
+            if child.is_directory() && !child.is_all_all && !self.filter.is_pruned(child) {
+                if let Ok(d) = child.to_dir() {
+                    counts.add(self.count_beneath(&d, child));
+                }
+            }
+        }
+        counts
+    }
+
+    /// Adds the number of files and directories beneath a directory after
+    /// its name, leaving out empty directories.
+    fn add_counts(&self, name: &mut TextCell, counts: Counts) {
+        let plural = |count, noun| {
+            if count == 1 {
+                format!("1 {noun}")
+            } else {
+                format!("{count} {noun}s")
+            }
+        };
+
+        let text = match counts {
+            Counts { files: 0, dirs: 0 } => return,
+            Counts { files, dirs: 0 } => plural(files, "file"),
+            Counts { files, dirs } => format!("{}, {}", plural(files, "file"), plural(dirs, "dir")),
+        };
+
+        name.append(TextCell::paint(
+            self.theme.ui.punctuation,
+            format!(" ({text})"),
+        ));
+    }
+
+    /// Renders the row that the arguments of a tree go beneath when there’s
+    /// more than one of them and they’re all in a directory that was named,
+    /// so they’re drawn as the siblings they are beneath it.
+    fn render_root(&self) -> Option<Row> {
+        let tree = self.recurse.map_or(false, |r| r.tree);
+        if !tree || self.is_flat() || self.dir.is_some() || self.files.len() < 2 {
+            return None;
+        }
+
+        let root = shared_parent(self.files.iter().map(|file| &*file.path))?;
+
+        Some(Row {
+            cells: None,
+            name: TextCell::paint(
+                self.theme.ui.filekinds.directory,
+                root.display().to_string(),
+            ),
+            tree: TreeParams::new(TreeDepth::root(), false),
+        })
+    }
+
+    /// Renders the row that ends a directory with entries left out by
+    /// `--tree-limit`, saying how many there are.
+    fn render_more(&self, hidden: usize, tree: TreeParams) -> Row {
+        let numeric =
+            locale::Numeric::load_user_locale().unwrap_or_else(|_| locale::Numeric::english());
+        let text = format!("… and {} more", numeric.format_int(hidden));
+
+        Row {
+            cells: None,
+            name: TextCell::paint(self.theme.ui.punctuation, text),
+            tree,
+        }
+    }
+
+    /// Renders the row that the config files in a directory get collapsed
+    /// into, which says how many there are and what they’re called.
+    fn render_configs(&self, configs: &[Egg<'_>], tree: TreeParams) -> Row {
+        let names = configs
+            .iter()
+            .map(|egg| &*egg.file.name)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let count = configs.len();
+        let noun = if count == 1 {
+            "config file"
+        } else {
+            "config files"
+        };
+        let name = TextCell::paint(
+            self.theme.ui.punctuation,
+            format!("[{count} {noun}: {names}]"),
+        );
+        Row {
+            cells: None,
+            name,
+            tree,
+        }
+    }
+
     fn render_xattr(&self, xattr: &Attribute, tree: TreeParams) -> Row {
         let name = TextCell::paint(
             self.theme.ui.perms.attribute,
@@ -443,23 +1051,242 @@ impl<'a> Render<'a> {
         }
     }
 
-    pub fn iterate_with_table(&'a self, table: Table<'a>, rows: Vec<Row>) -> TableIter<'a> {
+    pub fn iterate_with_table(
+        &'a self,
+        table: Table<'a>,
+        rows: Vec<Row>,
+        style: TableStyle,
+    ) -> TableIter<'a> {
+        let decoration = Decoration::new(
+            style,
+            self.theme.ui.punctuation,
+            table.widths(),
+            Alignment::Left,
+        );
         TableIter {
-            tree_trunk: TreeTrunk::default(),
-            total_width: table.widths().total(),
+            start: decoration.start(),
+            separator: decoration.separator(),
             table,
             inner: rows.into_iter(),
-            tree_style: self.theme.ui.punctuation,
+            branches: self.branches(),
         }
     }
 
     pub fn iterate(&'a self, rows: Vec<Row>) -> Iter {
         Iter {
-            tree_trunk: TreeTrunk::default(),
             inner: rows.into_iter(),
+            branches: self.branches(),
+        }
+    }
+
+    fn branches(&self) -> Branches {
+        Branches {
+            tree_trunk: TreeTrunk::default(),
             tree_style: self.theme.ui.punctuation,
+            tree_chars: self.opts.tree_style,
+            flat: self.is_flat(),
+            wrap_width: self.console_width.filter(|_| self.wraps_names()),
         }
     }
+
+    /// Whether names that don’t fit get wrapped, which they can’t be when
+    /// there are lines after them to keep lined up.
+    fn wraps_names(&self) -> bool {
+        let lined_up = self.opts.table.as_ref().map_or(false, |table| {
+            self.opts.table_style != TableStyle::Plain
+                || table.align.iter().any(|(name, _)| *name == "name")
+        });
+        self.opts.wrap_names && !lined_up
+    }
+
+    /// Whether the tree is drawn without its branches, with each file’s
+    /// path instead.
+    fn is_flat(&self) -> bool {
+        self.recurse.map_or(false, |r| r.flat)
+    }
+}
+
+/// The directory that all of the given paths are directly in, if they’re
+/// all in the same one and it was written out as part of them, so the tree
+/// doesn’t get a root that the user didn’t name.
+fn shared_parent<'p>(mut paths: impl Iterator<Item = &'p Path>) -> Option<&'p Path> {
+    let parent = paths.next()?.parent()?;
+    if parent.as_os_str().is_empty() {
+        return None;
+    }
+
+    paths
+        .all(|path| path.parent() == Some(parent))
+        .then_some(parent)
+}
+
+/// The rows that have been added to the listing so far. They either get
+/// kept until every row is in, or get written out as they come, once the
+/// rows after them start being added.
+struct Rows<'w> {
+    /// The rows that haven’t been written out yet.
+    rows: Vec<Row>,
+
+    /// How many rows have been written out already.
+    written: usize,
+
+    /// Where to write the rows out to, when they get written as they come,
+    /// and the branches of the tree they’re in.
+    stream: Option<(&'w mut dyn Write, Branches)>,
+
+    /// The error from writing a row out, after which no more get written.
+    error: Option<io::Error>,
+
+    /// Whether the rows go beneath a root of their own, which moves each
+    /// row added after it down a level.
+    nested: bool,
+}
+
+impl<'w> Rows<'w> {
+    fn buffered() -> Self {
+        Self {
+            rows: Vec::new(),
+            written: 0,
+            stream: None,
+            error: None,
+            nested: false,
+        }
+    }
+
+    fn streamed(w: &'w mut dyn Write, branches: Branches) -> Self {
+        Self {
+            stream: Some((w, branches)),
+            ..Self::buffered()
+        }
+    }
+
+    fn push(&mut self, mut row: Row) {
+        if self.nested {
+            row.tree = row.tree.nested();
+        }
+        self.rows.push(row);
+    }
+
+    /// Adds the row that the rest of the rows go beneath.
+    fn push_root(&mut self, row: Row) {
+        self.rows.push(row);
+        self.nested = true;
+    }
+
+    /// The depth that the arguments are at.
+    fn top_depth(&self) -> usize {
+        usize::from(self.nested)
+    }
+
+    /// How many rows have been added, including the ones written out.
+    fn len(&self) -> usize {
+        self.written + self.rows.len()
+    }
+
+    /// Writes out the rows that haven’t been yet, if they’re being written
+    /// as they come. Nothing can change them after this.
+    fn stream(&mut self) {
+        let (Some((w, branches)), None) = (&mut self.stream, &self.error) else {
+            return;
+        };
+
+        self.written += self.rows.len();
+        for row in self.rows.drain(..) {
+            let mut cell = TextCell::default();
+            branches.draw(&mut cell, row.tree, row.name);
+            if let Err(e) = writeln!(w, "{}", cell.strings()) {
+                self.error = Some(e);
+                break;
+            }
+        }
+    }
+
+    /// Writes out the last of the rows, if they’re being written as they
+    /// come, and returns the ones that still need to be.
+    fn finish(mut self) -> io::Result<Vec<Row>> {
+        self.stream();
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.rows),
+        }
+    }
+}
+
+impl Index<usize> for Rows<'_> {
+    type Output = Row;
+
+    fn index(&self, index: usize) -> &Row {
+        &self.rows[index - self.written]
+    }
+}
+
+impl IndexMut<usize> for Rows<'_> {
+    fn index_mut(&mut self, index: usize) -> &mut Row {
+        &mut self.rows[index - self.written]
+    }
+}
+
+/// Draws the branches of a tree in front of each row’s name, keeping track
+/// of which branches carry on from the rows before it.
+struct Branches {
+    tree_trunk: TreeTrunk,
+    tree_style: Style,
+    tree_chars: TreeStyle,
+
+    /// Whether to leave the branches out.
+    flat: bool,
+
+    /// The width to wrap names that don’t fit at, if they’re wrapped.
+    wrap_width: Option<usize>,
+}
+
+impl Branches {
+    /// Adds a row’s branches, then its name, to the end of a cell. A name
+    /// that gets wrapped carries on onto the lines after it, with the
+    /// branches that go past it drawn in front of them.
+    fn draw(&mut self, cell: &mut TextCell, tree: TreeParams, name: TextCell) {
+        let start = *cell.width;
+        let mut continued = Vec::new();
+        if !self.flat {
+            let parts = self.tree_trunk.new_row(tree).to_vec();
+            for tree_part in parts {
+                self.draw_part(cell, tree_part);
+                continued.push(tree_part.continued());
+            }
+
+            // If any tree characters have been printed, then add an extra
+            // space, which makes the output look much better.
+            if !tree.is_at_root() {
+                cell.add_spaces(1);
+            }
+        }
+
+        let Some(width) = self.wrap_width else {
+            cell.append(name);
+            return;
+        };
+
+        let indent = *cell.width;
+        let mut prefix = TextCell::default();
+        prefix.add_spaces(start);
+        for tree_part in continued {
+            self.draw_part(&mut prefix, tree_part);
+        }
+        prefix.add_spaces(indent - *prefix.width);
+
+        let mut lines = name.wrap(width.saturating_sub(indent)).into_iter();
+        cell.append(lines.next().unwrap_or_default());
+        for line in lines {
+            cell.push(Style::default().paint("\n"), 0);
+            cell.append(prefix.clone());
+            cell.append(line);
+        }
+    }
+
+    fn draw_part(&self, cell: &mut TextCell, tree_part: TreePart) {
+        let art = tree_part.ascii_art(self.tree_chars);
+        cell.push(self.tree_style.paint(art), *DisplayWidth::from(art));
+    }
 }
 
 pub struct Row {
@@ -484,9 +1311,9 @@ pub struct TableIter<'a> {
     inner: VecIntoIter<Row>,
     table: Table<'a>,
 
-    total_width: usize,
-    tree_style:  Style,
-    tree_trunk:  TreeTrunk,
+    start:       TextCell,
+    separator:   TextCell,
+    branches:    Branches,
 }
 
 impl<'a> Iterator for TableIter<'a> {
@@ -494,33 +1321,19 @@ impl<'a> Iterator for TableIter<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next().map(|row| {
-            let mut cell = if let Some(cells) = row.cells {
-                self.table.render(cells)
-            } else {
-                let mut cell = TextCell::default();
-                cell.add_spaces(self.total_width);
-                cell
-            };
+            let cells = row.cells.unwrap_or_else(|| self.table.blank_row());
+            let mut cell = self
+                .table
+                .render(cells, self.start.clone(), &self.separator);
 
-            for tree_part in self.tree_trunk.new_row(row.tree) {
-                cell.push(self.tree_style.paint(tree_part.ascii_art()), 4);
-            }
-
-            // If any tree characters have been printed, then add an extra
-            // space, which makes the output look much better.
-            if !row.tree.is_at_root() {
-                cell.add_spaces(1);
-            }
-
-            cell.append(row.name);
+            self.branches.draw(&mut cell, row.tree, row.name);
             cell
         })
     }
 }
 
 pub struct Iter {
-    tree_trunk: TreeTrunk,
-    tree_style: Style,
+    branches: Branches,
     inner: VecIntoIter<Row>,
 }
 
@@ -530,18 +1343,7 @@ impl Iterator for Iter {
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next().map(|row| {
             let mut cell = TextCell::default();
-
-            for tree_part in self.tree_trunk.new_row(row.tree) {
-                cell.push(self.tree_style.paint(tree_part.ascii_art()), 4);
-            }
-
-            // If any tree characters have been printed, then add an extra
-            // space, which makes the output look much better.
-            if !row.tree.is_at_root() {
-                cell.add_spaces(1);
-            }
-
-            cell.append(row.name);
+            self.branches.draw(&mut cell, row.tree, row.name);
             cell
         })
     }
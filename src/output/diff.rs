@@ -0,0 +1,266 @@
+//! The diff view compares two directories by drawing one tree of everything
+//! that’s in either of them, marking the entries that are only in one of
+//! them, and the files that differ in size or modification time.
+//!
+//! Files are matched up by name, and the markers say what changed going
+//! from the first directory to the second, like `diff` does: `-` for an
+//! entry that’s only in the first, `+` for one that’s only in the second,
+//! and `~` for one that’s in both but differs. A directory that’s only in
+//! one of them is marked without listing what’s in it.
+//!
+//! With `--only-changes`, the entries in each directory that are the same in
+//! both, along with everything beneath them, get collapsed into one row at
+//! the end of the directory saying how many there are.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use ansiterm::{ANSIString, ANSIStrings, Style};
+
+use crate::fs::dir_action::RecurseOptions;
+use crate::fs::feature::git::GitCache;
+use crate::fs::filter::{FileFilter, GitIgnore};
+use crate::fs::{Dir, File};
+use crate::output::cell::TextCellContents;
+use crate::output::file_name::Options as FileStyle;
+use crate::output::tree::{TreeDepth, TreeParams, TreeStyle, TreeTrunk};
+use crate::output::unreadable::Unreadable;
+use crate::theme::Theme;
+
+/// How an entry in one directory compares to the one with the same name in
+/// the other.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Change {
+    /// It’s the same in both, as far as can be told.
+    Same,
+
+    /// It’s only in the first directory.
+    Removed,
+
+    /// It’s only in the second directory.
+    Added,
+
+    /// It’s in both, but is a different kind of file, has a different size
+    /// or modification time, or is a directory with a change beneath it.
+    Changed,
+}
+
+impl Change {
+    /// Compares two entries with the same name. Directories that are in
+    /// both count as the same, as what’s in them gets compared instead.
+    fn between(a: &File<'_>, b: &File<'_>) -> Self {
+        if a.is_directory() != b.is_directory() || a.is_link() != b.is_link() {
+            Self::Changed
+        } else if a.is_directory() {
+            Self::Same
+        } else if a.length() != b.length() || a.modified_time() != b.modified_time() {
+            Self::Changed
+        } else {
+            Self::Same
+        }
+    }
+
+    fn marker(self, theme: &Theme) -> ANSIString<'static> {
+        match self {
+            Self::Same => Style::default().paint(" "),
+            Self::Removed => theme.ui.git.deleted.paint("-"),
+            Self::Added => theme.ui.git.new.paint("+"),
+            Self::Changed => theme.ui.git.modified.paint("~"),
+        }
+    }
+}
+
+/// The entries with the same name in each directory.
+#[allow(clippy::large_enum_variant)]
+enum Pair<'dir> {
+    Removed(File<'dir>),
+    Added(File<'dir>),
+    Both(File<'dir>, File<'dir>),
+}
+
+impl<'dir> AsRef<File<'dir>> for Pair<'dir> {
+    /// The entry that gets displayed and sorted by, which is the one in the
+    /// second directory when there’s one in both.
+    fn as_ref(&self) -> &File<'dir> {
+        match self {
+            Self::Removed(file) | Self::Added(file) | Self::Both(_, file) => file,
+        }
+    }
+}
+
+struct Row {
+    change: Change,
+    tree: TreeParams,
+    name: TextCellContents,
+}
+
+pub struct Render<'a> {
+    /// The directories to compare, in the order they were given.
+    pub dirs: (&'a Dir, &'a Dir),
+    pub theme: &'a Theme,
+    pub file_style: &'a FileStyle,
+    pub filter: &'a FileFilter,
+    pub recurse: RecurseOptions,
+    pub tree_style: TreeStyle,
+    pub git: Option<&'a GitCache>,
+    pub deref_links: bool,
+    pub unreadable: &'a Unreadable,
+
+    /// Whether the entries that are the same in both get collapsed.
+    pub only_changes: bool,
+}
+
+impl<'a> Render<'a> {
+    pub fn render<W: Write>(self, w: &mut W) -> io::Result<()> {
+        let (a, b) = self.dirs;
+        let ui = &self.theme.ui;
+        let name = vec![
+            ui.filekinds.directory.paint(a.path.display().to_string()),
+            ui.punctuation.paint(" → "),
+            ui.filekinds.directory.paint(b.path.display().to_string()),
+        ]
+        .into();
+
+        let mut rows = vec![Row {
+            change: Change::Same,
+            tree: TreeParams::new(TreeDepth::root(), false),
+            name,
+        }];
+        rows[0].change = self.add_dirs(&mut rows, a, b, TreeDepth::root())?.0;
+
+        let mut trunk = TreeTrunk::default();
+        for row in rows {
+            let mut line = vec![row.change.marker(self.theme), Style::default().paint(" ")];
+            for part in trunk.new_row(row.tree) {
+                line.push(ui.punctuation.paint(part.ascii_art(self.tree_style)));
+            }
+            if !row.tree.is_at_root() {
+                line.push(Style::default().paint(" "));
+            }
+            line.extend(row.name.to_vec());
+            writeln!(w, "{}", ANSIStrings(&line))?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds a row for everything in either directory, descending into the
+    /// directories that are in both, and returns whether anything differs,
+    /// along with how many entries there are beneath them.
+    fn add_dirs(
+        &self,
+        rows: &mut Vec<Row>,
+        a: &Dir,
+        b: &Dir,
+        depth: TreeDepth,
+    ) -> io::Result<(Change, usize)> {
+        let mut theirs = self
+            .read_children(b)?
+            .into_iter()
+            .map(|file| (file.name.clone(), file))
+            .collect::<HashMap<_, _>>();
+
+        let mut pairs = Vec::new();
+        for file in self.read_children(a)? {
+            pairs.push(match theirs.remove(&file.name) {
+                Some(other) => Pair::Both(file, other),
+                None => Pair::Removed(file),
+            });
+        }
+        pairs.extend(theirs.into_values().map(Pair::Added));
+        self.filter.sort_files(&mut pairs);
+
+        let depth = depth.deeper();
+        let count = pairs.len();
+        let mut change = Change::Same;
+        let start = rows.len();
+        let mut entries = 0;
+        let mut unchanged = 0;
+        for (i, pair) in pairs.into_iter().enumerate() {
+            let index = rows.len();
+            rows.push(Row {
+                change: match &pair {
+                    Pair::Removed(_) => Change::Removed,
+                    Pair::Added(_) => Change::Added,
+                    Pair::Both(a, b) => Change::between(a, b),
+                },
+                tree: TreeParams::new(depth, i + 1 == count),
+                name: self.file_style.for_file(pair.as_ref(), self.theme).paint(),
+            });
+
+            let mut beneath = 0;
+            if let Pair::Both(a, b) = &pair {
+                let descend = a.is_directory() && b.is_directory() && !self.filter.is_pruned(b);
+                if descend && !self.recurse.is_too_deep(depth.0) {
+                    match (a.to_dir(), b.to_dir()) {
+                        (Ok(a), Ok(b)) => {
+                            (rows[index].change, beneath) = self.add_dirs(rows, &a, &b, depth)?;
+                        }
+                        (Err(e), _) => self.unreadable.add(&a.path, &e)?,
+                        (_, Err(e)) => self.unreadable.add(&b.path, &e)?,
+                    }
+                }
+            }
+            entries += 1 + beneath;
+
+            if rows[index].change != Change::Same {
+                change = Change::Changed;
+            } else if self.only_changes {
+                rows.truncate(index);
+                unchanged += 1 + beneath;
+            }
+        }
+
+        if unchanged > 0 {
+            self.add_unchanged(rows, start, unchanged, depth);
+        }
+
+        Ok((change, entries))
+    }
+
+    /// Adds the row that the entries that are the same in both directories
+    /// get collapsed into, which goes after the ones that have changed, so
+    /// it’s the last one in the directory whose rows start at the index.
+    fn add_unchanged(&self, rows: &mut Vec<Row>, start: usize, unchanged: usize, depth: TreeDepth) {
+        if let Some(row) = rows[start..]
+            .iter_mut()
+            .rev()
+            .find(|row| row.tree.depth().0 == depth.0)
+        {
+            row.tree = TreeParams::new(depth, false);
+        }
+
+        let noun = if unchanged == 1 { "entry" } else { "entries" };
+        let numeric =
+            locale::Numeric::load_user_locale().unwrap_or_else(|_| locale::Numeric::english());
+        let text = format!("{} unchanged {noun}", numeric.format_int(unchanged));
+        rows.push(Row {
+            change: Change::Same,
+            tree: TreeParams::new(depth, true),
+            name: vec![self.theme.ui.punctuation.paint(text)].into(),
+        });
+    }
+
+    /// Reads the files in a directory that pass the filter, printing any
+    /// errors encountered along the way.
+    fn read_children<'dir>(&self, dir: &'dir Dir) -> io::Result<Vec<File<'dir>>> {
+        let mut children = Vec::new();
+        let git_ignore = self.filter.git_ignore == GitIgnore::CheckAndIgnore;
+        for file in dir.files(
+            self.filter.dot_filter,
+            self.filter.hidden_mode,
+            self.git,
+            git_ignore,
+            self.deref_links,
+            false,
+        ) {
+            match file {
+                Ok(file) => children.push(file),
+                Err((path, e)) => writeln!(io::stderr(), "[{}: {}]", path.display(), e)?,
+            }
+        }
+
+        self.filter.filter_child_files(&mut children);
+        Ok(children)
+    }
+}
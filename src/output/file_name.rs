@@ -4,10 +4,13 @@ use std::path::Path;
 use ansiterm::{ANSIString, Style};
 
 use crate::fs::mounts::MountedFs;
+#[cfg(target_os = "linux")]
+use crate::fs::sockets;
 use crate::fs::{File, FileTarget};
+use crate::info::suspicious;
 use crate::output::cell::TextCellContents;
 use crate::output::escape;
-use crate::output::icons::{icon_for_file, iconify_style};
+use crate::output::icons::{icon_for_file, iconify_style, IconSet};
 use crate::output::render::FiletypeColours;
 
 /// Basically a file name factory.
@@ -19,6 +22,12 @@ pub struct Options {
     /// Whether to prepend icon characters before file names.
     pub show_icons: ShowIcons,
 
+    /// How to pick the style to paint the icons in.
+    pub icon_colour: IconColour,
+
+    /// Which glyphs to use for the icons.
+    pub icon_set: IconSet,
+
     /// How to display file names with spaces (with or without quotes).
     pub quote_style: QuoteStyle,
 
@@ -48,7 +57,8 @@ impl Options {
                 None
             },
             mount_style: MountStyle::JustDirectoryNames,
-            mounted_fs: file.mount_point_info(),
+            mounted_fs: None,
+            parent_path: false,
         }
     }
 }
@@ -110,6 +120,26 @@ pub enum ShowIcons {
     Never,
 }
 
+/// Which style to paint the icons next to file names in.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum IconColour {
+    /// Use the colour of the file name, without any attributes such as bold
+    /// or underline.
+    File,
+
+    /// Use exactly the same style as the file name.
+    Name,
+
+    /// Use the same style for every icon, which is set in the theme.
+    Fixed,
+}
+
+impl Default for IconColour {
+    fn default() -> Self {
+        Self::File
+    }
+}
+
 /// Whether to embed hyperlinks.
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum EmbedHyperlinks {
@@ -150,6 +180,10 @@ pub struct FileName<'a, 'dir, C> {
 
     /// How to handle displaying a mounted filesystem.
     mount_style: MountStyle,
+
+    /// Whether to display the path to a file that’s been found in a
+    /// directory, as well as to the files given as arguments.
+    parent_path: bool,
 }
 
 impl<'a, 'dir, C> FileName<'a, 'dir, C> {
@@ -161,13 +195,23 @@ impl<'a, 'dir, C> FileName<'a, 'dir, C> {
     }
 
     /// Sets the flag on this file name to display mounted filesystem
-    ///details.
+    /// details. Mount points only get looked up, and given their own colour
+    /// and icon, when they are.
     pub fn with_mount_details(mut self, enable: bool) -> Self {
-        self.mount_style = if enable {
-            MountStyle::MountInfo
+        if enable {
+            self.mount_style = MountStyle::MountInfo;
+            self.mounted_fs = self.file.mount_point_info();
         } else {
-            MountStyle::JustDirectoryNames
-        };
+            self.mount_style = MountStyle::JustDirectoryNames;
+            self.mounted_fs = None;
+        }
+        self
+    }
+
+    /// Sets the flag on this file name to display the path it was reached
+    /// by, without a `./` in front of it, rather than just its name.
+    pub fn with_parent_path(mut self, enable: bool) -> Self {
+        self.parent_path = enable;
         self
     }
 }
@@ -189,16 +233,33 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
         };
 
         if let Some(spaces_count) = spaces_count_opt {
-            let style = iconify_style(self.style());
-            let file_icon = icon_for_file(self.file).to_string();
+            let style = self.colours.icon_for_file(self.file).unwrap_or_else(|| {
+                match self.options.icon_colour {
+                    IconColour::File => iconify_style(self.style()),
+                    IconColour::Name => self.style(),
+                    IconColour::Fixed => self.colours.icon(),
+                }
+            });
+            let file_icon = self
+                .colours
+                .icon_glyph_for_file(self.file)
+                .unwrap_or_else(|| {
+                    let mount_point = self.mounted_fs.is_some();
+                    icon_for_file(self.file, self.options.icon_set, mount_point).to_string()
+                });
             bits.push(style.paint(file_icon));
-            bits.push(style.paint(" ".repeat(spaces_count as usize)));
+            bits.push(iconify_style(style).paint(" ".repeat(spaces_count as usize)));
         }
 
         if self.file.parent_dir.is_none() {
             if let Some(parent) = self.file.path.parent() {
                 self.add_parent_bits(&mut bits, parent);
             }
+        } else if self.parent_path {
+            if let Some(parent) = self.file.path.parent() {
+                let parent = parent.strip_prefix(".").unwrap_or(parent);
+                self.add_parent_bits(&mut bits, parent);
+            }
         }
 
         if !self.file.name.is_empty() {
@@ -229,6 +290,8 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
                             classify: Classify::JustFilenames,
                             quote_style: QuoteStyle::QuoteSpaces,
                             show_icons: ShowIcons::Never,
+                            icon_colour: IconColour::File,
+                            icon_set: IconSet::Nerd3,
                             embed_hyperlinks: EmbedHyperlinks::Off,
                             is_a_tty: self.options.is_a_tty,
                         };
@@ -241,6 +304,7 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
                             options: target_options,
                             mounted_fs: None,
                             mount_style: MountStyle::JustDirectoryNames,
+                            parent_path: false,
                         };
 
                         for bit in target_name.escaped_file_name() {
@@ -269,6 +333,32 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
                     );
                 }
 
+                FileTarget::Virtual(target) => {
+                    bits.push(Style::default().paint(" "));
+                    bits.push(self.colours.normal_arrow().paint("->"));
+                    bits.push(Style::default().paint(" "));
+
+                    escape(
+                        target.display().to_string(),
+                        &mut bits,
+                        self.colours.special(),
+                        self.colours.control_char(),
+                        self.options.quote_style,
+                    );
+
+                    // Sockets get what they’re connected to, as with lsof
+                    #[cfg(target_os = "linux")]
+                    if let Some(socket) = target
+                        .to_str()
+                        .and_then(sockets::socket_inode)
+                        .and_then(sockets::describe)
+                    {
+                        bits.push(Style::default().paint(" ["));
+                        bits.push(Style::default().paint(socket));
+                        bits.push(Style::default().paint("]"));
+                    }
+                }
+
                 FileTarget::Err(_) => {
                     // Do nothing — the error gets displayed on the next line
                 }
@@ -393,7 +483,7 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
         }
 
         escape(
-            self.file.name.clone(),
+            self.file.name.to_string(),
             &mut bits,
             file_style,
             self.colours.control_char(),
@@ -412,8 +502,21 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
     /// Figures out which colour to paint the filename part of the output,
     /// depending on which “type” of file it appears to be — either from the
     /// class on the filesystem or from its name. (Or the broken link colour,
-    /// if there’s nowhere else for that fact to be shown.)
+    /// if there’s nowhere else for that fact to be shown.) Names that look
+    /// like they’re trying to deceive get a warning painted over them.
     pub fn style(&self) -> Style {
+        let style = self.kind_style();
+
+        if suspicious::is_suspicious(&self.file.name) {
+            self.colours.suspicious_filename(style)
+        } else {
+            style
+        }
+    }
+
+    /// The colour of the filename before any warnings get painted over it,
+    /// which depends on the kind of file it is.
+    fn kind_style(&self) -> Style {
         if let LinkStyle::JustFilenames = self.link_style {
             if let Some(ref target) = self.target {
                 if target.is_broken() {
@@ -424,8 +527,8 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
 
         #[rustfmt::skip]
         return match self.file {
-            f if f.is_mount_point()      => self.colours.mount_point(),
-            f if f.is_directory()        => self.colours.directory(),
+            _ if self.mounted_fs.is_some() => self.colours.mount_point(),
+            f if f.is_directory()        => self.colours.colour_directory(f),
             #[cfg(unix)]
             f if f.is_executable_file()  => self.colours.executable_file(),
             f if f.is_link()             => self.colours.symlink(),
@@ -472,11 +575,30 @@ pub trait Colours: FiletypeColours {
     /// when the filename is being displayed as a broken link target.
     fn broken_control_char(&self) -> Style;
 
+    /// The style to paint the filename of a file whose name looks like it’s
+    /// trying to deceive, given the style it would otherwise be painted in.
+    fn suspicious_filename(&self, style: Style) -> Style;
+
     /// The style to paint a file that has its executable bit set.
     fn executable_file(&self) -> Style;
 
     /// The style to paint a directory that has a filesystem mounted on it.
     fn mount_point(&self) -> Style;
 
+    /// The style to paint every icon in, when they don’t follow the colours
+    /// of their file names.
+    fn icon(&self) -> Style;
+
+    /// The style to paint the icon of the given file in, if the theme gives
+    /// it one of its own.
+    fn icon_for_file(&self, file: &File<'_>) -> Option<Style>;
+
+    /// The icon to show next to the given file in place of the built-in
+    /// one, if the theme gives it one.
+    fn icon_glyph_for_file(&self, file: &File<'_>) -> Option<String>;
+
     fn colour_file(&self, file: &File<'_>) -> Style;
+
+    /// The style to paint a directory, which can depend on its name.
+    fn colour_directory(&self, file: &File<'_>) -> Style;
 }
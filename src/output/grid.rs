@@ -10,9 +10,15 @@ use crate::theme::Theme;
 
 use super::file_name::QuoteStyle;
 
+/// The number of spaces between each column of the grid.
+const COLUMN_SPACING: usize = 2;
+
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub struct Options {
     pub across: bool,
+
+    /// How many rows to aim for, rather than as few as the files fit into.
+    pub rows: Option<usize>,
 }
 
 impl Options {
@@ -38,10 +44,11 @@ impl<'a> Render<'a> {
     pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
         let mut grid = tg::Grid::new(tg::GridOptions {
             direction: self.opts.direction(),
-            filling: tg::Filling::Spaces(2),
+            filling: tg::Filling::Spaces(COLUMN_SPACING),
         });
 
         grid.reserve(self.files.len());
+        let mut widths = Vec::with_capacity(self.files.len());
 
         self.filter.sort_files(&mut self.files);
         for file in &self.files {
@@ -87,6 +94,7 @@ impl<'a> Render<'a> {
                 (EmbedHyperlinks::Off, _) => *contents.width(),
             };
 
+            widths.push(width);
             grid.add(tg::Cell {
                 contents: contents.strings().to_string(),
                 // with hyperlink escape sequences,
@@ -95,8 +103,33 @@ impl<'a> Render<'a> {
             });
         }
 
-        if let Some(display) = grid.fit_into_width(self.console_width) {
-            write!(w, "{display}")
+        // The grid leaves the last column of the terminal free, so it
+        // doesn’t wrap around on terminals that wrap as soon as it’s filled.
+        let count = widths.len();
+        let lines = fewest_lines(
+            &widths,
+            self.console_width,
+            self.opts.across,
+            self.opts.rows.unwrap_or(1),
+        );
+
+        if count == 0 {
+            Ok(())
+        } else if let Some(lines) = lines {
+            // The grid has to be told how many columns to use, and works out
+            // the number of lines from that, so going down it gets padded out
+            // to fill each column it uses.
+            let columns = divide_rounding_up(count, lines);
+            if !self.opts.across {
+                for _ in count..columns * lines {
+                    grid.add(tg::Cell {
+                        contents: String::new(),
+                        width: 0,
+                    });
+                }
+            }
+
+            write!(w, "{}", grid.fit_into_columns(columns))
         } else {
             // File names too long for a grid - drop down to just listing them!
             // This isn’t *quite* the same as the lines view, which also
@@ -110,3 +143,211 @@ impl<'a> Render<'a> {
         }
     }
 }
+
+/// The most columns it’s worth trying to split cells with the given widths
+/// into, as every column has to be at least as wide as the narrowest cell
+/// plus the spacing between the columns.
+pub fn column_limit(widths: &[usize], console_width: usize, spacing: usize) -> usize {
+    let narrowest = widths.iter().copied().min().unwrap_or(1).max(1);
+    widths
+        .len()
+        .min((console_width + spacing) / (narrowest + spacing))
+        .max(1)
+}
+
+/// The most columns, up to the given limit, that a grid fits into, or
+/// `None` if it doesn’t fit into one.
+///
+/// This tries each number of columns in turn, and stops at the first one
+/// that doesn’t fit. The widest cells can end up in the same column with
+/// more columns than that, so a grid that doesn’t fit into some number of
+/// columns can still fit into more, but that isn’t the layout to use, and
+/// it means the columns can’t be binary searched for.
+pub fn most_columns(max_columns: usize, mut fits: impl FnMut(usize) -> bool) -> Option<usize> {
+    if !fits(1) {
+        return None;
+    }
+
+    let most = (2..=max_columns)
+        .take_while(|&columns| fits(columns))
+        .last();
+    Some(most.unwrap_or(1))
+}
+
+/// The fewest lines, but no fewer than `min_lines`, that a grid of cells
+/// with the given widths fits into, or `None` if it doesn’t fit at all.
+///
+/// This works it out the same way as `term_grid`, starting from the most
+/// lines the grid could need and going down until a number doesn’t fit,
+/// except that it only needs the widths rather than a laid out grid for
+/// each try. As with the columns, fewer lines than that can still fit.
+fn fewest_lines(
+    widths: &[usize],
+    console_width: usize,
+    across: bool,
+    min_lines: usize,
+) -> Option<usize> {
+    let count = widths.len();
+    if widths.iter().any(|width| *width > console_width) {
+        return None;
+    }
+
+    let min_lines = min_lines.clamp(1, count.max(1));
+    let max_lines = most_lines(widths, console_width, COLUMN_SPACING).max(min_lines);
+    if max_lines == 1 {
+        return Some(1);
+    }
+
+    let mut fewest = None;
+    for lines in (min_lines..=max_lines).rev() {
+        let columns = divide_rounding_up(count, lines);
+        if COLUMN_SPACING * (columns - 1) > console_width {
+            continue;
+        }
+
+        if grid_width(widths, lines, columns, across, COLUMN_SPACING) < console_width {
+            fewest = Some(lines);
+        } else {
+            break;
+        }
+    }
+
+    fewest
+}
+
+/// The most lines that a grid of cells with the given widths could need,
+/// which is when the widest cells all go on the same line.
+fn most_lines(widths: &[usize], console_width: usize, spacing: usize) -> usize {
+    let mut widths = widths.to_vec();
+    widths.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut total = 0;
+    for (i, width) in widths.iter().enumerate() {
+        if width + total <= console_width {
+            total += width + spacing;
+        } else {
+            return divide_rounding_up(widths.len(), i.max(1));
+        }
+    }
+
+    1
+}
+
+/// How wide a grid of cells with the given widths gets with the given
+/// number of lines and columns, the same way `term_grid` lays them out,
+/// without having to lay out the grid.
+pub fn grid_width(
+    widths: &[usize],
+    lines: usize,
+    columns: usize,
+    across: bool,
+    spacing: usize,
+) -> usize {
+    let mut column_widths = vec![0; columns];
+    for (i, width) in widths.iter().enumerate() {
+        let column = if across { i % columns } else { i / lines };
+        column_widths[column] = column_widths[column].max(*width);
+    }
+
+    column_widths.iter().sum::<usize>() + spacing * columns.saturating_sub(1)
+}
+
+pub fn divide_rounding_up(a: usize, b: usize) -> usize {
+    let mut result = a / b;
+
+    if a % b != 0 {
+        result += 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn widths() {
+        let widths = [3, 1, 4, 1, 5];
+        assert_eq!(grid_width(&widths, 5, 1, false, 2), 5);
+        assert_eq!(grid_width(&widths, 3, 2, false, 2), 4 + 2 + 5);
+        assert_eq!(grid_width(&widths, 4, 2, false, 2), 4 + 2 + 5);
+        assert_eq!(grid_width(&widths, 3, 2, true, 2), 5 + 2 + 1);
+        assert_eq!(grid_width(&widths, 1, 5, true, 2), 14 + 8);
+    }
+
+    #[test]
+    fn columns() {
+        assert_eq!(most_columns(10, |columns| columns <= 7), Some(7));
+        assert_eq!(most_columns(10, |_| true), Some(10));
+        assert_eq!(most_columns(10, |_| false), None);
+        assert_eq!(most_columns(0, |_| true), Some(1));
+        assert_eq!(most_columns(10, |columns| columns != 4), Some(3));
+    }
+
+    #[test]
+    fn columns_with_a_long_name() {
+        // This fits into five columns, but not four, so the grid gets three.
+        let widths = [2, 1, 1, 14, 2, 11, 2, 2, 3, 1, 1, 2, 2, 3];
+        let fits = |columns| {
+            let lines = divide_rounding_up(widths.len(), columns);
+            grid_width(&widths, lines, columns, false, 4) <= 40
+        };
+        assert!(fits(5));
+        assert_eq!(most_columns(widths.len(), fits), Some(3));
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(fewest_lines(&[4; 10], 20, false, 1), Some(4));
+        assert_eq!(fewest_lines(&[4; 10], 20, true, 1), Some(4));
+        assert_eq!(fewest_lines(&[4; 10], 20, false, 5), Some(5));
+        assert_eq!(fewest_lines(&[4; 10], 80, false, 1), Some(1));
+        assert_eq!(fewest_lines(&[4; 10], 3, false, 1), None);
+        assert_eq!(fewest_lines(&[], 80, false, 1), Some(1));
+    }
+
+    #[test]
+    fn lines_with_a_long_name() {
+        // A grid of nine lines would fit, but `term_grid` gives up on the
+        // six it starts from, so the names get listed one per line.
+        let widths = [2, 3, 2, 3, 2, 3, 2, 2, 2, 3, 2, 2, 10, 3, 3, 2, 3, 2];
+        assert!(grid_width(&widths, 9, 2, false, COLUMN_SPACING) < 20);
+        assert_eq!(fewest_lines(&widths, 20, false, 1), None);
+    }
+
+    #[test]
+    fn lines_like_term_grid() {
+        for (count, long, width) in [(956, 480, 70), (200, 10, 12)] {
+            let mut widths = vec![3; count];
+            widths[long] = width;
+            lines_like_term_grid_with(&widths);
+        }
+    }
+
+    fn lines_like_term_grid_with(widths: &[usize]) {
+        for across in [false, true] {
+            let mut grid = tg::Grid::new(tg::GridOptions {
+                direction: if across {
+                    tg::Direction::LeftToRight
+                } else {
+                    tg::Direction::TopToBottom
+                },
+                filling: tg::Filling::Spaces(COLUMN_SPACING),
+            });
+            for width in widths {
+                grid.add(tg::Cell {
+                    contents: "x".repeat(*width),
+                    width: *width,
+                });
+            }
+
+            let expected = grid.fit_into_width(80).map(|display| display.to_string());
+            let lines = fewest_lines(widths, 80, across, 1);
+            assert_eq!(
+                lines.map(|lines| lines.min(widths.len())),
+                expected.map(|display| display.lines().count())
+            );
+        }
+    }
+}
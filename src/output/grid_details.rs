@@ -14,13 +14,22 @@ use crate::output::details::{
 };
 use crate::output::file_name::Options as FileStyle;
 use crate::output::file_name::{EmbedHyperlinks, ShowIcons};
-use crate::output::grid::Options as GridOptions;
-use crate::output::table::{Options as TableOptions, Row as TableRow, Table};
+use crate::output::grid::{
+    column_limit, divide_rounding_up, grid_width, most_columns, Options as GridOptions,
+};
+use crate::output::table::{Options as TableOptions, Row as TableRow, Table, TableWidths};
 use crate::output::tree::{TreeDepth, TreeParams};
+use crate::output::TableStyle;
 use crate::theme::Theme;
 
 use super::file_name::QuoteStyle;
 
+/// The number of spaces between each column of the grid.
+const COLUMN_SPACING: usize = 4;
+
+/// The width of the “Name” header at the top of each column.
+const HEADER_NAME_WIDTH: usize = 4;
+
 #[derive(PartialEq, Eq, Debug)]
 pub struct Options {
     pub grid: GridOptions,
@@ -87,6 +96,9 @@ pub struct Render<'a> {
     pub git: Option<&'a GitCache>,
 
     pub console_width: usize,
+
+    /// How many threads to look up files’ details with.
+    pub threads: u32,
 }
 
 impl<'a> Render<'a> {
@@ -108,6 +120,10 @@ impl<'a> Render<'a> {
             filter:        self.filter,
             git_ignoring:  self.git_ignoring,
             git:           self.git,
+            unreadable:    None,
+            tally:         None,
+            console_width: None,
+            threads:       self.threads,
         };
     }
 
@@ -127,6 +143,10 @@ impl<'a> Render<'a> {
             filter:        self.filter,
             git_ignoring:  self.git_ignoring,
             git:           self.git,
+            unreadable:    None,
+            tally:         None,
+            console_width: Some(self.console_width),
+            threads:       self.threads,
         };
     }
 
@@ -194,50 +214,114 @@ impl<'a> Render<'a> {
             })
             .collect::<Vec<_>>();
 
-        let mut last_working_grid = self.make_grid(1, options, &file_names, rows.clone(), &drender);
-
         if file_names.len() == 1 {
-            return Some((last_working_grid, 1));
+            let grid = self.make_grid(1, options, &file_names, rows, &drender);
+            return Some((grid, 1));
         }
 
-        // If we can’t fit everything in a grid 100 columns wide, then
-        // something has gone seriously awry
-        for column_count in 2..100 {
-            let grid = self.make_grid(column_count, options, &file_names, rows.clone(), &drender);
-
-            let the_grid_fits = {
-                let d = grid.fit_into_columns(column_count);
-                d.width() <= self.console_width
-            };
-
-            if the_grid_fits {
-                last_working_grid = grid;
+        // Only the widths of the cells are needed to tell whether a number
+        // of columns fits, so the grid only gets made once it’s been found.
+        let header = self.details.header.then(|| first_table.header_row());
+        let name_widths = file_names
+            .iter()
+            .map(|cell| *cell.width)
+            .collect::<Vec<_>>();
+        let mut max_column_count =
+            column_limit(&name_widths, self.console_width, COLUMN_SPACING).max(2);
+        if let Some(rows) = self.grid.rows {
+            max_column_count = max_column_count.min(divide_rounding_up(name_widths.len(), rows));
+        }
+        let column_count = most_columns(max_column_count, |column_count| {
+            let widths = self.cell_widths(
+                column_count,
+                &name_widths,
+                first_table.widths().len(),
+                &rows,
+                header.as_ref(),
+            );
+            let lines = divide_rounding_up(widths.len(), column_count);
+            grid_width(
+                &widths,
+                lines,
+                column_count,
+                self.grid.across,
+                COLUMN_SPACING,
+            ) <= self.console_width
+        })
+        .unwrap_or(1);
+
+        let grid = self.make_grid(column_count, options, &file_names, rows, &drender);
+
+        // If we’ve figured out how many columns can fit in the user’s terminal,
+        // and it turns out there aren’t enough rows to make it worthwhile
+        // (according to EZA_GRID_ROWS), then just resort to the lines view.
+        if let RowThreshold::MinimumRows(thresh) = self.row_threshold {
+            if grid.fit_into_columns(column_count).row_count() < thresh {
+                return None;
             }
+        }
 
-            if !the_grid_fits || column_count == file_names.len() {
-                let last_column_count = if the_grid_fits {
-                    column_count
-                } else {
-                    column_count - 1
-                };
-                // If we’ve figured out how many columns can fit in the user’s terminal,
-                // and it turns out there aren’t enough rows to make it worthwhile
-                // (according to EZA_GRID_ROWS), then just resort to the lines view.
-                if let RowThreshold::MinimumRows(thresh) = self.row_threshold {
-                    if last_working_grid
-                        .fit_into_columns(last_column_count)
-                        .row_count()
-                        < thresh
-                    {
-                        return None;
+        Some((grid, column_count))
+    }
+
+    /// The widths of the cells that `make_grid` would put in a grid with the
+    /// given number of columns, in the same order, without rendering any of
+    /// them: each one is as wide as the cells of its column’s table, with
+    /// the separators after them, plus the file name.
+    fn cell_widths(
+        &self,
+        column_count: usize,
+        name_widths: &[usize],
+        table_columns: usize,
+        rows: &[TableRow],
+        header: Option<&TableRow>,
+    ) -> Vec<usize> {
+        let mut tables = (0..column_count)
+            .map(|_| {
+                let mut widths = TableWidths::zero(table_columns);
+                match header {
+                    Some(header) => {
+                        widths.add_widths(header);
+                        (widths, vec![HEADER_NAME_WIDTH])
                     }
+                    None => (widths, Vec::new()),
                 }
+            })
+            .collect::<Vec<_>>();
 
-                return Some((last_working_grid, last_column_count));
-            }
+        let original_height = divide_rounding_up(rows.len(), column_count);
+        for (i, (name_width, row)) in name_widths.iter().zip(rows).enumerate() {
+            let index = if self.grid.across {
+                i % column_count
+            } else {
+                i / original_height
+            };
+
+            let (widths, names) = &mut tables[index];
+            widths.add_widths(row);
+            names.push(*name_width);
         }
 
-        None
+        let columns = tables
+            .into_iter()
+            .map(|(widths, names)| {
+                let cells_width = widths.iter().sum::<usize>() + widths.len();
+                names
+                    .into_iter()
+                    .map(|name_width| cells_width + name_width)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        if self.grid.across {
+            let height = columns.iter().map(Vec::len).max().unwrap_or(0);
+            (0..height)
+                .flat_map(|row| columns.iter().filter_map(move |column| column.get(row)))
+                .copied()
+                .collect()
+        } else {
+            columns.concat()
+        }
     }
 
     fn make_table(
@@ -313,7 +397,7 @@ impl<'a> Render<'a> {
             .into_iter()
             .map(|(table, details_rows)| {
                 drender
-                    .iterate_with_table(table, details_rows)
+                    .iterate_with_table(table, details_rows, TableStyle::Plain)
                     .collect::<Vec<_>>()
             })
             .collect::<Vec<_>>();
@@ -324,7 +408,7 @@ impl<'a> Render<'a> {
             grid::Direction::TopToBottom
         };
 
-        let filling = grid::Filling::Spaces(4);
+        let filling = grid::Filling::Spaces(COLUMN_SPACING);
         let mut grid = grid::Grid::new(grid::GridOptions { direction, filling });
 
         if self.grid.across {
@@ -356,13 +440,3 @@ impl<'a> Render<'a> {
         grid
     }
 }
-
-fn divide_rounding_up(a: usize, b: usize) -> usize {
-    let mut result = a / b;
-
-    if a % b != 0 {
-        result += 1;
-    }
-
-    result
-}
@@ -0,0 +1,157 @@
+//! Splitting a listing into sections of files that have something in
+//! common, such as their category or their extension, so each section can
+//! be listed beneath a header saying what it is and how many files are in
+//! it, like “Images (14)”.
+
+use std::collections::BTreeMap;
+
+use crate::fs::File;
+use crate::info::filetype::FileType;
+
+/// What to split a listing into sections by.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum GroupBy {
+    /// The category worked out from each file’s name, the same as for
+    /// `--sort=category`.
+    Category,
+
+    /// Each file’s extension, ignoring its case.
+    Extension,
+
+    /// The first letter of each file’s name, ignoring a leading dot.
+    FirstLetter,
+}
+
+/// One section of a listing.
+pub struct Group<'dir> {
+    /// What the files in it have in common, to go in its header.
+    pub label: String,
+
+    pub files: Vec<File<'dir>>,
+}
+
+impl<'dir> Group<'dir> {
+    /// The text of the header, with the number of files in the section.
+    pub fn header(&self) -> String {
+        format!("{} ({})", self.label, self.files.len())
+    }
+}
+
+/// Which section a file goes in. The sections get listed in this order, so
+/// the files that don’t have whatever’s being grouped by come last.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone)]
+enum Key {
+    Category(FileType),
+    Extension(String),
+    Digit,
+    Letter(char),
+    Other,
+}
+
+impl GroupBy {
+    /// Splits the files into sections, in the order they get listed in. The
+    /// files in each one stay in the order they were in, as the views sort
+    /// them themselves.
+    pub fn split(self, files: Vec<File<'_>>) -> Vec<Group<'_>> {
+        let mut groups = BTreeMap::<Key, Vec<File<'_>>>::new();
+        for file in files {
+            groups.entry(self.key(&file)).or_default().push(file);
+        }
+
+        groups
+            .into_iter()
+            .map(|(key, files)| Group {
+                label: self.label(&key),
+                files,
+            })
+            .collect()
+    }
+
+    fn key(self, file: &File<'_>) -> Key {
+        match self {
+            Self::Category => FileType::get_file_type(file).map_or(Key::Other, Key::Category),
+            Self::Extension => file.ext.clone().map_or(Key::Other, Key::Extension),
+            Self::FirstLetter => first_letter(&file.name),
+        }
+    }
+
+    fn label(self, key: &Key) -> String {
+        match key {
+            Key::Category(file_type) => category_name(*file_type).into(),
+            Key::Extension(ext) => format!(".{ext}"),
+            Key::Digit => "0–9".into(),
+            Key::Letter(letter) => letter.to_string(),
+            Key::Other if self == Self::Extension => "No extension".into(),
+            Key::Other => "Other".into(),
+        }
+    }
+}
+
+/// The section for a name when grouping by its first letter, which puts
+/// the names starting with the same letter together whatever its case.
+fn first_letter(name: &str) -> Key {
+    let name = name.strip_prefix('.').unwrap_or(name);
+    match name.chars().next() {
+        Some(c) if c.is_numeric() => Key::Digit,
+        Some(c) if c.is_alphabetic() => Key::Letter(c.to_uppercase().next().unwrap_or(c)),
+        _ => Key::Other,
+    }
+}
+
+#[rustfmt::skip]
+fn category_name(file_type: FileType) -> &'static str {
+    match file_type {
+        FileType::Image       => "Images",
+        FileType::Video       => "Videos",
+        FileType::Music       => "Music",
+        FileType::Lossless    => "Lossless music",
+        FileType::Crypto      => "Crypto",
+        FileType::Document    => "Documents",
+        FileType::Compressed  => "Compressed",
+        FileType::Temp        => "Temporary files",
+        FileType::Compiled    => "Compiled files",
+        FileType::Build       => "Build files",
+        FileType::Source      => "Source code",
+        FileType::Config      => "Config files",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn letters() {
+        assert_eq!(first_letter("apple"), Key::Letter('A'));
+        assert_eq!(first_letter("Apple"), Key::Letter('A'));
+        assert_eq!(first_letter(".bashrc"), Key::Letter('B'));
+        assert_eq!(first_letter("éclair"), Key::Letter('É'));
+        assert_eq!(first_letter("2024"), Key::Digit);
+        assert_eq!(first_letter("_build"), Key::Other);
+    }
+
+    #[test]
+    fn order() {
+        let mut keys = vec![Key::Other, Key::Letter('B'), Key::Digit, Key::Letter('A')];
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![Key::Digit, Key::Letter('A'), Key::Letter('B'), Key::Other]
+        );
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!(
+            GroupBy::Category.label(&Key::Category(FileType::Image)),
+            "Images"
+        );
+        assert_eq!(GroupBy::Category.label(&Key::Other), "Other");
+        assert_eq!(
+            GroupBy::Extension.label(&Key::Extension("rs".into())),
+            ".rs"
+        );
+        assert_eq!(GroupBy::Extension.label(&Key::Other), "No extension");
+        assert_eq!(GroupBy::FirstLetter.label(&Key::Digit), "0–9");
+    }
+}
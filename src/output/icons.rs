@@ -1,7 +1,27 @@
 use ansiterm::Style;
 use phf::{phf_map, Map};
 
-use crate::fs::File;
+use crate::fs::{File, FileTarget};
+use crate::info::filetype::{ConfigFamily, FileType};
+
+/// Which glyphs to use for the icons next to file names, for the fonts that
+/// have them.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum IconSet {
+    /// The icons from version 3 of Nerd Fonts.
+    #[default]
+    Nerd3,
+
+    /// The icons from version 2 of Nerd Fonts, which don’t have the
+    /// Material Design icons where version 3 put them.
+    Nerd2,
+
+    /// Symbols that most fonts have, without any patching.
+    Unicode,
+
+    /// Emoji, which most terminals can show in colour.
+    Emoji,
+}
 
 #[non_exhaustive]
 struct Icons;
@@ -18,14 +38,17 @@ impl Icons {
     const CSS3: char            = '\u{e749}';  // 
     const DATABASE: char        = '\u{f1c0}';  // 
     const DIFF: char            = '\u{f440}';  // 
+    const DISK: char            = '\u{f02ca}';// 󰋊
     const DISK_IMAGE: char      = '\u{e271}';  // 
     const DOCKER: char          = '\u{e650}';  // 
     const DOCUMENT: char        = '\u{f1c2}';  // 
     const DOWNLOAD: char        = '\u{f01da}'; // 󰇚
     const EMACS: char           = '\u{e632}';  // 
+    const EDITORCONFIG: char    = '\u{e652}';  // 
     const ESLINT: char          = '\u{e655}';  // 
     const FILE: char            = '\u{f15b}';  // 
     const FILE_OUTLINE: char    = '\u{f016}';  // 
+    const FILE_SYMLINK: char    = '\u{f481}';// 
     const FOLDER: char          = '\u{e5ff}';  // 
     const FOLDER_CONFIG: char   = '\u{e5fc}';  // 
     const FOLDER_GIT: char      = '\u{e5fb}';  // 
@@ -34,6 +57,7 @@ impl Icons {
     const FOLDER_KEY: char      = '\u{f08ac}'; // 󰢬
     const FOLDER_NPM: char      = '\u{e5fa}';  // 
     const FOLDER_OPEN: char     = '\u{f115}';  // 
+    const FOLDER_SYMLINK: char  = '\u{f482}';// 
     const FONT: char            = '\u{f031}';  // 
     const GIST_SECRET: char     = '\u{eafa}';  // 
     const GIT: char             = '\u{f1d3}';  // 
@@ -73,6 +97,7 @@ impl Icons {
     const LANG_TYPESCRIPT: char = '\u{e628}';  // 
     const LIBRARY: char         = '\u{eb9c}';  // 
     const LICENSE: char         = '\u{f02d}';  // 
+    const LINK_BROKEN: char     = '\u{f0338}';// 󰌸
     const LOCK: char            = '\u{f023}';  // 
     const MAKE: char            = '\u{e673}';  // 
     const MARKDOWN: char        = '\u{f48a}';  // 
@@ -86,6 +111,7 @@ impl Icons {
     const OS_WINDOWS_CMD: char  = '\u{ebc4}';  // 
     const PLAYLIST: char        = '\u{f0cb9}'; // 󰲹
     const POWERSHELL: char      = '\u{ebc7}';  // 
+    const PRETTIER: char        = '\u{e6b4}';  // 
     const PRIVATE_KEY: char     = '\u{f0306}'; // 󰌆
     const PUBLIC_KEY: char      = '\u{f0dd6}'; // 󰷖
     const RAZOR: char           = '\u{f1fa}';  // 
@@ -117,12 +143,20 @@ impl Icons {
 /// Mapping from full filenames to directory icon. This mapping should contain
 /// all the directories that have a custom icon.
 const DIRECTORY_ICONS: Map<&'static str, char> = phf_map! {
+    ".cargo"              => Icons::LANG_RUST,      // 
     ".config"             => Icons::FOLDER_CONFIG,  // 
+    ".devcontainer"       => Icons::DOCKER,         // 
     ".git"                => Icons::FOLDER_GIT,     // 
     ".github"             => Icons::FOLDER_GITHUB,  // 
+    ".gitlab"             => '\u{f296}',            // 
+    ".idea"               => Icons::INTELLIJ,       // 
     ".npm"                => Icons::FOLDER_NPM,     // 
     ".ssh"                => Icons::FOLDER_KEY,     // 󰢬
     ".Trash"              => '\u{f1f8}',            // 
+    ".venv"               => Icons::LANG_PYTHON,    // 
+    ".vscode"             => '\u{f0a1e}',           // 󰨞
+    "__pycache__"         => Icons::LANG_PYTHON,    // 
+    "bin"                 => Icons::BINARY,         // 
     "config"              => Icons::FOLDER_CONFIG,  // 
     "Contacts"            => '\u{f024c}',           // 󰉌
     "cron.d"              => Icons::FOLDER_CONFIG,  // 
@@ -131,6 +165,7 @@ const DIRECTORY_ICONS: Map<&'static str, char> = phf_map! {
     "cron.monthly"        => Icons::FOLDER_CONFIG,  // 
     "cron.weekly"         => Icons::FOLDER_CONFIG,  // 
     "Desktop"             => '\u{f108}',            // 
+    "Documents"           => '\u{f0219}',           // 󰈙
     "Downloads"           => '\u{f024d}',           // 󰉍
     "etc"                 => Icons::FOLDER_CONFIG,  // 
     "Favorites"           => '\u{f069d}',           // 󰚝
@@ -146,6 +181,7 @@ const DIRECTORY_ICONS: Map<&'static str, char> = phf_map! {
     "Pictures"            => '\u{f024f}',           // 󰉏
     "ssh"                 => Icons::FOLDER_KEY,     // 󰢬
     "sudoers.d"           => Icons::FOLDER_KEY,     // 󰢬
+    "target"              => '\u{f03d7}',           // 󰏗
     "Videos"              => '\u{f03d}',            // 
     "xbps.d"              => Icons::FOLDER_CONFIG,  // 
     "xorg.conf.d"         => Icons::FOLDER_CONFIG,  // 
@@ -164,11 +200,6 @@ const FILENAME_ICONS: Map<&'static str, char> = phf_map! {
     ".cshrc"              => Icons::SHELL,          // 󱆃
     ".DS_Store"           => Icons::OS_APPLE,       // 
     ".emacs"              => Icons::EMACS,          // 
-    ".eslintrc.cjs"       => Icons::ESLINT,         // 
-    ".eslintrc.js"        => Icons::ESLINT,         // 
-    ".eslintrc.json"      => Icons::ESLINT,         // 
-    ".eslintrc.yaml"      => Icons::ESLINT,         // 
-    ".eslintrc.yml"       => Icons::ESLINT,         // 
     ".gitattributes"      => Icons::GIT,            // 
     ".gitconfig"          => Icons::GIT,            // 
     ".gitignore"          => Icons::GIT,            // 
@@ -767,20 +798,158 @@ pub fn iconify_style(style: Style) -> Style {
 
 /// Lookup the icon for a file based on the file's name, if the entry is a
 /// directory, or by the lowercase file extension.
-pub fn icon_for_file(file: &File<'_>) -> char {
-    if file.points_to_directory() {
-        *DIRECTORY_ICONS.get(file.name.as_str()).unwrap_or_else(|| {
+///
+/// Symbolic links get icons that depend on what they point to, unless links
+/// are being dereferenced, in which case they get the icon of their target.
+/// Mount points get their own icon, however they’re named, when they’re
+/// known to be one.
+pub fn icon_for_file(file: &File<'_>, set: IconSet, mount_point: bool) -> char {
+    match set {
+        IconSet::Nerd3 => nerd_icon_for_file(file, mount_point),
+        IconSet::Nerd2 => legacy_icon(
+            nerd_icon_for_file(file, mount_point),
+            file.points_to_directory(),
+        ),
+        IconSet::Unicode => symbols_for_file(file, mount_point).0,
+        IconSet::Emoji => symbols_for_file(file, mount_point).1,
+    }
+}
+
+fn nerd_icon_for_file(file: &File<'_>, mount_point: bool) -> char {
+    if file.is_link() && !file.deref_links {
+        return match file.link_target() {
+            FileTarget::Ok(target) if target.points_to_directory() => Icons::FOLDER_SYMLINK, // 
+            FileTarget::Ok(_) => icon_for_name(file).unwrap_or(Icons::FILE_SYMLINK),         // 
+            FileTarget::Virtual(_) => Icons::FILE_SYMLINK,                                   // 
+            FileTarget::Broken(_) | FileTarget::Err(_) => Icons::LINK_BROKEN,                // 󰌸
+        };
+    }
+
+    if mount_point {
+        Icons::DISK // 󰋊
+    } else if file.points_to_directory() {
+        *DIRECTORY_ICONS.get(&*file.name).unwrap_or_else(|| {
             if file.is_empty_dir() {
                 &Icons::FOLDER_OPEN // 
             } else {
                 &Icons::FOLDER // 
             }
         })
-    } else if let Some(icon) = FILENAME_ICONS.get(file.name.as_str()) {
-        *icon
-    } else if let Some(ext) = file.ext.as_ref() {
-        *EXTENSION_ICONS.get(ext.as_str()).unwrap_or(&Icons::FILE) // 
+    } else if let Some(icon) = icon_for_name(file) {
+        icon
+    } else if file.ext.is_some() {
+        Icons::FILE // 
     } else {
         Icons::FILE_OUTLINE // 
     }
 }
+
+/// Lookup the icon for a file by its name or by its lowercase extension, if
+/// either is a well-known one. All the files in a family of config files
+/// share an icon, however they’re named.
+fn icon_for_name(file: &File<'_>) -> Option<char> {
+    if let Some(icon) = FILENAME_ICONS.get(&*file.name) {
+        return Some(*icon);
+    }
+
+    #[rustfmt::skip]
+    let family = match ConfigFamily::of(&file.name) {
+        Some(ConfigFamily::EditorConfig) => Some(Icons::EDITORCONFIG),
+        Some(ConfigFamily::Prettier)     => Some(Icons::PRETTIER),
+        Some(ConfigFamily::ESLint)       => Some(Icons::ESLINT),
+        None                             => None,
+    };
+
+    family.or_else(|| EXTENSION_ICONS.get(file.ext.as_ref()?.as_str()).copied())
+}
+
+/// Swaps an icon from version 3 of Nerd Fonts for one that version 2 has.
+/// Version 3 moved the Material Design icons into the Supplementary Private
+/// Use Area, so the ones used here get replaced with similar
+/// Font Awesome icons, which are in the same place in both versions, and
+/// any others with a plain file or folder.
+fn legacy_icon(icon: char, is_directory: bool) -> char {
+    if u32::from(icon) < 0xF_0000 {
+        return icon;
+    }
+
+    #[rustfmt::skip]
+    return match icon {
+        Icons::DISK          => '\u{f0a0}',  // 
+        Icons::DOWNLOAD      => '\u{f019}',  // 
+        Icons::LINK_BROKEN   => '\u{f127}',  // 
+        Icons::PLAYLIST      => Icons::AUDIO,
+        Icons::PRIVATE_KEY   => '\u{f084}',  // 
+        Icons::PUBLIC_KEY    => '\u{f084}',  // 
+        Icons::README        => '\u{f05a}',  // 
+        Icons::SHELL         => Icons::SHELL_CMD,
+        Icons::SHIELD_CHECK  => '\u{f132}',  // 
+        Icons::SHIELD_KEY    => '\u{f132}',  // 
+        Icons::SHIELD_LOCK   => '\u{f132}',  // 
+        Icons::SIGNED_FILE   => '\u{f0a3}',  // 
+        Icons::SUBTITLE      => '\u{f20a}',  // 
+        Icons::VECTOR        => Icons::IMAGE,
+        Icons::XML           => '\u{f121}',  // 
+        _ if is_directory    => Icons::FOLDER,
+        _                    => Icons::FILE,
+    };
+}
+
+/// The plain Unicode symbol and the emoji for a file, which go by what kind
+/// of file it is rather than by its exact name, as there are far fewer of
+/// them than there are Nerd Font icons.
+#[rustfmt::skip]
+fn symbols_for_file(file: &File<'_>, mount_point: bool) -> (char, char) {
+    if file.is_link() && !file.deref_links {
+        return match file.link_target() {
+            FileTarget::Broken(_) | FileTarget::Err(_) => ('↯', '💔'),
+            FileTarget::Ok(_) | FileTarget::Virtual(_) => ('→', '🔗'),
+        };
+    }
+
+    if mount_point {
+        return ('⏏', '💽');
+    } else if file.points_to_directory() {
+        return if file.is_empty_dir() { ('▹', '📂') } else { ('▸', '📁') };
+    }
+
+    match FileType::get_file_type(file) {
+        Some(FileType::Image)       => ('▣', '📷'),
+        Some(FileType::Video)       => ('►', '🎬'),
+        Some(FileType::Music)       => ('♪', '🎵'),
+        Some(FileType::Lossless)    => ('♫', '🎶'),
+        Some(FileType::Crypto)      => ('⚿', '🔑'),
+        Some(FileType::Document)    => ('≡', '📑'),
+        Some(FileType::Compressed)  => ('▤', '📦'),
+        Some(FileType::Temp)        => ('⧖', '⏳'),
+        Some(FileType::Compiled)    => ('◆', '🧩'),
+        Some(FileType::Build)       => ('⚒', '🔨'),
+        Some(FileType::Source)      => ('⌨', '📜'),
+        Some(FileType::Config)      => ('⚙', '🔧'),
+        None                        => ('◦', '📄'),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn legacy_icons() {
+        assert_eq!(legacy_icon(Icons::LANG_RUST, false), Icons::LANG_RUST);
+        assert_eq!(legacy_icon(Icons::SHELL, false), Icons::SHELL_CMD);
+        assert_eq!(legacy_icon(Icons::DISK, true), '\u{f0a0}');
+        assert_eq!(legacy_icon(Icons::FOLDER_KEY, true), Icons::FOLDER);
+        assert_eq!(legacy_icon(Icons::TERRAFORM, false), Icons::FILE);
+    }
+
+    #[test]
+    fn legacy_icons_are_in_version_2() {
+        for icon in EXTENSION_ICONS.values().chain(FILENAME_ICONS.values()) {
+            assert!(u32::from(legacy_icon(*icon, false)) < 0xF_0000);
+        }
+        for icon in DIRECTORY_ICONS.values() {
+            assert!(u32::from(legacy_icon(*icon, true)) < 0xF_0000);
+        }
+    }
+}
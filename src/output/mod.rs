@@ -1,28 +1,106 @@
+use std::path::PathBuf;
+
+pub use self::borders::TableStyle;
 pub use self::cell::{DisplayWidth, TextCell, TextCellContents};
 pub use self::escape::escape;
+pub use self::group::GroupBy;
+pub use self::tally::Tally;
+pub use self::tree::TreeStyle;
+pub use self::unreadable::Unreadable;
 
 pub mod details;
+pub mod diff;
 pub mod file_name;
 pub mod grid;
 pub mod grid_details;
 pub mod icons;
 pub mod lines;
+pub mod prompt;
 pub mod render;
 pub mod table;
 pub mod time;
 
+mod borders;
 mod cell;
 mod escape;
+mod group;
+mod tally;
 mod tree;
+mod unreadable;
 
 /// The **view** contains all information about how to format output.
 #[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct View {
     pub mode: Mode,
     pub width: TerminalWidth,
     pub file_style: file_name::Options,
     pub deref_links: bool,
+    pub deref_command_line: bool,
     pub total_size: bool,
+    pub empty_placeholder: bool,
+    pub count_only: bool,
+    pub prompt: bool,
+    pub summary: bool,
+    pub git_summary: bool,
+    pub limit: Option<Limit>,
+    pub semantic_markers: bool,
+
+    /// Where to save a snapshot of the sizes of everything that was listed,
+    /// for `--delta` to compare against later.
+    pub save_snapshot: Option<PathBuf>,
+
+    /// Whether the snapshot already in that file gets updated, rather than
+    /// everything being read again.
+    pub update_snapshot: bool,
+
+    /// Whether a tree compares the two directories it was given, instead
+    /// of listing each of them.
+    pub diff: bool,
+
+    /// Whether the entries that are the same in both directories of a diff
+    /// get collapsed into one row for each directory.
+    pub only_changes: bool,
+
+    /// When the output gets written out.
+    pub flush: Flush,
+
+    /// Whether the directories that can’t be read while recursing get
+    /// their errors printed where they are, rather than all at the end.
+    pub inline_errors: bool,
+
+    /// What to split the files in each listing into sections by, if
+    /// anything.
+    pub group_by: Option<GroupBy>,
+}
+
+/// When the output gets **flushed** to wherever it’s going, which matters
+/// to programs that read it as it comes in.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum Flush {
+    /// After every line, which is how standard output usually works.
+    #[default]
+    Line,
+
+    /// After the files in each directory have been listed, so a program
+    /// reading the output gets a directory at a time.
+    Dir,
+
+    /// Only when the buffer fills up, and once everything has been listed,
+    /// which makes as few writes as possible.
+    End,
+}
+
+/// The **limit** stops a listing after some number of entries, once they’ve
+/// been sorted, so only the first few get displayed.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Limit {
+    /// How many entries to display.
+    pub count: usize,
+
+    /// Whether to end the listing with a line saying how many entries
+    /// weren’t displayed.
+    pub marker: bool,
 }
 
 /// The **mode** is the “type” of output.
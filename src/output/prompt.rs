@@ -0,0 +1,131 @@
+//! The prompt view prints a single-line summary of the files in a directory,
+//! compact enough to be embedded in a shell prompt.
+
+use std::io::{self, Write};
+
+use ansiterm::{ANSIString, ANSIStrings, Style};
+use chrono::prelude::*;
+
+use crate::fs::fields as f;
+use crate::fs::File;
+use crate::theme::Theme;
+
+/// The prompt view counts the files by their type, and follows them with
+/// the rolled-up Git status and the age of the newest file.
+pub struct Render<'a> {
+    pub files: Vec<File<'a>>,
+    pub theme: &'a Theme,
+
+    /// The Git status of the directory, if it’s in a repository.
+    pub git: Option<f::Git>,
+}
+
+impl<'a> Render<'a> {
+    pub fn render<W: Write>(self, w: &mut W) -> io::Result<()> {
+        let (mut dirs, mut files, mut links, mut others) = (0, 0, 0, 0);
+        for file in &self.files {
+            if file.is_link() {
+                links += 1;
+            } else if file.is_directory() {
+                dirs += 1;
+            } else if file.is_file() {
+                files += 1;
+            } else {
+                others += 1;
+            }
+        }
+
+        let ui = &self.theme.ui;
+        let counts = [
+            (dirs, "d", ui.filekinds.directory),
+            (files, "f", ui.filekinds.normal),
+            (links, "l", ui.filekinds.symlink),
+            (others, "o", ui.filekinds.special),
+        ];
+
+        let mut segments: Vec<Vec<ANSIString<'static>>> = counts
+            .into_iter()
+            .filter(|&(count, _, _)| count > 0)
+            .map(|(count, suffix, style)| vec![style.paint(format!("{count}{suffix}"))])
+            .collect();
+
+        if segments.is_empty() {
+            segments.push(vec![Style::default().paint("0")]);
+        }
+
+        if let Some(git) = self.git {
+            segments.push(git.render(self.theme).contents.to_vec());
+        }
+
+        if let Some(newest) = self.files.iter().filter_map(File::modified_time).max() {
+            let age = Utc::now().naive_utc() - newest;
+            segments.push(vec![ui.date.paint(compact_age(age.num_seconds()))]);
+        }
+
+        let separator = Style::default().paint(" ");
+        let mut line = Vec::new();
+        for (index, segment) in segments.into_iter().enumerate() {
+            if index > 0 {
+                line.push(separator.clone());
+            }
+            line.extend(segment);
+        }
+
+        writeln!(w, "{}", ANSIStrings(&line))
+    }
+}
+
+/// Formats an age in seconds using only its largest unit, such as “5m” or
+/// “3d”, so it takes up as little of the prompt as possible.
+fn compact_age(seconds: i64) -> String {
+    const MINUTE: i64 = 60;
+    const HOUR: i64 = 60 * MINUTE;
+    const DAY: i64 = 24 * HOUR;
+    const WEEK: i64 = 7 * DAY;
+    const YEAR: i64 = 365 * DAY;
+
+    #[rustfmt::skip]
+    return match seconds.max(0) {
+        s if s < MINUTE => format!("{s}s"),
+        s if s < HOUR   => format!("{}m", s / MINUTE),
+        s if s < DAY    => format!("{}h", s / HOUR),
+        s if s < WEEK   => format!("{}d", s / DAY),
+        s if s < YEAR   => format!("{}w", s / WEEK),
+        s               => format!("{}y", s / YEAR),
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::compact_age;
+
+    #[test]
+    fn seconds() {
+        assert_eq!(compact_age(42), "42s");
+    }
+
+    #[test]
+    fn minutes() {
+        assert_eq!(compact_age(60 * 5 + 59), "5m");
+    }
+
+    #[test]
+    fn days() {
+        assert_eq!(compact_age(60 * 60 * 24 * 3), "3d");
+    }
+
+    #[test]
+    fn weeks() {
+        assert_eq!(compact_age(60 * 60 * 24 * 7 * 51), "51w");
+    }
+
+    #[test]
+    fn years() {
+        assert_eq!(compact_age(60 * 60 * 24 * 365 * 2), "2y");
+    }
+
+    #[test]
+    fn future() {
+        assert_eq!(compact_age(-100), "0s");
+    }
+}
@@ -33,13 +33,32 @@ impl f::Blocksize {
                 // But format the number directly using the locale.
                 let string = numerics.format_int(size);
 
-                return TextCell::paint(colours.blocksize(prefix), string);
+                return TextCell::paint(colours.blocksize(size, prefix), string);
+            }
+            SizeFormat::Blocks(block_size) => {
+                let prefix = match NumberPrefix::binary(size as f64) {
+                    NumberPrefix::Standalone(_) => None,
+                    NumberPrefix::Prefixed(p, _) => Some(p),
+                };
+
+                let number = block_size.format(size, numerics);
+                let suffix = block_size.suffix.unwrap_or_default();
+
+                return TextCell {
+                    // the suffix is ASCII, as the units are hardcoded.
+                    width: DisplayWidth::from(&*number) + suffix.len(),
+                    contents: vec![
+                        colours.blocksize(size, prefix).paint(number),
+                        colours.unit(size, prefix).paint(suffix),
+                    ]
+                    .into(),
+                };
             }
         };
 
         let (prefix, n) = match result {
             NumberPrefix::Standalone(b) => {
-                return TextCell::paint(colours.blocksize(None), numerics.format_int(b))
+                return TextCell::paint(colours.blocksize(size, None), numerics.format_int(b))
             }
             NumberPrefix::Prefixed(p, n) => (p, n),
         };
@@ -55,8 +74,8 @@ impl f::Blocksize {
             // symbol is guaranteed to be ASCII since unit prefixes are hardcoded.
             width: DisplayWidth::from(&*number) + symbol.len(),
             contents: vec![
-                colours.blocksize(Some(prefix)).paint(number),
-                colours.unit(Some(prefix)).paint(symbol),
+                colours.blocksize(size, Some(prefix)).paint(number),
+                colours.unit(size, Some(prefix)).paint(symbol),
             ]
             .into(),
         }
@@ -65,9 +84,9 @@ impl f::Blocksize {
 
 #[rustfmt::skip]
 pub trait Colours {
-    fn blocksize(&self, prefix: Option<Prefix>) -> Style;
-    fn unit(&self, prefix: Option<Prefix>)      -> Style;
-    fn no_blocksize(&self)                      -> Style;
+    fn blocksize(&self, size: u64, prefix: Option<Prefix>) -> Style;
+    fn unit(&self, size: u64, prefix: Option<Prefix>)      -> Style;
+    fn no_blocksize(&self)                                 -> Style;
 }
 
 #[cfg(test)]
@@ -87,9 +106,9 @@ pub mod test {
 
     #[rustfmt::skip]
     impl Colours for TestColours {
-        fn blocksize(&self, _prefix: Option<Prefix>) -> Style { Fixed(66).normal() }
-        fn unit(&self, _prefix: Option<Prefix>)      -> Style { Fixed(77).bold() }
-        fn no_blocksize(&self)                       -> Style { Black.italic() }
+        fn blocksize(&self, _size: u64, _prefix: Option<Prefix>) -> Style { Fixed(66).normal() }
+        fn unit(&self, _size: u64, _prefix: Option<Prefix>)      -> Style { Fixed(77).bold() }
+        fn no_blocksize(&self)                                   -> Style { Black.italic() }
     }
 
     #[test]
@@ -103,7 +122,7 @@ pub mod test {
                 SizeFormat::JustBytes,
                 &NumericLocale::english()
             )
-        )
+        );
     }
 
     #[test]
@@ -121,7 +140,7 @@ pub mod test {
                 SizeFormat::DecimalBytes,
                 &NumericLocale::english()
             )
-        )
+        );
     }
 
     #[test]
@@ -139,7 +158,7 @@ pub mod test {
                 SizeFormat::BinaryBytes,
                 &NumericLocale::english()
             )
-        )
+        );
     }
 
     #[test]
@@ -157,6 +176,6 @@ pub mod test {
                 SizeFormat::JustBytes,
                 &NumericLocale::english()
             )
-        )
+        );
     }
 }
@@ -0,0 +1,174 @@
+use ansiterm::{ANSIString, Style};
+use locale::Numeric as NumericLocale;
+use number_prefix::NumberPrefix;
+
+use crate::fs::snapshot::Delta;
+use crate::output::cell::{DisplayWidth, TextCell};
+
+impl Delta {
+    /// Renders how much a file has grown or shrunk since the snapshot, such
+    /// as `+12.4Mi`, along with the change in how many files are beneath it
+    /// for a directory. Sizes always use binary prefixes, whatever the size
+    /// column uses, as the change is usually far smaller than the size.
+    pub fn render<C: Colours>(
+        self,
+        colours: &C,
+        is_directory: bool,
+        numerics: &NumericLocale,
+    ) -> TextCell {
+        let (size, files) = match self {
+            Self::New => return TextCell::paint_str(colours.grown(), "new"),
+            Self::Changed { size: 0, files: 0 } => return TextCell::blank(colours.unchanged()),
+            Self::Changed { size, files } => (size, files),
+        };
+
+        let mut parts = Vec::with_capacity(2);
+        if size != 0 {
+            parts.push(signed(
+                size,
+                colours,
+                &format_size(size.unsigned_abs(), numerics),
+            ));
+        }
+
+        if files != 0 && is_directory {
+            let plural = if files.unsigned_abs() == 1 { "" } else { "s" };
+            let count = format!("{} file{plural}", files.unsigned_abs());
+            parts.push(signed(files, colours, &count));
+        }
+
+        if parts.is_empty() {
+            return TextCell::blank(colours.unchanged());
+        }
+
+        let width = parts
+            .iter()
+            .map(|p| DisplayWidth::from(&**p))
+            .sum::<DisplayWidth>()
+            + (parts.len() - 1);
+
+        let mut contents = Vec::with_capacity(parts.len() * 2);
+        for (i, part) in parts.into_iter().enumerate() {
+            if i > 0 {
+                contents.push(colours.unchanged().paint(" "));
+            }
+            contents.push(part);
+        }
+
+        TextCell {
+            width,
+            contents: contents.into(),
+        }
+    }
+}
+
+/// Paints a change with a `+` or `-` in front of it, in the colour for
+/// whether it’s grown or shrunk.
+fn signed<C: Colours>(change: i128, colours: &C, text: &str) -> ANSIString<'static> {
+    if change > 0 {
+        colours.grown().paint(format!("+{text}"))
+    } else {
+        colours.shrunk().paint(format!("-{text}"))
+    }
+}
+
+/// Formats a number of bytes with a binary prefix.
+fn format_size(bytes: u128, numerics: &NumericLocale) -> String {
+    match NumberPrefix::binary(bytes as f64) {
+        NumberPrefix::Standalone(_) => bytes.to_string(),
+        NumberPrefix::Prefixed(prefix, n) if n < 10_f64 => {
+            format!("{}{}", numerics.format_float(n, 1), prefix.symbol())
+        }
+        NumberPrefix::Prefixed(prefix, n) => {
+            format!(
+                "{}{}",
+                numerics.format_int(n.round() as isize),
+                prefix.symbol()
+            )
+        }
+    }
+}
+
+pub trait Colours {
+    fn grown(&self) -> Style;
+    fn shrunk(&self) -> Style;
+    fn unchanged(&self) -> Style;
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::Colours;
+    use crate::fs::snapshot::Delta;
+    use crate::output::cell::{DisplayWidth, TextCell};
+
+    use ansiterm::Colour::*;
+    use ansiterm::Style;
+
+    struct TestColours;
+
+    impl Colours for TestColours {
+        fn grown(&self) -> Style {
+            Red.normal()
+        }
+        fn shrunk(&self) -> Style {
+            Green.normal()
+        }
+        fn unchanged(&self) -> Style {
+            Black.normal()
+        }
+    }
+
+    fn render(delta: Delta, is_directory: bool) -> TextCell {
+        delta.render(&TestColours, is_directory, &locale::Numeric::english())
+    }
+
+    #[test]
+    fn new() {
+        let expected = TextCell::paint_str(Red.normal(), "new");
+        assert_eq!(expected, render(Delta::New, false));
+    }
+
+    #[test]
+    fn unchanged() {
+        let delta = Delta::Changed { size: 0, files: 0 };
+        assert_eq!(TextCell::blank(Black.normal()), render(delta, true));
+    }
+
+    #[test]
+    fn grown() {
+        let delta = Delta::Changed {
+            size: 13_002_342,
+            files: 0,
+        };
+        let expected = TextCell::paint_str(Red.normal(), "+12Mi");
+        assert_eq!(expected, render(delta, false));
+    }
+
+    #[test]
+    fn shrunk_directory() {
+        let delta = Delta::Changed {
+            size: -3072,
+            files: -3,
+        };
+        let expected = TextCell {
+            width: DisplayWidth::from(15),
+            contents: vec![
+                Green.paint("-3.0Ki"),
+                Black.paint(" "),
+                Green.paint("-3 files"),
+            ]
+            .into(),
+        };
+        assert_eq!(expected, render(delta, true));
+    }
+
+    #[test]
+    fn files_only_for_directories() {
+        let delta = Delta::Changed { size: 0, files: 1 };
+        assert_eq!(TextCell::blank(Black.normal()), render(delta, false));
+        assert_eq!(
+            TextCell::paint_str(Red.normal(), "+1 file"),
+            render(delta, true)
+        );
+    }
+}
@@ -31,10 +31,13 @@ impl Render for Option<f::Group> {
         let mut style = colours.not_yours();
 
         let group = match self {
-            Some(g) => match users.get_group_by_gid(g.0) {
-                Some(g) => (*g).clone(),
-                None => return TextCell::paint(style, g.0.to_string()),
-            },
+            Some(g) => {
+                let Some(group) = users.get_group_by_gid(g.0) else {
+                    let gid = g.0.to_string();
+                    return TextCell::paint(colours.named(style, &gid), gid);
+                };
+                (*group).clone()
+            }
             None => return TextCell::blank(colours.no_group()),
         };
 
@@ -51,6 +54,7 @@ impl Render for Option<f::Group> {
             style = colours.root_group();
         }
 
+        let style = colours.named(style, &group.name().to_string_lossy());
         let mut group_name = match user_format {
             UserFormat::Name => group.name().to_string_lossy().into(),
             UserFormat::Numeric => group.gid().to_string(),
@@ -75,6 +79,10 @@ pub trait Colours {
     fn not_yours(&self) -> Style;
     fn no_group(&self) -> Style;
     fn root_group(&self) -> Style;
+
+    /// The style for the group with the given name, starting from the one
+    /// it would get otherwise.
+    fn named(&self, style: Style, name: &str) -> Style;
 }
 
 #[cfg(test)]
@@ -99,6 +107,7 @@ pub mod test {
         fn not_yours(&self) -> Style { Fixed(81).normal() }
         fn no_group(&self)   -> Style { Black.italic() }
         fn root_group(&self) -> Style { Fixed(82).normal() }
+        fn named(&self, style: Style, _name: &str) -> Style { style }
     }
 
     #[test]
@@ -180,7 +189,7 @@ pub mod test {
                 GroupFormat::Regular,
                 file_user
             )
-        )
+        );
     }
 
     #[test]
@@ -203,7 +212,7 @@ pub mod test {
                 GroupFormat::Regular,
                 file_user
             )
-        )
+        );
     }
 
     #[test]
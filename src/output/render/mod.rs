@@ -3,6 +3,9 @@ mod blocks;
 #[cfg(unix)]
 pub use self::blocks::Colours as BlocksColours;
 
+mod delta;
+pub use self::delta::Colours as DeltaColours;
+
 mod filetype;
 pub use self::filetype::Colours as FiletypeColours;
 
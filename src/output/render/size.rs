@@ -36,13 +36,32 @@ impl f::Size {
                 // But format the number directly using the locale.
                 let string = numerics.format_int(size);
 
-                return TextCell::paint(colours.size(prefix), string);
+                return TextCell::paint(colours.size(size, prefix), string);
+            }
+            SizeFormat::Blocks(block_size) => {
+                let prefix = match NumberPrefix::binary(size as f64) {
+                    NumberPrefix::Standalone(_) => None,
+                    NumberPrefix::Prefixed(p, _) => Some(p),
+                };
+
+                let number = block_size.format(size, numerics);
+                let suffix = block_size.suffix.unwrap_or_default();
+
+                return TextCell {
+                    // the suffix is ASCII, as the units are hardcoded.
+                    width: DisplayWidth::from(&*number) + suffix.len(),
+                    contents: vec![
+                        colours.size(size, prefix).paint(number),
+                        colours.unit(size, prefix).paint(suffix),
+                    ]
+                    .into(),
+                };
             }
         };
 
         #[rustfmt::skip]
         let (prefix, n) = match result {
-            NumberPrefix::Standalone(b)   => return TextCell::paint(colours.size(None), numerics.format_int(b)),
+            NumberPrefix::Standalone(b)   => return TextCell::paint(colours.size(size, None), numerics.format_int(b)),
             NumberPrefix::Prefixed(p, n)  => (p, n),
         };
 
@@ -57,8 +76,8 @@ impl f::Size {
             // symbol is guaranteed to be ASCII since unit prefixes are hardcoded.
             width: DisplayWidth::from(&*number) + symbol.len(),
             contents: vec![
-                colours.size(Some(prefix)).paint(number),
-                colours.unit(Some(prefix)).paint(symbol),
+                colours.size(size, Some(prefix)).paint(number),
+                colours.unit(size, Some(prefix)).paint(symbol),
             ]
             .into(),
         }
@@ -83,8 +102,8 @@ impl f::DeviceIDs {
 }
 
 pub trait Colours {
-    fn size(&self, prefix: Option<Prefix>) -> Style;
-    fn unit(&self, prefix: Option<Prefix>) -> Style;
+    fn size(&self, size: u64, prefix: Option<Prefix>) -> Style;
+    fn unit(&self, size: u64, prefix: Option<Prefix>) -> Style;
     fn no_size(&self) -> Style;
 
     fn major(&self) -> Style;
@@ -97,7 +116,7 @@ pub mod test {
     use super::Colours;
     use crate::fs::fields as f;
     use crate::output::cell::{DisplayWidth, TextCell};
-    use crate::output::table::SizeFormat;
+    use crate::output::table::{BlockSize, SizeFormat};
 
     use ansiterm::Colour::*;
     use ansiterm::Style;
@@ -108,9 +127,9 @@ pub mod test {
 
     #[rustfmt::skip]
     impl Colours for TestColours {
-        fn size(&self, _prefix: Option<Prefix>) -> Style { Fixed(66).normal() }
-        fn unit(&self, _prefix: Option<Prefix>) -> Style { Fixed(77).bold() }
-        fn no_size(&self)                       -> Style { Black.italic() }
+        fn size(&self, _size: u64, _prefix: Option<Prefix>) -> Style { Fixed(66).normal() }
+        fn unit(&self, _size: u64, _prefix: Option<Prefix>) -> Style { Fixed(77).bold() }
+        fn no_size(&self)                                   -> Style { Black.italic() }
 
         fn major(&self) -> Style { Blue.on(Red) }
         fn comma(&self) -> Style { Green.italic() }
@@ -128,7 +147,7 @@ pub mod test {
                 SizeFormat::JustBytes,
                 &NumericLocale::english()
             )
-        )
+        );
     }
 
     #[test]
@@ -146,7 +165,7 @@ pub mod test {
                 SizeFormat::DecimalBytes,
                 &NumericLocale::english()
             )
-        )
+        );
     }
 
     #[test]
@@ -164,7 +183,7 @@ pub mod test {
                 SizeFormat::BinaryBytes,
                 &NumericLocale::english()
             )
-        )
+        );
     }
 
     #[test]
@@ -182,7 +201,30 @@ pub mod test {
                 SizeFormat::JustBytes,
                 &NumericLocale::english()
             )
-        )
+        );
+    }
+
+    #[test]
+    fn file_blocks() {
+        let directory = f::Size::Some(1_048_577);
+        let block_size = BlockSize {
+            bytes: 1024,
+            suffix: Some("K"),
+            separators: true,
+        };
+        let expected = TextCell {
+            width: DisplayWidth::from(6),
+            contents: vec![Fixed(66).paint("1,025"), Fixed(77).bold().paint("K")].into(),
+        };
+
+        assert_eq!(
+            expected,
+            directory.render(
+                &TestColours,
+                SizeFormat::Blocks(block_size),
+                &NumericLocale::english()
+            )
+        );
     }
 
     #[test]
@@ -208,6 +250,6 @@ pub mod test {
                 SizeFormat::JustBytes,
                 &NumericLocale::english()
             )
-        )
+        );
     }
 }
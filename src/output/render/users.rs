@@ -30,7 +30,7 @@ impl Render for Option<f::User> {
         } else {
             colours.other()
         };
-        TextCell::paint(style, user_name)
+        TextCell::paint(colours.named(style, &user_name), user_name)
     }
 }
 
@@ -39,6 +39,10 @@ pub trait Colours {
     fn other(&self) -> Style;
     fn root(&self) -> Style;
     fn no_user(&self) -> Style;
+
+    /// The style for the user with the given name, starting from the one it
+    /// would get otherwise.
+    fn named(&self, style: Style, name: &str) -> Style;
 }
 
 #[cfg(test)]
@@ -62,6 +66,7 @@ pub mod test {
         fn other(&self) -> Style { Blue.underline() }
         fn root(&self)         -> Style { Blue.underline() }
         fn no_user(&self)      -> Style { Black.italic() }
+        fn named(&self, style: Style, _name: &str) -> Style { style }
     }
 
     #[test]
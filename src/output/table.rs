@@ -1,8 +1,10 @@
 use std::cmp::max;
 use std::ops::Deref;
+use std::path::PathBuf;
 #[cfg(unix)]
 use std::sync::{Mutex, MutexGuard};
 
+use ansiterm::Style;
 use chrono::prelude::*;
 
 use log::*;
@@ -11,7 +13,8 @@ use once_cell::sync::Lazy;
 use uzers::UsersCache;
 
 use crate::fs::feature::git::GitCache;
-use crate::fs::{fields as f, File};
+use crate::fs::snapshot::{self, Snapshot};
+use crate::fs::{codeowners, fields as f, File};
 use crate::output::cell::TextCell;
 #[cfg(unix)]
 use crate::output::render::{GroupRender, OctalPermissionsRender, UserRender};
@@ -23,10 +26,60 @@ use crate::theme::Theme;
 #[derive(PartialEq, Eq, Debug)]
 pub struct Options {
     pub size_format: SizeFormat,
+
+    /// The format of the blocksize column, which can be different from the
+    /// one for sizes when it comes from the environment.
+    pub blocksize_format: SizeFormat,
+
+    /// Whether the size column shows how much space files take up on disk,
+    /// rather than how big they are.
+    pub disk_usage: bool,
+
     pub time_format: TimeFormat,
     pub user_format: UserFormat,
     pub group_format: GroupFormat,
     pub columns: Columns,
+
+    /// The snapshot that the delta column compares against.
+    pub snapshot: Option<PathBuf>,
+
+    /// The names of the columns in the order they were given to
+    /// `--columns`, if they were picked that way.
+    pub order: Option<Vec<&'static str>>,
+
+    /// The columns given to `--align`, with the alignment each one gets in
+    /// place of its usual one. Later ones win.
+    pub align: Vec<(&'static str, Alignment)>,
+}
+
+/// The names that columns can be picked out by, in the order they’re
+/// displayed in when they aren’t given one. The file name always comes last.
+#[rustfmt::skip]
+pub const COLUMN_NAMES: &[&str] = &[
+    "inode", "octal", "perms", "links", "size", "blocksize", "user", "group", "context",
+    "modified", "changed", "created", "accessed", "age", "git", "repos", "repos-no-status",
+    "codeowners", "delta", "name",
+];
+
+/// The names that can be given to `--hide`, which are the names of the
+/// columns apart from the file name, and `time` for every timestamp.
+#[rustfmt::skip]
+pub const HIDE_NAMES: &[&str] = &[
+    "inode", "octal", "perms", "links", "size", "blocksize", "user", "group", "context",
+    "modified", "changed", "created", "accessed", "age", "git", "repos", "repos-no-status",
+    "codeowners", "delta", "time",
+];
+
+impl Options {
+    /// The alignment given to the column with the given name by `--align`,
+    /// if it was given one.
+    fn alignment_of(&self, name: &str) -> Option<Alignment> {
+        self.align
+            .iter()
+            .rev()
+            .find(|(n, _)| *n == name)
+            .map(|(_, alignment)| *alignment)
+    }
 }
 
 /// Extra columns to display in the table.
@@ -46,6 +99,11 @@ pub struct Columns {
     pub subdir_git_repos_no_stat: bool,
     pub octal: bool,
     pub security_context: bool,
+    pub codeowners: bool,
+    pub delta: bool,
+
+    /// The unit to show each file’s age in, if the age column is shown.
+    pub age: Option<AgeUnit>,
 
     // Defaults to true:
     pub permissions: bool,
@@ -116,6 +174,10 @@ impl Columns {
             columns.push(Column::Timestamp(TimeType::Accessed));
         }
 
+        if let Some(unit) = self.age {
+            columns.push(Column::Age(self.time_types.first(), unit));
+        }
+
         if self.git && actually_enable_git {
             columns.push(Column::GitStatus);
         }
@@ -128,6 +190,14 @@ impl Columns {
             columns.push(Column::SubdirGitRepo(false));
         }
 
+        if self.codeowners {
+            columns.push(Column::CodeOwners);
+        }
+
+        if self.delta {
+            columns.push(Column::Delta);
+        }
+
         columns
     }
 }
@@ -138,6 +208,7 @@ pub enum Column {
     Permissions,
     FileSize,
     Timestamp(TimeType),
+    Age(TimeType, AgeUnit),
     #[cfg(unix)]
     Blocksize,
     #[cfg(unix)]
@@ -154,11 +225,13 @@ pub enum Column {
     Octal,
     #[cfg(unix)]
     SecurityContext,
+    CodeOwners,
+    Delta,
 }
 
 /// Each column can pick its own **Alignment**. Usually, numbers are
 /// right-aligned, and text is left-aligned.
-#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum Alignment {
     Left,
     Right,
@@ -170,9 +243,13 @@ impl Column {
     pub fn alignment(self) -> Alignment {
         #[allow(clippy::wildcard_in_or_patterns)]
         match self {
-            Self::FileSize | Self::HardLinks | Self::Inode | Self::Blocksize | Self::GitStatus => {
-                Alignment::Right
-            }
+            Self::FileSize
+            | Self::HardLinks
+            | Self::Inode
+            | Self::Blocksize
+            | Self::GitStatus
+            | Self::Age(..)
+            | Self::Delta => Alignment::Right,
             Self::Timestamp(_) | _ => Alignment::Left,
         }
     }
@@ -180,11 +257,43 @@ impl Column {
     #[cfg(windows)]
     pub fn alignment(self) -> Alignment {
         match self {
-            Self::FileSize | Self::GitStatus => Alignment::Right,
+            Self::FileSize | Self::GitStatus | Self::Age(..) | Self::Delta => Alignment::Right,
             _ => Alignment::Left,
         }
     }
 
+    /// The name that picks this column out in `--columns`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Permissions => "perms",
+            Self::FileSize => "size",
+            Self::Timestamp(TimeType::Modified) => "modified",
+            Self::Timestamp(TimeType::Changed) => "changed",
+            Self::Timestamp(TimeType::Created) => "created",
+            Self::Timestamp(TimeType::Accessed) => "accessed",
+            Self::Age(..) => "age",
+            #[cfg(unix)]
+            Self::Blocksize => "blocksize",
+            #[cfg(unix)]
+            Self::User => "user",
+            #[cfg(unix)]
+            Self::Group => "group",
+            #[cfg(unix)]
+            Self::HardLinks => "links",
+            #[cfg(unix)]
+            Self::Inode => "inode",
+            Self::GitStatus => "git",
+            Self::SubdirGitRepo(true) => "repos",
+            Self::SubdirGitRepo(false) => "repos-no-status",
+            #[cfg(unix)]
+            Self::Octal => "octal",
+            #[cfg(unix)]
+            Self::SecurityContext => "context",
+            Self::CodeOwners => "codeowners",
+            Self::Delta => "delta",
+        }
+    }
+
     /// Get the text that should be printed at the top, when the user elects
     /// to have a header row printed.
     pub fn header(self) -> &'static str {
@@ -195,6 +304,7 @@ impl Column {
             Self::Permissions => "Mode",
             Self::FileSize => "Size",
             Self::Timestamp(t) => t.header(),
+            Self::Age(..) => "Age",
             #[cfg(unix)]
             Self::Blocksize => "Blocksize",
             #[cfg(unix)]
@@ -211,6 +321,8 @@ impl Column {
             Self::Octal => "Octal",
             #[cfg(unix)]
             Self::SecurityContext => "Security Context",
+            Self::CodeOwners => "Owners",
+            Self::Delta => "Delta",
         }
     }
 }
@@ -229,6 +341,43 @@ pub enum SizeFormat {
 
     /// Do no formatting and just display the size as a number of bytes.
     JustBytes,
+
+    /// Display the size as a number of blocks of a fixed size, in the same
+    /// way as the `--block-size` option to GNU `ls`.
+    Blocks(BlockSize),
+}
+
+/// The size of the blocks to scale file sizes by, such as `K` or `1M`.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct BlockSize {
+    /// The number of bytes in each block.
+    pub bytes: u64,
+
+    /// The unit to write after each size, which is only done when the block
+    /// size is given as just a unit, without a number.
+    pub suffix: Option<&'static str>,
+
+    /// Whether to group the digits using the locale’s thousands separator,
+    /// or commas if it doesn’t have one.
+    pub separators: bool,
+}
+
+impl BlockSize {
+    /// Formats a number of bytes as a number of blocks, rounding up, as even
+    /// a one-byte file takes up part of a block.
+    pub fn format(self, bytes: u64, numerics: &locale::Numeric) -> String {
+        let blocks = bytes / self.bytes + u64::from(bytes % self.bytes != 0);
+
+        if !self.separators {
+            blocks.to_string()
+        } else if numerics.thousands_sep.is_empty() {
+            // The C locale doesn’t group digits at all, which would make
+            // asking for them do nothing.
+            locale::Numeric::english().format_int(blocks)
+        } else {
+            numerics.format_int(blocks)
+        }
+    }
 }
 
 /// Formatting options for user and group.
@@ -299,6 +448,24 @@ pub struct TimeTypes {
     pub created:  bool,
 }
 
+impl TimeTypes {
+    /// The first of the timestamps that get shown, which is the one the age
+    /// column counts from, or the modified time if none of them are.
+    fn first(self) -> TimeType {
+        if self.modified {
+            TimeType::Modified
+        } else if self.changed {
+            TimeType::Changed
+        } else if self.created {
+            TimeType::Created
+        } else if self.accessed {
+            TimeType::Accessed
+        } else {
+            TimeType::Modified
+        }
+    }
+}
+
 impl Default for TimeTypes {
     /// By default, display just the ‘modified’ time. This is the most
     /// common option, which is why it has this shorthand.
@@ -312,6 +479,29 @@ impl Default for TimeTypes {
     }
 }
 
+/// The unit of time that the age column counts in. Ages are always whole
+/// numbers of the one unit, so they can be compared and sorted by scripts.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum AgeUnit {
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+impl AgeUnit {
+    /// How many whole units of this kind there are in a length of time,
+    /// which is negative for times in the future.
+    pub fn count(self, duration: chrono::Duration) -> i64 {
+        match self {
+            Self::Seconds => duration.num_seconds(),
+            Self::Minutes => duration.num_minutes(),
+            Self::Hours => duration.num_hours(),
+            Self::Days => duration.num_days(),
+        }
+    }
+}
+
 /// The **environment** struct contains any data that could change between
 /// running instances of exa, depending on the user’s computer’s configuration.
 ///
@@ -323,6 +513,10 @@ pub struct Environment {
     /// Localisation rules for formatting numbers.
     numeric: locale::Numeric,
 
+    /// The time when eza started, which ages are counted up to, so every
+    /// file gets compared against the same moment.
+    now: NaiveDateTime,
+
     /// Mapping cache of user IDs to usernames.
     #[cfg(unix)]
     users: Mutex<UsersCache>,
@@ -346,6 +540,7 @@ impl Environment {
         Self {
             time_offset,
             numeric,
+            now: Utc::now().naive_utc(),
             #[cfg(unix)]
             users,
         }
@@ -356,16 +551,25 @@ static ENVIRONMENT: Lazy<Environment> = Lazy::new(Environment::load_all);
 
 pub struct Table<'a> {
     columns: Vec<Column>,
+    alignments: Vec<Alignment>,
+    name_alignment: Alignment,
+
+    /// The styles that each column gets painted in, if the theme gives it
+    /// one in place of its colours.
+    styles: Vec<Option<Style>>,
     theme: &'a Theme,
     env: &'a Environment,
     widths: TableWidths,
     time_format: TimeFormat,
     size_format: SizeFormat,
+    blocksize_format: SizeFormat,
+    disk_usage: bool,
     #[cfg(unix)]
     user_format: UserFormat,
     #[cfg(unix)]
     group_format: GroupFormat,
     git: Option<&'a GitCache>,
+    snapshot: Option<&'static Snapshot>,
 }
 
 #[derive(Clone)]
@@ -375,18 +579,36 @@ pub struct Row {
 
 impl<'a> Table<'a> {
     pub fn new(options: &'a Options, git: Option<&'a GitCache>, theme: &'a Theme) -> Table<'a> {
-        let columns = options.columns.collect(git.is_some());
+        let mut columns = options.columns.collect(git.is_some());
+        if let Some(order) = &options.order {
+            columns.sort_by_key(|c| order.iter().position(|name| *name == c.name()));
+        }
         let widths = TableWidths::zero(columns.len());
         let env = &*ENVIRONMENT;
+        let alignments = columns
+            .iter()
+            .map(|c| {
+                options
+                    .alignment_of(c.name())
+                    .unwrap_or_else(|| c.alignment())
+            })
+            .collect();
+        let styles = columns.iter().map(|c| theme.ui.column(c.name())).collect();
 
         Table {
             theme,
             widths,
             columns,
+            alignments,
+            styles,
+            name_alignment: options.alignment_of("name").unwrap_or(Alignment::Left),
             git,
             env,
+            snapshot: options.snapshot.as_deref().and_then(snapshot::load),
             time_format: options.time_format.clone(),
             size_format: options.size_format,
+            blocksize_format: options.blocksize_format,
+            disk_usage: options.disk_usage,
             #[cfg(unix)]
             user_format: options.user_format,
             #[cfg(unix)]
@@ -394,6 +616,11 @@ impl<'a> Table<'a> {
         }
     }
 
+    /// How the names after the table should be aligned.
+    pub fn name_alignment(&self) -> Alignment {
+        self.name_alignment
+    }
+
     pub fn widths(&self) -> &TableWidths {
         &self.widths
     }
@@ -412,7 +639,14 @@ impl<'a> Table<'a> {
         let cells = self
             .columns
             .iter()
-            .map(|c| self.display(file, *c, xattrs))
+            .zip(&self.styles)
+            .map(|(c, style)| {
+                let mut cell = self.display(file, *c, xattrs);
+                if let Some(style) = style {
+                    cell.restyle(*style);
+                }
+                cell
+            })
             .collect();
 
         Row { cells }
@@ -448,12 +682,40 @@ impl<'a> Table<'a> {
             .map(|p| f::OctalPermissions { permissions: p })
     }
 
+    fn timestamp(file: &File<'_>, time_type: TimeType) -> Option<NaiveDateTime> {
+        match time_type {
+            TimeType::Modified => file.modified_time(),
+            TimeType::Changed => file.changed_time(),
+            TimeType::Accessed => file.accessed_time(),
+            TimeType::Created => file.created_time(),
+        }
+    }
+
+    /// How long ago the given timestamp of a file was, as a plain number
+    /// of the given unit.
+    fn age(&self, file: &File<'_>, time_type: TimeType, unit: AgeUnit) -> TextCell {
+        match Self::timestamp(file, time_type) {
+            Some(time) => {
+                let age = self.env.now - time;
+                TextCell::paint(self.theme.date(age), unit.count(age).to_string())
+            }
+            None => TextCell::blank(self.theme.ui.punctuation),
+        }
+    }
+
     fn display(&self, file: &File<'_>, column: Column, xattrs: bool) -> TextCell {
         match column {
             Column::Permissions => self.permissions_plus(file, xattrs).render(self.theme),
-            Column::FileSize => file
-                .size()
-                .render(self.theme, self.size_format, &self.env.numeric),
+            Column::FileSize if self.disk_usage => file.disk_usage().render(
+                &self.theme.size_colours(file),
+                self.size_format,
+                &self.env.numeric,
+            ),
+            Column::FileSize => file.size().render(
+                &self.theme.size_colours(file),
+                self.size_format,
+                &self.env.numeric,
+            ),
             #[cfg(unix)]
             Column::HardLinks => file.links().render(self.theme, &self.env.numeric),
             #[cfg(unix)]
@@ -461,7 +723,7 @@ impl<'a> Table<'a> {
             #[cfg(unix)]
             Column::Blocksize => {
                 file.blocksize()
-                    .render(self.theme, self.size_format, &self.env.numeric)
+                    .render(self.theme, self.blocksize_format, &self.env.numeric)
             }
             #[cfg(unix)]
             Column::User => {
@@ -480,29 +742,26 @@ impl<'a> Table<'a> {
             Column::SecurityContext => file.security_context().render(self.theme),
             Column::GitStatus => self.git_status(file).render(self.theme),
             Column::SubdirGitRepo(status) => self.subdir_git_repo(file, status).render(self.theme),
+            Column::CodeOwners => match codeowners::owners(&file.path, file.is_directory()) {
+                Some(owners) => TextCell::paint(self.theme.ui.codeowners, owners),
+                None => TextCell::blank(self.theme.ui.punctuation),
+            },
+            Column::Delta => match self.snapshot.and_then(|s| s.delta(&file.path)) {
+                Some(delta) => delta.render(self.theme, file.is_directory(), &self.env.numeric),
+                None => TextCell::blank(self.theme.ui.punctuation),
+            },
             #[cfg(unix)]
             Column::Octal => self.octal_permissions(file).render(self.theme.ui.octal),
 
-            Column::Timestamp(TimeType::Modified) => file.modified_time().render(
-                self.theme.ui.date,
-                self.env.time_offset,
-                self.time_format.clone(),
-            ),
-            Column::Timestamp(TimeType::Changed) => file.changed_time().render(
-                self.theme.ui.date,
-                self.env.time_offset,
-                self.time_format.clone(),
-            ),
-            Column::Timestamp(TimeType::Created) => file.created_time().render(
-                self.theme.ui.date,
-                self.env.time_offset,
-                self.time_format.clone(),
-            ),
-            Column::Timestamp(TimeType::Accessed) => file.accessed_time().render(
-                self.theme.ui.date,
-                self.env.time_offset,
-                self.time_format.clone(),
-            ),
+            Column::Age(time_type, unit) => self.age(file, time_type, unit),
+
+            Column::Timestamp(time_type) => {
+                let time = Self::timestamp(file, time_type);
+                let style = time.map_or(self.theme.ui.date, |time| {
+                    self.theme.date(self.env.now - time)
+                });
+                time.render(style, self.env.time_offset, self.time_format.clone())
+            }
         }
     }
 
@@ -523,15 +782,29 @@ impl<'a> Table<'a> {
         f::SubdirGitRepo::default()
     }
 
-    pub fn render(&self, row: Row) -> TextCell {
-        let mut cell = TextCell::default();
+    /// A row with nothing in any of its cells, for the rows that only have
+    /// something in the name column.
+    pub fn blank_row(&self) -> Row {
+        Row {
+            cells: vec![TextCell::default(); self.columns.len()],
+        }
+    }
+
+    /// Puts the cells of a row together, padding each one out to the width
+    /// of its column and putting the separator after it.
+    pub fn render(&self, row: Row, start: TextCell, separator: &TextCell) -> TextCell {
+        let mut cell = start;
 
-        let iter = row.cells.into_iter().zip(self.widths.iter()).enumerate();
+        let iter = row
+            .cells
+            .into_iter()
+            .zip(self.widths.iter())
+            .zip(&self.alignments);
 
-        for (n, (this_cell, width)) in iter {
+        for ((this_cell, width), alignment) in iter {
             let padding = width - *this_cell.width;
 
-            match self.columns[n].alignment() {
+            match alignment {
                 Alignment::Left => {
                     cell.append(this_cell);
                     cell.add_spaces(padding);
@@ -542,7 +815,7 @@ impl<'a> Table<'a> {
                 }
             }
 
-            cell.add_spaces(1);
+            cell.append(separator.clone());
         }
 
         cell
@@ -569,8 +842,4 @@ impl TableWidths {
             *old_width = max(*old_width, *cell.width);
         }
     }
-
-    pub fn total(&self) -> usize {
-        self.0.len() + self.0.iter().sum::<usize>()
-    }
 }
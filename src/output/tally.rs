@@ -0,0 +1,101 @@
+//! The running totals printed by `--count-only`, `--summary`, and
+//! `--git-summary`, which get added to as the entries are listed, so the
+//! directories don’t have to be read a second time to count them.
+
+use crate::fs::feature::git::GitCache;
+use crate::fs::fields as f;
+use crate::fs::File;
+
+/// How many entries were listed, how big they are, and what Git thinks of
+/// them.
+#[derive(Debug, Default)]
+pub struct Tally {
+    /// The number of entries that were listed.
+    pub count: usize,
+
+    /// How many of those entries are directories.
+    pub dirs: usize,
+
+    /// The combined size of those entries, in bytes.
+    pub size: u64,
+
+    /// How many of those entries have changes in Git, when the Git column
+    /// is being shown.
+    pub modified: usize,
+
+    /// How many of those entries are new to Git.
+    pub untracked: usize,
+
+    /// How many of those entries have changes that haven’t been staged,
+    /// not counting new ones.
+    pub unstaged: usize,
+
+    /// How many of those entries have changes that have been staged.
+    pub staged: usize,
+
+    /// How many of those entries have merge conflicts.
+    pub conflicted: usize,
+
+    /// Whether to add up the space the entries take up on disk, rather
+    /// than their sizes.
+    disk_usage: bool,
+
+    /// Whether to count the entries’ Git statuses, which only get counted
+    /// when they would be shown.
+    git: bool,
+}
+
+impl Tally {
+    pub fn new(disk_usage: bool, git: bool) -> Self {
+        Self {
+            disk_usage,
+            git,
+            ..Self::default()
+        }
+    }
+
+    /// Counts an entry, adding its size to the total.
+    pub fn add(&mut self, file: &File<'_>, git: Option<&GitCache>) {
+        self.add_unsized(file, git);
+        let size = if self.disk_usage {
+            file.disk_usage()
+        } else {
+            file.size()
+        };
+        if let f::Size::Some(bytes) = size {
+            self.size += bytes;
+        }
+    }
+
+    /// Counts an entry without its size, which is how directories that get
+    /// descended into are counted, so that their contents don’t get counted
+    /// twice with `--total-size`.
+    pub fn add_unsized(&mut self, file: &File<'_>, git: Option<&GitCache>) {
+        use crate::fs::fields::GitStatus::*;
+
+        self.count += 1;
+        if file.is_directory() {
+            self.dirs += 1;
+        }
+
+        if let Some(git) = git.filter(|_| self.git) {
+            let status = git.get(&file.path, file.is_directory());
+            match (status.staged, status.unstaged) {
+                (_, New) => self.untracked += 1,
+                (NotModified | Ignored, NotModified | Ignored) => {}
+                _ => self.modified += 1,
+            }
+
+            if status.staged == Conflicted || status.unstaged == Conflicted {
+                self.conflicted += 1;
+            } else {
+                if !matches!(status.staged, NotModified | Ignored) {
+                    self.staged += 1;
+                }
+                if matches!(status.unstaged, Modified | Deleted | Renamed | TypeChange) {
+                    self.unstaged += 1;
+                }
+            }
+        }
+    }
+}
@@ -53,16 +53,56 @@ pub enum TreePart {
     Blank,
 }
 
+/// Which characters the tree parts get drawn with.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum TreeStyle {
+    /// Thin box-drawing characters, like `├──`.
+    #[default]
+    Unicode,
+
+    /// Plain ASCII, like `|--`, for terminals and logs that can’t show
+    /// box-drawing characters.
+    Ascii,
+
+    /// Thin box-drawing characters with a rounded corner, like `╰──`.
+    Rounded,
+
+    /// Heavy box-drawing characters, like `┣━━`.
+    Bold,
+}
+
 impl TreePart {
+    /// The part to draw beneath this one on the lines that a row’s name
+    /// carries on onto, which only carries on the branches that go past it.
+    pub fn continued(self) -> Self {
+        match self {
+            Self::Edge | Self::Line => Self::Line,
+            Self::Corner | Self::Blank => Self::Blank,
+        }
+    }
+
     /// Turn this tree part into ASCII-licious box drawing characters!
-    /// (Warning: not actually ASCII)
-    pub fn ascii_art(self) -> &'static str {
+    /// (Warning: only actually ASCII with the ASCII style)
+    pub fn ascii_art(self, style: TreeStyle) -> &'static str {
         #[rustfmt::skip]
-        return match self {
-            Self::Edge    => "├──",
-            Self::Line    => "│  ",
-            Self::Corner  => "└──",
-            Self::Blank   => "   ",
+        return match (style, self) {
+            (TreeStyle::Unicode, Self::Edge)    => "├──",
+            (TreeStyle::Unicode, Self::Line)    => "│  ",
+            (TreeStyle::Unicode, Self::Corner)  => "└──",
+
+            (TreeStyle::Ascii,   Self::Edge)    => "|--",
+            (TreeStyle::Ascii,   Self::Line)    => "|  ",
+            (TreeStyle::Ascii,   Self::Corner)  => "`--",
+
+            (TreeStyle::Rounded, Self::Edge)    => "├──",
+            (TreeStyle::Rounded, Self::Line)    => "│  ",
+            (TreeStyle::Rounded, Self::Corner)  => "╰──",
+
+            (TreeStyle::Bold,    Self::Edge)    => "┣━━",
+            (TreeStyle::Bold,    Self::Line)    => "┃  ",
+            (TreeStyle::Bold,    Self::Corner)  => "┗━━",
+
+            (_,                  Self::Blank)   => "   ",
         };
     }
 }
@@ -147,6 +187,43 @@ impl TreeParams {
     pub fn is_at_root(&self) -> bool {
         self.depth.0 == 0
     }
+
+    pub fn depth(&self) -> TreeDepth {
+        self.depth
+    }
+
+    /// The same entry, one level further down, for when the whole tree goes
+    /// beneath another root.
+    pub fn nested(self) -> Self {
+        Self {
+            depth: self.depth.deeper(),
+            ..self
+        }
+    }
+}
+
+/// Moves the entries deeper than the given number of levels up by that many,
+/// for when the entries in those levels (other than at the root) have been
+/// left out, and works out again which entries are the last at their depth,
+/// so the lines join up.
+pub fn skip_levels<'a, I>(params: I, levels: usize)
+where
+    I: DoubleEndedIterator<Item = &'a mut TreeParams>,
+{
+    // Whether an entry comes later at each depth, before the tree goes back
+    // up past it.
+    let mut followed = Vec::new();
+
+    for params in params.rev() {
+        if params.depth.0 > levels {
+            params.depth.0 -= levels;
+        }
+
+        let depth = params.depth.0;
+        followed.resize(depth + 1, false);
+        params.last = !followed[depth];
+        followed[depth] = true;
+    }
 }
 
 impl TreeDepth {
@@ -252,6 +329,34 @@ mod trunk_test {
     }
 }
 
+#[cfg(test)]
+mod skip_test {
+    use super::*;
+
+    #[test]
+    fn relinks() {
+        // Two directories at depth 1 have been left out, leaving the entries
+        // in them at depth 2.
+        let mut params = [
+            TreeParams::new(TreeDepth(0), true),
+            TreeParams::new(TreeDepth(2), false),
+            TreeParams::new(TreeDepth(2), true),
+            TreeParams::new(TreeDepth(3), true),
+            TreeParams::new(TreeDepth(2), true),
+        ];
+        skip_levels(params.iter_mut(), 1);
+
+        let shape = params
+            .iter()
+            .map(|p| (p.depth.0, p.last))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            shape,
+            [(0, true), (1, false), (1, false), (2, true), (1, true)]
+        );
+    }
+}
+
 #[cfg(test)]
 mod iter_test {
     use super::*;
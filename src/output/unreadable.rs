@@ -0,0 +1,139 @@
+//! Keeping track of the directories that couldn’t be read while recursing,
+//! so they can be listed together once everything else has been printed,
+//! instead of being mixed in with the output where a program reading it
+//! would trip over them.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+/// The directories that couldn’t be read, along with why.
+#[derive(Debug, Default)]
+pub struct Unreadable {
+    /// Whether each error gets printed where it happened, which is how it
+    /// used to be done, rather than in a summary at the end.
+    inline: bool,
+
+    dirs: Mutex<Vec<(PathBuf, String)>>,
+}
+
+impl Unreadable {
+    pub fn new(inline: bool) -> Self {
+        Self {
+            inline,
+            dirs: Mutex::default(),
+        }
+    }
+
+    /// Whether errors get printed where they happened.
+    pub fn is_inline(&self) -> bool {
+        self.inline
+    }
+
+    /// Notes that the directory at the given path couldn’t be read. This
+    /// doesn’t print anything, so the caller has to print the error itself
+    /// if it’s inline.
+    pub fn note(&self, path: &Path, error: &io::Error) {
+        self.dirs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push((path.to_path_buf(), error.to_string()));
+    }
+
+    /// Notes that the directory at the given path couldn’t be read, printing
+    /// the error to standard error straight away if it’s inline.
+    pub fn add(&self, path: &Path, error: &io::Error) -> io::Result<()> {
+        self.note(path, error);
+        if self.inline {
+            writeln!(io::stderr(), "{}: {}", path.display(), error)?;
+        }
+        Ok(())
+    }
+
+    /// Whether any directories couldn’t be read.
+    pub fn is_empty(&self) -> bool {
+        self.dirs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_empty()
+    }
+
+    /// Prints the directories that couldn’t be read, grouped by the error,
+    /// unless they’ve been printed already.
+    pub fn print_summary<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        if self.inline {
+            return Ok(());
+        }
+
+        let mut groups = BTreeMap::<&str, Vec<&Path>>::new();
+        let dirs = self.dirs.lock().unwrap_or_else(PoisonError::into_inner);
+        for (path, error) in dirs.iter() {
+            groups.entry(error).or_default().push(path);
+        }
+
+        for (error, mut paths) in groups {
+            paths.sort_unstable();
+            paths.dedup();
+
+            let plural = if paths.len() == 1 { "y" } else { "ies" };
+            writeln!(
+                w,
+                "eza: {} director{plural} couldn’t be read: {error}",
+                paths.len()
+            )?;
+            for path in paths {
+                writeln!(w, "  {}", path.display())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn summary(unreadable: &Unreadable) -> String {
+        let mut out = Vec::new();
+        unreadable.print_summary(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn grouped() {
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+        let missing = io::Error::from(io::ErrorKind::NotFound);
+
+        let unreadable = Unreadable::new(false);
+        unreadable.note(Path::new("b/secret"), &denied);
+        unreadable.note(Path::new("gone"), &missing);
+        unreadable.note(Path::new("a/secret"), &denied);
+
+        assert_eq!(
+            summary(&unreadable),
+            "eza: 1 directory couldn’t be read: entity not found\n  \
+             gone\n\
+             eza: 2 directories couldn’t be read: permission denied\n  \
+             a/secret\n  b/secret\n"
+        );
+    }
+
+    #[test]
+    fn nothing() {
+        assert_eq!(summary(&Unreadable::new(false)), "");
+    }
+
+    #[test]
+    fn inline() {
+        let unreadable = Unreadable::new(true);
+        unreadable.note(
+            Path::new("secret"),
+            &io::Error::from(io::ErrorKind::PermissionDenied),
+        );
+
+        assert!(!unreadable.is_empty());
+        assert_eq!(summary(&unreadable), "");
+    }
+}
@@ -0,0 +1,150 @@
+//! Working out whether the terminal has a light or a dark background, for
+//! `--theme=auto` and the light and dark palettes in the theme file.
+//!
+//! The `COLORFGBG` variable gets checked first, as it doesn’t take anything
+//! to read it. Without it, the terminal gets asked for its background colour
+//! with the OSC 11 escape sequence, followed by a request for its attributes
+//! that every terminal answers, so one that doesn’t know about OSC 11 can be
+//! given up on without having to wait.
+
+/// Whether the terminal has a light or a dark background.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Background {
+    Light,
+    Dark,
+}
+
+impl Background {
+    /// Works out the background from a `COLORFGBG` value, such as `15;0` or
+    /// `0;default;15`. The background is the last number, and the light
+    /// colours are white and the bright colours other than grey.
+    pub fn from_colorfgbg(colorfgbg: &str) -> Option<Self> {
+        match colorfgbg.rsplit(';').next()?.parse::<u8>().ok()? {
+            7 | 9..=15 => Some(Self::Light),
+            _ => Some(Self::Dark),
+        }
+    }
+
+    /// Works out the background from a terminal’s answer to OSC 11, such as
+    /// `ESC ] 11 ; rgb:ffff/ffff/dddd BEL`, by how bright its colour is.
+    fn from_osc_11(response: &str) -> Option<Self> {
+        let start = response.find("]11;rgb:")? + "]11;rgb:".len();
+        let rgb = &response[start..];
+        let end = rgb
+            .find(|c: char| !(c.is_ascii_hexdigit() || c == '/'))
+            .unwrap_or(rgb.len());
+
+        let mut channels = rgb[..end].split('/').map(|channel| {
+            let max = 16_f64.powi(i32::try_from(channel.len()).ok()?) - 1.0;
+            let value = u16::from_str_radix(channel, 16).ok()?;
+            Some(f64::from(value) / max)
+        });
+        let (r, g, b) = (channels.next()??, channels.next()??, channels.next()??);
+
+        let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        Some(if luminance > 0.5 {
+            Self::Light
+        } else {
+            Self::Dark
+        })
+    }
+
+    /// Asks the terminal what colour its background is, giving up if it
+    /// doesn’t answer within a tenth of a second.
+    #[cfg(unix)]
+    pub fn query() -> Option<Self> {
+        use std::fs::OpenOptions;
+        use std::io::{Read, Write};
+        use std::os::unix::io::AsRawFd;
+        use std::time::{Duration, Instant};
+
+        let mut tty = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")
+            .ok()?;
+        let fd = tty.as_raw_fd();
+
+        // Turn off echoing and line buffering, so the answer can be read as
+        // it arrives without showing up on the screen.
+        let mut original = unsafe { std::mem::zeroed::<libc::termios>() };
+        if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+            return None;
+        }
+        let mut raw = original;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+        if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+            return None;
+        }
+
+        let mut response = Vec::new();
+        if tty.write_all(b"\x1b]11;?\x1b\\\x1b[c").is_ok() {
+            let deadline = Instant::now() + Duration::from_millis(100);
+            let mut buffer = [0; 64];
+
+            // Read until the answer to the attributes request, which ends
+            // with a `c`, as it comes after the one for the background.
+            while !response.ends_with(b"c") {
+                let left = deadline.saturating_duration_since(Instant::now());
+                let mut poll = libc::pollfd {
+                    fd,
+                    events: libc::POLLIN,
+                    revents: 0,
+                };
+                let timeout = libc::c_int::try_from(left.as_millis()).unwrap_or(0);
+                if left.is_zero() || unsafe { libc::poll(&mut poll, 1, timeout) } <= 0 {
+                    break;
+                }
+
+                match tty.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(count) => response.extend_from_slice(&buffer[..count]),
+                }
+            }
+        }
+
+        unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original) };
+        Self::from_osc_11(&String::from_utf8_lossy(&response))
+    }
+
+    #[cfg(not(unix))]
+    pub fn query() -> Option<Self> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn colorfgbg() {
+        assert_eq!(Background::from_colorfgbg("15;0"), Some(Background::Dark));
+        assert_eq!(Background::from_colorfgbg("0;15"), Some(Background::Light));
+        assert_eq!(
+            Background::from_colorfgbg("0;default;7"),
+            Some(Background::Light)
+        );
+        assert_eq!(Background::from_colorfgbg("0;8"), Some(Background::Dark));
+        assert_eq!(Background::from_colorfgbg("0;default"), None);
+        assert_eq!(Background::from_colorfgbg(""), None);
+    }
+
+    #[test]
+    fn osc_11() {
+        assert_eq!(
+            Background::from_osc_11("\x1b]11;rgb:ffff/ffff/dddd\x07\x1b[?62;22c"),
+            Some(Background::Light)
+        );
+        assert_eq!(
+            Background::from_osc_11("\x1b]11;rgb:1c1c/1c1c/1c1c\x1b\\"),
+            Some(Background::Dark)
+        );
+        assert_eq!(
+            Background::from_osc_11("\x1b]11;rgb:fd/f6/e3\x07"),
+            Some(Background::Light)
+        );
+        assert_eq!(Background::from_osc_11("\x1b[?62;22c"), None);
+        assert_eq!(Background::from_osc_11("\x1b]11;rgb:ffff/ffff\x07"), None);
+    }
+}
@@ -1,6 +1,7 @@
 use ansiterm::Colour::*;
 use ansiterm::Style;
 
+use crate::theme::gradient::Gradient;
 use crate::theme::ui_styles::*;
 use crate::theme::ColourScale;
 
@@ -21,6 +22,7 @@ impl UiStyles {
                 special:      Yellow.normal(),
                 executable:   Green.bold(),
                 mount_point:  Blue.bold().underline(),
+                tool_directory: None,
             },
 
             #[rustfmt::skip]
@@ -105,19 +107,37 @@ impl UiStyles {
                 compiled:   Yellow.normal(),
                 build:      Yellow.bold().underline(),
                 source:     Yellow.bold(), // Need to discuss color
+                config:     Yellow.italic(),
             },
 
+            size_file_type: SizeFileType::default(),
+
             punctuation: DarkGray.bold(),
             date: Blue.normal(),
             inode: Purple.normal(),
             blocks: Cyan.normal(),
             octal: Purple.normal(),
+            codeowners: Yellow.normal(),
+            delta_grown: Red.normal(),
+            delta_shrunk: Green.normal(),
             header: Style::default().underline(),
 
             symlink_path: Cyan.normal(),
             control_char: Red.normal(),
             broken_symlink: Red.normal(),
             broken_path_overlay: Style::default().underline(),
+            suspicious_overlay: Style::default().on(Red),
+
+            empty_placeholder: DarkGray.italic(),
+            group_header: Style::default().bold(),
+            zebra: None,
+            icon: Style::default(),
+            icons: Vec::new(),
+            glyphs: Vec::new(),
+            columns: Vec::new(),
+            size_gradient: Gradient::default(),
+            age_gradient: Gradient::default(),
+            hashed_owners: false,
         }
     }
 }
@@ -146,6 +166,8 @@ impl Size {
             unit_mega: Green.normal(),
             unit_giga: Green.normal(),
             unit_huge: Green.normal(),
+
+            thresholds: [None; 4],
         }
     }
 
@@ -165,6 +187,8 @@ impl Size {
             unit_mega: Yellow.normal(),
             unit_giga: Red.normal(),
             unit_huge: Purple.normal(),
+
+            thresholds: [None; 4],
         }
     }
 }
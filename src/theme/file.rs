@@ -0,0 +1,328 @@
+//! Reading a theme from the `theme.toml` file in the config directory, which
+//! can set everything that `EZA_COLORS` can without it all having to go in
+//! one variable, as well as the colours of the icons of some files.
+//!
+//! The file has a table for each kind of style, with the same keys and style
+//! codes as `EZA_COLORS`:
+//!
+//! ```toml
+//! [palette]
+//! accent = "38;5;208"
+//!
+//! [ui]
+//! di = "1;accent"
+//! "column.inode" = "2"
+//!
+//! [files]
+//! "*.rs" = "accent"
+//!
+//! [icons]
+//! "*.rs" = "33"
+//!
+//! [glyphs]
+//! "*.acme" = "󰈙"
+//! ```
+//!
+//! The names in the palette can be used in place of codes in any of the
+//! other styles. The `[palette.light]` and `[palette.dark]` tables can give
+//! some of those names different styles on light and dark backgrounds. The
+//! file gets applied after the built-in theme and before the environment
+//! variables, so they still override it. The icons in the `[glyphs]` table
+//! get shown in place of the built-in ones.
+
+use log::*;
+
+use crate::theme::lsc::Pair;
+use crate::theme::{Background, ExtensionMappings, UiStyles};
+
+/// The styles from a theme file.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct ThemeFile {
+    /// The names that can be used in the styles, and what they stand for.
+    palette: Vec<(String, String)>,
+
+    /// Names that stand for something else on a light background.
+    light: Vec<(String, String)>,
+
+    /// Names that stand for something else on a dark background.
+    dark: Vec<(String, String)>,
+
+    /// Styles for the keys understood by `LS_COLORS` and `EZA_COLORS`.
+    ui: Vec<(String, String)>,
+
+    /// Styles for the file names that match each glob.
+    files: Vec<(String, String)>,
+
+    /// Styles for the icons of the files that match each glob.
+    icons: Vec<(String, String)>,
+
+    /// The icons for the files that match each glob.
+    glyphs: Vec<(String, String)>,
+}
+
+impl ThemeFile {
+    /// The name of the file that the theme is read from, in the config
+    /// directory.
+    pub const FILE_NAME: &'static str = "theme.toml";
+
+    /// Reads the styles out of a theme file, in the order they’re defined.
+    /// Lines that can’t be read are skipped with a warning, as are tables
+    /// other than the ones above.
+    pub fn parse(contents: &str) -> Self {
+        let mut file = Self::default();
+        let mut table = String::new();
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                table = name.trim().to_owned();
+                continue;
+            }
+
+            let Some(pair) = parse_pair(line) else {
+                warn!("Invalid line in theme file: {line}");
+                continue;
+            };
+
+            match table.as_str() {
+                "palette" => file.palette.push(pair),
+                "palette.light" => file.light.push(pair),
+                "palette.dark" => file.dark.push(pair),
+                "ui" => file.ui.push(pair),
+                "files" => file.files.push(pair),
+                "icons" => file.icons.push(pair),
+                "glyphs" => file.glyphs.push(pair),
+                _ => warn!("Unknown table [{table}] in theme file"),
+            }
+        }
+
+        file
+    }
+
+    /// Whether the file has a palette for light or dark backgrounds, so
+    /// which one the terminal has needs to be known.
+    pub fn has_backgrounds(&self) -> bool {
+        !self.light.is_empty() || !self.dark.is_empty()
+    }
+
+    /// Sets the styles from the file on the UI styles, and adds the ones for
+    /// file names to the mappings, using the palette for the background if
+    /// it’s known.
+    pub(super) fn apply(
+        &self,
+        ui: &mut UiStyles,
+        exts: &mut ExtensionMappings,
+        background: Option<Background>,
+    ) {
+        let mut palette = self.palette.clone();
+        match background {
+            Some(Background::Light) => palette.extend_from_slice(&self.light),
+            Some(Background::Dark) => palette.extend_from_slice(&self.dark),
+            None => {}
+        }
+
+        for (key, value) in &self.ui {
+            let value = &substitute(value, &palette);
+            let pair = Pair { key, value };
+            if !ui.set_ls(&pair) && !ui.set_exa(&pair) {
+                warn!("Unknown key {key:?} in theme file");
+            }
+        }
+
+        for (glob, value) in &self.files {
+            let value = &substitute(value, &palette);
+            match glob::Pattern::new(glob) {
+                Ok(pattern) => exts.add(pattern, Pair { key: glob, value }.to_style()),
+                Err(e) => warn!("Couldn't parse glob pattern {glob:?}: {e}"),
+            }
+        }
+
+        for (glob, value) in &self.icons {
+            let value = &substitute(value, &palette);
+            match glob::Pattern::new(glob) {
+                Ok(pattern) => ui
+                    .icons
+                    .push((pattern, Pair { key: glob, value }.to_style())),
+                Err(e) => warn!("Couldn't parse glob pattern {glob:?}: {e}"),
+            }
+        }
+
+        for (glob, glyph) in &self.glyphs {
+            match glob::Pattern::new(glob) {
+                Ok(pattern) => ui.glyphs.push((pattern, glyph.clone())),
+                Err(e) => warn!("Couldn't parse glob pattern {glob:?}: {e}"),
+            }
+        }
+    }
+}
+
+/// Replaces the names from the palette in a style with the codes they stand
+/// for, leaving everything else as it is. Later names win.
+fn substitute(style: &str, palette: &[(String, String)]) -> String {
+    style
+        .split(';')
+        .map(|part| {
+            palette
+                .iter()
+                .rev()
+                .find(|(name, _)| name == part.trim())
+                .map_or(part, |(_, codes)| codes)
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Splits a line into its key, which can be bare or in quotes, and its
+/// value, which has to be a string in quotes.
+fn parse_pair(line: &str) -> Option<(String, String)> {
+    let (key, rest) = if line.starts_with(['"', '\'']) {
+        parse_string(line)?
+    } else {
+        let end = line
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(line.len());
+        if end == 0 {
+            return None;
+        }
+        (line[..end].to_owned(), &line[end..])
+    };
+
+    let rest = rest.trim_start().strip_prefix('=')?.trim_start();
+    let (value, rest) = parse_string(rest)?;
+
+    match rest.trim_start().chars().next() {
+        None | Some('#') => Some((key, value)),
+        Some(_) => None,
+    }
+}
+
+/// Parses a string at the start of some text, which can be in double quotes
+/// (with backslash escapes) or single quotes (without), returning it along
+/// with whatever comes after it.
+fn parse_string(text: &str) -> Option<(String, &str)> {
+    let mut chars = text.char_indices();
+    let (_, quote @ ('"' | '\'')) = chars.next()? else {
+        return None;
+    };
+
+    let mut string = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            c if c == quote => return Some((string, &text[i + 1..])),
+            '\\' if quote == '"' => match chars.next()?.1 {
+                'n' => string.push('\n'),
+                't' => string.push('\t'),
+                c => string.push(c),
+            },
+            c => string.push(c),
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ansiterm::Colour::*;
+    use ansiterm::Style;
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn tables() {
+        let file = ThemeFile::parse(
+            "# a theme\n\
+             [ui]\n\
+             di = \"1;34\"\n\
+             \"column.inode\" = '2'  # dim\n\
+             \n\
+             [files]\n\
+             \"*.rs\" = \"33\"\n\
+             \n\
+             [icons]\n\
+             '*.md' = \"36\"\n\
+             \n\
+             [glyphs]\n\
+             \"*.acme\" = \"A\"\n\
+             \n\
+             [other]\n\
+             di = \"31\"\n",
+        );
+
+        assert_eq!(file.ui, pairs(&[("di", "1;34"), ("column.inode", "2")]));
+        assert_eq!(file.files, pairs(&[("*.rs", "33")]));
+        assert_eq!(file.icons, pairs(&[("*.md", "36")]));
+        assert_eq!(file.glyphs, pairs(&[("*.acme", "A")]));
+        assert!(!file.has_backgrounds());
+    }
+
+    #[test]
+    fn palette() {
+        let palette = pairs(&[("accent", "38;5;208")]);
+        assert_eq!(substitute("1;accent", &palette), "1;38;5;208");
+        assert_eq!(substitute("2; accent ;4", &palette), "2;38;5;208;4");
+        assert_eq!(substitute("other", &palette), "other");
+    }
+
+    #[test]
+    fn backgrounds() {
+        let file = ThemeFile::parse(
+            "[palette]\n\
+             accent = \"33\"\n\
+             [palette.light]\n\
+             accent = \"34\"\n\
+             [ui]\n\
+             di = \"1;accent\"\n",
+        );
+        assert!(file.has_backgrounds());
+
+        let directory = |background| {
+            let mut ui = UiStyles::default();
+            file.apply(&mut ui, &mut ExtensionMappings::default(), background);
+            ui.filekinds.directory
+        };
+        assert_eq!(directory(None), Yellow.bold());
+        assert_eq!(directory(Some(Background::Dark)), Yellow.bold());
+        assert_eq!(directory(Some(Background::Light)), Blue.bold());
+    }
+
+    #[test]
+    fn invalid_lines() {
+        assert_eq!(parse_pair("di = 34"), None);
+        assert_eq!(parse_pair("di \"34\""), None);
+        assert_eq!(parse_pair("di = \"34"), None);
+        assert_eq!(parse_pair("di = \"34\" 35"), None);
+        assert_eq!(parse_pair("= \"34\""), None);
+        assert_eq!(
+            parse_pair("\"a\\\"b\" = \"1\""),
+            Some((String::from("a\"b"), String::from("1")))
+        );
+    }
+
+    #[test]
+    fn applied() {
+        let file = ThemeFile::parse(
+            "[ui]\ndi = \"1;34\"\n[icons]\n\"*.rs\" = \"33\"\n[glyphs]\n\"*.rs\" = \"R\"\nMakefile = \"M\"\n",
+        );
+        let mut ui = UiStyles::default();
+        let mut exts = ExtensionMappings::default();
+        file.apply(&mut ui, &mut exts, None);
+
+        assert_eq!(ui.filekinds.directory, Blue.bold());
+        assert_eq!(ui.icon_for("main.rs"), Some(Yellow.normal()));
+        assert_eq!(ui.icon_for("main.c"), None::<Style>);
+        assert_eq!(ui.glyph_for("main.rs"), Some("R"));
+        assert_eq!(ui.glyph_for("Makefile"), Some("M"));
+        assert_eq!(ui.glyph_for("main.c"), None);
+    }
+}
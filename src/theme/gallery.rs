@@ -0,0 +1,245 @@
+//! The built-in themes that can be picked with `--theme`.
+//!
+//! Each theme is written in the same format as `EZA_COLORS`, and gets applied
+//! on top of the default theme, so anything it doesn’t mention keeps its
+//! default colour. `LS_COLORS` and `EZA_COLORS` are applied afterwards, so
+//! they can still override anything a theme sets.
+
+use super::{LSColors, UiStyles};
+
+/// A built-in colour theme.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct NamedTheme {
+    pub name: &'static str,
+
+    /// The colour codes, as they would be given in `EZA_COLORS`.
+    pub colours: &'static str,
+}
+
+impl NamedTheme {
+    /// The theme to use when none has been picked, which changes nothing.
+    pub const DEFAULT: Self = Self {
+        name: "default",
+        colours: "",
+    };
+
+    /// Looks up one of the built-in themes by its name.
+    pub fn find(name: &str) -> Option<Self> {
+        THEMES.iter().find(|theme| theme.name == name).copied()
+    }
+
+    /// The theme picked by `--theme=auto`, `--theme=light`, and
+    /// `--theme=dark`, depending on whether the terminal has a light or a
+    /// dark background.
+    pub fn for_background(light: bool) -> Self {
+        if light {
+            SOLARIZED_LIGHT
+        } else {
+            SOLARIZED_DARK
+        }
+    }
+
+    /// Sets every colour this theme mentions on the set of UI styles.
+    pub fn apply(&self, ui: &mut UiStyles) {
+        LSColors(self.colours).each_pair(|pair| {
+            if !ui.set_ls(&pair) && !ui.set_exa(&pair) {
+                log::warn!("Theme {:?} has unknown key {:?}", self.name, pair.key);
+            }
+        });
+    }
+}
+
+/// All the built-in themes, in the order they get listed in.
+pub static THEMES: &[NamedTheme] = &[
+    NamedTheme::DEFAULT,
+    GRUVBOX,
+    SOLARIZED_DARK,
+    SOLARIZED_LIGHT,
+    CATPPUCCIN,
+    HIGH_CONTRAST,
+];
+
+/// The names of the themes accepted by `--theme`, including the ones that
+/// pick a theme for the background.
+pub const THEME_NAMES: &[&str] = &[
+    "auto",
+    "light",
+    "dark",
+    "default",
+    "gruvbox",
+    "solarized-dark",
+    "solarized-light",
+    "catppuccin",
+    "high-contrast",
+];
+
+/// Gruvbox, for dark backgrounds.
+const GRUVBOX: NamedTheme = NamedTheme {
+    name: "gruvbox",
+    colours: concat!(
+        "di=1;38;2;131;165;152:ex=1;38;2;184;187;38:fi=38;2;235;219;178:",
+        "ln=38;2;142;192;124:or=38;2;251;73;52:pi=38;2;250;189;47:so=38;2;211;134;155:",
+        "bd=1;38;2;250;189;47:cd=38;2;250;189;47:",
+        "ur=38;2;250;189;47:uw=38;2;251;73;52:ux=38;2;184;187;38:ue=38;2;184;187;38:",
+        "gr=38;2;250;189;47:gw=38;2;251;73;52:gx=38;2;184;187;38:",
+        "tr=38;2;250;189;47:tw=38;2;251;73;52:tx=38;2;184;187;38:",
+        "su=38;2;211;134;155:sf=38;2;211;134;155:xa=38;2;146;131;116:",
+        "sn=38;2;184;187;38:sb=38;2;152;151;26:df=38;2;184;187;38:ds=38;2;152;151;26:",
+        "uu=38;2;250;189;47:un=38;2;235;219;178:uR=38;2;251;73;52:",
+        "gu=38;2;250;189;47:gn=38;2;235;219;178:gR=38;2;251;73;52:",
+        "lc=38;2;251;73;52:lm=1;38;2;251;73;52:",
+        "ga=38;2;184;187;38:gm=38;2;131;165;152:gd=38;2;251;73;52:gv=38;2;250;189;47:",
+        "gt=38;2;211;134;155:gi=38;2;146;131;116:gc=38;2;251;73;52:",
+        "Gm=38;2;184;187;38:Go=38;2;250;189;47:Gc=38;2;184;187;38:Gd=38;2;251;73;52:",
+        "xx=38;2;146;131;116:da=38;2;131;165;152:in=38;2;211;134;155:bl=38;2;142;192;124:",
+        "hd=4;38;2;235;219;178:lp=38;2;142;192;124:cc=38;2;251;73;52:",
+        "im=38;2;211;134;155:vi=1;38;2;211;134;155:mu=38;2;142;192;124:lo=1;38;2;142;192;124:",
+        "cr=1;38;2;184;187;38:do=38;2;146;131;116:co=38;2;254;128;25:",
+        "tm=38;2;146;131;116:cm=38;2;146;131;116:bu=1;4;38;2;250;189;47:sc=38;2;250;189;47:cf=38;2;146;131;116",
+    ),
+};
+
+/// Solarized, for dark backgrounds.
+const SOLARIZED_DARK: NamedTheme = NamedTheme {
+    name: "solarized-dark",
+    colours: concat!(
+        "di=1;38;2;38;139;210:ex=1;38;2;133;153;0:fi=38;2;131;148;150:",
+        "ln=38;2;42;161;152:or=38;2;220;50;47:pi=38;2;181;137;0:so=38;2;211;54;130:",
+        "bd=1;38;2;181;137;0:cd=38;2;181;137;0:",
+        "ur=38;2;181;137;0:uw=38;2;220;50;47:ux=38;2;133;153;0:ue=38;2;133;153;0:",
+        "gr=38;2;181;137;0:gw=38;2;220;50;47:gx=38;2;133;153;0:",
+        "tr=38;2;181;137;0:tw=38;2;220;50;47:tx=38;2;133;153;0:",
+        "su=38;2;108;113;196:sf=38;2;108;113;196:xa=38;2;88;110;117:",
+        "sn=38;2;133;153;0:sb=38;2;88;110;117:df=38;2;133;153;0:ds=38;2;88;110;117:",
+        "uu=38;2;181;137;0:un=38;2;131;148;150:uR=38;2;220;50;47:",
+        "gu=38;2;181;137;0:gn=38;2;131;148;150:gR=38;2;220;50;47:",
+        "lc=38;2;203;75;22:lm=1;38;2;203;75;22:",
+        "ga=38;2;133;153;0:gm=38;2;38;139;210:gd=38;2;220;50;47:gv=38;2;181;137;0:",
+        "gt=38;2;108;113;196:gi=38;2;88;110;117:gc=38;2;220;50;47:",
+        "Gm=38;2;133;153;0:Go=38;2;181;137;0:Gc=38;2;133;153;0:Gd=38;2;220;50;47:",
+        "xx=38;2;88;110;117:da=38;2;38;139;210:in=38;2;211;54;130:bl=38;2;42;161;152:",
+        "hd=4;38;2;147;161;161:lp=38;2;42;161;152:cc=38;2;220;50;47:",
+        "im=38;2;211;54;130:vi=1;38;2;211;54;130:mu=38;2;42;161;152:lo=1;38;2;42;161;152:",
+        "cr=1;38;2;133;153;0:do=38;2;88;110;117:co=38;2;203;75;22:",
+        "tm=38;2;88;110;117:cm=38;2;88;110;117:bu=1;4;38;2;181;137;0:sc=38;2;181;137;0:cf=38;2;88;110;117",
+    ),
+};
+
+/// Solarized, for light backgrounds. It uses the same accent colours as the
+/// dark variant, with the darker base colours for everything else.
+const SOLARIZED_LIGHT: NamedTheme = NamedTheme {
+    name: "solarized-light",
+    colours: concat!(
+        "di=1;38;2;38;139;210:ex=1;38;2;133;153;0:fi=38;2;101;123;131:",
+        "ln=38;2;42;161;152:or=38;2;220;50;47:pi=38;2;181;137;0:so=38;2;211;54;130:",
+        "bd=1;38;2;181;137;0:cd=38;2;181;137;0:",
+        "ur=38;2;181;137;0:uw=38;2;220;50;47:ux=38;2;133;153;0:ue=38;2;133;153;0:",
+        "gr=38;2;181;137;0:gw=38;2;220;50;47:gx=38;2;133;153;0:",
+        "tr=38;2;181;137;0:tw=38;2;220;50;47:tx=38;2;133;153;0:",
+        "su=38;2;108;113;196:sf=38;2;108;113;196:xa=38;2;147;161;161:",
+        "sn=38;2;133;153;0:sb=38;2;147;161;161:df=38;2;133;153;0:ds=38;2;147;161;161:",
+        "uu=38;2;181;137;0:un=38;2;101;123;131:uR=38;2;220;50;47:",
+        "gu=38;2;181;137;0:gn=38;2;101;123;131:gR=38;2;220;50;47:",
+        "lc=38;2;203;75;22:lm=1;38;2;203;75;22:",
+        "ga=38;2;133;153;0:gm=38;2;38;139;210:gd=38;2;220;50;47:gv=38;2;181;137;0:",
+        "gt=38;2;108;113;196:gi=38;2;147;161;161:gc=38;2;220;50;47:",
+        "Gm=38;2;133;153;0:Go=38;2;181;137;0:Gc=38;2;133;153;0:Gd=38;2;220;50;47:",
+        "xx=38;2;147;161;161:da=38;2;38;139;210:in=38;2;211;54;130:bl=38;2;42;161;152:",
+        "hd=4;38;2;88;110;117:lp=38;2;42;161;152:cc=38;2;220;50;47:",
+        "im=38;2;211;54;130:vi=1;38;2;211;54;130:mu=38;2;42;161;152:lo=1;38;2;42;161;152:",
+        "cr=1;38;2;133;153;0:do=38;2;147;161;161:co=38;2;203;75;22:",
+        "tm=38;2;147;161;161:cm=38;2;147;161;161:bu=1;4;38;2;181;137;0:sc=38;2;181;137;0:cf=38;2;147;161;161",
+    ),
+};
+
+/// Catppuccin Mocha, for dark backgrounds.
+const CATPPUCCIN: NamedTheme = NamedTheme {
+    name: "catppuccin",
+    colours: concat!(
+        "di=1;38;2;137;180;250:ex=1;38;2;166;227;161:fi=38;2;205;214;244:",
+        "ln=38;2;148;226;213:or=38;2;243;139;168:pi=38;2;249;226;175:so=38;2;245;194;231:",
+        "bd=1;38;2;249;226;175:cd=38;2;249;226;175:",
+        "ur=38;2;249;226;175:uw=38;2;243;139;168:ux=38;2;166;227;161:ue=38;2;166;227;161:",
+        "gr=38;2;249;226;175:gw=38;2;243;139;168:gx=38;2;166;227;161:",
+        "tr=38;2;249;226;175:tw=38;2;243;139;168:tx=38;2;166;227;161:",
+        "su=38;2;203;166;247:sf=38;2;203;166;247:xa=38;2;108;112;134:",
+        "sn=38;2;166;227;161:sb=38;2;108;112;134:df=38;2;166;227;161:ds=38;2;108;112;134:",
+        "uu=38;2;249;226;175:un=38;2;205;214;244:uR=38;2;243;139;168:",
+        "gu=38;2;249;226;175:gn=38;2;205;214;244:gR=38;2;243;139;168:",
+        "lc=38;2;250;179;135:lm=1;38;2;250;179;135:",
+        "ga=38;2;166;227;161:gm=38;2;137;180;250:gd=38;2;243;139;168:gv=38;2;249;226;175:",
+        "gt=38;2;203;166;247:gi=38;2;108;112;134:gc=38;2;243;139;168:",
+        "Gm=38;2;166;227;161:Go=38;2;249;226;175:Gc=38;2;166;227;161:Gd=38;2;243;139;168:",
+        "xx=38;2;108;112;134:da=38;2;137;220;235:in=38;2;203;166;247:bl=38;2;148;226;213:",
+        "hd=4;38;2;205;214;244:lp=38;2;148;226;213:cc=38;2;243;139;168:",
+        "im=38;2;245;194;231:vi=1;38;2;245;194;231:mu=38;2;148;226;213:lo=1;38;2;148;226;213:",
+        "cr=1;38;2;166;227;161:do=38;2;108;112;134:co=38;2;250;179;135:",
+        "tm=38;2;108;112;134:cm=38;2;108;112;134:bu=1;4;38;2;249;226;175:sc=38;2;249;226;175:cf=38;2;108;112;134",
+    ),
+};
+
+/// A theme that only uses bold versions of the basic terminal colours, so it
+/// stays readable on any background and in terminals without true colour.
+const HIGH_CONTRAST: NamedTheme = NamedTheme {
+    name: "high-contrast",
+    colours: concat!(
+        "di=1;94:ex=1;92:fi=1:ln=1;96:or=1;97;41:pi=1;93:so=1;95:bd=1;93:cd=1;93:",
+        "ur=1;93:uw=1;91:ux=1;92:ue=1;92:gr=1;93:gw=1;91:gx=1;92:tr=1;93:tw=1;91:tx=1;92:",
+        "su=1;95:sf=1;95:xa=1:",
+        "sn=1;92:sb=92:df=1;92:ds=92:",
+        "uu=1;93:un=1:uR=1;91:gu=1;93:gn=1:gR=1;91:",
+        "lc=1;91:lm=1;91;4:",
+        "ga=1;92:gm=1;94:gd=1;91:gv=1;93:gt=1;95:gi=1;37:gc=1;91:",
+        "Gm=1;92:Go=1;93:Gc=1;92:Gd=1;91:",
+        "xx=1:da=1;94:in=1;95:bl=1;96:hd=1;4:lp=1;96:cc=1;91:",
+        "im=1;95:vi=1;95:mu=1;96:lo=1;96:cr=1;92:do=1:co=1;91:tm=1:cm=1:bu=1;4;93:sc=1;93:cf=1",
+    ),
+};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::theme::ColourScale;
+
+    #[test]
+    fn names_match() {
+        let listed = THEMES.iter().map(|theme| theme.name);
+        assert!(listed.eq(THEME_NAMES[3..].iter().copied()));
+    }
+
+    #[test]
+    fn every_key_is_known() {
+        for theme in THEMES {
+            LSColors(theme.colours).each_pair(|pair| {
+                let mut ui = UiStyles::default();
+                assert!(
+                    ui.set_ls(&pair) || ui.set_exa(&pair),
+                    "{}: {}",
+                    theme.name,
+                    pair.key
+                );
+            });
+        }
+    }
+
+    #[test]
+    fn default_changes_nothing() {
+        let mut ui = UiStyles::default_theme(ColourScale::Fixed);
+        NamedTheme::DEFAULT.apply(&mut ui);
+        assert_eq!(ui, UiStyles::default_theme(ColourScale::Fixed));
+    }
+
+    #[test]
+    fn themes_change_something() {
+        for theme in &THEMES[1..] {
+            let mut ui = UiStyles::default_theme(ColourScale::Fixed);
+            theme.apply(&mut ui);
+            assert_ne!(
+                ui,
+                UiStyles::default_theme(ColourScale::Fixed),
+                "{}",
+                theme.name
+            );
+        }
+    }
+}
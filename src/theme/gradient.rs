@@ -0,0 +1,219 @@
+//! Smooth colour scales for `--color-scale`, which blend between the colours
+//! at either end of the scale in as many steps as the terminal can show.
+//!
+//! Sizes and ages both go on a logarithmic scale, so the difference between
+//! a kilobyte and a megabyte, or an hour and a day, is as easy to see as the
+//! one between a megabyte and a gigabyte, or a day and a month. Terminals
+//! that can’t show 24-bit colours get the closest of the 256 colours.
+
+use ansiterm::{Colour, Style};
+
+/// The colours at either end of a scale.
+#[derive(PartialEq, Debug, Default, Copy, Clone)]
+pub struct Gradient {
+    /// The style at the start of the scale, for the smallest or newest
+    /// files. Its attributes, such as bold, get used all along the scale.
+    pub from: Option<Style>,
+
+    /// The style at the end of the scale, for the largest or oldest files.
+    pub to: Option<Style>,
+
+    /// Whether the terminal can show 24-bit colours.
+    pub truecolour: bool,
+}
+
+/// The size at the end of the size scale, which is a tebibyte.
+const LARGEST_SIZE: f64 = 1_099_511_627_776.0;
+
+/// The age at the end of the age scale, in seconds, which is a year.
+const OLDEST_AGE: f64 = 31_536_000.0;
+
+impl Gradient {
+    /// The style for somewhere along the scale, from 0 at the start to 1 at
+    /// the end, if both of its ends have colours.
+    pub fn at(&self, position: f64) -> Option<Style> {
+        let from = self.from?;
+        let start = rgb(from.foreground?)?;
+        let end = rgb(self.to?.foreground?)?;
+
+        let position = position.clamp(0.0, 1.0);
+        let blend = |a: u8, b: u8| {
+            let (a, b) = (f64::from(a), f64::from(b));
+            (a + (b - a) * position).round() as u8
+        };
+        let (r, g, b) = (
+            blend(start.0, end.0),
+            blend(start.1, end.1),
+            blend(start.2, end.2),
+        );
+
+        let colour = if self.truecolour {
+            Colour::RGB(r, g, b)
+        } else {
+            Colour::Fixed(closest_fixed(r, g, b))
+        };
+        Some(Style {
+            foreground: Some(colour),
+            ..from
+        })
+    }
+
+    /// Where a file of the given size goes on the scale.
+    pub fn for_size(bytes: u64) -> f64 {
+        (bytes as f64 + 1.0).ln() / LARGEST_SIZE.ln()
+    }
+
+    /// Where a file that was changed the given length of time ago goes on
+    /// the scale. Times in the future go at the start.
+    pub fn for_age(age: chrono::Duration) -> f64 {
+        let seconds = age.num_seconds().max(0) as f64;
+        (seconds + 1.0).ln() / OLDEST_AGE.ln()
+    }
+}
+
+/// The levels of red, green, and blue that each of the 256 colours in the
+/// cube goes through.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The red, green, and blue of a colour, using the usual xterm values for
+/// the ones that terminals let people change.
+fn rgb(colour: Colour) -> Option<(u8, u8, u8)> {
+    #[rustfmt::skip]
+    const BASIC: [(u8, u8, u8); 16] = [
+        (0, 0, 0),       (205, 0, 0),     (0, 205, 0),     (205, 205, 0),
+        (0, 0, 238),     (205, 0, 205),   (0, 205, 205),   (229, 229, 229),
+        (127, 127, 127), (255, 0, 0),     (0, 255, 0),     (255, 255, 0),
+        (92, 92, 255),   (255, 0, 255),   (0, 255, 255),   (255, 255, 255),
+    ];
+
+    let fixed = match colour {
+        Colour::RGB(r, g, b) => return Some((r, g, b)),
+        Colour::Default => return None,
+        Colour::Fixed(n) => n,
+        Colour::Black => 0,
+        Colour::Red => 1,
+        Colour::Green => 2,
+        Colour::Yellow => 3,
+        Colour::Blue => 4,
+        Colour::Purple => 5,
+        Colour::Cyan => 6,
+        Colour::White => 7,
+        Colour::DarkGray => 8,
+        Colour::BrightRed => 9,
+        Colour::BrightGreen => 10,
+        Colour::BrightYellow => 11,
+        Colour::BrightBlue => 12,
+        Colour::BrightPurple => 13,
+        Colour::BrightCyan => 14,
+        Colour::BrightGray => 15,
+    };
+
+    Some(match fixed {
+        0..=15 => BASIC[usize::from(fixed)],
+        16..=231 => {
+            let n = usize::from(fixed - 16);
+            (
+                CUBE_LEVELS[n / 36],
+                CUBE_LEVELS[n / 6 % 6],
+                CUBE_LEVELS[n % 6],
+            )
+        }
+        232..=255 => {
+            let level = 8 + (fixed - 232) * 10;
+            (level, level, level)
+        }
+    })
+}
+
+/// The one of the 256 colours that’s closest to the given one, out of the
+/// ones in the cube and the greys, as the first 16 can be changed.
+fn closest_fixed(r: u8, g: u8, b: u8) -> u8 {
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+        d(r, r2) + d(g, g2) + d(b, b2)
+    };
+
+    let nearest_level = |value: u8| {
+        (0..6)
+            .min_by_key(|&i| (i32::from(CUBE_LEVELS[i]) - i32::from(value)).abs())
+            .unwrap_or(0)
+    };
+    let cube = (16 + 36 * nearest_level(r) + 6 * nearest_level(g) + nearest_level(b)) as u8;
+
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let grey = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    [cube, grey]
+        .into_iter()
+        .min_by_key(|&n| rgb(Colour::Fixed(n)).map_or(i32::MAX, distance))
+        .unwrap_or(cube)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ansiterm::Colour::*;
+
+    fn gradient(truecolour: bool) -> Gradient {
+        Gradient {
+            from: Some(RGB(0, 200, 0).bold()),
+            to: Some(RGB(200, 0, 0).normal()),
+            truecolour,
+        }
+    }
+
+    #[test]
+    fn ends() {
+        assert_eq!(gradient(true).at(0.0), Some(RGB(0, 200, 0).bold()));
+        assert_eq!(gradient(true).at(1.0), Some(RGB(200, 0, 0).bold()));
+        assert_eq!(gradient(true).at(-3.0), Some(RGB(0, 200, 0).bold()));
+    }
+
+    #[test]
+    fn middle() {
+        assert_eq!(gradient(true).at(0.5), Some(RGB(100, 100, 0).bold()));
+    }
+
+    #[test]
+    fn without_truecolour() {
+        assert_eq!(gradient(false).at(0.0), Some(Fixed(40).bold()));
+        assert_eq!(gradient(false).at(1.0), Some(Fixed(160).bold()));
+    }
+
+    #[test]
+    fn missing_end() {
+        let gradient = Gradient {
+            to: None,
+            ..gradient(true)
+        };
+        assert_eq!(gradient.at(0.5), None);
+    }
+
+    #[test]
+    fn fixed_colours() {
+        let gradient = Gradient {
+            from: Some(Fixed(16).normal()),
+            to: Some(White.normal()),
+            truecolour: true,
+        };
+        assert_eq!(gradient.at(0.0), Some(RGB(0, 0, 0).normal()));
+        assert_eq!(gradient.at(1.0), Some(RGB(229, 229, 229).normal()));
+    }
+
+    #[test]
+    fn closest() {
+        assert_eq!(closest_fixed(0, 0, 0), 16);
+        assert_eq!(closest_fixed(255, 255, 255), 231);
+        assert_eq!(closest_fixed(128, 128, 128), 244);
+        assert_eq!(closest_fixed(255, 135, 0), 208);
+    }
+
+    #[test]
+    fn positions() {
+        assert!(Gradient::for_size(0).abs() < f64::EPSILON);
+        assert!((Gradient::for_size(1 << 20) - 0.5).abs() < 0.01);
+        assert!(Gradient::for_size(1 << 50) > 1.0);
+        assert!(Gradient::for_age(chrono::Duration::seconds(-10)).abs() < f64::EPSILON);
+        assert!(Gradient::for_age(chrono::Duration::days(1)) < 0.7);
+    }
+}
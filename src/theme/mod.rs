@@ -1,9 +1,12 @@
-use ansiterm::Style;
+use std::collections::HashMap;
+
+use ansiterm::{Colour, Style};
 
 use crate::fs::File;
-use crate::info::filetype::FileType;
+use crate::info::filetype::{self, FileType};
 use crate::output::file_name::Colours as FileNameColours;
 use crate::output::render;
+use crate::output::table::COLUMN_NAMES;
 
 mod ui_styles;
 pub use self::ui_styles::UiStyles;
@@ -13,15 +16,52 @@ pub use self::lsc::LSColors;
 
 mod default_theme;
 
+mod background;
+pub use self::background::Background;
+
+mod file;
+pub use self::file::ThemeFile;
+
+mod gradient;
+pub use self::gradient::Gradient;
+
+mod gallery;
+pub use self::gallery::{NamedTheme, THEME_NAMES};
+
 #[derive(PartialEq, Eq, Debug)]
 pub struct Options {
     pub use_colours: UseColours,
 
     pub colour_scale: ColourScale,
 
+    /// Whether the terminal can show 24-bit colours, which the colour scales
+    /// get approximated from the 256 colours without.
+    pub truecolour: bool,
+
+    pub column_colours: ColumnColours,
+
+    pub owner_colours: OwnerColours,
+
+    /// The built-in theme to start from, before the variables get applied.
+    pub base: BaseTheme,
+
+    /// Whether the terminal has a light or a dark background, if that’s
+    /// known without having to ask it.
+    pub background: Option<Background>,
+
     pub definitions: Definitions,
 }
 
+/// Which built-in theme to start from.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum BaseTheme {
+    /// The theme with this name.
+    Named(NamedTheme),
+
+    /// The theme for the terminal’s background, whichever it is.
+    ForBackground,
+}
+
 /// Under what circumstances we should display coloured, rather than plain,
 /// output to the terminal.
 ///
@@ -47,10 +87,49 @@ pub enum ColourScale {
     Gradient,
 }
 
+/// Whether the columns of the long view get coloured, apart from the file
+/// names, which some people find easier on the eyes.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum ColumnColours {
+    /// Colour them the same way as everything else.
+    Always,
+
+    /// Dim them, in place of their colours.
+    Dim,
+
+    /// Leave them uncoloured.
+    Never,
+}
+
+/// How the user and group columns get coloured.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum OwnerColours {
+    /// By whether they’re the current user and their groups, or root.
+    Fixed,
+
+    /// With a colour worked out from each name, so the same name always
+    /// gets the same colour, keeping the attributes of the usual style.
+    Hashed,
+}
+
+impl ColumnColours {
+    /// The style to paint all of each column in, in place of its colours.
+    fn style(self) -> Option<Style> {
+        match self {
+            Self::Always => None,
+            Self::Dim => Some(Style::default().dimmed()),
+            Self::Never => Some(Style::default()),
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Default)]
 pub struct Definitions {
     pub ls: Option<String>,
     pub exa: Option<String>,
+
+    /// The theme file, which gets applied before the variables.
+    pub file: Option<ThemeFile>,
 }
 
 pub struct Theme {
@@ -69,9 +148,49 @@ impl Options {
             return Theme { ui, exts };
         }
 
+        // The terminal only gets asked about its background when something
+        // depends on it, as it takes a moment to answer
+        let wants_background = self.base == BaseTheme::ForBackground
+            || self
+                .definitions
+                .file
+                .as_ref()
+                .map_or(false, ThemeFile::has_backgrounds);
+        let background = match self.background {
+            Some(background) => Some(background),
+            None if wants_background => Background::query(),
+            None => None,
+        };
+
+        let base = match self.base {
+            BaseTheme::Named(theme) => theme,
+            BaseTheme::ForBackground => {
+                NamedTheme::for_background(background == Some(Background::Light))
+            }
+        };
+
         // Parse the environment variables into colours and extension mappings
         let mut ui = UiStyles::default_theme(self.colour_scale);
-        let (exts, use_default_filetypes) = self.definitions.parse_color_vars(&mut ui);
+        base.apply(&mut ui);
+        let (exts, use_default_filetypes) = self.definitions.parse_color_vars(&mut ui, background);
+
+        ui.hashed_owners = self.owner_colours == OwnerColours::Hashed;
+
+        if self.colour_scale == ColourScale::Gradient {
+            ui.size_gradient.truecolour = self.truecolour;
+            ui.age_gradient.truecolour = self.truecolour;
+        } else {
+            ui.size_gradient = Gradient::default();
+            ui.age_gradient = Gradient::default();
+        }
+
+        if let Some(style) = self.column_colours.style() {
+            ui.columns = COLUMN_NAMES
+                .iter()
+                .filter(|name| **name != "name")
+                .map(|name| (*name, style))
+                .collect();
+        }
 
         // Use between 0 and 2 file name highlighters
         #[rustfmt::skip]
@@ -93,11 +212,19 @@ impl Definitions {
     ///
     /// Also returns if the `EZA_COLORS` variable should reset the existing file
     /// type mappings or not. The `reset` code needs to be the first one.
-    fn parse_color_vars(&self, colours: &mut UiStyles) -> (ExtensionMappings, bool) {
+    fn parse_color_vars(
+        &self,
+        colours: &mut UiStyles,
+        background: Option<Background>,
+    ) -> (ExtensionMappings, bool) {
         use log::*;
 
         let mut exts = ExtensionMappings::default();
 
+        if let Some(file) = &self.file {
+            file.apply(colours, &mut exts, background);
+        }
+
         if let Some(lsc) = &self.ls {
             LSColors(lsc).each_pair(|pair| {
                 if !colours.set_ls(&pair) {
@@ -144,6 +271,12 @@ pub trait FileStyle: Sync {
     /// Return the style to paint the filename text for `file` from the given
     /// `theme`.
     fn get_style(&self, file: &File<'_>, theme: &Theme) -> Option<Style>;
+
+    /// Return the style to paint the name of the directory `file`, if it
+    /// has one other than the regular directory style.
+    fn get_directory_style(&self, _file: &File<'_>, _theme: &Theme) -> Option<Style> {
+        None
+    }
 }
 
 #[derive(PartialEq, Debug)]
@@ -169,11 +302,27 @@ where
             .get_style(file, theme)
             .or_else(|| self.1.get_style(file, theme))
     }
+
+    fn get_directory_style(&self, file: &File<'_>, theme: &Theme) -> Option<Style> {
+        self.0
+            .get_directory_style(file, theme)
+            .or_else(|| self.1.get_directory_style(file, theme))
+    }
 }
 
 #[derive(PartialEq, Debug, Default)]
 struct ExtensionMappings {
     mappings: Vec<(glob::Pattern, Style)>,
+
+    /// The position of the last pattern for each extension, for patterns
+    /// that only match an extension such as `*.txt`. These are most of
+    /// them, and looking them up saves matching every one of them against
+    /// every file name.
+    by_extension: HashMap<String, usize>,
+
+    /// The positions of the patterns that have to be matched against the
+    /// whole file name.
+    others: Vec<usize>,
 }
 
 impl ExtensionMappings {
@@ -182,19 +331,50 @@ impl ExtensionMappings {
     }
 
     fn add(&mut self, pattern: glob::Pattern, style: Style) {
+        let index = self.mappings.len();
+        match pattern.as_str().strip_prefix("*.") {
+            Some(ext) if !ext.is_empty() && !ext.contains(['*', '?', '[', ']', '.', '/']) => {
+                self.by_extension.insert(ext.to_owned(), index);
+            }
+            _ => self.others.push(index),
+        }
+
         self.mappings.push((pattern, style));
     }
-}
 
-// Loop through backwards so that colours specified later in the list override
-// colours specified earlier, like we do with options and strict mode
+    // Patterns specified later in the list override ones specified earlier,
+    // like we do with options and strict mode, so the later match wins.
+    fn style_for_name(&self, name: &str) -> Option<Style> {
+        let by_extension = name
+            .rsplit_once('.')
+            .and_then(|(_, ext)| self.by_extension.get(ext))
+            .copied();
+
+        let other = self
+            .others
+            .iter()
+            .rev()
+            .find(|&&i| self.mappings[i].0.matches(name))
+            .copied();
+
+        by_extension.max(other).map(|i| self.mappings[i].1)
+    }
+}
 
 impl FileStyle for ExtensionMappings {
     fn get_style(&self, file: &File<'_>, _theme: &Theme) -> Option<Style> {
+        self.style_for_name(&file.name)
+    }
+
+    // Patterns ending in a slash, such as `node_modules/`, only match
+    // directories. File names can’t contain slashes, so these never match
+    // anything in `get_style`.
+    fn get_directory_style(&self, file: &File<'_>, _theme: &Theme) -> Option<Style> {
+        let name = format!("{}/", file.name);
         self.mappings
             .iter()
             .rev()
-            .find(|t| t.0.matches(&file.name))
+            .find(|t| t.0.as_str().ends_with('/') && t.0.matches(&name))
             .map(|t| t.1)
     }
 }
@@ -217,6 +397,7 @@ impl FileStyle for FileTypes {
             Some(FileType::Compiled)   => Some(theme.ui.file_type.compiled),
             Some(FileType::Build)      => Some(theme.ui.file_type.build),
             Some(FileType::Source)     => Some(theme.ui.file_type.source),
+            Some(FileType::Config)     => Some(theme.ui.file_type.config),
             None                       => None
         };
     }
@@ -224,30 +405,34 @@ impl FileStyle for FileTypes {
 
 #[cfg(unix)]
 impl render::BlocksColours for Theme {
-    fn blocksize(&self, prefix: Option<number_prefix::Prefix>) -> Style {
-        use number_prefix::Prefix::*;
+    fn blocksize(&self, bytes: u64, prefix: Option<number_prefix::Prefix>) -> Style {
+        if let Some(style) = self.size_gradient(bytes) {
+            return style;
+        }
 
-        #[rustfmt::skip]
-        return match prefix {
-            Some(Kilo | Kibi) => self.ui.size.number_kilo,
-            Some(Mega | Mebi) => self.ui.size.number_mega,
-            Some(Giga | Gibi) => self.ui.size.number_giga,
-            Some(_)           => self.ui.size.number_huge,
-            None              => self.ui.size.number_byte,
-        };
+        let size = &self.ui.size;
+        [
+            size.number_byte,
+            size.number_kilo,
+            size.number_mega,
+            size.number_giga,
+            size.number_huge,
+        ][self.size_scale(bytes, prefix)]
     }
 
-    fn unit(&self, prefix: Option<number_prefix::Prefix>) -> Style {
-        use number_prefix::Prefix::*;
+    fn unit(&self, bytes: u64, prefix: Option<number_prefix::Prefix>) -> Style {
+        if let Some(style) = self.size_gradient(bytes) {
+            return style;
+        }
 
-        #[rustfmt::skip]
-        return match prefix {
-            Some(Kilo | Kibi) => self.ui.size.unit_kilo,
-            Some(Mega | Mebi) => self.ui.size.unit_mega,
-            Some(Giga | Gibi) => self.ui.size.unit_giga,
-            Some(_)           => self.ui.size.unit_huge,
-            None              => self.ui.size.unit_byte,
-        };
+        let size = &self.ui.size;
+        [
+            size.unit_byte,
+            size.unit_kilo,
+            size.unit_mega,
+            size.unit_giga,
+            size.unit_huge,
+        ][self.size_scale(bytes, prefix)]
     }
 
     fn no_blocksize(&self) -> Style {
@@ -255,6 +440,13 @@ impl render::BlocksColours for Theme {
     }
 }
 
+#[rustfmt::skip]
+impl render::DeltaColours for Theme {
+    fn grown(&self)     -> Style { self.ui.delta_grown }
+    fn shrunk(&self)    -> Style { self.ui.delta_shrunk }
+    fn unchanged(&self) -> Style { self.ui.punctuation }
+}
+
 #[rustfmt::skip]
 impl render::FiletypeColours for Theme {
     fn normal(&self)       -> Style { self.ui.filekinds.normal }
@@ -296,6 +488,7 @@ impl render::GroupColours for Theme {
     fn not_yours(&self)  -> Style { self.ui.users.group_other }
     fn root_group(&self) -> Style { self.ui.users.group_root }
     fn no_group(&self)   -> Style { self.ui.punctuation }
+    fn named(&self, style: Style, name: &str) -> Style { self.owner(style, name) }
 }
 
 #[rustfmt::skip]
@@ -323,30 +516,34 @@ impl render::PermissionsColours for Theme {
 }
 
 impl render::SizeColours for Theme {
-    fn size(&self, prefix: Option<number_prefix::Prefix>) -> Style {
-        use number_prefix::Prefix::*;
+    fn size(&self, bytes: u64, prefix: Option<number_prefix::Prefix>) -> Style {
+        if let Some(style) = self.size_gradient(bytes) {
+            return style;
+        }
 
-        #[rustfmt::skip]
-        return match prefix {
-            Some(Kilo | Kibi) => self.ui.size.number_kilo,
-            Some(Mega | Mebi) => self.ui.size.number_mega,
-            Some(Giga | Gibi) => self.ui.size.number_giga,
-            Some(_)           => self.ui.size.number_huge,
-            None              => self.ui.size.number_byte,
-        };
+        let size = &self.ui.size;
+        [
+            size.number_byte,
+            size.number_kilo,
+            size.number_mega,
+            size.number_giga,
+            size.number_huge,
+        ][self.size_scale(bytes, prefix)]
     }
 
-    fn unit(&self, prefix: Option<number_prefix::Prefix>) -> Style {
-        use number_prefix::Prefix::*;
+    fn unit(&self, bytes: u64, prefix: Option<number_prefix::Prefix>) -> Style {
+        if let Some(style) = self.size_gradient(bytes) {
+            return style;
+        }
 
-        #[rustfmt::skip]
-        return match prefix {
-            Some(Kilo | Kibi) => self.ui.size.unit_kilo,
-            Some(Mega | Mebi) => self.ui.size.unit_mega,
-            Some(Giga | Gibi) => self.ui.size.unit_giga,
-            Some(_)           => self.ui.size.unit_huge,
-            None              => self.ui.size.unit_byte,
-        };
+        let size = &self.ui.size;
+        [
+            size.unit_byte,
+            size.unit_kilo,
+            size.unit_mega,
+            size.unit_giga,
+            size.unit_huge,
+        ][self.size_scale(bytes, prefix)]
     }
 
     #[rustfmt::skip]
@@ -359,6 +556,132 @@ impl render::SizeColours for Theme {
     fn minor(&self)   -> Style { self.ui.size.minor }
 }
 
+impl Theme {
+    /// The style for a user or group with the given name, which gets a
+    /// colour worked out from the name with `--color-owners`.
+    #[cfg(unix)]
+    fn owner(&self, style: Style, name: &str) -> Style {
+        if !self.ui.hashed_owners {
+            return style;
+        }
+
+        Style {
+            foreground: Some(Colour::Fixed(owner_colour(name))),
+            ..style
+        }
+    }
+
+    /// The style for a size from the colour scale, if there is one.
+    fn size_gradient(&self, bytes: u64) -> Option<Style> {
+        self.ui.size_gradient.at(Gradient::for_size(bytes))
+    }
+
+    /// The style for a timestamp from the given length of time ago, which
+    /// comes from the colour scale if there is one.
+    pub fn date(&self, age: chrono::Duration) -> Style {
+        self.ui
+            .age_gradient
+            .at(Gradient::for_age(age))
+            .unwrap_or(self.ui.date)
+    }
+
+    /// Which of the size colours, from bytes up to huge, a size gets. This
+    /// goes by the size’s prefix, unless the theme sets the sizes at which
+    /// each colour starts, in which case the ones it leaves out default to
+    /// powers of 1000.
+    fn size_scale(&self, bytes: u64, prefix: Option<number_prefix::Prefix>) -> usize {
+        use number_prefix::Prefix::*;
+
+        let thresholds = self.ui.size.thresholds;
+        if thresholds.iter().any(Option::is_some) {
+            let defaults = [1_000, 1_000_000, 1_000_000_000, 1_000_000_000_000];
+            return thresholds
+                .iter()
+                .zip(defaults)
+                .rposition(|(threshold, default)| bytes >= threshold.unwrap_or(default))
+                .map_or(0, |i| i + 1);
+        }
+
+        match prefix {
+            None => 0,
+            Some(Kilo | Kibi) => 1,
+            Some(Mega | Mebi) => 2,
+            Some(Giga | Gibi) => 3,
+            Some(_) => 4,
+        }
+    }
+
+    /// The colours to render the size of the given file with, which depend
+    /// on its type if the theme sets a size style for it.
+    pub fn size_colours(&self, file: &File<'_>) -> FileSizeColours<'_> {
+        let types = &self.ui.size_file_type;
+
+        #[rustfmt::skip]
+        let style = match FileType::get_file_type(file) {
+            Some(FileType::Image)      => types.image,
+            Some(FileType::Video)      => types.video,
+            Some(FileType::Music)      => types.music,
+            Some(FileType::Lossless)   => types.lossless,
+            Some(FileType::Crypto)     => types.crypto,
+            Some(FileType::Document)   => types.document,
+            Some(FileType::Compressed) => types.compressed,
+            Some(FileType::Temp)       => types.temp,
+            Some(FileType::Compiled)   => types.compiled,
+            Some(FileType::Build)      => types.build,
+            Some(FileType::Source)     => types.source,
+            Some(FileType::Config)     => types.config,
+            None                       => None,
+        };
+
+        FileSizeColours { theme: self, style }
+    }
+}
+
+/// The colours that users and groups can get with `--color-owners`, out of
+/// the 256, which are all bright enough to read on a dark background and
+/// dark enough to read on a light one.
+#[cfg(unix)]
+const OWNER_COLOURS: [u8; 16] = [
+    32, 37, 41, 70, 97, 99, 130, 133, 136, 166, 168, 172, 31, 65, 95, 161,
+];
+
+/// The colour for a user or group with the given name. This hashes the name
+/// with FNV-1a, rather than the standard library’s hasher, so names keep
+/// their colours from one version to the next.
+#[cfg(unix)]
+fn owner_colour(name: &str) -> u8 {
+    let hash = name.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+    OWNER_COLOURS[hash as usize % OWNER_COLOURS.len()]
+}
+
+/// Size colours for one particular file, overriding the theme’s number and
+/// unit styles with the style for its file type.
+pub struct FileSizeColours<'t> {
+    theme: &'t Theme,
+    style: Option<Style>,
+}
+
+impl render::SizeColours for FileSizeColours<'_> {
+    fn size(&self, bytes: u64, prefix: Option<number_prefix::Prefix>) -> Style {
+        self.style.unwrap_or_else(|| self.theme.size(bytes, prefix))
+    }
+
+    fn unit(&self, bytes: u64, prefix: Option<number_prefix::Prefix>) -> Style {
+        self.style.unwrap_or_else(|| self.theme.unit(bytes, prefix))
+    }
+
+    #[rustfmt::skip]
+    fn no_size(&self) -> Style { self.theme.no_size() }
+    #[rustfmt::skip]
+    fn major(&self)   -> Style { self.theme.major() }
+    #[rustfmt::skip]
+    fn comma(&self)   -> Style { self.theme.comma() }
+    #[rustfmt::skip]
+    fn minor(&self)   -> Style { self.theme.minor() }
+}
+
 #[rustfmt::skip]
 #[cfg(unix)]
 impl render::UserColours for Theme {
@@ -366,6 +689,7 @@ impl render::UserColours for Theme {
     fn other(&self)         -> Style { self.ui.users.user_other }
     fn root(&self)          -> Style { self.ui.users.user_root }
     fn no_user(&self)       -> Style { self.ui.punctuation }
+    fn named(&self, style: Style, name: &str) -> Style { self.owner(style, name) }
 }
 
 #[rustfmt::skip]
@@ -376,14 +700,29 @@ impl FileNameColours for Theme {
     fn broken_filename(&self)     -> Style { apply_overlay(self.ui.broken_symlink, self.ui.broken_path_overlay) }
     fn control_char(&self)        -> Style { self.ui.control_char }
     fn broken_control_char(&self) -> Style { apply_overlay(self.ui.control_char,   self.ui.broken_path_overlay) }
+    fn suspicious_filename(&self, style: Style) -> Style { apply_overlay(style, self.ui.suspicious_overlay) }
     fn executable_file(&self)     -> Style { self.ui.filekinds.executable }
     fn mount_point(&self)         -> Style { self.ui.filekinds.mount_point }
+    fn icon(&self)                -> Style { self.ui.icon }
+    fn icon_for_file(&self, file: &File<'_>) -> Option<Style> { self.ui.icon_for(&file.name) }
+    fn icon_glyph_for_file(&self, file: &File<'_>) -> Option<String> { self.ui.glyph_for(&file.name).map(String::from) }
 
     fn colour_file(&self, file: &File<'_>) -> Style {
         self.exts
             .get_style(file, self)
             .unwrap_or(self.ui.filekinds.normal)
     }
+
+    fn colour_directory(&self, file: &File<'_>) -> Style {
+        if let Some(style) = self.exts.get_directory_style(file, self) {
+            return style;
+        }
+
+        match self.ui.filekinds.tool_directory {
+            Some(style) if filetype::is_tool_directory(file) => style,
+            _ => self.ui.filekinds.directory,
+        }
+    }
 }
 
 #[rustfmt::skip]
@@ -444,10 +783,11 @@ mod customs_test {
                 let definitions = Definitions {
                     ls: Some($ls.into()),
                     exa: Some($exa.into()),
+                    file: None,
                 };
 
                 let mut result = UiStyles::default();
-                let (_, _) = definitions.parse_color_vars(&mut result);
+                let (_, _) = definitions.parse_color_vars(&mut result, None);
                 assert_eq!($expected, result);
             }
         };
@@ -462,10 +802,11 @@ mod customs_test {
                 let definitions = Definitions {
                     ls: Some($ls.into()),
                     exa: Some($exa.into()),
+                    file: None,
                 };
 
-                let (result, _) = definitions.parse_color_vars(&mut UiStyles::default());
-                assert_eq!(ExtensionMappings { mappings }, result);
+                let (result, _) = definitions.parse_color_vars(&mut UiStyles::default(), None);
+                assert_eq!(mappings, result.mappings);
             }
         };
         ($name:ident:  ls $ls:expr, exa $exa:expr  =>  colours $expected:ident -> $process_expected:expr, exts $mappings:expr) => {
@@ -482,11 +823,12 @@ mod customs_test {
                 let definitions = Definitions {
                     ls: Some($ls.into()),
                     exa: Some($exa.into()),
+                    file: None,
                 };
 
                 let mut result = UiStyles::default();
-                let (exts, _) = definitions.parse_color_vars(&mut result);
-                assert_eq!(ExtensionMappings { mappings }, exts);
+                let (exts, _) = definitions.parse_color_vars(&mut result, None);
+                assert_eq!(mappings, exts.mappings);
                 assert_eq!($expected, result);
             }
         };
@@ -564,6 +906,10 @@ mod customs_test {
     test!(exa_df:  ls "", exa "df=38;5;115"  =>  colours c -> { c.size.major                            = Fixed(115).normal(); });
     test!(exa_ds:  ls "", exa "ds=38;5;116"  =>  colours c -> { c.size.minor                            = Fixed(116).normal(); });
 
+    test!(exa_zk:  ls "", exa "zk=100k"      =>  colours c -> { c.size.thresholds[0]                    = Some(100_000); });
+    test!(exa_zt:  ls "", exa "zt=2TiB"      =>  colours c -> { c.size.thresholds[3]                    = Some(2 << 40); });
+    test!(exa_zm_bad: ls "", exa "zm=lots"   =>  colours c -> { c.size.thresholds[1]                    = None; });
+
     test!(exa_uu:  ls "", exa "uu=38;5;117"  =>  colours c -> { c.users.user_you                        = Fixed(117).normal(); });
     test!(exa_un:  ls "", exa "un=38;5;118"  =>  colours c -> { c.users.user_other                      = Fixed(118).normal(); });
     test!(exa_gu:  ls "", exa "gu=38;5;119"  =>  colours c -> { c.users.group_yours                     = Fixed(119).normal(); });
@@ -588,9 +934,22 @@ mod customs_test {
     test!(exa_lp:  ls "", exa "lp=38;5;133"  =>  colours c -> { c.symlink_path                          = Fixed(133).normal(); });
     test!(exa_cc:  ls "", exa "cc=38;5;134"  =>  colours c -> { c.control_char                          = Fixed(134).normal(); });
     test!(exa_oc:  ls "", exa "oc=38;5;135"  =>  colours c -> { c.octal                                 = Fixed(135).normal(); });
+    test!(exa_ow:  ls "", exa "ow=38;5;135"  =>  colours c -> { c.codeowners                            = Fixed(135).normal(); });
+    test!(exa_Dg:  ls "", exa "Dg=38;5;136"  =>  colours c -> { c.delta_grown                           = Fixed(136).normal(); });
+    test!(exa_Ds:  ls "", exa "Ds=38;5;137"  =>  colours c -> { c.delta_shrunk                          = Fixed(137).normal(); });
     test!(exa_bo:  ls "", exa "bO=4"         =>  colours c -> { c.broken_path_overlay                   = Style::default().underline(); });
+    test!(exa_so_overlay: ls "", exa "sO=43" =>  colours c -> { c.suspicious_overlay                    = Style::default().on(Yellow); });
+    test!(exa_ep:  ls "", exa "ep=38;5;136"  =>  colours c -> { c.empty_placeholder                     = Fixed(136).normal(); });
+    test!(exa_gh:  ls "", exa "gh=1;34"      =>  colours c -> { c.group_header                          = Blue.bold(); });
+    test!(exa_zb:  ls "", exa "zb=48;5;236"  =>  colours c -> { c.zebra                                 = Some(Style::default().on(Fixed(236))); });
+    test!(exa_ic:  ls "", exa "ic=33"        =>  colours c -> { c.icon                                  = Yellow.normal(); });
+
+    // Whole columns
+    test!(exa_col: ls "", exa "column.inode=2"  =>  colours c -> { c.columns = vec![("inode", Style::default().dimmed())]; });
+    test!(exa_cl2: ls "", exa "column.size=0:column.inode=33"  =>  colours c -> { c.columns = vec![("size", Style::default()), ("inode", Yellow.normal())]; });
 
     test!(exa_mp:  ls "", exa "mp=1;34;4"    =>  colours c -> { c.filekinds.mount_point                 = Blue.bold().underline(); });
+    test!(exa_td:  ls "", exa "td=2;34"      =>  colours c -> { c.filekinds.tool_directory              = Some(Blue.dimmed()); });
     test!(exa_sp:  ls "", exa "sp=1;35;4"    =>  colours c -> { c.filekinds.special                     = Purple.bold().underline(); });
 
     test!(exa_im:  ls "", exa "im=38;5;128"  =>  colours c -> { c.file_type.image                       = Fixed(128).normal(); });
@@ -605,6 +964,10 @@ mod customs_test {
     test!(exa_ie:  ls "", exa "bu=38;5;137"  =>  colours c -> { c.file_type.build                       = Fixed(137).normal(); });
     test!(exa_bu:  ls "", exa "bu=38;5;137"  =>  colours c -> { c.file_type.build                       = Fixed(137).normal(); });
     test!(exa_sc:  ls "", exa "sc=38;5;138"  =>  colours c -> { c.file_type.source                      = Fixed(138).normal(); });
+    test!(exa_cf:  ls "", exa "cf=38;5;139"  =>  colours c -> { c.file_type.config                      = Fixed(139).normal(); });
+
+    test!(exa_szim: ls "", exa "szim=35"     =>  colours c -> { c.size_file_type.image                  = Some(Purple.normal()); });
+    test!(exa_szco: ls "", exa "szco=1;31"   =>  colours c -> { c.size_file_type.compressed             = Some(Red.bold()); });
 
     test!(exa_Sn:  ls "", exa "Sn=38;5;128"  =>  colours c -> { c.security_context.none                 = Fixed(128).normal(); });
     test!(exa_Su:  ls "", exa "Su=38;5;129"  =>  colours c -> { c.security_context.selinux.user         = Fixed(129).normal(); });
@@ -612,6 +975,9 @@ mod customs_test {
     test!(exa_St:  ls "", exa "St=38;5;131"  =>  colours c -> { c.security_context.selinux.typ          = Fixed(131).normal(); });
     test!(exa_Sl:  ls "", exa "Sl=38;5;132"  =>  colours c -> { c.security_context.selinux.range        = Fixed(132).normal(); });
 
+    test!(exa_gradient_size: ls "", exa "gradient.size.from=32:gradient.size.to=31"  =>  colours c -> { c.size_gradient.from = Some(Green.normal()); c.size_gradient.to = Some(Red.normal()); });
+    test!(exa_gradient_age:  ls "", exa "gradient.age.from=1;36:gradient.age.to=34"  =>  colours c -> { c.age_gradient.from  = Some(Cyan.bold());    c.age_gradient.to  = Some(Blue.normal()); });
+
     // All the while, LS_COLORS treats them as filenames:
     test!(ls_uu:   ls "uu=38;5;117", exa ""  =>  exts [ ("uu", Fixed(117).normal()) ]);
     test!(ls_un:   ls "un=38;5;118", exa ""  =>  exts [ ("un", Fixed(118).normal()) ]);
@@ -625,6 +991,7 @@ mod customs_test {
     test!(exa_txt: ls "", exa "*.zip=31"           =>  exts [ ("*.zip",      Red.normal())             ]);
     test!(exa_mp3: ls "", exa "lev.*=38;5;153"     =>  exts [ ("lev.*",      Fixed(153).normal())      ]);
     test!(exa_mak: ls "", exa "Cargo.toml=4;32;1"  =>  exts [ ("Cargo.toml", Green.bold().underline()) ]);
+    test!(exa_dir: ls "", exa "node_modules/=2"    =>  exts [ ("node_modules/", Style::default().dimmed()) ]);
 
     // Testing whether a glob from EZA_COLORS overrides a glob from LS_COLORS
     // can’t be tested here, because they’ll both be added to the same vec
@@ -647,4 +1014,99 @@ mod customs_test {
     test!(ls_fi_exa_txt:  ls "fi=33", exa "*.txt=31"  => colours c -> { c.filekinds.normal = Yellow.normal(); }, exts [ ("*.txt", Red.normal()) ]);
     test!(ls_txt_exa_fi:  ls "*.txt=31", exa "fi=33"  => colours c -> { c.filekinds.normal = Yellow.normal(); }, exts [ ("*.txt", Red.normal()) ]);
     test!(eza_fi_exa_txt: ls "", exa "fi=33:*.txt=31" => colours c -> { c.filekinds.normal = Yellow.normal(); }, exts [ ("*.txt", Red.normal()) ]);
+
+    fn mappings(patterns: &[(&str, Style)]) -> ExtensionMappings {
+        let mut mappings = ExtensionMappings::default();
+        for (pattern, style) in patterns {
+            mappings.add(glob::Pattern::new(pattern).unwrap(), *style);
+        }
+        mappings
+    }
+
+    #[test]
+    fn lookup_extensions() {
+        let exts = mappings(&[("*.txt", Red.normal()), ("*.gz", Blue.normal())]);
+        assert_eq!(exts.style_for_name("notes.txt"), Some(Red.normal()));
+        assert_eq!(exts.style_for_name("backup.tar.gz"), Some(Blue.normal()));
+        assert_eq!(exts.style_for_name(".txt"), Some(Red.normal()));
+        assert_eq!(exts.style_for_name("notes.TXT"), None);
+        assert_eq!(exts.style_for_name("txt"), None);
+    }
+
+    fn theme(thresholds: [Option<u64>; 4]) -> Theme {
+        let mut ui = UiStyles::default();
+        ui.size.thresholds = thresholds;
+        Theme {
+            ui,
+            exts: Box::new(NoFileStyle),
+        }
+    }
+
+    #[test]
+    fn size_scale_by_prefix() {
+        use number_prefix::Prefix::*;
+
+        let theme = theme([None; 4]);
+        assert_eq!(theme.size_scale(999, None), 0);
+        assert_eq!(theme.size_scale(2048, Some(Kibi)), 1);
+        assert_eq!(theme.size_scale(5_000_000_000_000, Some(Tera)), 4);
+    }
+
+    #[test]
+    fn size_scale_by_thresholds() {
+        use number_prefix::Prefix::*;
+
+        let theme = theme([Some(100_000), None, Some(10_000_000_000), None]);
+        assert_eq!(theme.size_scale(50_000, Some(Kilo)), 0);
+        assert_eq!(theme.size_scale(100_000, Some(Kilo)), 1);
+        assert_eq!(theme.size_scale(5_000_000, Some(Mega)), 2);
+        assert_eq!(theme.size_scale(5_000_000_000, Some(Giga)), 2);
+        assert_eq!(theme.size_scale(20_000_000_000_000, Some(Tera)), 4);
+    }
+
+    #[test]
+    fn size_gradient() {
+        use crate::output::render::SizeColours;
+
+        let mut theme = theme([None; 4]);
+        theme.ui.size.number_byte = Green.normal();
+        assert_eq!(theme.size(1, None), Green.normal());
+
+        theme.ui.size_gradient = Gradient {
+            from: Some(RGB(0, 0, 0).normal()),
+            to: Some(RGB(200, 200, 200).normal()),
+            truecolour: true,
+        };
+        assert_eq!(theme.size(0, None), RGB(0, 0, 0).normal());
+        assert_eq!(theme.unit(1 << 20, None), RGB(100, 100, 100).normal());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn hashed_owners() {
+        let mut theme = theme([None; 4]);
+        assert_eq!(theme.owner(Yellow.bold(), "root"), Yellow.bold());
+
+        theme.ui.hashed_owners = true;
+        let style = theme.owner(Yellow.bold(), "root");
+        assert_eq!(style, Fixed(owner_colour("root")).bold());
+        assert_eq!(
+            theme.owner(Style::default(), "root"),
+            Fixed(owner_colour("root")).normal()
+        );
+        assert_ne!(owner_colour("alice"), owner_colour("bob"));
+    }
+
+    #[test]
+    fn lookup_later_patterns_win() {
+        let exts = mappings(&[
+            ("*.gz", Blue.normal()),
+            ("*.tar.gz", Green.normal()),
+            ("README*", Yellow.normal()),
+            ("*.md", Red.normal()),
+        ]);
+        assert_eq!(exts.style_for_name("backup.tar.gz"), Some(Green.normal()));
+        assert_eq!(exts.style_for_name("README.md"), Some(Red.normal()));
+        assert_eq!(exts.style_for_name("README.txt"), Some(Yellow.normal()));
+    }
 }
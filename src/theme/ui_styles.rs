@@ -1,5 +1,8 @@
 use ansiterm::Style;
 
+use crate::fs::filter::parse_size;
+use crate::output::table::COLUMN_NAMES;
+use crate::theme::gradient::Gradient;
 use crate::theme::lsc::Pair;
 
 #[rustfmt::skip]
@@ -16,6 +19,7 @@ pub struct UiStyles {
     pub git_repo:         GitRepo,
     pub security_context: SecurityContext,
     pub file_type:        FileType,
+    pub size_file_type:   SizeFileType,
 
     pub punctuation:  Style,          // xx
     pub date:         Style,          // da
@@ -23,11 +27,44 @@ pub struct UiStyles {
     pub blocks:       Style,          // bl
     pub header:       Style,          // hd
     pub octal:        Style,          // oc
+    pub codeowners:   Style,          // ow
+    pub delta_grown:  Style,          // Dg
+    pub delta_shrunk: Style,          // Ds
 
     pub symlink_path:         Style,  // lp
     pub control_char:         Style,  // cc
     pub broken_symlink:       Style,  // or
     pub broken_path_overlay:  Style,  // bO
+    pub suspicious_overlay:   Style,  // sO
+
+    pub empty_placeholder:    Style,  // ep
+    pub group_header:         Style,  // gh
+
+    /// The background of every other row in the long view, which only
+    /// gets striped if this is set.
+    pub zebra:                Option<Style>,  // zb
+    pub icon:                 Style,  // ic
+
+    /// The styles to paint the icons of the files that match each glob in,
+    /// which can only be set in the theme file.
+    pub icons:                Vec<(glob::Pattern, Style)>,
+
+    /// The icons to show next to the files that match each glob in place of
+    /// the built-in ones, which can also only be set in the theme file.
+    pub glyphs:               Vec<(glob::Pattern, String)>,
+
+    /// The styles that whole columns of the long view get painted in, in
+    /// place of their own colours, by the name of the column.
+    pub columns:              Vec<(&'static str, Style)>,  // column.NAME
+
+    /// The colour scales for sizes and timestamps, which only get used
+    /// with `--color-scale` when both of their ends are set.
+    pub size_gradient:        Gradient,  // gradient.size.from, gradient.size.to
+    pub age_gradient:         Gradient,  // gradient.age.from, gradient.age.to
+
+    /// Whether users and groups get a colour worked out from their names,
+    /// for `--color-owners`.
+    pub hashed_owners:        bool,
 }
 
 #[rustfmt::skip]
@@ -43,6 +80,10 @@ pub struct FileKinds {
     pub special: Style,       // sp
     pub executable: Style,    // ex
     pub mount_point: Style,   // mp
+
+    /// Directories that belong to development tools, which use the regular
+    /// directory style unless this is set.
+    pub tool_directory: Option<Style>,  // td
 }
 
 #[rustfmt::skip]
@@ -84,6 +125,10 @@ pub struct Size {
     pub unit_mega: Style,    // sb um
     pub unit_giga: Style,    // sb ug
     pub unit_huge: Style,    // sb ut
+
+    /// The sizes, in bytes, at which the kilo, mega, giga, and huge
+    /// colours start to get used, overriding the size’s prefix.
+    pub thresholds: [Option<u64>; 4],  // zk zm zg zt
 }
 
 #[rustfmt::skip]
@@ -156,6 +201,26 @@ pub struct FileType {
     pub compiled: Style,    // cm - compilation artifact
     pub build: Style,       // bu - file that is used to build a project
     pub source: Style,      // sc - source code
+    pub config: Style,      // cf - configures a tool used on a project
+}
+
+/// Size column styles based on the type of file, which replace the usual
+/// styles for the number and its unit when set.
+#[rustfmt::skip]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SizeFileType {
+    pub image: Option<Style>,       // szim
+    pub video: Option<Style>,       // szvi
+    pub music: Option<Style>,       // szmu
+    pub lossless: Option<Style>,    // szlo
+    pub crypto: Option<Style>,      // szcr
+    pub document: Option<Style>,    // szdo
+    pub compressed: Option<Style>,  // szco
+    pub temp: Option<Style>,        // sztm
+    pub compiled: Option<Style>,    // szcm
+    pub build: Option<Style>,       // szbu
+    pub source: Option<Style>,      // szsc
+    pub config: Option<Style>,      // szcf
 }
 
 impl UiStyles {
@@ -221,6 +286,10 @@ impl UiStyles {
             "um" => self.size.unit_mega                 = pair.to_style(),
             "ug" => self.size.unit_giga                 = pair.to_style(),
             "ut" => self.size.unit_huge                 = pair.to_style(),
+            "zk" => self.size.thresholds[0]             = parse_size(pair.value),
+            "zm" => self.size.thresholds[1]             = parse_size(pair.value),
+            "zg" => self.size.thresholds[2]             = parse_size(pair.value),
+            "zt" => self.size.thresholds[3]             = parse_size(pair.value),
             "df" => self.size.major                     = pair.to_style(),
             "ds" => self.size.minor                     = pair.to_style(),
 
@@ -253,11 +322,20 @@ impl UiStyles {
             "bl" => self.blocks                         = pair.to_style(),
             "hd" => self.header                         = pair.to_style(),
             "oc" => self.octal                          = pair.to_style(),
+            "ow" => self.codeowners                     = pair.to_style(),
+            "Dg" => self.delta_grown                    = pair.to_style(),
+            "Ds" => self.delta_shrunk                   = pair.to_style(),
             "lp" => self.symlink_path                   = pair.to_style(),
             "cc" => self.control_char                   = pair.to_style(),
             "bO" => self.broken_path_overlay            = pair.to_style(),
+            "sO" => self.suspicious_overlay             = pair.to_style(),
+            "ep" => self.empty_placeholder              = pair.to_style(),
+            "gh" => self.group_header                   = pair.to_style(),
+            "zb" => self.zebra                          = Some(pair.to_style()),
+            "ic" => self.icon                           = pair.to_style(),
 
             "mp" => self.filekinds.mount_point          = pair.to_style(),
+            "td" => self.filekinds.tool_directory       = Some(pair.to_style()),
             "sp" => self.filekinds.special              = pair.to_style(),  // Catch-all for unrecognized file kind
 
             "im" => self.file_type.image                = pair.to_style(),
@@ -271,6 +349,25 @@ impl UiStyles {
             "cm" => self.file_type.compiled             = pair.to_style(),
             "bu" => self.file_type.build                = pair.to_style(),
             "sc" => self.file_type.source               = pair.to_style(),
+            "cf" => self.file_type.config               = pair.to_style(),
+
+            "szim" => self.size_file_type.image         = Some(pair.to_style()),
+            "szvi" => self.size_file_type.video         = Some(pair.to_style()),
+            "szmu" => self.size_file_type.music         = Some(pair.to_style()),
+            "szlo" => self.size_file_type.lossless      = Some(pair.to_style()),
+            "szcr" => self.size_file_type.crypto        = Some(pair.to_style()),
+            "szdo" => self.size_file_type.document      = Some(pair.to_style()),
+            "szco" => self.size_file_type.compressed    = Some(pair.to_style()),
+            "sztm" => self.size_file_type.temp          = Some(pair.to_style()),
+            "szcm" => self.size_file_type.compiled      = Some(pair.to_style()),
+            "szbu" => self.size_file_type.build         = Some(pair.to_style()),
+            "szsc" => self.size_file_type.source        = Some(pair.to_style()),
+            "szcf" => self.size_file_type.config        = Some(pair.to_style()),
+
+            "gradient.size.from" => self.size_gradient.from = Some(pair.to_style()),
+            "gradient.size.to"   => self.size_gradient.to   = Some(pair.to_style()),
+            "gradient.age.from"  => self.age_gradient.from  = Some(pair.to_style()),
+            "gradient.age.to"    => self.age_gradient.to    = Some(pair.to_style()),
 
             "Sn" => self.security_context.none          = pair.to_style(),
             "Su" => self.security_context.selinux.user  = pair.to_style(),
@@ -278,12 +375,60 @@ impl UiStyles {
             "St" => self.security_context.selinux.typ   = pair.to_style(),
             "Sl" => self.security_context.selinux.range = pair.to_style(),
 
-             _   => return false,
+             _   => return self.set_column(pair),
         };
 
         true
     }
 
+    /// Sets the style of a whole column from a `column.NAME` key, returning
+    /// whether the key was one. The names can’t be coloured this way, as
+    /// they’re coloured by the file type instead.
+    fn set_column(&mut self, pair: &Pair<'_>) -> bool {
+        let name = pair.key.strip_prefix("column.").and_then(|name| {
+            COLUMN_NAMES
+                .iter()
+                .find(|column| **column == name && name != "name")
+        });
+
+        if let Some(name) = name {
+            self.columns.push((name, pair.to_style()));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The style to paint the icon of the file with the given name in, if
+    /// the theme file gives it one. Later globs win.
+    pub fn icon_for(&self, name: &str) -> Option<Style> {
+        self.icons
+            .iter()
+            .rev()
+            .find(|(glob, _)| glob.matches(name))
+            .map(|(_, style)| *style)
+    }
+
+    /// The icon to show next to the file with the given name, if the theme
+    /// file gives it one. Later globs win.
+    pub fn glyph_for(&self, name: &str) -> Option<&str> {
+        self.glyphs
+            .iter()
+            .rev()
+            .find(|(glob, _)| glob.matches(name))
+            .map(|(_, glyph)| glyph.as_str())
+    }
+
+    /// The style that the column with the given name gets painted in, if
+    /// it’s been given one.
+    pub fn column(&self, name: &str) -> Option<Style> {
+        self.columns
+            .iter()
+            .rev()
+            .find(|(column, _)| *column == name)
+            .map(|(_, style)| *style)
+    }
+
     pub fn set_number_style(&mut self, style: Style) {
         self.size.number_byte = style;
         self.size.number_kilo = style;